@@ -1,7 +1,9 @@
 use std::env;
+use std::fmt::Write;
 use std::path::Path;
 use std::process;
 
+use mp3tags_r::batch::Batch;
 use mp3tags_r::{get_title, get_artist, get_album, get_year, get_genre, get_comment, get_all_meta_entries};
 
 #[derive(Default)]
@@ -24,53 +26,59 @@ impl TagOptions {
 fn get_tag_value<P, F>(path: P, getter: F, field_name: &str) -> String
 where
     P: AsRef<Path>,
-    F: FnOnce(P) -> mp3tags_r::Result<String>,
+    F: FnOnce(P) -> mp3tags_r::Result<Option<String>>,
 {
     getter(path)
-        .unwrap_or_else(|_| format!("N/A (no {} tag)", field_name))
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| format!("N/A (no {} tag)", field_name))
 }
 
-fn read_tags_in_file<P: AsRef<Path>>(file_path: P, options: &TagOptions) {
+fn read_tags_in_file<P: AsRef<Path>>(file_path: P, options: &TagOptions) -> String {
     let path = file_path.as_ref();
     let filename = path.file_name()
         .map(|n| n.to_string_lossy())
         .unwrap_or_else(|| "Unknown".into());
+    let mut output = String::new();
     
     if options.title {
-        println!("Get title of file: {} : {}", filename, get_tag_value(path, get_title, "title"));
+        let _ = writeln!(output, "Get title of file: {} : {}", filename, get_tag_value(path, get_title, "title"));
     }
     
     if options.artist {
-        println!("Get artist of file: {} : {}", filename, get_tag_value(path, get_artist, "artist"));
+        let _ = writeln!(output, "Get artist of file: {} : {}", filename, get_tag_value(path, get_artist, "artist"));
     }
     
     if options.album {
-        println!("Get album of file: {} : {}", filename, get_tag_value(path, get_album, "album"));
+        let _ = writeln!(output, "Get album of file: {} : {}", filename, get_tag_value(path, get_album, "album"));
     }
     
     if options.year {
-        println!("Get year of file: {} : {}", filename, get_tag_value(path, get_year, "year"));
+        let _ = writeln!(output, "Get year of file: {} : {}", filename, get_tag_value(path, get_year, "year"));
     }
     
     if options.genre {
-        println!("Get genre of file: {} : {}", filename, get_tag_value(path, get_genre, "genre"));
+        let _ = writeln!(output, "Get genre of file: {} : {}", filename, get_tag_value(path, get_genre, "genre"));
     }
     
     if options.comment {
-        println!("Get comment of file: {} : {}", filename, get_tag_value(path, get_comment, "comment"));
+        let _ = writeln!(output, "Get comment of file: {} : {}", filename, get_tag_value(path, get_comment, "comment"));
     }
     
     if options.all_entries {
-        println!("All meta entries for file: {}", filename);
+        let _ = writeln!(output, "All meta entries for file: {}", filename);
         match get_all_meta_entries(path) {
             Ok(entries) => {
                 for (entry, value) in entries {
-                    println!("  {:?}: {}", entry, value);
+                    let _ = writeln!(output, "  {:?}: {}", entry, value);
                 }
             }
-            Err(e) => println!("  Error reading entries: {}", e),
+            Err(e) => {
+                let _ = writeln!(output, "  Error reading entries: {}", e);
+            }
         }
     }
+    output
 }
 
 fn read_tags<P: AsRef<Path>>(path: P, options: &TagOptions) {
@@ -82,22 +90,20 @@ fn read_tags<P: AsRef<Path>>(path: P, options: &TagOptions) {
     }
     
     if path.is_file() {
-        read_tags_in_file(path, options);
+        print!("{}", read_tags_in_file(path, options));
     } else if path.is_dir() {
-        // Read tags from all files in directory recursively
-        if let Ok(entries) = std::fs::read_dir(path) {
-            for entry in entries.flatten() {
-                let file_path = entry.path();
-                
-                if file_path.is_file() {
-                    if let Some(extension) = file_path.extension() {
-                        if extension == "mp3" {
-                            read_tags_in_file(&file_path, options);
-                        }
-                    }
-                } else if file_path.is_dir() {
-                    read_tags(&file_path, options);
-                }
+        // Read tags from all MP3 files in the directory tree in parallel
+        let batch = match Batch::from_directory(path) {
+            Ok(batch) => batch,
+            Err(e) => {
+                eprintln!("Error listing directory {}: {}", path.display(), e);
+                return;
+            }
+        };
+        let summary = batch.run(|file_path| Ok(read_tags_in_file(file_path, options)));
+        for (_, output) in summary.results {
+            if let Ok(output) = output {
+                print!("{}", output);
             }
         }
     }
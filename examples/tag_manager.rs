@@ -4,6 +4,7 @@ use std::path::Path;
 use std::process;
 
 use mp3tags_r::{TagReader, TagWriter, MetaEntry, TagType, Result, Error};
+use mp3tags_r::strip::strip_tags;
 
 fn print_usage() {
     println!("MP3 Tag Manager - A simple tool to read and modify MP3 tags");
@@ -101,9 +102,10 @@ fn get_tag(file_path: &Path, tag: &str) -> Result<()> {
     let reader = TagReader::new(file_path)?;
     
     // Get the tag value
-    let value = reader.get_meta_entry(&meta_entry)?;
-    
-    println!("{}: {}", tag, value);
+    match reader.get_meta_entry(&meta_entry)? {
+        Some(value) => println!("{}: {}", tag, value),
+        None => println!("{}: (not set)", tag),
+    }
     Ok(())
 }
 
@@ -148,37 +150,14 @@ fn remove_tag(file_path: &Path, tag: &str) -> Result<()> {
 }
 
 fn clear_tags(file_path: &Path) -> Result<()> {
-    // Create a new tag writer
-    let mut writer = TagWriter::new(file_path, TagType::Id3v2)?;
-    
-    // For each meta entry type, set it to empty string
-    let entries = [
-        MetaEntry::Title,
-        MetaEntry::Artist,
-        MetaEntry::Album,
-        MetaEntry::Year,
-        MetaEntry::Genre,
-        MetaEntry::Track,
-        MetaEntry::Comment,
-        MetaEntry::Composer,
-    ];
-    
-    // Track any errors that occur during tag clearing
-    let mut errors = Vec::new();
+    // Physically remove every tag structure from the file
+    let removed = strip_tags(file_path, &[TagType::Id3v2, TagType::Ape, TagType::Id3v1])?;
     
-    // Try to clear each tag
-    for entry in &entries {
-        if let Err(e) = writer.set_meta_entry(entry, "") {
-            errors.push(format!("Failed to clear {:?}: {}", entry, e));
-        }
-    }
-    
-    // If any errors occurred, return them as a combined error
-    if !errors.is_empty() {
-        return Err(Error::Other(format!("Some tags could not be removed: {}", errors.join(", "))));
+    if removed.is_empty() {
+        println!("No tags found.");
+    } else {
+        println!("Removed tags: {:?}", removed);
     }
-    
-    println!("All tags removed.");
     Ok(())
 }
 
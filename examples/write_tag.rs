@@ -2,6 +2,7 @@ use std::env;
 use std::path::Path;
 use std::process;
 
+use mp3tags_r::batch::Batch;
 use mp3tags_r::{TagWriter, MetaEntry, Result, Error, tag::TagType};
 
 #[derive(Default)]
@@ -46,32 +47,32 @@ fn change_tags_in_file<P: AsRef<Path>>(file_path: P, options: &TagOptions) -> Re
     let mut changes_made = false;
     
     if let Some(ref title) = options.title {
-        set_tag_value(&mut writer, &path, MetaEntry::Title, title, "title")?;
+        set_tag_value(&mut writer, path, MetaEntry::Title, title, "title")?;
         changes_made = true;
     }
     
     if let Some(ref artist) = options.artist {
-        set_tag_value(&mut writer, &path, MetaEntry::Artist, artist, "artist")?;
+        set_tag_value(&mut writer, path, MetaEntry::Artist, artist, "artist")?;
         changes_made = true;
     }
     
     if let Some(ref album) = options.album {
-        set_tag_value(&mut writer, &path, MetaEntry::Album, album, "album")?;
+        set_tag_value(&mut writer, path, MetaEntry::Album, album, "album")?;
         changes_made = true;
     }
     
     if let Some(ref genre) = options.genre {
-        set_tag_value(&mut writer, &path, MetaEntry::Genre, genre, "genre")?;
+        set_tag_value(&mut writer, path, MetaEntry::Genre, genre, "genre")?;
         changes_made = true;
     }
     
     if let Some(ref year) = options.year {
-        set_tag_value(&mut writer, &path, MetaEntry::Year, year, "year")?;
+        set_tag_value(&mut writer, path, MetaEntry::Year, year, "year")?;
         changes_made = true;
     }
     
     if let Some(ref comment) = options.comment {
-        set_tag_value(&mut writer, &path, MetaEntry::Comment, comment, "comment")?;
+        set_tag_value(&mut writer, path, MetaEntry::Comment, comment, "comment")?;
         changes_made = true;
     }
     
@@ -98,24 +99,19 @@ fn write_tags<P: AsRef<Path>>(path: P, options: &TagOptions) {
             eprintln!("Error changing tags in file {}: {}", path.display(), e);
         }
     } else if path.is_dir() {
-        // Change tags in all MP3 files in directory recursively
-        if let Ok(entries) = std::fs::read_dir(path) {
-            for entry in entries.flatten() {
-                let file_path = entry.path();
-                
-                if file_path.is_file() {
-                    if let Some(extension) = file_path.extension() {
-                        if extension == "mp3" {
-                            if let Err(e) = change_tags_in_file(&file_path, options) {
-                                eprintln!("Error changing tags in file {}: {}", file_path.display(), e);
-                            }
-                        }
-                    }
-                } else if file_path.is_dir() {
-                    write_tags(&file_path, options);
-                }
+        // Change tags in all MP3 files in the directory tree in parallel
+        let batch = match Batch::from_directory(path) {
+            Ok(batch) => batch,
+            Err(e) => {
+                eprintln!("Error listing directory {}: {}", path.display(), e);
+                return;
             }
+        };
+        let summary = batch.run(|file_path| change_tags_in_file(file_path, options));
+        for (file_path, e) in summary.failures() {
+            eprintln!("Error changing tags in file {}: {}", file_path.display(), e);
         }
+        println!("Changed tags in {} of {} files", summary.succeeded(), summary.results.len());
     }
 }
 
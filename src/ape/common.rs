@@ -1,6 +1,4 @@
 use std::path::Path;
-use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
 
 use crate::error::{Error, Result};
 
@@ -15,6 +13,9 @@ pub mod constants {
     /// APE tag identifier
     pub const APE_TAG_IDENTIFIER: &[u8] = b"APETAGEX";
     
+    /// APE tag version 1.0 (no header, no flags, text items only)
+    pub const APE_TAG_VERSION_1_0: u32 = 1000;
+    
     /// APE tag version 2.0
     pub const APE_TAG_VERSION_2_0: u32 = 2000;
     
@@ -32,11 +33,20 @@ pub mod constants {
     
     /// APE item flags
     pub mod item_flags {
+        /// Item must not be modified
+        pub const APE_ITEM_FLAG_READ_ONLY: u32 = 1;
+        
         /// Item contains binary data
         pub const APE_ITEM_FLAG_BINARY: u32 = 2;
         
+        /// Item contains a locator (URL or path) of external data
+        pub const APE_ITEM_FLAG_EXTERNAL: u32 = 4;
+        
         /// Item contains UTF-8 text
         pub const APE_ITEM_FLAG_UTF8: u32 = 0;
+        
+        /// Bits holding the item type (text, binary, external)
+        pub const APE_ITEM_TYPE_MASK: u32 = 6;
     }
 }
 
@@ -121,22 +131,72 @@ impl ApeTagHeader {
         Ok(())
     }
     
+    /// Whether this belongs to an APEv1 tag, which has no header and ignores flags
+    pub fn is_v1(&self) -> bool {
+        self.version == constants::APE_TAG_VERSION_1_0
+    }
+    
     /// Check if this is a header (not a footer)
     pub fn is_header(&self) -> bool {
-        self.flags & constants::flags::APE_TAG_FLAG_IS_HEADER != 0
+        !self.is_v1() && self.flags & constants::flags::APE_TAG_FLAG_IS_HEADER != 0
     }
     
     /// Check if the tag has a header
     pub fn has_header(&self) -> bool {
-        self.flags & constants::flags::APE_TAG_FLAG_HAS_HEADER != 0
+        !self.is_v1() && self.flags & constants::flags::APE_TAG_FLAG_HAS_HEADER != 0
     }
     
     /// Check if the tag has a footer
     pub fn has_footer(&self) -> bool {
-        self.flags & constants::flags::APE_TAG_FLAG_NO_FOOTER == 0
+        self.is_v1() || self.flags & constants::flags::APE_TAG_FLAG_NO_FOOTER == 0
+    }
+}
+
+/// Decoded APE item flags
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ItemFlags {
+    pub read_only: bool,
+    pub binary: bool,
+    pub external: bool,
+}
+
+impl ItemFlags {
+    /// Decode the flags field of an item
+    pub fn from_bits(bits: u32) -> Self {
+        let kind = bits & constants::item_flags::APE_ITEM_TYPE_MASK;
+        Self {
+            read_only: bits & constants::item_flags::APE_ITEM_FLAG_READ_ONLY != 0,
+            binary: kind == constants::item_flags::APE_ITEM_FLAG_BINARY,
+            external: kind == constants::item_flags::APE_ITEM_FLAG_EXTERNAL,
+        }
+    }
+    
+    /// Encode the flags field; binary wins when both types are set
+    pub fn to_bits(self) -> u32 {
+        let mut bits = 0;
+        if self.read_only {
+            bits |= constants::item_flags::APE_ITEM_FLAG_READ_ONLY;
+        }
+        if self.binary {
+            bits |= constants::item_flags::APE_ITEM_FLAG_BINARY;
+        } else if self.external {
+            bits |= constants::item_flags::APE_ITEM_FLAG_EXTERNAL;
+        }
+        bits
     }
 }
 
+/// Typed value of an APE item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemValue {
+    /// UTF-8 text
+    Text(String),
+    /// Binary data, such as cover art
+    Binary(Vec<u8>),
+    /// URL or path of data stored outside the tag
+    Locator(String),
+}
+
 /// APE tag item structure
 #[derive(Debug, Clone)]
 pub struct ApeItem {
@@ -166,17 +226,65 @@ impl ApeItem {
         Self::new(key, value.as_bytes().to_vec(), constants::item_flags::APE_ITEM_FLAG_UTF8)
     }
     
+    /// Create a new external item pointing at data elsewhere (a URL or path)
+    pub fn new_locator(key: &str, locator: &str) -> Self {
+        Self::new(key, locator.as_bytes().to_vec(), constants::item_flags::APE_ITEM_FLAG_EXTERNAL)
+    }
+    
+    /// Create a new binary APE item, such as "Cover Art (Front)"
+    pub fn new_binary(key: &str, value: Vec<u8>) -> Self {
+        Self::new(key, value, constants::item_flags::APE_ITEM_FLAG_BINARY)
+    }
+    
     /// Get the size of the item (including key and value)
     pub fn total_size(&self) -> u32 {
         // Size + Flags + Key (null-terminated) + Value
         8 + self.key.len() as u32 + 1 + self.size
     }
     
+    /// Decoded flags of the item
+    pub fn item_flags(&self) -> ItemFlags {
+        ItemFlags::from_bits(self.flags)
+    }
+    
+    /// Replace the flags of the item
+    pub fn set_item_flags(&mut self, flags: ItemFlags) {
+        self.flags = flags.to_bits();
+    }
+    
+    /// Whether the item is flagged read-only
+    pub fn is_read_only(&self) -> bool {
+        self.flags & constants::item_flags::APE_ITEM_FLAG_READ_ONLY != 0
+    }
+    
+    /// Whether the item holds a locator of external data
+    pub fn is_external(&self) -> bool {
+        self.flags & constants::item_flags::APE_ITEM_TYPE_MASK == constants::item_flags::APE_ITEM_FLAG_EXTERNAL
+    }
+    
+    /// The value of the item according to its type
+    pub fn item_value(&self) -> Result<ItemValue> {
+        if self.is_binary() {
+            return Ok(ItemValue::Binary(self.value.clone()));
+        }
+        let text = String::from_utf8(self.value.clone())
+            .map_err(|_| Error::Other("Invalid UTF-8 data".to_string()))?;
+        Ok(if self.is_external() { ItemValue::Locator(text) } else { ItemValue::Text(text) })
+    }
+    
+    /// Whether the item holds binary data rather than text
+    pub fn is_binary(&self) -> bool {
+        self.flags & constants::item_flags::APE_ITEM_FLAG_BINARY != 0
+    }
+    
     /// Get the text value of the item
     pub fn get_text(&self) -> Result<String> {
         if self.flags & constants::item_flags::APE_ITEM_FLAG_BINARY != 0 {
             return Err(Error::Other("Item is binary, not text".to_string()));
         }
+        if self.is_external() {
+            return Err(Error::Other("Item is an external locator, not text".to_string()));
+        }
         
         match String::from_utf8(self.value.clone()) {
             Ok(text) => Ok(text),
@@ -185,85 +293,7 @@ impl ApeItem {
     }
 }
 
-/// APE tag search location
-#[derive(Debug, Clone, Copy)]
-enum ApeTagLocation {
-    /// At the end of the file
-    EndOfFile,
-    /// At the beginning of the file
-    StartOfFile,
-    /// Before ID3v1 tag (128 bytes from end)
-    BeforeId3v1,
-}
-
-impl ApeTagLocation {
-    /// Get the seek position for this location
-    fn get_seek_position(&self, file_size: u64) -> Option<SeekFrom> {
-        match self {
-            ApeTagLocation::EndOfFile => {
-                if file_size >= constants::APE_TAG_FOOTER_SIZE as u64 {
-                    Some(SeekFrom::End(-(constants::APE_TAG_FOOTER_SIZE as i64)))
-                } else {
-                    None
-                }
-            }
-            ApeTagLocation::StartOfFile => Some(SeekFrom::Start(0)),
-            ApeTagLocation::BeforeId3v1 => {
-                if file_size >= (constants::APE_TAG_FOOTER_SIZE + 128) as u64 {
-                    Some(SeekFrom::End(-((constants::APE_TAG_FOOTER_SIZE + 128) as i64)))
-                } else {
-                    None
-                }
-            }
-        }
-    }
-    
-    /// Validate the found tag header for this location
-    fn validate_header(&self, header: &ApeTagHeader) -> bool {
-        match self {
-            ApeTagLocation::StartOfFile => header.is_header(),
-            _ => true, // Footer locations don't need special validation
-        }
-    }
-}
-
-/// Template function to check for APE tag at a specific location
-fn check_ape_tag_at_location(file: &mut File, file_size: u64, location: ApeTagLocation) -> Result<bool> {
-    if let Some(seek_pos) = location.get_seek_position(file_size) {
-        file.seek(seek_pos)?;
-        
-        let mut buffer = [0u8; constants::APE_TAG_FOOTER_SIZE];
-        file.read_exact(&mut buffer)?;
-        
-        if let Ok(tag_header) = ApeTagHeader::from_buffer(&buffer) {
-            if location.validate_header(&tag_header) {
-                return Ok(true);
-            }
-        }
-    }
-    
-    Ok(false)
-}
-
-/// Check if a file has an APE tag
+/// Check if a file has an APE tag at any of the locations the reader supports
 pub fn has_ape_tag<P: AsRef<Path>>(path: P) -> Result<bool> {
-    let mut file = File::open(path)?;
-    let file_size = file.metadata()?.len();
-    
-    // Define search locations in priority order
-    let locations = [
-        ApeTagLocation::EndOfFile,
-        ApeTagLocation::StartOfFile,
-        ApeTagLocation::BeforeId3v1,
-    ];
-    
-    // Check each location using the template function
-    for location in &locations {
-        if check_ape_tag_at_location(&mut file, file_size, *location)? {
-            return Ok(true);
-        }
-    }
-    
-    Ok(false)
+    Ok(crate::ape::ApeReader::new().locate_tag(path)?.is_some())
 }
-
@@ -1,6 +1,7 @@
 mod reader;
 mod writer;
-mod common;
+pub(crate) mod common;
 
-pub use reader::ApeReader;
+pub use common::{has_ape_tag, ApeItem, ItemFlags, ItemValue};
+pub use reader::{ApeReader, ApeTag, ApeTagPlacement, ApeTagSpan, ItemIter, LazyItem};
 pub use writer::ApeWriter;
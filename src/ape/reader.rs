@@ -1,6 +1,6 @@
 use std::path::Path;
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{self, Read, Seek, SeekFrom};
 use std::collections::HashMap;
 
 use crate::Result;
@@ -8,10 +8,13 @@ use crate::Error;
 use crate::MetaEntry;
 use crate::tag::TagReaderStrategy;
 use crate::TagType;
-use crate::ape::common::{constants, ApeTagHeader, ApeItem};
+use crate::ape::common::{constants, ApeTagHeader, ApeItem, ItemFlags, ItemValue};
+use crate::telemetry::{self, AnomalyKind};
+use crate::util;
+use crate::id3::constants::ID3V1_TAG_SIZE;
 
 /// Convert MetaEntry to APE tag key (shared with writer)
-fn meta_entry_to_ape_key(entry: &MetaEntry) -> &str {
+pub(crate) fn meta_entry_to_ape_key(entry: &MetaEntry) -> &str {
     match entry {
         MetaEntry::Title => "TITLE",
         MetaEntry::Artist => "ARTIST",
@@ -29,14 +32,93 @@ fn meta_entry_to_ape_key(entry: &MetaEntry) -> &str {
         MetaEntry::OriginalFilename => "ORIGINALFILENAME",
         MetaEntry::FileType => "FILETYPE",
         MetaEntry::BandOrchestra => "BANDORCHESTRA",
+        MetaEntry::AttachedPicture => "Cover Art (Front)",
+        MetaEntry::Lyrics => "LYRICS",
+        MetaEntry::PodcastDescription => "PODCASTDESC",
+        MetaEntry::PodcastId => "PODCASTID",
+        MetaEntry::PodcastFeedUrl => "PODCASTURL",
+        MetaEntry::PodcastCategory => "PODCASTCATEGORY",
+        MetaEntry::PodcastKeywords => "PODCASTKEYWORDS",
+        MetaEntry::Compilation => "COMPILATION",
+        MetaEntry::AlbumArtistSortOrder => "ALBUMARTISTSORT",
+        MetaEntry::ComposerSortOrder => "COMPOSERSORT",
+        MetaEntry::TitleSortOrder => "TITLESORT",
+        MetaEntry::ArtistSortOrder => "ARTISTSORT",
+        MetaEntry::AlbumSortOrder => "ALBUMSORT",
+        MetaEntry::PartOfSet => "DISC",
+        MetaEntry::BeatsPerMinute => "BPM",
+        MetaEntry::InitialKey => "INITIALKEY",
+        MetaEntry::Isrc => "ISRC",
+        MetaEntry::Publisher => "PUBLISHER",
+        MetaEntry::Copyright => "COPYRIGHT",
+        MetaEntry::EncodedBy => "ENCODEDBY",
+        MetaEntry::Conductor => "CONDUCTOR",
+        MetaEntry::Remixer => "REMIXER",
+        MetaEntry::Rating => "RATING",
         MetaEntry::Custom(key) => key,
     }
 }
 
+/// Validate that an item is a text item (not binary or external)
+fn validate_text_item(item: &ApeItem) -> Result<()> {
+    if item.flags & constants::item_flags::APE_ITEM_FLAG_BINARY != 0 {
+        return Err(Error::Other("Item is binary, not text".to_string()));
+    }
+    if item.is_external() {
+        return Err(Error::Other(format!("APE item '{}' is an external locator, not text", item.key)));
+    }
+    Ok(())
+}
+
+/// Convert item value bytes to a string
+///
+/// APEv1 predates the UTF-8 requirement, so its values fall back to ISO-8859-1.
+fn decode_item_text(item: &ApeItem, v1: bool) -> Result<String> {
+    if v1 {
+        return Ok(String::from_utf8(item.value.clone()).unwrap_or_else(|_| util::decode_latin1(&item.value)));
+    }
+    String::from_utf8(item.value.clone()).map_err(|_| {
+        telemetry::report(
+            AnomalyKind::InvalidText,
+            TagType::Ape,
+            None,
+            format!("APE item '{}' contains invalid UTF-8", item.key),
+        );
+        Error::Other("Invalid UTF-8 data".to_string())
+    })
+}
+
 // ============================================================================
 // APE Tag Data Structure
 // ============================================================================
 
+/// Where an APE tag is stored in a file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ApeTagPlacement {
+    /// Header first, at the start of the file
+    Start,
+    /// Footer last, at the end of the file or before an ID3v1 tag
+    #[default]
+    End,
+}
+
+/// Byte range of an APE tag in a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApeTagSpan {
+    pub placement: ApeTagPlacement,
+    /// Offset of the first byte of the tag
+    pub offset: u64,
+    /// Length of the tag including header and footer
+    pub len: u64,
+}
+
+impl ApeTagSpan {
+    /// Offset just past the tag
+    pub fn end(&self) -> u64 {
+        self.offset + self.len
+    }
+}
+
 /// APE tag structure
 #[derive(Debug, Clone)]
 pub struct ApeTag {
@@ -46,6 +128,8 @@ pub struct ApeTag {
     pub footer: ApeTagHeader,
     /// Tag items
     pub items: Vec<ApeItem>,
+    /// Where the tag is read from and written to
+    pub placement: ApeTagPlacement,
 }
 
 impl ApeTag {
@@ -61,9 +145,28 @@ impl ApeTag {
             header: Some(header),
             footer,
             items: Vec::new(),
+            placement: ApeTagPlacement::End,
         }
     }
     
+    /// Parse a tag from bytes, such as a whole file held in memory
+    ///
+    /// The tag is looked for as by `ApeReader::read_tag`: at the end of the
+    /// data, before an ID3v1 tag and, header first, at the start.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        ApeReader::new().read_tag_from(&mut io::Cursor::new(data))
+    }
+    
+    /// Tag version (1000 or 2000)
+    pub fn version(&self) -> u32 {
+        self.footer.version
+    }
+    
+    /// Whether this is an APEv1 tag
+    pub fn is_v1(&self) -> bool {
+        self.footer.is_v1()
+    }
+    
     // ------------------------------------------------------------------------
     // Core Item Access Methods
     // ------------------------------------------------------------------------
@@ -80,24 +183,29 @@ impl ApeTag {
             None => return Err(Error::EntryNotFound),
         };
 
-        self.validate_text_item(item)?;
-        self.item_value_to_string(item)
+        validate_text_item(item)?;
+        decode_item_text(item, self.is_v1())
     }
 
-    /// Validate that an item is a text item (not binary)
-    fn validate_text_item(&self, item: &ApeItem) -> Result<()> {
-        if item.flags & constants::item_flags::APE_ITEM_FLAG_BINARY != 0 {
-            return Err(Error::Other("Item is binary, not text".to_string()));
+    /// Get the bytes of a binary item by key
+    pub fn get_binary_item(&self, key: &str) -> Result<&[u8]> {
+        let item = self.get_item(key).ok_or(Error::EntryNotFound)?;
+        if !item.is_binary() {
+            return Err(Error::Other(format!("APE item '{}' is text, not binary", item.key)));
         }
-        Ok(())
+        Ok(&item.value)
     }
 
-    /// Convert item value bytes to UTF-8 string
-    fn item_value_to_string(&self, item: &ApeItem) -> Result<String> {
-        String::from_utf8(item.value.clone())
-            .map_err(|_| Error::Other("Invalid UTF-8 data".to_string()))
+    /// Get the typed value of an item by key
+    pub fn get_item_value(&self, key: &str) -> Result<ItemValue> {
+        let item = self.get_item(key).ok_or(Error::EntryNotFound)?;
+        if self.is_v1() {
+            // APEv1 items are always text
+            return decode_item_text(item, self.is_v1()).map(ItemValue::Text);
+        }
+        item.item_value()
     }
-    
+
     // ------------------------------------------------------------------------
     // Item Modification Methods
     // ------------------------------------------------------------------------
@@ -117,8 +225,8 @@ impl ApeTag {
     pub fn set_text_item(&mut self, key: &str, value: &str) {
         // Find existing item or add new one
         if let Some(index) = self.items.iter().position(|i| i.key.eq_ignore_ascii_case(key)) {
-            // Update existing item
-            let item = ApeItem::new_text(key, value);
+            // Update existing item, keeping the spelling of its key
+            let item = ApeItem::new_text(&self.items[index].key, value);
             self.items[index] = item;
         } else {
             // Add new item
@@ -127,21 +235,36 @@ impl ApeTag {
         }
         
         // Update tag size and item count
-        let mut total_size = constants::APE_TAG_FOOTER_SIZE;
-        if self.header.is_some() {
-            total_size += constants::APE_TAG_HEADER_SIZE;
-        }
-        
-        for item in &self.items {
-            total_size += item.total_size() as usize;
-        }
-        
-        self.footer.item_count = self.items.len() as u32;
-        self.footer.size = total_size as u32;
-        
-        if let Some(header) = &mut self.header {
-            header.item_count = self.items.len() as u32;
-            header.size = total_size as u32;
+        self.update_size_and_count();
+    }
+    
+    /// Set a binary item
+    pub fn set_binary_item(&mut self, key: &str, data: Vec<u8>) {
+        self.set_item(ApeItem::new_binary(key, data));
+    }
+    
+    /// Set an external item holding the URL or path of data stored elsewhere
+    pub fn set_locator_item(&mut self, key: &str, locator: &str) {
+        self.set_item(ApeItem::new_locator(key, locator));
+    }
+    
+    /// Decoded flags of an item by key
+    pub fn item_flags(&self, key: &str) -> Option<ItemFlags> {
+        self.get_item(key).map(ApeItem::item_flags)
+    }
+    
+    /// Replace the flags of an existing item
+    pub fn set_item_flags(&mut self, key: &str, flags: ItemFlags) -> Result<()> {
+        let item = self.items.iter_mut().find(|item| item.key.eq_ignore_ascii_case(key)).ok_or(Error::EntryNotFound)?;
+        item.set_item_flags(flags);
+        Ok(())
+    }
+    
+    /// Fail with `ReadOnlyItem` if the item exists, is read-only and `force` is not set
+    pub fn ensure_writable(&self, key: &str, force: bool) -> Result<()> {
+        match self.get_item(key) {
+            Some(item) if item.is_read_only() && !force => Err(Error::ReadOnlyItem(item.key.clone())),
+            _ => Ok(()),
         }
     }
     
@@ -167,7 +290,10 @@ impl ApeTag {
         let mut entries = HashMap::new();
         
         for item in &self.items {
-            if let Ok(text) = item.get_text() {
+            if item.is_binary() || item.is_external() {
+                continue;
+            }
+            if let Ok(text) = decode_item_text(item, self.is_v1()) {
                 let key = &item.key;
                 
                 // Try to map to standard MetaEntry first
@@ -187,7 +313,30 @@ impl ApeTag {
                     "TIME" => MetaEntry::Time,
                     "ORIGINALFILENAME" => MetaEntry::OriginalFilename,
                     "FILETYPE" => MetaEntry::FileType,
-                    "BANDORCHESTRA" => MetaEntry::BandOrchestra,
+                    "BANDORCHESTRA" | "ALBUM ARTIST" => MetaEntry::BandOrchestra,
+                    "COVER ART (FRONT)" => MetaEntry::AttachedPicture,
+                    "LYRICS" => MetaEntry::Lyrics,
+                    "PODCASTDESC" => MetaEntry::PodcastDescription,
+                    "PODCASTID" => MetaEntry::PodcastId,
+                    "PODCASTURL" => MetaEntry::PodcastFeedUrl,
+                    "PODCASTCATEGORY" => MetaEntry::PodcastCategory,
+                    "PODCASTKEYWORDS" => MetaEntry::PodcastKeywords,
+                    "COMPILATION" => MetaEntry::Compilation,
+                    "ALBUMARTISTSORT" => MetaEntry::AlbumArtistSortOrder,
+                    "COMPOSERSORT" => MetaEntry::ComposerSortOrder,
+                    "TITLESORT" => MetaEntry::TitleSortOrder,
+                    "ARTISTSORT" => MetaEntry::ArtistSortOrder,
+                    "ALBUMSORT" => MetaEntry::AlbumSortOrder,
+                    "DISC" => MetaEntry::PartOfSet,
+                    "BPM" => MetaEntry::BeatsPerMinute,
+                    "INITIALKEY" => MetaEntry::InitialKey,
+                    "ISRC" => MetaEntry::Isrc,
+                    "PUBLISHER" => MetaEntry::Publisher,
+                    "COPYRIGHT" => MetaEntry::Copyright,
+                    "ENCODEDBY" => MetaEntry::EncodedBy,
+                    "CONDUCTOR" => MetaEntry::Conductor,
+                    "REMIXER" => MetaEntry::Remixer,
+                    "RATING" => MetaEntry::Rating,
                     _ => MetaEntry::Custom(key.clone()),
                 };
                 
@@ -200,7 +349,12 @@ impl ApeTag {
     
     /// Set a meta entry
     pub fn set_meta_entry(&mut self, entry: &MetaEntry, value: &str) -> Result<()> {
+        if *entry == MetaEntry::AttachedPicture {
+            // Cover art is a binary item and cannot be set from text
+            return Err(Error::UnsupportedMetaEntry(entry.to_string()));
+        }
         let key = meta_entry_to_ape_key(entry);
+        self.ensure_writable(key, false)?;
         self.set_text_item(key, value);
         Ok(())
     }
@@ -216,16 +370,22 @@ impl ApeTag {
         writer.write_tag(path, self)
     }
     
+    /// Serialize the tag as `write_to_file` would write it: header, items and footer
+    ///
+    /// Fails for APEv1 tags holding binary or external items.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        crate::ape::writer::ApeWriter::new().render_tag(self)
+    }
+    
     // ------------------------------------------------------------------------
     // Private Helper Methods
     // ------------------------------------------------------------------------
     
     /// Update tag size and item count after modifications
+    ///
+    /// Per the APE spec the size covers the items and the footer, not the header.
     fn update_size_and_count(&mut self) {
-        let mut total_size = constants::APE_TAG_FOOTER_SIZE;
-        if self.header.is_some() {
-            total_size += constants::APE_TAG_HEADER_SIZE;
-        }
+        let mut total_size = if self.footer.has_footer() { constants::APE_TAG_FOOTER_SIZE } else { 0 };
         
         for item in &self.items {
             total_size += item.total_size() as usize;
@@ -245,81 +405,171 @@ impl ApeTag {
 // APE Tag Reader
 // ============================================================================
 
+/// Values up to this size are kept in memory by `init_from_file` and `init_from_bytes`
+const CACHED_VALUE_LIMIT: u32 = 64 * 1024;
+
 /// APE tag reader
 #[derive(Debug, Default)]
-pub struct ApeReader;
+pub struct ApeReader {
+    items: ItemSource,
+}
+
+/// Where `ApeReader::get_meta_entry` finds the items of the tag
+#[derive(Debug, Default)]
+enum ItemSource {
+    /// Indexed and read from the file on every request, after `init`
+    #[default]
+    File,
+    /// Read once by `init_from_file` or `init_from_bytes`; `None` when there is no tag
+    Cached(Option<CachedTag>),
+}
+
+/// The items of a tag read by `init_from_file` or `init_from_bytes`
+#[derive(Debug)]
+struct CachedTag {
+    v1: bool,
+    /// Items with values of up to `CACHED_VALUE_LIMIT` bytes
+    items: Vec<ApeItem>,
+    /// Larger items, such as cover art, whose values stay in the file
+    lazy: Vec<LazyItem>,
+}
 
 impl ApeReader {
     /// Create a new APE tag reader
     pub fn new() -> Self {
-        Self
+        Self::default()
     }
     
     /// Read APE tag from a file
+    ///
+    /// Tags are looked for at the end of the file, before an ID3v1 tag and,
+    /// header first, at the start of the file.
     pub fn read_tag<P: AsRef<Path>>(&self, path: P) -> Result<ApeTag> {
-        let mut file = File::open(path)?;
-        let file_size = file.metadata()?.len();
-        
-        if file_size < constants::APE_TAG_FOOTER_SIZE as u64 {
-            return Err(Error::TagNotFound);
-        }
+        self.read_tag_from(&mut File::open(path)?)
+    }
+    
+    /// Read the APE tag of a file or of a tag held in a reader
+    fn read_tag_from<R: Read + Seek>(&self, file: &mut R) -> Result<ApeTag> {
+        let (primary, span) = self.locate(file)?.ok_or(Error::TagNotFound)?;
+        file.seek(SeekFrom::Start(span.offset))?;
         
-        // Try APE tag at end of file
-        if let Some(footer) = self.try_read_footer_at(&mut file, -(constants::APE_TAG_FOOTER_SIZE as i64))? {
-            return self.read_tag_with_footer(&mut file, footer);
-        }
+        let (header, footer) = match span.placement {
+            ApeTagPlacement::End => (self.read_header_if_present(file, &primary)?, primary),
+            ApeTagPlacement::Start => {
+                // The header was already parsed by `locate`; derive the footer from it
+                file.seek(SeekFrom::Current(constants::APE_TAG_HEADER_SIZE as i64))?;
+                let mut footer = primary.clone();
+                footer.flags &= !constants::flags::APE_TAG_FLAG_IS_HEADER;
+                (Some(primary), footer)
+            }
+        };
         
-        // Try APE tag before ID3v1 tag
-        if file_size >= (constants::APE_TAG_FOOTER_SIZE + 128) as u64 {
-            if let Some(footer) = self.try_read_footer_at(&mut file, -((constants::APE_TAG_FOOTER_SIZE + 128) as i64))? {
-                return self.read_tag_with_footer(&mut file, footer);
+        let footer_len = if span.placement == ApeTagPlacement::End || footer.has_footer() { constants::APE_TAG_FOOTER_SIZE as u64 } else { 0 };
+        let items_start = file.stream_position()?;
+        let items_len = (span.end() - footer_len).saturating_sub(items_start);
+        let mut items = self.read_items(file, items_len, footer.item_count as usize)?;
+        if footer.is_v1() {
+            // APEv1 has no item flags; whatever is stored there is meaningless
+            for item in &mut items {
+                item.flags = constants::item_flags::APE_ITEM_FLAG_UTF8;
             }
         }
         
-        Err(Error::TagNotFound)
+        Ok(ApeTag {
+            header,
+            footer,
+            items,
+            placement: span.placement,
+        })
+    }
+    
+    /// Find the byte range of the APE tag in a file
+    pub fn locate_tag<P: AsRef<Path>>(&self, path: P) -> Result<Option<ApeTagSpan>> {
+        let mut file = File::open(path)?;
+        Ok(self.locate(&mut file)?.map(|(_, span)| span))
+    }
+    
+    /// List the items of the tag with the position of their values, without reading the values
+    ///
+    /// Use `LazyItem::open_value` or `LazyItem::read_value` to fetch a value,
+    /// which avoids loading large binary items such as cover art.
+    pub fn read_item_index<P: AsRef<Path>>(&self, path: P) -> Result<Vec<LazyItem>> {
+        let mut file = File::open(path)?;
+        Ok(self.index(&mut file)?.1)
+    }
+    
+    /// Iterate over the items of the tag, reading one item at a time
+    ///
+    /// Unlike `read_tag`, only the item being yielded is held in memory.
+    pub fn iter_items<P: AsRef<Path>>(&self, path: P) -> Result<ItemIter<io::BufReader<File>>> {
+        let mut file = File::open(path)?;
+        let (primary, span) = self.locate(&mut file)?.ok_or(Error::TagNotFound)?;
+        let header_len = if primary.has_header() || span.placement == ApeTagPlacement::Start { constants::APE_TAG_HEADER_SIZE as u64 } else { 0 };
+        let footer_len = if span.placement == ApeTagPlacement::End || primary.has_footer() { constants::APE_TAG_FOOTER_SIZE as u64 } else { 0 };
+        file.seek(SeekFrom::Start(span.offset + header_len))?;
+        Ok(ItemIter {
+            reader: io::BufReader::new(file),
+            v1: primary.is_v1(),
+            items_left: primary.item_count,
+            remaining: span.len.saturating_sub(header_len + footer_len),
+        })
+    }
+    
+    /// Read the bytes of a binary item, such as "Cover Art (Front)"
+    pub fn get_binary_item<P: AsRef<Path>>(&self, path: P, key: &str) -> Result<Vec<u8>> {
+        Ok(self.read_tag(path)?.get_binary_item(key)?.to_vec())
     }
     
     // ------------------------------------------------------------------------
     // Private Helper Methods
     // ------------------------------------------------------------------------
     
-    /// Try to read APE footer at given position
-    fn try_read_footer_at(&self, file: &mut File, offset: i64) -> Result<Option<ApeTagHeader>> {
-        file.seek(SeekFrom::End(offset))?;
-        let mut footer_buffer = [0u8; constants::APE_TAG_FOOTER_SIZE];
-        file.read_exact(&mut footer_buffer)?;
+    /// Locate the tag, returning its footer (or, for header-first tags, its header) and span
+    pub(crate) fn locate<R: Read + Seek>(&self, file: &mut R) -> Result<Option<(ApeTagHeader, ApeTagSpan)>> {
+        let file_size = file.seek(SeekFrom::End(0))?;
+        let block = constants::APE_TAG_FOOTER_SIZE as u64;
         
-        match ApeTagHeader::from_buffer(&footer_buffer) {
-            Ok(footer) => Ok(Some(footer)),
-            Err(_) => Ok(None),
+        // Footer at the end of the file, then before an ID3v1 tag
+        for trailer in [0, ID3V1_TAG_SIZE as u64] {
+            if file_size < trailer + block {
+                continue;
+            }
+            let footer_end = file_size - trailer;
+            if let Some(footer) = self.read_header_at(file, footer_end - block)? {
+                let len = footer.size as u64 + if footer.has_header() { block } else { 0 };
+                if !footer.is_header() && len <= footer_end {
+                    let span = ApeTagSpan { placement: ApeTagPlacement::End, offset: footer_end - len, len };
+                    return Ok(Some((footer, span)));
+                }
+            }
+        }
+        
+        // Header first at the start of the file
+        if file_size >= block {
+            if let Some(header) = self.read_header_at(file, 0)? {
+                let len = block + header.size as u64;
+                if header.is_header() && len <= file_size {
+                    return Ok(Some((header, ApeTagSpan { placement: ApeTagPlacement::Start, offset: 0, len })));
+                }
+            }
         }
+        
+        Ok(None)
     }
     
-    /// Read APE tag with known footer
-    fn read_tag_with_footer(&self, file: &mut File, footer: ApeTagHeader) -> Result<ApeTag> {
-        self.seek_to_tag_data(file, &footer)?;
-
-        let header = self.read_header_if_present(file, &footer)?;
-        let items = self.read_items(file, footer.item_count as usize)?;
-
-        Ok(ApeTag {
-            header,
-            footer,
-            items,
-        })
-    }
-
-    fn seek_to_tag_data(&self, file: &mut File, footer: &ApeTagHeader) -> Result<u64> {
-        let tag_size = footer.size as i64;
-        let seek_offset = if footer.has_header() {
-            -(tag_size + constants::APE_TAG_HEADER_SIZE as i64)
-        } else {
-            -tag_size
-        };
-        Ok(file.seek(SeekFrom::End(seek_offset))?)
+    /// Try to read an APE header or footer at the given offset
+    fn read_header_at<R: Read + Seek>(&self, file: &mut R, offset: u64) -> Result<Option<ApeTagHeader>> {
+        file.seek(SeekFrom::Start(offset))?;
+        let mut buffer = [0u8; constants::APE_TAG_FOOTER_SIZE];
+        file.read_exact(&mut buffer)?;
+        
+        match ApeTagHeader::from_buffer(&buffer) {
+            Ok(header) => Ok(Some(header)),
+            Err(_) => Ok(None),
+        }
     }
 
-    fn read_header_if_present(&self, file: &mut File, footer: &ApeTagHeader) -> Result<Option<ApeTagHeader>> {
+    fn read_header_if_present<R: Read + Seek>(&self, file: &mut R, footer: &ApeTagHeader) -> Result<Option<ApeTagHeader>> {
         if !footer.has_header() {
             return Ok(None);
         }
@@ -335,17 +585,41 @@ impl ApeReader {
         Ok(Some(header))
     }
 
-    fn read_items(&self, file: &mut File, item_count: usize) -> Result<Vec<ApeItem>> {
-        let mut items = Vec::with_capacity(item_count);
+    /// Read the item area of `len` bytes at once and parse `item_count` items from it
+    fn read_items<R: Read + Seek>(&self, file: &mut R, len: u64, item_count: usize) -> Result<Vec<ApeItem>> {
+        let mut data = Vec::new();
+        file.take(len).read_to_end(&mut data)?;
+        let mut position = 0;
+        let mut items = Vec::with_capacity(item_count.min(data.len() / 9));
         for _ in 0..item_count {
-            items.push(self.read_item(file)?);
+            items.push(self.parse_item(&data, &mut position)?);
         }
         Ok(items)
     }
 
-    fn read_item(&self, file: &mut File) -> Result<ApeItem> {
+    /// Parse the item at `position` in the item area, advancing past it
+    fn parse_item(&self, data: &[u8], position: &mut usize) -> Result<ApeItem> {
+        let mut rest = &data[*position..];
+        let (size, flags, key) = self.read_item_head(&mut rest)?;
+        check_value_size(size)?;
+
+        let value = rest
+            .get(..size as usize)
+            .ok_or_else(|| Error::Other(format!("APE item '{}' extends past the end of the tag", key)))?
+            .to_vec();
+        *position = data.len() - rest.len() + value.len();
+
+        Ok(ApeItem {
+            size,
+            flags,
+            key,
+            value,
+        })
+    }
+
+    /// Read the size, flags and key of an item, leaving the reader at its value
+    fn read_item_head<R: Read>(&self, file: &mut R) -> Result<(u32, u32, String)> {
         const MAX_KEY_LENGTH: usize = 255; // APE spec limit
-        const MAX_VALUE_SIZE: usize = 16 * 1024 * 1024; // 16MB reasonable limit
         
         let mut size_flags_buffer = [0u8; 8];
         file.read_exact(&mut size_flags_buffer)?;
@@ -353,11 +627,6 @@ impl ApeReader {
         let size = u32::from_le_bytes(size_flags_buffer[0..4].try_into().unwrap());
         let flags = u32::from_le_bytes(size_flags_buffer[4..8].try_into().unwrap());
 
-        // Security check: prevent excessive memory allocation
-        if size as usize > MAX_VALUE_SIZE {
-            return Err(Error::Other(format!("APE item value too large: {} bytes", size)));
-        }
-
         // Read key bytes until null terminator with length limit
         let mut key_bytes = Vec::new();
         for _ in 0..MAX_KEY_LENGTH {
@@ -371,21 +640,166 @@ impl ApeReader {
         
         // Security check: ensure we found null terminator
         if key_bytes.len() >= MAX_KEY_LENGTH {
+            telemetry::report(AnomalyKind::InvalidKey, TagType::Ape, None, "APE item key too long or missing null terminator");
             return Err(Error::Other("APE item key too long or missing null terminator".to_string()));
         }
 
-        let key = String::from_utf8(key_bytes)
-            .map_err(|_| Error::Other("Invalid UTF-8 in APE item key".to_string()))?;
+        let key = String::from_utf8(key_bytes).map_err(|_| {
+            telemetry::report(AnomalyKind::InvalidKey, TagType::Ape, None, "Invalid UTF-8 in APE item key");
+            Error::Other("Invalid UTF-8 in APE item key".to_string())
+        })?;
 
-        let mut value = vec![0u8; size as usize];
-        file.read_exact(&mut value)?;
+        Ok((size, flags, key))
+    }
 
-        Ok(ApeItem {
-            size,
-            flags,
-            key,
-            value,
-        })
+    /// Index the items of the tag without reading their values
+    fn index<R: Read + Seek>(&self, file: &mut R) -> Result<(ApeTagHeader, Vec<LazyItem>)> {
+        let (primary, span) = self.locate(file)?.ok_or(Error::TagNotFound)?;
+        let items_start = span.offset
+            + if primary.has_header() || span.placement == ApeTagPlacement::Start { constants::APE_TAG_HEADER_SIZE as u64 } else { 0 };
+        file.seek(SeekFrom::Start(items_start))?;
+
+        // Item heads are read from a buffer; values are skipped without discarding it
+        let mut reader = io::BufReader::new(file);
+        let mut items = Vec::new();
+        for _ in 0..primary.item_count {
+            let (len, flags, key) = self.read_item_head(&mut reader)?;
+            let offset = reader.stream_position()?;
+            if offset + len as u64 > span.end() {
+                return Err(Error::Other(format!("APE item '{}' extends past the end of the tag", key)));
+            }
+            reader.seek_relative(len as i64)?;
+            // APEv1 has no item flags
+            let flags = if primary.is_v1() { constants::item_flags::APE_ITEM_FLAG_UTF8 } else { flags };
+            items.push(LazyItem { key, flags, offset, len });
+        }
+        Ok((primary, items))
+    }
+
+    /// Index the tag and read the values of up to `CACHED_VALUE_LIMIT` bytes
+    fn cache_tag<R: Read + Seek>(&self, file: &mut R) -> Result<Option<CachedTag>> {
+        let (primary, index) = match self.index(file) {
+            Err(Error::TagNotFound) => return Ok(None),
+            result => result?,
+        };
+        let mut tag = CachedTag { v1: primary.is_v1(), items: Vec::new(), lazy: Vec::new() };
+        for lazy in index {
+            if lazy.len > CACHED_VALUE_LIMIT {
+                tag.lazy.push(lazy);
+                continue;
+            }
+            let mut value = vec![0u8; lazy.len as usize];
+            file.seek(SeekFrom::Start(lazy.offset))?;
+            file.read_exact(&mut value)?;
+            tag.items.push(ApeItem { size: lazy.len, flags: lazy.flags, key: lazy.key, value });
+        }
+        Ok(Some(tag))
+    }
+}
+
+/// Refuse item values too large to hold in memory
+fn check_value_size(size: u32) -> Result<()> {
+    const MAX_VALUE_SIZE: usize = 16 * 1024 * 1024; // 16MB reasonable limit
+
+    // Security check: prevent excessive memory allocation
+    if size as usize > MAX_VALUE_SIZE {
+        telemetry::report(
+            AnomalyKind::OversizedItem,
+            TagType::Ape,
+            None,
+            format!("APE item value of {} bytes exceeds the {} byte limit", size, MAX_VALUE_SIZE),
+        );
+        return Err(Error::Other(format!("APE item value too large: {} bytes", size)));
+    }
+    Ok(())
+}
+
+/// Read the value of a text item from the file, refusing binary items before reading them
+fn read_lazy_text(path: &Path, lazy: &LazyItem, v1: bool) -> Result<String> {
+    let mut item = ApeItem { size: lazy.len, flags: lazy.flags, key: lazy.key.clone(), value: Vec::new() };
+    validate_text_item(&item)?;
+    item.value = lazy.read_value(path)?;
+    decode_item_text(&item, v1)
+}
+
+/// Iterator over the items of an APE tag, read from disk one at a time
+///
+/// Created by `ApeReader::iter_items`; iteration ends after an error.
+#[derive(Debug)]
+pub struct ItemIter<R> {
+    reader: R,
+    v1: bool,
+    items_left: u32,
+    /// Bytes of the item area not read yet
+    remaining: u64,
+}
+
+impl<R: Read> ItemIter<R> {
+    /// Read the next item
+    fn read_item(&mut self) -> Result<ApeItem> {
+        // Item heads are at most 8 + 255 bytes
+        let mut head = (&mut self.reader).take(self.remaining.min(8 + 255));
+        let (size, flags, key) = ApeReader::new().read_item_head(&mut head)?;
+        self.remaining -= 8 + key.len() as u64 + 1;
+        check_value_size(size)?;
+        if size as u64 > self.remaining {
+            return Err(Error::Other(format!("APE item '{}' extends past the end of the tag", key)));
+        }
+
+        let mut value = Vec::with_capacity(size as usize);
+        (&mut self.reader).take(size as u64).read_to_end(&mut value)?;
+        if value.len() < size as usize {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        self.remaining -= size as u64;
+        // APEv1 has no item flags
+        let flags = if self.v1 { constants::item_flags::APE_ITEM_FLAG_UTF8 } else { flags };
+        Ok(ApeItem { size, flags, key, value })
+    }
+}
+
+impl<R: Read> Iterator for ItemIter<R> {
+    type Item = Result<ApeItem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.items_left == 0 {
+            return None;
+        }
+        let item = self.read_item();
+        self.items_left = if item.is_ok() { self.items_left - 1 } else { 0 };
+        Some(item)
+    }
+}
+
+/// An APE item whose value stays in the file until it is requested
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LazyItem {
+    pub key: String,
+    pub flags: u32,
+    /// Offset of the value in the file
+    pub offset: u64,
+    /// Length of the value in bytes
+    pub len: u32,
+}
+
+impl LazyItem {
+    /// Decoded flags of the item
+    pub fn item_flags(&self) -> ItemFlags {
+        ItemFlags::from_bits(self.flags)
+    }
+
+    /// Stream the value from the file
+    pub fn open_value<P: AsRef<Path>>(&self, path: P) -> Result<io::Take<File>> {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(self.offset))?;
+        Ok(file.take(self.len as u64))
+    }
+
+    /// Read the whole value into memory
+    pub fn read_value<P: AsRef<Path>>(&self, path: P) -> Result<Vec<u8>> {
+        let mut value = Vec::with_capacity(self.len as usize);
+        self.open_value(path)?.read_to_end(&mut value)?;
+        Ok(value)
     }
 }
 
@@ -395,19 +809,39 @@ impl ApeReader {
 
 impl TagReaderStrategy for ApeReader {
     fn init(&mut self, _path: &Path) -> Result<()> {
-        // No initialization needed for APE reader
+        // Entries are read from the file on demand
+        self.items = ItemSource::File;
+        Ok(())
+    }
+    
+    fn init_from_file(&mut self, _path: &Path, file: &mut File) -> Result<()> {
+        self.items = ItemSource::Cached(self.cache_tag(file)?);
+        Ok(())
+    }
+    
+    fn init_from_bytes(&mut self, _path: &Path, data: &[u8]) -> Result<()> {
+        self.items = ItemSource::Cached(self.cache_tag(&mut io::Cursor::new(data))?);
         Ok(())
     }
     
     fn get_meta_entry(&self, path: &Path, entry: &MetaEntry) -> Result<String> {
-        match self.read_tag(path) {
-            Ok(tag) => {
-                let key = meta_entry_to_ape_key(entry);
-                let entry = tag.get_item_text(key)?;
-                Ok(entry)
+        let key = meta_entry_to_ape_key(entry);
+        let matches = |item_key: &str| item_key.eq_ignore_ascii_case(key);
+        let tag = match &self.items {
+            ItemSource::Cached(tag) => tag.as_ref().ok_or(Error::TagNotFound)?,
+            ItemSource::File => {
+                // Only the requested value is read, so large binary items cost nothing here
+                let (primary, items) = self.index(&mut File::open(path)?)?;
+                let lazy = items.iter().find(|item| matches(&item.key)).ok_or(Error::EntryNotFound)?;
+                return read_lazy_text(path, lazy, primary.is_v1());
             }
-            Err(e) => Err(e)
+        };
+        if let Some(item) = tag.items.iter().find(|item| matches(&item.key)) {
+            validate_text_item(item)?;
+            return decode_item_text(item, tag.v1);
         }
+        let lazy = tag.lazy.iter().find(|item| matches(&item.key)).ok_or(Error::EntryNotFound)?;
+        read_lazy_text(path, lazy, tag.v1)
     }
     
     fn tag_type(&self) -> TagType {
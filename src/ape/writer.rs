@@ -8,38 +8,26 @@ use crate::Result;
 use crate::Error;
 use crate::MetaEntry;
 use crate::tag::TagWriterStrategy;
-use crate::util;
-use crate::ape::common::{constants, has_ape_tag};
-use crate::ape::reader::{ApeReader, ApeTag};
+use crate::util::{self, PreserveOptions};
+use crate::id3::constants::ID3V1_TAG_SIZE;
+use crate::file_access::FileLock;
+use crate::validation::{BaseValidator, StandardValidator};
+use crate::ape::common::{constants, ApeItem, ItemFlags};
+use crate::ape::reader::{meta_entry_to_ape_key, ApeReader, ApeTag, ApeTagPlacement};
 
 /// APE tag writers
 #[derive(Debug, Default)]
 pub struct ApeWriter {
     path: Option<PathBuf>,
     tag: Option<ApeTag>,
-}
-
-/// Convert MetaEntry to APE tag key
-fn meta_entry_to_ape_key(entry: &MetaEntry) -> &str {
-    match entry {
-        MetaEntry::Title => "TITLE",
-        MetaEntry::Artist => "ARTIST",
-        MetaEntry::Album => "ALBUM",
-        MetaEntry::Year => "YEAR",
-        MetaEntry::Genre => "GENRE",
-        MetaEntry::Comment => "COMMENT",
-        MetaEntry::Composer => "COMPOSER",
-        MetaEntry::Track => "TRACK",
-        MetaEntry::Date => "DATE",
-        MetaEntry::TextWriter => "TEXTWRITER",
-        MetaEntry::AudioEncryption => "AUDIOENCRYPTION",
-        MetaEntry::Language => "LANGUAGE",
-        MetaEntry::Time => "TIME",
-        MetaEntry::OriginalFilename => "ORIGINALFILENAME",
-        MetaEntry::FileType => "FILETYPE",
-        MetaEntry::BandOrchestra => "BANDORCHESTRA",
-        MetaEntry::Custom(key) => key,
-    }
+    /// File attributes kept when the file is rewritten
+    preserve: PreserveOptions,
+    /// Whether items flagged read-only may be modified
+    force: bool,
+    /// Whether to write items sorted by size instead of in their existing order
+    sort_items: bool,
+    /// Whether `save` writes a tag left without items instead of removing it
+    keep_empty_tag: bool,
 }
 
 /// Check if file has ID3v1 tag and return the tag data if present
@@ -65,12 +53,65 @@ impl ApeWriter {
         Self {
             path: None,
             tag: None,
+            preserve: PreserveOptions::default(),
+            force: false,
+            sort_items: false,
+            keep_empty_tag: false,
         }
     }
     
+    /// Write items sorted by size, smallest first, as the APE spec recommends
+    ///
+    /// By default items keep the order they were read in, with new items appended.
+    pub fn set_sort_items(&mut self, sort_items: bool) {
+        self.sort_items = sort_items;
+    }
+    
+    /// Keep the tag when `save` finds it without items, instead of removing it
+    pub fn set_keep_empty_tag(&mut self, keep: bool) {
+        self.keep_empty_tag = keep;
+    }
+
+    /// Allow modifying items flagged read-only, which otherwise fails with `ReadOnlyItem`
+    pub fn set_force(&mut self, force: bool) {
+        self.force = force;
+    }
+
+    /// Choose which attributes of the original file survive a rewrite
+    pub fn set_preserve_options(&mut self, preserve: PreserveOptions) {
+        self.preserve = preserve;
+    }
+    
     /// Write APE tag to a file
     pub fn write_tag<P: AsRef<Path>>(&self, path: P, tag: &ApeTag) -> Result<()> {
-        let path = path.as_ref();
+        let _lock = FileLock::acquire(path.as_ref())?;
+        self.write_tag_locked(path.as_ref(), tag)
+    }
+
+    /// Write APE tag to a file whose lock is already held
+    fn write_tag_locked(&self, path: &Path, tag: &ApeTag) -> Result<()> {
+        let bytes = self.render_tag(tag)?;
+        self.rewrite(path, Some((&bytes, tag.placement)))
+    }
+    
+    /// Rewrite the file with `tag` in place of its existing APE tag, or without one
+    ///
+    /// The audio is whatever lies between a header-first tag at the start and
+    /// a footer-last tag (or ID3v1 tag) at the end; an ID3v1 tag is kept last.
+    fn rewrite(&self, path: &Path, tag: Option<(&[u8], ApeTagPlacement)>) -> Result<()> {
+        let mut file = File::open(path)?;
+        let file_size = file.metadata()?.len();
+        let existing = ApeReader::new().locate(&mut file)?.map(|(_, span)| span);
+        let id3v1_size = if check_id3v1_tag(&mut file, file_size)?.is_some() { ID3V1_TAG_SIZE as u64 } else { 0 };
+        
+        let (audio_start, audio_end, trailer_start) = match existing {
+            Some(span) if span.placement == ApeTagPlacement::Start => {
+                (span.end(), file_size - id3v1_size, file_size - id3v1_size)
+            }
+            Some(span) => (0, span.offset, span.end()),
+            None => (0, file_size - id3v1_size, file_size - id3v1_size),
+        };
+        let audio_end = audio_end.max(audio_start);
         
         // Create a temporary file
         let temp_path = util::get_temp_path(path);
@@ -80,152 +121,214 @@ impl ApeWriter {
             .truncate(true)
             .open(&temp_path)?;
         
-        // Open the original file for reading
-        let mut file = File::open(path)?;
-        let file_size = file.metadata()?.len();
-        
-        // Check for ID3v1 tag
-        let id3v1_tag = check_id3v1_tag(&mut file, file_size)?;
-        
-        // Copy audio data to the temporary file
-        file.seek(SeekFrom::Start(0))?;
-        util::copy_file_range(&mut file, &mut temp_file)?;
+        let result = (|| -> Result<()> {
+            if let Some((bytes, ApeTagPlacement::Start)) = tag {
+                temp_file.write_all(bytes)?;
+            }
+            file.seek(SeekFrom::Start(audio_start))?;
+            util::copy_file_range(&mut (&mut file).take(audio_end - audio_start), &mut temp_file)?;
+            if let Some((bytes, ApeTagPlacement::End)) = tag {
+                temp_file.write_all(bytes)?;
+            }
+            // Trailing ID3v1 tag, if any
+            file.seek(SeekFrom::Start(trailer_start))?;
+            util::copy_file_range(&mut file, &mut temp_file)?;
+            temp_file.sync_all()?;
+            drop(file);
+            
+            // Replace the original file with the temporary file
+            util::replace_file(&temp_path, path, self.preserve)
+        })();
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        result
+    }
+    
+    /// Serialize header, items and footer
+    pub(crate) fn render_tag(&self, tag: &ApeTag) -> Result<Vec<u8>> {
+        if tag.is_v1() && tag.items.iter().any(|item| item.is_binary() || item.is_external()) {
+            return Err(Error::Other("APEv1 tags can only hold text items".to_string()));
+        }
+        let mut bytes = Vec::new();
+        let mut buffer = [0u8; constants::APE_TAG_HEADER_SIZE];
         
-        // Write APE tag header if present
-        if let Some(header) = &tag.header {
-            let mut header_buffer = [0u8; constants::APE_TAG_HEADER_SIZE];
-            header.to_buffer(&mut header_buffer)?;
-            temp_file.write_all(&header_buffer)?;
+        // Write APE tag header if present (APEv1 tags never have one); header-first tags require it
+        let header = match tag.placement {
+            ApeTagPlacement::Start => Some(tag.header.clone().unwrap_or_else(|| {
+                let mut header = tag.footer.clone();
+                header.flags |= constants::flags::APE_TAG_FLAG_HAS_HEADER | constants::flags::APE_TAG_FLAG_IS_HEADER;
+                header
+            })),
+            ApeTagPlacement::End => tag.header.clone().filter(|_| !tag.is_v1()),
+        };
+        if let Some(header) = header {
+            header.to_buffer(&mut buffer)?;
+            bytes.extend_from_slice(&buffer);
         }
         
         // Write APE tag items
-        for item in &tag.items {
-            // Write size and flags
-            temp_file.write_all(&item.size.to_le_bytes())?;
-            temp_file.write_all(&item.flags.to_le_bytes())?;
+        let mut items: Vec<&ApeItem> = tag.items.iter().collect();
+        if self.sort_items {
+            items.sort_by_key(|item| item.total_size());
+        }
+        for item in items {
+            // Write size and flags (always zero in APEv1)
+            let flags = if tag.is_v1() { 0 } else { item.flags };
+            bytes.extend_from_slice(&item.size.to_le_bytes());
+            bytes.extend_from_slice(&flags.to_le_bytes());
             
             // Write key (null-terminated)
-            temp_file.write_all(item.key.as_bytes())?;
-            temp_file.write_all(&[0])?;
+            bytes.extend_from_slice(item.key.as_bytes());
+            bytes.push(0);
             
             // Write value
-            temp_file.write_all(&item.value)?;
+            bytes.extend_from_slice(&item.value);
         }
         
-        // Write APE tag footer
-        let mut footer_buffer = [0u8; constants::APE_TAG_FOOTER_SIZE];
-        tag.footer.to_buffer(&mut footer_buffer)?;
-        temp_file.write_all(&footer_buffer)?;
-        
-        // Write ID3v1 tag if present
-        if let Some(id3v1_data) = id3v1_tag {
-            temp_file.write_all(&id3v1_data)?;
+        // Write APE tag footer, which only header-first tags may omit
+        let mut footer = tag.footer.clone();
+        if footer.is_v1() {
+            footer.flags = 0;
         }
-        
-        // Replace the original file with the temporary file
-        util::rename_file(&temp_path, path)?;
-        
-        Ok(())
+        if tag.placement == ApeTagPlacement::End || footer.has_footer() {
+            footer.to_buffer(&mut buffer)?;
+            bytes.extend_from_slice(&buffer);
+        }
+        Ok(bytes)
     }
     
     /// Remove APE tag from a file
     pub fn remove_tag<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let path = path.as_ref();
-        
+        let _lock = FileLock::acquire(path.as_ref())?;
+        self.remove_tag_locked(path.as_ref())
+    }
+
+    /// Remove APE tag from a file whose lock is already held
+    fn remove_tag_locked(&self, path: &Path) -> Result<()> {
         // Check if the file has an APE tag
-        if !has_ape_tag(path)? {
+        if ApeReader::new().locate_tag(path)?.is_none() {
             return Ok(());
         }
-        
-        // Create a temporary file
-        let temp_path = util::get_temp_path(path);
-        let mut temp_file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(&temp_path)?;
-        
-        // Open the original file for reading
-        let mut file = File::open(path)?;
-        let file_size = file.metadata()?.len();
-        
-        // Check for ID3v1 tag
-        let id3v1_tag = check_id3v1_tag(&mut file, file_size)?;
-        
-        // Copy audio data to the temporary file
-        file.seek(SeekFrom::Start(0))?;
-        util::copy_file_range(&mut file, &mut temp_file)?;
-        
-        // Write ID3v1 tag if present
-        if let Some(id3v1_data) = id3v1_tag {
-            temp_file.write_all(&id3v1_data)?;
-        }
-        
-        // Replace the original file with the temporary file
-        util::rename_file(&temp_path, path)?;
-        
-        Ok(())
+        self.rewrite(path, None)
     }
     
     /// Set meta entries in a file
     pub fn set_meta_entries<P: AsRef<Path>>(&self, path: P, entries: &HashMap<MetaEntry, String>) -> Result<()> {
-        let path = path.as_ref();
-        
-        // Read existing tag or create a new one
-        let reader = ApeReader::new();
-        let mut tag = match reader.read_tag(path) {
-            Ok(tag) => tag,
-            Err(Error::TagNotFound) => ApeTag::new(constants::APE_TAG_VERSION_2_0),
-            Err(e) => return Err(e),
-        };
-        
-        // Update tag with new entries
-        for (entry, value) in entries {
-            let key = meta_entry_to_ape_key(entry);
-            tag.set_text_item(key, value);
-        }
-        
-        // Write the updated tag
-        self.write_tag(path, &tag)
+        self.set_meta_entries_and_binary_items(path, entries, &[])
+    }
+
+    /// Set meta entries and binary items, such as pictures, in a single write
+    pub fn set_meta_entries_and_binary_items<P: AsRef<Path>>(
+        &self,
+        path: P,
+        entries: &HashMap<MetaEntry, String>,
+        items: &[(String, Vec<u8>)],
+    ) -> Result<()> {
+        self.update_tag(path.as_ref(), |tag| {
+            for (entry, value) in entries {
+                StandardValidator.validate_format(entry, value)?;
+                let key = meta_entry_to_ape_key(entry);
+                tag.ensure_writable(key, self.force)?;
+                tag.set_text_item(key, value);
+            }
+            for (key, data) in items {
+                tag.ensure_writable(key, self.force)?;
+                tag.set_binary_item(key, data.clone());
+            }
+            Ok(())
+        })
     }
     
     /// Remove meta entries from a file
     pub fn remove_meta_entries<P: AsRef<Path>>(&self, path: P, entries: &[MetaEntry]) -> Result<()> {
-        let path = path.as_ref();
+        self.update_tag(path.as_ref(), |tag| {
+            for entry in entries {
+                let key = meta_entry_to_ape_key(entry);
+                tag.ensure_writable(key, self.force)?;
+                tag.remove_item(key);
+            }
+            Ok(())
+        })
+    }
+    
+    /// Set a binary item, such as "Cover Art (Front)", in a file
+    pub fn set_binary_item<P: AsRef<Path>>(&self, path: P, key: &str, data: &[u8]) -> Result<()> {
+        self.update_tag(path.as_ref(), |tag| {
+            tag.ensure_writable(key, self.force)?;
+            tag.set_binary_item(key, data.to_vec());
+            Ok(())
+        })
+    }
+    
+    /// Set an external item holding the URL or path of data stored elsewhere
+    pub fn set_locator_item<P: AsRef<Path>>(&self, path: P, key: &str, locator: &str) -> Result<()> {
+        self.update_tag(path.as_ref(), |tag| {
+            tag.ensure_writable(key, self.force)?;
+            tag.set_locator_item(key, locator);
+            Ok(())
+        })
+    }
+    
+    /// Remove an item, text or binary, by key
+    pub fn remove_item<P: AsRef<Path>>(&self, path: P, key: &str) -> Result<()> {
+        self.update_tag(path.as_ref(), |tag| {
+            tag.ensure_writable(key, self.force)?;
+            tag.remove_item(key);
+            Ok(())
+        })
+    }
+    
+    /// Change the flags (read-only, binary, external) of an existing item
+    pub fn set_item_flags<P: AsRef<Path>>(&self, path: P, key: &str, flags: ItemFlags) -> Result<()> {
+        self.update_tag(path.as_ref(), |tag| {
+            tag.ensure_writable(key, self.force)?;
+            tag.set_item_flags(key, flags)
+        })
+    }
+    
+    /// Read-modify-write cycle under the file lock
+    ///
+    /// A missing tag starts out empty; a tag left without items is removed.
+    fn update_tag<F: FnOnce(&mut ApeTag) -> Result<()>>(&self, path: &Path, update: F) -> Result<()> {
+        let _lock = FileLock::acquire(path)?;
         
-        // Read existing tag
+        // Read existing tag or create a new one
         let reader = ApeReader::new();
         let mut tag = match reader.read_tag(path) {
             Ok(tag) => tag,
-            Err(Error::TagNotFound) => return Ok(()), // No tag to remove entries from
+            Err(Error::TagNotFound) => ApeTag::new(constants::APE_TAG_VERSION_2_0),
             Err(e) => return Err(e),
         };
         
-        // Remove entries
-        for entry in entries {
-            let key = meta_entry_to_ape_key(entry);
-            tag.remove_item(key);
-        }
+        update(&mut tag)?;
         
         // If no items left, remove the tag
         if tag.items.is_empty() {
-            self.remove_tag(path)
+            self.remove_tag_locked(path)
         } else {
             // Write the updated tag
-            self.write_tag(path, &tag)
+            self.write_tag_locked(path, &tag)
         }
     }
 }
 
 impl TagWriterStrategy for ApeWriter {
-    fn init(&mut self, _path: &Path) -> Result<()> {
-        // No initialization needed for APE writer
+    fn init(&mut self, path: &Path) -> Result<()> {
+        self.path = Some(path.to_path_buf());
+        self.tag = match ApeReader::new().read_tag(path) {
+            Ok(tag) => Some(tag),
+            Err(Error::TagNotFound) => Some(ApeTag::new(constants::APE_TAG_VERSION_2_0)),
+            Err(e) => return Err(e),
+        };
         Ok(())
     }
     
     fn set_meta_entry(&mut self, entry: &MetaEntry, value: &str) -> Result<()> {
+        StandardValidator.validate_format(entry, value)?;
         if let Some(tag) = &mut self.tag {
             let key = meta_entry_to_ape_key(entry);
+            tag.ensure_writable(key, self.force)?;
             tag.set_text_item(key, value);
             Ok(())
         } else {
@@ -233,10 +336,27 @@ impl TagWriterStrategy for ApeWriter {
         }
     }
     
+    fn remove_meta_entry(&mut self, entry: &MetaEntry) -> Result<()> {
+        if let Some(tag) = &mut self.tag {
+            let key = meta_entry_to_ape_key(entry);
+            tag.ensure_writable(key, self.force)?;
+            tag.remove_item(key);
+            Ok(())
+        } else {
+            Err(Error::TagNotFound)
+        }
+    }
+    
     fn save(&mut self) -> Result<()> {
         if let Some(tag) = &self.tag {
             if let Some(path) = &self.path {
-                tag.write_to_file(path)
+                // A tag left without items is removed, as by `update_tag`; without
+                // an existing tag this leaves the file alone
+                if tag.items.is_empty() && !self.keep_empty_tag {
+                    return self.remove_tag(path);
+                }
+                // Through this writer, so that its sorting, force and preserve options apply
+                self.write_tag(path, tag)
             } else {
                 Err(Error::Other("No path set for APE writer".to_string()))
             }
@@ -245,6 +365,10 @@ impl TagWriterStrategy for ApeWriter {
         }
     }
     
+    fn set_keep_empty_tag(&mut self, keep: bool) {
+        ApeWriter::set_keep_empty_tag(self, keep);
+    }
+
     fn tag_type(&self) -> TagType {
         TagType::Ape
     }
@@ -0,0 +1,137 @@
+//! Embedded artwork extraction.
+//!
+//! Collects every picture stored in a file, from ID3v2 APIC frames and from APE
+//! binary "Cover Art" items, without going through the text-based `MetaEntry` API.
+
+use std::path::Path;
+
+use crate::ape::{ApeItem, ApeReader};
+use crate::file_access::FileManager;
+use crate::id3::v2::picture::guess_mime_type;
+use crate::id3::v2::tag::TagReader as Id3v2Reader;
+use crate::tag::TagReaderStrategy;
+use crate::Result;
+
+pub use crate::id3::v2::picture::{Picture, PictureType};
+
+/// Extract all embedded pictures, ID3v2 pictures first followed by APE cover art
+pub fn extract_pictures<P: AsRef<Path>>(path: P) -> Result<Vec<Picture>> {
+    let path = path.as_ref();
+    FileManager::with_default_strategy().validate_file_path(path)?;
+
+    let mut pictures = Vec::new();
+
+    let mut id3v2 = Id3v2Reader::new();
+    if id3v2.init(path).is_ok() {
+        pictures.extend(id3v2.pictures());
+    }
+
+    if let Ok(tag) = ApeReader::new().read_tag(path) {
+        pictures.extend(tag.items.iter().filter_map(ape_item_to_picture));
+    }
+
+    Ok(pictures)
+}
+
+/// Convert an APE "Cover Art (...)" binary item into a picture
+///
+/// The item value holds the original file name, a null byte and the image bytes.
+pub fn ape_item_to_picture(item: &ApeItem) -> Option<Picture> {
+    if !item.is_binary() {
+        return None;
+    }
+    let kind = item.key.strip_prefix("Cover Art (")?.strip_suffix(')')?;
+    let picture_type = match kind.to_ascii_lowercase().as_str() {
+        "front" => PictureType::CoverFront,
+        "back" => PictureType::CoverBack,
+        "leaflet" => PictureType::Leaflet,
+        "media" => PictureType::Media,
+        "lead artist" => PictureType::LeadArtist,
+        "artist" => PictureType::Artist,
+        "conductor" => PictureType::Conductor,
+        "band" => PictureType::Band,
+        "composer" => PictureType::Composer,
+        "lyricist" => PictureType::Lyricist,
+        "recording location" => PictureType::RecordingLocation,
+        "during recording" => PictureType::DuringRecording,
+        "during performance" => PictureType::DuringPerformance,
+        "video capture" => PictureType::ScreenCapture,
+        "illustration" => PictureType::Illustration,
+        "band logotype" => PictureType::BandLogo,
+        "publisher logotype" => PictureType::PublisherLogo,
+        "icon" => PictureType::FileIcon,
+        "other icon" => PictureType::OtherFileIcon,
+        _ => PictureType::Other,
+    };
+
+    let (description, data) = match item.value.iter().position(|&b| b == 0) {
+        Some(pos) => (String::from_utf8_lossy(&item.value[..pos]).into_owned(), item.value[pos + 1..].to_vec()),
+        None => (String::new(), item.value.clone()),
+    };
+    let mime_type = guess_mime_type(&data)
+        .map(str::to_string)
+        .unwrap_or_else(|| mime_type_from_file_name(&description));
+
+    Some(Picture {
+        mime_type,
+        picture_type,
+        description,
+        data,
+    })
+}
+
+/// Convert a picture into the key and value of an APE "Cover Art (...)" binary item
+///
+/// The description is used as the stored file name, falling back to one
+/// derived from the MIME type.
+pub fn picture_to_ape_item(picture: &Picture) -> (String, Vec<u8>) {
+    let kind = match picture.picture_type {
+        PictureType::CoverFront => "Front",
+        PictureType::CoverBack => "Back",
+        PictureType::Leaflet => "Leaflet",
+        PictureType::Media => "Media",
+        PictureType::LeadArtist => "Lead Artist",
+        PictureType::Artist => "Artist",
+        PictureType::Conductor => "Conductor",
+        PictureType::Band => "Band",
+        PictureType::Composer => "Composer",
+        PictureType::Lyricist => "Lyricist",
+        PictureType::RecordingLocation => "Recording Location",
+        PictureType::DuringRecording => "During Recording",
+        PictureType::DuringPerformance => "During Performance",
+        PictureType::ScreenCapture => "Video Capture",
+        PictureType::Illustration => "Illustration",
+        PictureType::BandLogo => "Band Logotype",
+        PictureType::PublisherLogo => "Publisher Logotype",
+        PictureType::FileIcon => "Icon",
+        PictureType::OtherFileIcon => "Other Icon",
+        _ => "Other",
+    };
+
+    let file_name = if picture.description.is_empty() {
+        let extension = match picture.mime_type.rsplit('/').next().unwrap_or_default() {
+            "jpeg" | "" => "jpg",
+            other => other,
+        };
+        format!("cover.{}", extension)
+    } else {
+        picture.description.clone()
+    };
+    let mut value = file_name.into_bytes();
+    value.push(0);
+    value.extend_from_slice(&picture.data);
+    (format!("Cover Art ({})", kind), value)
+}
+
+fn mime_type_from_file_name(name: &str) -> String {
+    let extension = Path::new(name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg".to_string(),
+        "" => "image/".to_string(),
+        other => format!("image/{}", other),
+    }
+}
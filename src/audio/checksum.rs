@@ -0,0 +1,43 @@
+//! Checksums of the audio stream alone
+//!
+//! Tags are left out, so the checksums stay the same across tag edits as long
+//! as the audio itself is untouched.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::error::Result;
+use crate::id3::v2::util::crc32_update;
+
+use super::locate_audio;
+
+/// MD5 digest of the audio stream, as located by `audio_range`
+pub fn audio_md5<P: AsRef<Path>>(path: P) -> Result<[u8; 16]> {
+    let mut context = md5::Context::new();
+    for_each_chunk(path.as_ref(), |chunk| context.consume(chunk))?;
+    Ok(context.compute().0)
+}
+
+/// CRC-32 of the audio stream, as located by `audio_range`
+pub fn audio_crc32<P: AsRef<Path>>(path: P) -> Result<u32> {
+    let mut crc = 0;
+    for_each_chunk(path.as_ref(), |chunk| crc = crc32_update(crc, chunk))?;
+    Ok(crc)
+}
+
+/// Feed the audio stream of a file to `f` in order
+fn for_each_chunk(path: &Path, mut f: impl FnMut(&[u8])) -> Result<()> {
+    let mut file = File::open(path)?;
+    let range = locate_audio(&mut file)?;
+    file.seek(SeekFrom::Start(range.start))?;
+    let mut audio = file.take(range.end - range.start);
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = audio.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(());
+        }
+        f(&buffer[..read]);
+    }
+}
@@ -0,0 +1,246 @@
+//! MPEG audio frame headers
+
+use std::fmt;
+
+/// MPEG audio version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MpegVersion {
+    V1,
+    V2,
+    /// Unofficial extension of MPEG-2 to lower sample rates
+    V2_5,
+}
+
+impl fmt::Display for MpegVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::V1 => write!(f, "MPEG-1"),
+            Self::V2 => write!(f, "MPEG-2"),
+            Self::V2_5 => write!(f, "MPEG-2.5"),
+        }
+    }
+}
+
+/// MPEG audio layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layer {
+    I,
+    II,
+    III,
+}
+
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::I => write!(f, "Layer I"),
+            Self::II => write!(f, "Layer II"),
+            Self::III => write!(f, "Layer III"),
+        }
+    }
+}
+
+/// Channel mode of an MPEG audio frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelMode {
+    Stereo,
+    JointStereo,
+    /// Two independent mono channels
+    DualChannel,
+    Mono,
+}
+
+impl ChannelMode {
+    /// Number of audio channels
+    pub fn channels(self) -> u8 {
+        if self == Self::Mono {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+/// De-emphasis to apply on playback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Emphasis {
+    None,
+    /// 50/15 µs
+    Ms50_15,
+    Reserved,
+    /// CCITT J.17
+    CcittJ17,
+}
+
+/// Bitrates in kbit/s by bitrate index 1 to 14
+const BITRATES_V1: [[u32; 14]; 3] = [
+    [32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448],
+    [32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384],
+    [32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320],
+];
+const BITRATES_V2_LAYER_I: [u32; 14] = [32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256];
+const BITRATES_V2_LAYER_II_III: [u32; 14] = [8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+/// Number of audio samples per channel in a frame of the given version and layer
+pub(crate) fn samples_per_frame(version: MpegVersion, layer: Layer) -> u32 {
+    match (layer, version) {
+        (Layer::I, _) => 384,
+        (Layer::II, _) | (Layer::III, MpegVersion::V1) => 1152,
+        (Layer::III, _) => 576,
+    }
+}
+
+/// Header of a single MPEG audio frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameHeader {
+    pub version: MpegVersion,
+    pub layer: Layer,
+    /// Bitrate in kbit/s
+    pub bitrate: u32,
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+    pub padding: bool,
+    pub channel_mode: ChannelMode,
+    /// Joint stereo coding tools in use, see `StreamInfo`
+    pub mode_extension: u8,
+    /// Whether a CRC-16 follows the header
+    pub crc_protected: bool,
+    pub copyright: bool,
+    /// Whether this is the original media rather than a copy
+    pub original: bool,
+    pub emphasis: Emphasis,
+}
+
+impl FrameHeader {
+    /// Size of a frame header in bytes
+    pub const SIZE: usize = 4;
+
+    /// Parse the four header bytes of a frame
+    ///
+    /// Returns `None` without a frame sync, for reserved values and for
+    /// free-format frames, whose length cannot be derived from the header.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let &[b0, b1, b2, b3] = bytes.get(..Self::SIZE)? else {
+            return None;
+        };
+        if b0 != 0xFF || b1 & 0xE0 != 0xE0 {
+            return None;
+        }
+        let version = match (b1 >> 3) & 0x03 {
+            0 => MpegVersion::V2_5,
+            2 => MpegVersion::V2,
+            3 => MpegVersion::V1,
+            _ => return None,
+        };
+        let layer = match (b1 >> 1) & 0x03 {
+            1 => Layer::III,
+            2 => Layer::II,
+            3 => Layer::I,
+            _ => return None,
+        };
+        let bitrate_index = (b2 >> 4) as usize;
+        if bitrate_index == 0 || bitrate_index == 15 {
+            return None;
+        }
+        let bitrates = match (version, layer) {
+            (MpegVersion::V1, Layer::I) => &BITRATES_V1[0],
+            (MpegVersion::V1, Layer::II) => &BITRATES_V1[1],
+            (MpegVersion::V1, Layer::III) => &BITRATES_V1[2],
+            (_, Layer::I) => &BITRATES_V2_LAYER_I,
+            _ => &BITRATES_V2_LAYER_II_III,
+        };
+        let sample_rates = match version {
+            MpegVersion::V1 => [44100, 48000, 32000],
+            MpegVersion::V2 => [22050, 24000, 16000],
+            MpegVersion::V2_5 => [11025, 12000, 8000],
+        };
+        let sample_rate = *sample_rates.get(((b2 >> 2) & 0x03) as usize)?;
+        let channel_mode = match b3 >> 6 {
+            0 => ChannelMode::Stereo,
+            1 => ChannelMode::JointStereo,
+            2 => ChannelMode::DualChannel,
+            _ => ChannelMode::Mono,
+        };
+
+        Some(Self {
+            version,
+            layer,
+            bitrate: bitrates[bitrate_index - 1],
+            sample_rate,
+            padding: b2 & 0x02 != 0,
+            channel_mode,
+            mode_extension: (b3 >> 4) & 0x03,
+            crc_protected: b1 & 0x01 == 0,
+            copyright: b3 & 0x08 != 0,
+            original: b3 & 0x04 != 0,
+            emphasis: match b3 & 0x03 {
+                0 => Emphasis::None,
+                1 => Emphasis::Ms50_15,
+                2 => Emphasis::Reserved,
+                _ => Emphasis::CcittJ17,
+            },
+        })
+    }
+
+    /// Number of audio samples per channel in the frame
+    pub fn samples_per_frame(&self) -> u32 {
+        samples_per_frame(self.version, self.layer)
+    }
+
+    /// Length of the frame in bytes, including the header
+    pub fn frame_length(&self) -> usize {
+        let bits = self.bitrate as usize * 1000;
+        let sample_rate = self.sample_rate as usize;
+        match self.layer {
+            Layer::I => (12 * bits / sample_rate + self.padding as usize) * 4,
+            _ => self.samples_per_frame() as usize / 8 * bits / sample_rate + self.padding as usize,
+        }
+    }
+
+    /// Whether `other` can belong to the same stream
+    pub(crate) fn is_consistent_with(&self, other: &Self) -> bool {
+        self.version == other.version && self.layer == other.layer && self.sample_rate == other.sample_rate
+    }
+}
+
+/// Coding details of an MPEG audio stream, taken from its first frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamInfo {
+    pub version: MpegVersion,
+    pub layer: Layer,
+    pub channel_mode: ChannelMode,
+    /// Mid/side stereo coding, only in joint stereo Layer III streams
+    pub mid_side_stereo: bool,
+    /// Intensity stereo coding, always used by joint stereo Layer I and II streams
+    pub intensity_stereo: bool,
+    pub emphasis: Emphasis,
+    pub copyright: bool,
+    pub original: bool,
+    /// Whether frames carry a CRC-16
+    pub crc_protected: bool,
+    /// Number of audio samples per channel in each frame
+    pub samples_per_frame: u32,
+}
+
+impl From<&FrameHeader> for StreamInfo {
+    fn from(header: &FrameHeader) -> Self {
+        let joint_stereo = header.channel_mode == ChannelMode::JointStereo;
+        // Layer III signals each tool with a bit; Layers I and II only use the
+        // mode extension for the intensity stereo bound
+        let (mid_side_stereo, intensity_stereo) = match header.layer {
+            Layer::III => (header.mode_extension & 0x02 != 0, header.mode_extension & 0x01 != 0),
+            _ => (false, true),
+        };
+        Self {
+            version: header.version,
+            layer: header.layer,
+            channel_mode: header.channel_mode,
+            mid_side_stereo: joint_stereo && mid_side_stereo,
+            intensity_stereo: joint_stereo && intensity_stereo,
+            emphasis: header.emphasis,
+            copyright: header.copyright,
+            original: header.original,
+            crc_protected: header.crc_protected,
+            samples_per_frame: header.samples_per_frame(),
+        }
+    }
+}
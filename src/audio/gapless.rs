@@ -0,0 +1,88 @@
+//! Encoder delay and padding for gapless playback
+
+use std::path::Path;
+
+use crate::ape::ApeReader;
+use crate::error::Result;
+use crate::id3::v2::tag::TagReader as Id3v2Reader;
+use crate::tag::TagReaderStrategy;
+
+use super::audio_properties;
+
+/// Description of the comment or TXXX frame, or key of the APE item, iTunes stores gapless data in
+pub const ITUNSMPB: &str = "iTunSMPB";
+
+/// Where the gapless information was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GaplessSource {
+    /// LAME extension of the Xing or Info header
+    Lame,
+    /// iTunes "iTunSMPB" comment, TXXX frame or APE item
+    ITunSmpb,
+}
+
+/// Silence the encoder added around the audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GaplessInfo {
+    /// Samples per channel to skip at the start, not counting the decoder delay
+    pub encoder_delay: u32,
+    /// Samples per channel to drop at the end
+    pub encoder_padding: u32,
+    /// Samples per channel of the original audio, when known
+    pub total_samples: Option<u64>,
+    pub source: GaplessSource,
+}
+
+/// Gapless playback information of an MPEG audio file
+///
+/// The LAME header of the stream is preferred, as it cannot go stale like a
+/// tag; without one the iTunSMPB value of the ID3v2 or APE tag is used.
+/// Returns `None` when the file has neither.
+pub fn gapless_info<P: AsRef<Path>>(path: P) -> Result<Option<GaplessInfo>> {
+    let path = path.as_ref();
+    let properties = audio_properties(path)?;
+    if let Some(vbr_header) = &properties.vbr_header {
+        if let Some(lame) = &vbr_header.lame {
+            let silence = lame.encoder_delay as u64 + lame.encoder_padding as u64;
+            let total_samples = vbr_header
+                .frames
+                .and_then(|frames| (frames as u64 * properties.samples_per_frame() as u64).checked_sub(silence));
+            return Ok(Some(GaplessInfo {
+                encoder_delay: lame.encoder_delay as u32,
+                encoder_padding: lame.encoder_padding as u32,
+                total_samples,
+                source: GaplessSource::Lame,
+            }));
+        }
+    }
+
+    let mut id3v2 = Id3v2Reader::new();
+    let id3v2_value = if id3v2.init(path).is_ok() {
+        id3v2
+            .comment(ITUNSMPB)
+            .map(|comment| comment.text)
+            .or_else(|| id3v2.user_text(ITUNSMPB))
+    } else {
+        None
+    };
+    let value = id3v2_value.or_else(|| {
+        ApeReader::new()
+            .read_tag(path)
+            .ok()
+            .and_then(|tag| tag.get_item_text(ITUNSMPB).ok())
+    });
+    Ok(value.as_deref().and_then(parse_itunsmpb))
+}
+
+/// Parse an iTunSMPB value: hexadecimal fields, of which the second to fourth
+/// hold the delay, the padding and the original sample count
+pub fn parse_itunsmpb(value: &str) -> Option<GaplessInfo> {
+    let fields: Vec<&str> = value.split_whitespace().collect();
+    let hex = |index: usize| fields.get(index).and_then(|field| u64::from_str_radix(field, 16).ok());
+    Some(GaplessInfo {
+        encoder_delay: hex(1)?.try_into().ok()?,
+        encoder_padding: hex(2)?.try_into().ok()?,
+        total_samples: hex(3).filter(|&samples| samples > 0),
+        source: GaplessSource::ITunSmpb,
+    })
+}
@@ -0,0 +1,216 @@
+//! Properties of the MPEG audio stream of a file
+
+pub mod checksum;
+pub mod frame;
+pub mod gapless;
+pub mod vbr;
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::layout::{leading_tags, trailing_tags, Structure};
+
+pub use checksum::{audio_crc32, audio_md5};
+pub use frame::{ChannelMode, Emphasis, FrameHeader, Layer, MpegVersion, StreamInfo};
+pub use gapless::{gapless_info, GaplessInfo, GaplessSource};
+pub use vbr::{LameHeader, VbrHeader, VbrHeaderKind};
+
+/// Number of bytes searched for the first frame after any leading ID3v2 tag
+const SYNC_SEARCH_LIMIT: usize = 64 * 1024;
+
+/// Number of frame headers sampled to tell constant from variable bitrate
+/// streams without a VBR header
+const SAMPLED_FRAMES: usize = 100;
+
+/// Whether all frames of a stream share one bitrate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BitrateMode {
+    Constant,
+    Variable,
+}
+
+/// Basic stream information taken from the first MPEG audio frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioProperties {
+    pub version: MpegVersion,
+    pub layer: Layer,
+    /// Bitrate of the first frame in kbit/s
+    pub bitrate: u32,
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+    pub channel_mode: ChannelMode,
+    pub bitrate_mode: BitrateMode,
+    /// Average bitrate over the stream in kbit/s
+    pub average_bitrate: u32,
+    /// Offset of the first audio frame in the file
+    pub first_frame_offset: u64,
+    /// Playing time
+    pub duration: Duration,
+    /// Number of audio frames, estimated from the stream size without a VBR header
+    pub frame_count: u32,
+    /// Xing, Info or VBRI header of the first frame
+    pub vbr_header: Option<VbrHeader>,
+}
+
+impl AudioProperties {
+    /// Number of audio channels
+    pub fn channels(&self) -> u8 {
+        self.channel_mode.channels()
+    }
+
+    /// Number of audio samples per channel in each frame
+    pub fn samples_per_frame(&self) -> u32 {
+        frame::samples_per_frame(self.version, self.layer)
+    }
+}
+
+/// Read the stream properties of an MPEG audio file
+///
+/// The first frame is searched for after any ID3v2 tag. A header only counts
+/// when the frame it describes is followed by another matching header, or by
+/// the end of the file or a trailing tag, so sync-like bytes in junk data are
+/// skipped.
+///
+/// The duration of files with a Xing or VBRI header is exact; otherwise it is
+/// estimated from the size of the audio and the average bitrate. Without such
+/// a header, the bitrate mode and average bitrate come from the headers of the
+/// first frames.
+pub fn audio_properties<P: AsRef<Path>>(path: P) -> Result<AudioProperties> {
+    let mut file = File::open(path.as_ref())?;
+    let range = locate_audio(&mut file)?;
+    let Range { start, end: audio_end } = range;
+    let buffer = read_stream_head(&mut file, &range)?;
+
+    let (offset, header) = find_first_frame(&buffer, start, audio_end)
+        .ok_or_else(no_frame_found)?;
+    let first_frame_offset = start + offset as u64;
+    let frame = &buffer[offset..buffer.len().min(offset + header.frame_length())];
+    let vbr_header = VbrHeader::parse(frame, &header);
+
+    let samples_per_frame = header.samples_per_frame() as u64;
+    let sample_rate = header.sample_rate as u64;
+    // The frame holding a VBR header carries no audio
+    let skipped = if vbr_header.is_some() { frame.len() } else { 0 };
+    let audio_bytes = audio_end.saturating_sub(first_frame_offset + skipped as u64);
+
+    // A corrupt header may claim more frames than fit in the file; every frame has at least its 4 byte header
+    let header_frames = vbr_header
+        .as_ref()
+        .and_then(|vbr| vbr.frames)
+        .filter(|&frames| frames > 0 && frames as u64 <= audio_bytes / 4);
+    let (bitrate_mode, average_bitrate) = match (&vbr_header, header_frames) {
+        (Some(vbr), _) if vbr.kind == VbrHeaderKind::Info => (BitrateMode::Constant, header.bitrate),
+        (Some(vbr), Some(frames)) => {
+            let bytes = vbr.bytes.map_or(audio_bytes, u64::from);
+            let bits_per_second = bytes * 8 * sample_rate / (frames as u64 * samples_per_frame);
+            (BitrateMode::Variable, (bits_per_second / 1000) as u32)
+        }
+        _ => sample_bitrates(buffer.get(offset + skipped..).unwrap_or_default(), &header),
+    };
+    let frame_count = match header_frames {
+        Some(frames) => frames as u64,
+        None => {
+            let bytes_per_second = average_bitrate.max(1) as u64 * 1000 / 8;
+            (audio_bytes * sample_rate / bytes_per_second + samples_per_frame / 2) / samples_per_frame
+        }
+    };
+    let samples = frame_count * samples_per_frame;
+
+    Ok(AudioProperties {
+        version: header.version,
+        layer: header.layer,
+        bitrate: header.bitrate,
+        sample_rate: header.sample_rate,
+        channel_mode: header.channel_mode,
+        bitrate_mode,
+        average_bitrate,
+        first_frame_offset,
+        duration: Duration::from_nanos((samples as u128 * 1_000_000_000 / sample_rate as u128).try_into().unwrap_or(u64::MAX)),
+        frame_count: frame_count.try_into().unwrap_or(u32::MAX),
+        vbr_header,
+    })
+}
+
+/// Coding details of the stream of an MPEG audio file, from its first frame
+///
+/// The first frame is located as by `audio_properties`.
+pub fn stream_info<P: AsRef<Path>>(path: P) -> Result<StreamInfo> {
+    let mut file = File::open(path.as_ref())?;
+    let range = locate_audio(&mut file)?;
+    let buffer = read_stream_head(&mut file, &range)?;
+    let (_, header) = find_first_frame(&buffer, range.start, range.end).ok_or_else(no_frame_found)?;
+    Ok(StreamInfo::from(&header))
+}
+
+fn no_frame_found() -> Error {
+    Error::Other("No MPEG audio frame found".to_string())
+}
+
+/// Byte range of the audio stream in a file
+///
+/// The range starts behind any ID3v2 tags at the start of the file and ends
+/// before the APE, Lyrics3, ID3v1 (with TAG+) and appended ID3v2.4 tags at
+/// its end, in whichever order they are stacked.
+pub fn audio_range<P: AsRef<Path>>(path: P) -> Result<Range<u64>> {
+    locate_audio(&mut File::open(path.as_ref())?)
+}
+
+pub(crate) fn locate_audio(file: &mut File) -> Result<Range<u64>> {
+    let file_size = file.metadata()?.len();
+    let start = leading_tags(file, file_size)?.last().map_or(0, Structure::end);
+    let end = trailing_tags(file, start, file_size)?.first().map_or(file_size, |tag| tag.offset);
+    Ok(start.min(end)..end)
+}
+
+/// The start of the audio region, enough to find its first frame and sample the following ones
+pub(crate) fn read_stream_head(file: &mut File, range: &Range<u64>) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    file.seek(SeekFrom::Start(range.start))?;
+    // One frame is at most 2881 bytes, so a following header is always in the buffer
+    file.take(((SYNC_SEARCH_LIMIT + 4096) as u64).min(range.end - range.start)).read_to_end(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Position and header of the first frame in `buffer`, which starts at `start`,
+/// in audio ending at `audio_end`
+pub(crate) fn find_first_frame(buffer: &[u8], start: u64, audio_end: u64) -> Option<(usize, FrameHeader)> {
+    let search_end = buffer.len().min(SYNC_SEARCH_LIMIT);
+    (0..search_end).find_map(|offset| {
+        let header = FrameHeader::parse(&buffer[offset..])?;
+        let next = offset + header.frame_length();
+        let rest = buffer.get(next..).unwrap_or_default();
+        let confirmed = match FrameHeader::parse(rest) {
+            Some(next_header) => header.is_consistent_with(&next_header),
+            // A single frame before the end of the audio
+            None => start + next as u64 >= audio_end,
+        };
+        confirmed.then_some((offset, header))
+    })
+}
+
+/// Bitrate mode and average bitrate of the consecutive frames at the start of `data`
+fn sample_bitrates(data: &[u8], first: &FrameHeader) -> (BitrateMode, u32) {
+    let mut bitrates = Vec::new();
+    let mut position = 0;
+    while bitrates.len() < SAMPLED_FRAMES {
+        let Some(header) = data.get(position..).and_then(FrameHeader::parse) else {
+            break;
+        };
+        if !header.is_consistent_with(first) {
+            break;
+        }
+        bitrates.push(header.bitrate);
+        position += header.frame_length();
+    }
+    match bitrates.first() {
+        Some(&bitrate) if bitrates.iter().any(|&other| other != bitrate) => {
+            let sum: u64 = bitrates.iter().map(|&bitrate| bitrate as u64).sum();
+            (BitrateMode::Variable, (sum / bitrates.len() as u64) as u32)
+        }
+        _ => (BitrateMode::Constant, first.bitrate),
+    }
+}
@@ -0,0 +1,168 @@
+//! Xing, Info and VBRI headers in the first frame of a stream
+
+use crate::audio::frame::{ChannelMode, FrameHeader, MpegVersion};
+use crate::replaygain::ReplayGain;
+
+/// Encoder that wrote the header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VbrHeaderKind {
+    /// "Xing" header of a variable bitrate stream
+    Xing,
+    /// "Info" header, the Xing layout written by LAME for constant bitrate streams
+    Info,
+    /// Fraunhofer "VBRI" header
+    Vbri,
+}
+
+/// Stream summary stored in place of the audio of the first frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct VbrHeader {
+    pub kind: VbrHeaderKind,
+    /// Number of audio frames, excluding the frame holding the header
+    pub frames: Option<u32>,
+    /// Size of the audio stream in bytes
+    pub bytes: Option<u32>,
+    /// Encoder information following a Xing or Info header
+    pub lame: Option<LameHeader>,
+}
+
+/// LAME extension of a Xing or Info header
+#[derive(Debug, Clone, PartialEq)]
+pub struct LameHeader {
+    /// Encoder name and version, such as "LAME3.100"
+    pub encoder: String,
+    /// Revision of the extension format
+    pub revision: u8,
+    /// Bitrate mode: 1 and 8 constant, 2 and 9 average, 3 to 6 variable
+    pub vbr_method: u8,
+    /// Lowpass filter frequency in Hz
+    pub lowpass: Option<u32>,
+    /// Samples of silence the encoder added at the start
+    pub encoder_delay: u16,
+    /// Samples of silence the encoder added at the end
+    pub encoder_padding: u16,
+    /// Peak amplitude and the radio (track) and audiophile (album) gains
+    pub replay_gain: ReplayGain,
+}
+
+const XING_FLAG_FRAMES: u32 = 0x01;
+const XING_FLAG_BYTES: u32 = 0x02;
+const XING_FLAG_TOC: u32 = 0x04;
+const XING_FLAG_QUALITY: u32 = 0x08;
+
+/// Size of the LAME extension
+const LAME_HEADER_SIZE: usize = 36;
+
+/// Offset of the VBRI header from the start of the frame
+const VBRI_OFFSET: usize = FrameHeader::SIZE + 32;
+
+impl VbrHeader {
+    /// Find the header in `frame`, the bytes of the first frame starting with its header
+    pub fn parse(frame: &[u8], header: &FrameHeader) -> Option<Self> {
+        Self::parse_xing(frame, header).or_else(|| Self::parse_vbri(frame))
+    }
+
+    fn parse_xing(frame: &[u8], header: &FrameHeader) -> Option<Self> {
+        let data = frame.get(xing_offset(header)..)?;
+        let kind = match data.get(..4)? {
+            b"Xing" => VbrHeaderKind::Xing,
+            b"Info" => VbrHeaderKind::Info,
+            _ => return None,
+        };
+        let flags = read_u32(data, 4)?;
+        // Present fields follow the flags in order
+        let mut position = 8;
+        let mut field = |flag: u32| {
+            if flags & flag == 0 {
+                return None;
+            }
+            let value = read_u32(data, position);
+            position += 4;
+            value
+        };
+        let frames = field(XING_FLAG_FRAMES);
+        let bytes = field(XING_FLAG_BYTES);
+        for (flag, size) in [(XING_FLAG_TOC, 100), (XING_FLAG_QUALITY, 4)] {
+            if flags & flag != 0 {
+                position += size;
+            }
+        }
+        let lame = data.get(position..).and_then(LameHeader::parse);
+        Some(Self { kind, frames, bytes, lame })
+    }
+
+    fn parse_vbri(frame: &[u8]) -> Option<Self> {
+        let data = frame.get(VBRI_OFFSET..)?;
+        if data.get(..4)? != b"VBRI" {
+            return None;
+        }
+        Some(Self {
+            kind: VbrHeaderKind::Vbri,
+            bytes: Some(read_u32(data, 10)?),
+            frames: Some(read_u32(data, 14)?),
+            lame: None,
+        })
+    }
+}
+
+impl LameHeader {
+    /// Parse the extension at the start of `data`
+    ///
+    /// Returns `None` when `data` does not start with an encoder name.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let data = data.get(..LAME_HEADER_SIZE)?;
+        let encoder = &data[..9];
+        if !encoder[0].is_ascii_alphabetic() || !encoder.iter().all(|&b| b == 0 || b.is_ascii_graphic() || b == b' ') {
+            return None;
+        }
+        let encoder = String::from_utf8_lossy(encoder).trim_end_matches(['\0', ' ']).to_string();
+
+        // Peak as a fixed point number with 23 fractional bits
+        let peak = read_u32(data, 11)?;
+        let radio_gain = parse_gain(u16::from_be_bytes([data[15], data[16]]));
+        let audiophile_gain = parse_gain(u16::from_be_bytes([data[17], data[18]]));
+        let delay_padding = u32::from_be_bytes([0, data[21], data[22], data[23]]);
+
+        Some(Self {
+            encoder,
+            revision: data[9] >> 4,
+            vbr_method: data[9] & 0x0F,
+            lowpass: Some(data[10] as u32 * 100).filter(|&lowpass| lowpass > 0),
+            encoder_delay: (delay_padding >> 12) as u16,
+            encoder_padding: (delay_padding & 0x0FFF) as u16,
+            replay_gain: ReplayGain {
+                track_gain: radio_gain,
+                track_peak: Some(peak as f32 / (1 << 23) as f32).filter(|&peak| peak > 0.0),
+                album_gain: audiophile_gain,
+                album_peak: None,
+            },
+        })
+    }
+}
+
+/// Gain in dB of a LAME ReplayGain field, `None` when the name code is unset
+///
+/// The field holds a name code (3 bits), an originator code (3 bits), a sign
+/// bit and the absolute gain in tenths of a dB (9 bits).
+fn parse_gain(field: u16) -> Option<f32> {
+    if field >> 13 == 0 {
+        return None;
+    }
+    let gain = (field & 0x01FF) as f32 / 10.0;
+    Some(if field & 0x0200 != 0 { -gain } else { gain })
+}
+
+/// Offset of a Xing header from the start of the frame, after the side information
+pub(crate) fn xing_offset(header: &FrameHeader) -> usize {
+    let side_info = match (header.version, header.channel_mode) {
+        (MpegVersion::V1, ChannelMode::Mono) => 17,
+        (MpegVersion::V1, _) => 32,
+        (_, ChannelMode::Mono) => 9,
+        _ => 17,
+    };
+    FrameHeader::SIZE + side_info
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
@@ -0,0 +1,34 @@
+//! `.bak` copies taken before a file is modified, and rollback to them
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::util::rename_file;
+
+/// Extension appended to the file name of a backup
+pub const BACKUP_EXTENSION: &str = "bak";
+
+/// Path of the backup of `path`, e.g. `song.mp3.bak`
+pub fn backup_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let mut name = path.as_ref().as_os_str().to_owned();
+    name.push(".");
+    name.push(BACKUP_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Copy `path` to its backup path, replacing an older backup
+pub fn create_backup<P: AsRef<Path>>(path: P) -> Result<PathBuf> {
+    let backup = backup_path(&path);
+    fs::copy(path.as_ref(), &backup)?;
+    Ok(backup)
+}
+
+/// Restore `path` from its backup, consuming the backup
+pub fn rollback<P: AsRef<Path>>(path: P) -> Result<()> {
+    let backup = backup_path(&path);
+    if !backup.exists() {
+        return Err(Error::FileNotFound(backup.display().to_string()));
+    }
+    rename_file(&backup, path)
+}
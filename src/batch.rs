@@ -0,0 +1,170 @@
+//! Reads and edits applied to many files in parallel
+//!
+//! ```no_run
+//! use mp3tags_r::batch::Batch;
+//!
+//! let summary = Batch::from_directory("music")?.edit(|tag| tag.album("Album"));
+//! for (path, error) in summary.failures() {
+//!     eprintln!("{}: {}", path.display(), error);
+//! }
+//! # Ok::<(), mp3tags_r::Error>(())
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::editor::Tag;
+use crate::error::{Error, Result};
+use crate::metadata::Metadata;
+use crate::progress::{with_progress, Progress};
+
+/// A set of files to read or edit with a pool of worker threads
+#[derive(Debug, Clone)]
+pub struct Batch {
+    files: Vec<PathBuf>,
+    threads: usize,
+    progress: Option<Progress>,
+}
+
+impl Batch {
+    /// Batch over the given files
+    pub fn new<I, P>(files: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+        Self { files: files.into_iter().map(Into::into).collect(), threads, progress: None }
+    }
+
+    /// Batch over the MP3 files in a directory and its subdirectories, in path order
+    pub fn from_directory<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let mut files = Vec::new();
+        collect_mp3_files(dir.as_ref(), &mut files)?;
+        files.sort();
+        Ok(Self::new(files))
+    }
+
+    /// Use at most `threads` worker threads; by default as many as the machine runs in parallel
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Report every processed file and the bytes copied by rewrites to `progress`
+    ///
+    /// Once its token is cancelled no further files are started, a rewrite in
+    /// progress is abandoned and the files left fail with `Error::Cancelled`.
+    pub fn progress(mut self, progress: Progress) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// The files of the batch
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Run `operation` on every file
+    ///
+    /// The files are handed out to the worker threads one at a time; the
+    /// results are reported in the order of the files.
+    pub fn run<T, F>(&self, operation: F) -> BatchSummary<T>
+    where
+        T: Send,
+        F: Fn(&Path) -> Result<T> + Sync,
+    {
+        let progress = self.progress.clone().unwrap_or_default();
+        progress.set_files_total(self.files.len());
+        let next = AtomicUsize::new(0);
+        let worker = || {
+            let mut results = Vec::new();
+            loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = self.files.get(index) else {
+                    return results;
+                };
+                let result = progress.check().and_then(|()| with_progress(&progress, || operation(path)));
+                if !matches!(result, Err(Error::Cancelled)) {
+                    progress.file_done();
+                }
+                results.push((index, result));
+            }
+        };
+
+        let mut results: Vec<(usize, Result<T>)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads.min(self.files.len())).map(|_| scope.spawn(worker)).collect();
+            workers
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+                .collect()
+        });
+        results.sort_by_key(|&(index, _)| index);
+
+        BatchSummary {
+            results: results.into_iter().map(|(index, result)| (self.files[index].clone(), result)).collect(),
+        }
+    }
+
+    /// Read the metadata of every file
+    pub fn read(&self) -> BatchSummary<Metadata> {
+        self.run(|path| Metadata::read(path))
+    }
+
+    /// Open every file with `Tag::open`, change it with `edit` and save it
+    pub fn edit<F>(&self, edit: F) -> BatchSummary<()>
+    where
+        F: Fn(Tag) -> Tag + Sync,
+    {
+        self.run(|path| edit(Tag::open(path)?).save())
+    }
+}
+
+/// Per-file results of a batch, in the order of its files
+#[derive(Debug)]
+pub struct BatchSummary<T> {
+    pub results: Vec<(PathBuf, Result<T>)>,
+}
+
+impl<T> BatchSummary<T> {
+    /// Number of files the operation succeeded on
+    pub fn succeeded(&self) -> usize {
+        self.results.iter().filter(|(_, result)| result.is_ok()).count()
+    }
+
+    /// Number of files the operation failed on
+    pub fn failed(&self) -> usize {
+        self.results.len() - self.succeeded()
+    }
+
+    /// Whether the operation succeeded on every file
+    pub fn is_success(&self) -> bool {
+        self.failed() == 0
+    }
+
+    /// The files the operation failed on, with their errors
+    pub fn failures(&self) -> impl Iterator<Item = (&Path, &Error)> {
+        self.results.iter().filter_map(|(path, result)| result.as_ref().err().map(|error| (path.as_path(), error)))
+    }
+}
+
+/// Symbolic links are skipped, so a link to a parent directory cannot loop
+fn collect_mp3_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_mp3_files(&path, files)?;
+        } else if file_type.is_file() && is_mp3(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn is_mp3(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("mp3"))
+}
@@ -0,0 +1,131 @@
+//! Parsed tag readers shared between lookups of unchanged files
+//!
+//! A `ReaderCache` keeps the `TagReader` of every file it was asked for,
+//! together with the file's modification time and size. Later lookups of the
+//! same path return the same reader without parsing the file again, as long as
+//! neither changed.
+//!
+//! ```no_run
+//! use mp3tags_r::cache::ReaderCache;
+//! use mp3tags_r::MetaEntry;
+//!
+//! let cache = ReaderCache::with_capacity(1000);
+//! let title = cache.reader("song.mp3")?.get_meta_entry(&MetaEntry::Title)?;
+//! # Ok::<(), mp3tags_r::Error>(())
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::SystemTime;
+
+use crate::error::Result;
+use crate::tag::TagReader;
+
+struct CachedReader {
+    modified: SystemTime,
+    size: u64,
+    reader: Arc<TagReader>,
+    /// Value of `Entries::clock` at the last lookup
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Entries {
+    readers: HashMap<PathBuf, CachedReader>,
+    clock: u64,
+}
+
+/// Cache of `TagReader`s by path, checked against the modification time and size of the file
+///
+/// The cache can be shared between threads. Paths are used as given, so
+/// different spellings of one file are cached separately. Modification times
+/// are only as fine as the filesystem stores them: after rewriting a file
+/// without changing its size, call `invalidate` to be sure the change is seen.
+pub struct ReaderCache {
+    entries: Mutex<Entries>,
+    capacity: Option<usize>,
+}
+
+impl Default for ReaderCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReaderCache {
+    /// A cache without size limit
+    pub fn new() -> Self {
+        Self { entries: Mutex::new(Entries::default()), capacity: None }
+    }
+
+    /// A cache holding at most `capacity` files, dropping the least recently used one first
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { entries: Mutex::new(Entries::default()), capacity: Some(capacity.max(1)) }
+    }
+
+    /// The reader of a file, parsed with `TagReader::new` unless a reader of the unchanged file is cached
+    pub fn reader<P: AsRef<Path>>(&self, path: P) -> Result<Arc<TagReader>> {
+        let path = path.as_ref();
+        let metadata = fs::metadata(path)?;
+        let (modified, size) = (metadata.modified()?, metadata.len());
+        {
+            let mut entries = self.lock();
+            entries.clock += 1;
+            let clock = entries.clock;
+            if let Some(cached) = entries.readers.get_mut(path) {
+                if cached.modified == modified && cached.size == size {
+                    cached.last_used = clock;
+                    return Ok(Arc::clone(&cached.reader));
+                }
+            }
+        }
+
+        // Parsed without holding the lock, so lookups of other files go on meanwhile
+        let reader = Arc::new(TagReader::new(path)?);
+        let mut entries = self.lock();
+        let last_used = entries.clock;
+        entries.readers.insert(path.to_path_buf(), CachedReader { modified, size, reader: Arc::clone(&reader), last_used });
+        if let Some(capacity) = self.capacity {
+            while entries.readers.len() > capacity {
+                let Some(oldest) = entries.readers.iter().min_by_key(|(_, cached)| cached.last_used).map(|(path, _)| path.clone()) else {
+                    break;
+                };
+                entries.readers.remove(&oldest);
+            }
+        }
+        Ok(reader)
+    }
+
+    /// Drop the reader of a file, so that the next lookup parses it again
+    ///
+    /// Returns whether a reader was cached.
+    pub fn invalidate<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.lock().readers.remove(path.as_ref()).is_some()
+    }
+
+    /// Drop all readers
+    pub fn clear(&self) {
+        self.lock().readers.clear();
+    }
+
+    /// Number of cached readers
+    pub fn len(&self) -> usize {
+        self.lock().readers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl std::fmt::Debug for ReaderCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReaderCache").field("len", &self.len()).field("capacity", &self.capacity).finish()
+    }
+}
@@ -0,0 +1,286 @@
+//! Conversion of tags between formats and files
+//!
+//! Copies the text fields and embedded artwork of an ID3v1, ID3v2 or APE tag
+//! into a tag of another format, so a library can be migrated to one format,
+//! or into the tags of another file, e.g. after re-encoding the audio.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::ape::{ApeReader, ApeWriter};
+use crate::artwork::{ape_item_to_picture, picture_to_ape_item, Picture};
+use crate::error::{Error, Result};
+use crate::id3::v1::report::{FieldIssue, WriteReport};
+use crate::id3::v1::tag::{remove_id3v1_tag, TagReader as V1Reader, TagWriter as V1Writer};
+use crate::id3::v2::tag::{TagReader as V2Reader, TagWriter as V2Writer};
+use crate::meta_entry::{all_standard_entries, MetaEntry};
+use crate::strip::strip_tags;
+use crate::tag::{detect_tags, TagReaderStrategy, TagType, TagWriterStrategy};
+
+/// Text values read from a tag, standard entries first followed by custom ones
+pub(crate) type Entries = Vec<(MetaEntry, String)>;
+
+/// Options for `convert_tag`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvertOptions {
+    /// Remove the source tag once the target tag has been written
+    pub remove_source: bool,
+    /// Copy embedded pictures (ignored when either tag is ID3v1)
+    pub pictures: bool,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            remove_source: false,
+            pictures: true,
+        }
+    }
+}
+
+/// Outcome of `convert_tag`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConvertReport {
+    /// Entries copied from the source tag
+    pub copied: Vec<MetaEntry>,
+    /// Number of pictures copied
+    pub pictures: usize,
+    /// Values truncated or dropped by the target tag (only for ID3v1 targets)
+    pub lossy: WriteReport,
+    /// Whether the source tag was removed
+    pub source_removed: bool,
+}
+
+/// Copy the fields and artwork of the `from` tag into the `to` tag
+///
+/// Entries and pictures missing from the source are left alone in the target.
+/// ID3v2 genres such as "(17)Rock" are mapped to their name, and genres
+/// written to ID3v1 go through the genre table. Fails with `TagNotFound`
+/// when the file has no source tag.
+pub fn convert_tag<P: AsRef<Path>>(path: P, from: TagType, to: TagType, options: ConvertOptions) -> Result<ConvertReport> {
+    let path = path.as_ref();
+    if from == to {
+        return Err(Error::Other(format!("Cannot convert a {:?} tag to itself", from)));
+    }
+
+    let (values, pictures) = read_source(path, from, options.pictures)?;
+    let mut report = ConvertReport {
+        copied: values.iter().map(|(entry, _)| entry.clone()).collect(),
+        ..Default::default()
+    };
+    report.lossy = write_target(path, to, &values, &pictures)?;
+    if to != TagType::Id3v1 {
+        report.pictures = pictures.len();
+    }
+
+    if options.remove_source {
+        match from {
+            TagType::Id3v1 => {
+                remove_id3v1_tag(path)?;
+            }
+            TagType::Id3v2 => {
+                let mut writer = V2Writer::new();
+                writer.init(path)?;
+                writer.remove_tag()?;
+            }
+            TagType::Ape => ApeWriter::new().remove_tag(path)?,
+            TagType::Custom(_) => return Err(Error::InvalidTagType),
+        }
+        report.source_removed = true;
+    }
+    Ok(report)
+}
+
+/// Options for `copy_tags`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopyOptions {
+    /// Write everything into one tag of this type instead of mirroring the source tags
+    pub tag_type: Option<TagType>,
+    /// Remove the destination's tags of the written types first, so no stale values remain
+    pub clear_destination: bool,
+    /// Copy embedded pictures
+    pub pictures: bool,
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        Self {
+            tag_type: None,
+            clear_destination: false,
+            pictures: true,
+        }
+    }
+}
+
+/// Outcome of `copy_tags`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CopyReport {
+    /// Tag types written to the destination
+    pub written: Vec<TagType>,
+    /// Values an ID3v1 destination tag could not hold
+    pub lossy: WriteReport,
+}
+
+/// Copy the metadata, artwork and custom entries of one file into another
+///
+/// By default each tag of `src` is copied into a tag of the same type in `dst`.
+/// With `CopyOptions::tag_type` the source tags are merged, ID3v2 first, then
+/// APE, then ID3v1, and written into a single tag. Fails with `TagNotFound`
+/// when `src` has no tags.
+pub fn copy_tags<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q, options: CopyOptions) -> Result<CopyReport> {
+    let (src, dst) = (src.as_ref(), dst.as_ref());
+    let present = detect_tags(src)?;
+    let sources: Vec<TagType> = [TagType::Id3v2, TagType::Ape, TagType::Id3v1]
+        .into_iter()
+        .filter(|tag_type| present.has(*tag_type))
+        .collect();
+    if sources.is_empty() {
+        return Err(Error::TagNotFound);
+    }
+
+    let mut plan = Vec::new();
+    match options.tag_type {
+        None => {
+            for tag_type in sources {
+                let (values, pictures) = read_source(src, tag_type, options.pictures)?;
+                plan.push((tag_type, values, pictures));
+            }
+        }
+        Some(target) => {
+            let mut merged: Entries = Vec::new();
+            let mut merged_pictures: Vec<Picture> = Vec::new();
+            for tag_type in sources {
+                let (values, pictures) = read_source(src, tag_type, options.pictures)?;
+                for (entry, value) in values {
+                    if !merged.iter().any(|(existing, _)| *existing == entry) {
+                        merged.push((entry, value));
+                    }
+                }
+                for picture in pictures {
+                    if !merged_pictures.iter().any(|existing| existing.picture_type == picture.picture_type) {
+                        merged_pictures.push(picture);
+                    }
+                }
+            }
+            plan.push((target, merged, merged_pictures));
+        }
+    }
+
+    let mut report = CopyReport::default();
+    if options.clear_destination {
+        let types: Vec<TagType> = plan.iter().map(|(tag_type, _, _)| *tag_type).collect();
+        strip_tags(dst, &types)?;
+    }
+    for (tag_type, values, pictures) in &plan {
+        let lossy = write_target(dst, *tag_type, values, pictures)?;
+        report.lossy.issues.extend(lossy.issues);
+        report.written.push(*tag_type);
+    }
+    Ok(report)
+}
+
+/// Write text entries and pictures into the `to` tag, creating it if needed
+///
+/// Custom entries become TXXX frames in ID3v2. The report lists what an
+/// ID3v1 tag could not hold; it is empty for the other formats.
+pub(crate) fn write_target(path: &Path, to: TagType, values: &[(MetaEntry, String)], pictures: &[Picture]) -> Result<WriteReport> {
+    match to {
+        TagType::Id3v1 => {
+            let mut writer = V1Writer::new();
+            writer.init(path)?;
+            for (entry, value) in values {
+                writer.set_meta_entry(entry, value)?;
+            }
+            writer.save()?;
+            let mut report = writer.take_report();
+            for picture in pictures {
+                report.push(&MetaEntry::AttachedPicture, FieldIssue::Unsupported(picture.mime_type.clone()));
+            }
+            Ok(report)
+        }
+        TagType::Id3v2 => {
+            let mut writer = V2Writer::new();
+            writer.init(path)?;
+            writer.set_deferred(true);
+            for (entry, value) in values {
+                writer.set_meta_entry(entry, value)?;
+            }
+            for picture in pictures {
+                writer.set_picture(picture)?;
+            }
+            writer.save()?;
+            Ok(WriteReport::default())
+        }
+        TagType::Ape => {
+            let entries: HashMap<MetaEntry, String> = values.iter().cloned().collect();
+            let items: Vec<_> = pictures.iter().map(picture_to_ape_item).collect();
+            if !entries.is_empty() || !items.is_empty() {
+                ApeWriter::new().set_meta_entries_and_binary_items(path, &entries, &items)?;
+            }
+            Ok(WriteReport::default())
+        }
+        TagType::Custom(_) => Err(Error::InvalidTagType),
+    }
+}
+
+/// Non-empty text entries and, if wanted, pictures of the source tag
+///
+/// Custom entries come from ID3v2 TXXX frames and unmapped APE items.
+pub(crate) fn read_source(path: &Path, from: TagType, with_pictures: bool) -> Result<(Entries, Vec<Picture>)> {
+    match from {
+        TagType::Id3v1 => Ok((read_entries(&mut V1Reader::new(), path)?, Vec::new())),
+        TagType::Custom(_) => Err(Error::InvalidTagType),
+        TagType::Id3v2 => {
+            let mut reader = V2Reader::new();
+            let mut values = read_entries(&mut reader, path)?;
+            values.extend(
+                reader
+                    .user_texts()
+                    .into_iter()
+                    .filter(|text| !text.description.is_empty() && !text.value.is_empty())
+                    .map(|text| (MetaEntry::Custom(text.description), text.value)),
+            );
+            let pictures = if with_pictures { reader.pictures() } else { Vec::new() };
+            Ok((values, pictures))
+        }
+        TagType::Ape => {
+            let tag = ApeReader::new().read_tag(path)?;
+            let entries = tag.get_meta_entries();
+            let mut values: Entries = all_standard_entries()
+                .into_iter()
+                .filter(|entry| *entry != MetaEntry::AttachedPicture)
+                .filter_map(|entry| entries.get(&entry).filter(|value| !value.is_empty()).map(|value| (entry, value.clone())))
+                .collect();
+            let mut custom: Entries = entries
+                .into_iter()
+                .filter(|(entry, value)| matches!(entry, MetaEntry::Custom(_)) && !value.is_empty())
+                .collect();
+            custom.sort_by_key(|(entry, _)| entry.to_string());
+            values.extend(custom);
+            let pictures = if with_pictures {
+                tag.items.iter().filter_map(ape_item_to_picture).collect()
+            } else {
+                Vec::new()
+            };
+            Ok((values, pictures))
+        }
+    }
+}
+
+/// Read every standard text entry the source reader has a value for
+fn read_entries(reader: &mut dyn TagReaderStrategy, path: &Path) -> Result<Entries> {
+    reader.init(path)?;
+    let mut values = Vec::new();
+    for entry in all_standard_entries() {
+        // Pictures are copied as such, not through their text value
+        if entry == MetaEntry::AttachedPicture {
+            continue;
+        }
+        match reader.get_meta_entry(path, &entry) {
+            Ok(value) if !value.is_empty() => values.push((entry, value)),
+            Ok(_) | Err(Error::EntryNotFound) | Err(Error::UnsupportedMetaEntry(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(values)
+}
@@ -0,0 +1,133 @@
+//! Recording dates at the precision a tag stores them
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// Recording date, from a year down to the second
+///
+/// Formats as an ID3v2.4 timestamp ("2004-05-17T20:15"), which ID3v2.3 stores
+/// split into TYER, TDAT and TIME frames. Each field is only set when the
+/// fields before it are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RecordingDate {
+    pub year: u16,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+    pub hour: Option<u8>,
+    pub minute: Option<u8>,
+    pub second: Option<u8>,
+}
+
+impl RecordingDate {
+    /// A date known to the year only
+    pub fn new(year: u16) -> Self {
+        Self {
+            year,
+            month: None,
+            day: None,
+            hour: None,
+            minute: None,
+            second: None,
+        }
+    }
+
+    /// A calendar date
+    pub fn ymd(year: u16, month: u8, day: u8) -> Result<Self> {
+        let date = Self {
+            month: Some(month),
+            day: Some(day),
+            ..Self::new(year)
+        };
+        date.validate()?;
+        Ok(date)
+    }
+
+    /// The same date at the given hour and minute
+    pub fn at(self, hour: u8, minute: u8) -> Result<Self> {
+        let date = Self {
+            hour: Some(hour),
+            minute: Some(minute),
+            second: None,
+            ..self
+        };
+        date.validate()?;
+        Ok(date)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let fields = [
+            (self.month, 1..=12),
+            (self.day, 1..=31),
+            (self.hour, 0..=23),
+            (self.minute, 0..=59),
+            (self.second, 0..=59),
+        ];
+        // Every field must be in range and only set when the one before it is
+        let mut valid = self.year <= 9999;
+        let mut previous_set = true;
+        for (value, range) in fields {
+            valid &= value.is_none_or(|value| previous_set && range.contains(&value));
+            previous_set = value.is_some();
+        }
+        if !valid {
+            return Err(Error::Other(format!("Invalid recording date: {}", self)));
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for RecordingDate {
+    type Err = Error;
+
+    /// Parse "YYYY", "YYYY-MM", "YYYY-MM-DD", "YYYY-MM-DDTHH", "YYYY-MM-DDTHH:MM" or
+    /// "YYYY-MM-DDTHH:MM:SS"
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = || Error::Other(format!("Invalid recording date: {:?}", value));
+        let value = value.trim();
+        let number = |part: &str, len: usize| -> Result<u16> {
+            if part.len() != len || !part.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            part.parse().map_err(|_| invalid())
+        };
+
+        let (date, time) = match value.split_once(['T', ' ']) {
+            Some((date, time)) => (date, Some(time)),
+            None => (value, None),
+        };
+        let mut date_parts = date.split('-');
+        let mut result = Self::new(number(date_parts.next().ok_or_else(invalid)?, 4)?);
+        result.month = date_parts.next().map(|part| number(part, 2)).transpose()?.map(|n| n as u8);
+        result.day = date_parts.next().map(|part| number(part, 2)).transpose()?.map(|n| n as u8);
+        if date_parts.next().is_some() || (time.is_some() && result.day.is_none()) {
+            return Err(invalid());
+        }
+        if let Some(time) = time {
+            let mut time_parts = time.split(':');
+            result.hour = time_parts.next().map(|part| number(part, 2)).transpose()?.map(|n| n as u8);
+            result.minute = time_parts.next().map(|part| number(part, 2)).transpose()?.map(|n| n as u8);
+            result.second = time_parts.next().map(|part| number(part, 2)).transpose()?.map(|n| n as u8);
+            if time_parts.next().is_some() {
+                return Err(invalid());
+            }
+        }
+        result.validate().map_err(|_| invalid())?;
+        Ok(result)
+    }
+}
+
+impl fmt::Display for RecordingDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        let parts = [(self.month, "-"), (self.day, "-"), (self.hour, "T"), (self.minute, ":"), (self.second, ":")];
+        for (value, separator) in parts {
+            match value {
+                Some(value) => write!(f, "{}{:02}", separator, value)?,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+}
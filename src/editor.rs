@@ -0,0 +1,125 @@
+//! Fluent tag editing
+//!
+//! ```no_run
+//! use mp3tags_r::{MetaEntry, Tag};
+//!
+//! Tag::open("song.mp3")?
+//!     .title("Title")
+//!     .artist("Artist")
+//!     .remove(MetaEntry::Comment)
+//!     .save()?;
+//! # Ok::<(), mp3tags_r::Error>(())
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::file_access::FileManager;
+use crate::meta_entry::MetaEntry;
+use crate::tag::{detect_tags, TagType, TagWriter};
+
+/// Changes to the tag of a file, written at once by `save`
+#[derive(Debug, Clone)]
+#[must_use = "changes are only written by `save`"]
+pub struct Tag {
+    path: PathBuf,
+    tag_type: TagType,
+    /// Entries to set, or to remove when the value is `None`, in call order
+    changes: Vec<(MetaEntry, Option<String>)>,
+}
+
+impl Tag {
+    /// Edit the first tag found in the file, trying ID3v2, then APE, then ID3v1
+    ///
+    /// A file without tags gets an ID3v2 tag.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let presence = detect_tags(path.as_ref())?;
+        let tag_type = [TagType::Id3v2, TagType::Ape, TagType::Id3v1]
+            .into_iter()
+            .find(|&tag_type| presence.has(tag_type))
+            .unwrap_or(TagType::Id3v2);
+        Self::open_as(path, tag_type)
+    }
+
+    /// Edit the tag of the given type, creating it on `save` if needed
+    pub fn open_as<P: AsRef<Path>>(path: P, tag_type: TagType) -> Result<Self> {
+        let path = path.as_ref();
+        FileManager::with_default_strategy().validate_file_path(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            tag_type,
+            changes: Vec::new(),
+        })
+    }
+
+    /// The tag type the changes are written to
+    pub fn tag_type(&self) -> TagType {
+        self.tag_type
+    }
+
+    /// Set an entry
+    pub fn set(mut self, entry: MetaEntry, value: &str) -> Self {
+        self.changes.push((entry, Some(value.to_string())));
+        self
+    }
+
+    /// Remove an entry
+    pub fn remove(mut self, entry: MetaEntry) -> Self {
+        self.changes.push((entry, None));
+        self
+    }
+
+    /// Set the title
+    pub fn title(self, value: &str) -> Self {
+        self.set(MetaEntry::Title, value)
+    }
+
+    /// Set the artist
+    pub fn artist(self, value: &str) -> Self {
+        self.set(MetaEntry::Artist, value)
+    }
+
+    /// Set the album
+    pub fn album(self, value: &str) -> Self {
+        self.set(MetaEntry::Album, value)
+    }
+
+    /// Set the year
+    pub fn year(self, value: &str) -> Self {
+        self.set(MetaEntry::Year, value)
+    }
+
+    /// Set the track number, e.g. "3" or "3/12"
+    pub fn track(self, value: &str) -> Self {
+        self.set(MetaEntry::Track, value)
+    }
+
+    /// Set the genre
+    pub fn genre(self, value: &str) -> Self {
+        self.set(MetaEntry::Genre, value)
+    }
+
+    /// Set the comment
+    pub fn comment(self, value: &str) -> Self {
+        self.set(MetaEntry::Comment, value)
+    }
+
+    /// Apply the changes with a single write of the tag
+    pub fn save(self) -> Result<()> {
+        if self.changes.is_empty() {
+            return Ok(());
+        }
+        let mut writer = TagWriter::builder(&self.path)
+            .tag_type(self.tag_type)
+            .deferred(true)
+            .fallback(false)
+            .build()?;
+        for (entry, value) in &self.changes {
+            match value {
+                Some(value) => writer.set_meta_entry(entry, value)?,
+                None => writer.remove_meta_entry(entry)?,
+            }
+        }
+        writer.save()
+    }
+}
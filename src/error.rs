@@ -5,6 +5,7 @@ use thiserror::Error;
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// Error types for the mp3tags_r library
+use crate::tag::TagType;
 use crate::validation::ValidationError;
 
 #[derive(Error, Debug)]
@@ -97,7 +98,27 @@ pub enum Error {
     #[error("File not found: {0}")]
     FileNotFound(String),
 
+    /// Error when the CRC stored in a tag does not match its data
+    #[error("CRC mismatch: tag declares {0:08X}, data has {1:08X}")]
+    CrcMismatch(u32, u32),
+
+    /// Error when an APE item flagged read-only would be modified
+    #[error("APE item is read-only: {0}")]
+    ReadOnlyItem(String),
+
     /// Error when a meta entry is not found
     #[error("Meta entry not found")]
     EntryNotFound,
+
+    /// Error when an operation was stopped through its `CancellationToken`
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    /// Error when a change reached one tag but could not be synced to another
+    #[error("Written to the {written:?} tag but not to the {failed:?} tag: {source}")]
+    PartialWrite {
+        written: TagType,
+        failed: TagType,
+        source: Box<Error>,
+    },
 }
@@ -1,7 +1,28 @@
 use std::fs::File;
+use std::ops::Deref;
 use std::path::Path;
 use crate::Result;
 
+/// The whole contents of a file, read into memory or mapped
+#[derive(Debug)]
+pub enum FileContents {
+    Buffered(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl Deref for FileContents {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Buffered(data) => data,
+            #[cfg(feature = "mmap")]
+            Self::Mapped(map) => map,
+        }
+    }
+}
+
 /// Strategy trait for different file access patterns
 pub trait FileAccessStrategy: Send + Sync {
     /// Open a file for reading
@@ -18,6 +39,11 @@ pub trait FileAccessStrategy: Send + Sync {
     
     /// Get file metadata
     fn metadata(&self, path: &Path) -> Result<std::fs::Metadata>;
+
+    /// The whole file for parsing from memory; read into a buffer by default
+    fn read_contents(&self, path: &Path) -> Result<FileContents> {
+        Ok(FileContents::Buffered(std::fs::read(path)?))
+    }
 }
 
 
@@ -50,6 +76,49 @@ impl FileAccessStrategy for StandardFileAccess {
 }
 
 
+/// File access that maps files into memory for reading
+///
+/// Parsing a mapped file reads only the pages that are touched, without a
+/// seek and read per probe. The file must not be truncated by another process
+/// while it is mapped.
+#[cfg(feature = "mmap")]
+pub struct MappedFileAccess;
+
+#[cfg(feature = "mmap")]
+impl FileAccessStrategy for MappedFileAccess {
+    fn open_for_read(&self, path: &Path) -> Result<File> {
+        StandardFileAccess.open_for_read(path)
+    }
+
+    fn open_for_write(&self, path: &Path) -> Result<File> {
+        StandardFileAccess.open_for_write(path)
+    }
+
+    fn open_for_read_write(&self, path: &Path) -> Result<File> {
+        StandardFileAccess.open_for_read_write(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        StandardFileAccess.exists(path)
+    }
+
+    fn metadata(&self, path: &Path) -> Result<std::fs::Metadata> {
+        StandardFileAccess.metadata(path)
+    }
+
+    fn read_contents(&self, path: &Path) -> Result<FileContents> {
+        let file = File::open(path)?;
+        // Empty files cannot be mapped on every platform
+        if file.metadata()?.len() == 0 {
+            return Ok(FileContents::Buffered(Vec::new()));
+        }
+        // SAFETY: the map is only read, and is valid as long as no other
+        // process truncates the file, as documented on `MappedFileAccess`
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(FileContents::Mapped(map))
+    }
+}
+
 /// Factory for creating file access strategies
 pub struct FileAccessFactory;
 
@@ -59,6 +128,12 @@ impl FileAccessFactory {
         Box::new(StandardFileAccess)
     }
     
+    /// Create a strategy that maps files into memory for reading
+    #[cfg(feature = "mmap")]
+    pub fn create_mapped() -> Box<dyn FileAccessStrategy> {
+        Box::new(MappedFileAccess)
+    }
+    
     /// Create the default strategy (standard for now)
     pub fn create_default() -> Box<dyn FileAccessStrategy> {
         Self::create_standard()
@@ -106,6 +181,11 @@ impl FileManager {
         self.strategy.metadata(path)
     }
     
+    /// The whole file for parsing from memory
+    pub fn read_contents(&self, path: &Path) -> Result<FileContents> {
+        self.strategy.read_contents(path)
+    }
+    
     /// Validate that a path exists and is a readable file
     pub fn validate_file_path(&self, path: &Path) -> Result<()> {
         if !self.exists(path) {
@@ -127,10 +207,54 @@ impl FileManager {
     }
 }
 
+/// Exclusive advisory lock on a file, released when dropped
+///
+/// Writers hold it across their read-modify-write cycle so that concurrent
+/// writers, in this or another process, do not interleave. Other programs
+/// are only kept out if they take advisory locks as well.
+///
+/// Only unix platforms lock. Elsewhere locks are mandatory, so the lock would
+/// make the writer's own handles fail, and acquiring it does nothing.
+#[derive(Debug)]
+pub struct FileLock {
+    _file: Option<File>,
+}
+
+impl FileLock {
+    /// Block until the lock on `path` is acquired
+    #[cfg(unix)]
+    pub fn acquire(path: &Path) -> Result<Self> {
+        loop {
+            let file = File::open(path)?;
+            file.lock()?;
+            // A writer may have replaced the file (temp file + rename) while we
+            // waited; the lock is then on the old file and must be retaken
+            if is_same_file(&file, path)? {
+                return Ok(Self { _file: Some(file) });
+            }
+        }
+    }
+
+    /// Block until the lock on `path` is acquired
+    #[cfg(not(unix))]
+    pub fn acquire(_path: &Path) -> Result<Self> {
+        Ok(Self { _file: None })
+    }
+}
+
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let locked = file.metadata()?;
+    let current = std::fs::metadata(path)?;
+    Ok(locked.dev() == current.dev() && locked.ino() == current.ino())
+}
+
 /// Global default file manager instance
 static DEFAULT_FILE_MANAGER: std::sync::OnceLock<FileManager> = std::sync::OnceLock::new();
 
 /// Get the default file manager instance
 pub fn default_file_manager() -> &'static FileManager {
-    DEFAULT_FILE_MANAGER.get_or_init(|| FileManager::with_default_strategy())
+    DEFAULT_FILE_MANAGER.get_or_init(FileManager::with_default_strategy)
 }
@@ -4,6 +4,9 @@ pub const HEADER_SIZE: usize = 10;
 /// ID3v2 identifier
 pub const ID3V2_IDENTIFIER: &[u8] = b"ID3";
 
+/// Default padding appended after ID3v2 frames when a tag is written out of place
+pub const ID3V2_PADDING_SIZE: usize = 2048;
+
 /// ID3v1 tag size
 pub const ID3V1_TAG_SIZE: usize = 128;
 
@@ -0,0 +1,209 @@
+//! Genre table shared by ID3v1 genre bytes and ID3v2 TCON references
+
+use std::fmt;
+
+use crate::id3::v2::version::Version;
+
+/// Genre names indexed by their ID3v1 byte
+///
+/// 0–79 are the standard ID3v1 genres, 80–191 the Winamp extensions.
+pub const GENRES: [&str; 192] = [
+    // 0–79: ID3v1 standard
+    "Blues", "Classic Rock", "Country", "Dance", "Disco", "Funk", "Grunge", "Hip-Hop",
+    "Jazz", "Metal", "New Age", "Oldies", "Other", "Pop", "R&B", "Rap",
+    "Reggae", "Rock", "Techno", "Industrial", "Alternative", "Ska", "Death Metal", "Pranks",
+    "Soundtrack", "Euro-Techno", "Ambient", "Trip-Hop", "Vocal", "Jazz+Funk", "Fusion", "Trance",
+    "Classical", "Instrumental", "Acid", "House", "Game", "Sound Clip", "Gospel", "Noise",
+    "AlternRock", "Bass", "Soul", "Punk", "Space", "Meditative", "Instrumental Pop", "Instrumental Rock",
+    "Ethnic", "Gothic", "Darkwave", "Techno-Industrial", "Electronic", "Pop-Folk", "Eurodance", "Dream",
+    "Southern Rock", "Comedy", "Cult", "Gangsta", "Top 40", "Christian Rap", "Pop/Funk", "Jungle",
+    "Native American", "Cabaret", "New Wave", "Psychadelic", "Rave", "Showtunes", "Trailer", "Lo-Fi",
+    "Tribal", "Acid Punk", "Acid Jazz", "Polka", "Retro", "Musical", "Rock & Roll", "Hard Rock",
+    // 80–125: Winamp extensions
+    "Folk", "Folk-Rock", "National Folk", "Swing", "Fast Fusion", "Bebob", "Latin", "Revival",
+    "Celtic", "Bluegrass", "Avantgarde", "Gothic Rock", "Progressive Rock", "Psychedelic Rock", "Symphonic Rock", "Slow Rock",
+    "Big Band", "Chorus", "Easy Listening", "Acoustic", "Humour", "Speech", "Chanson", "Opera",
+    "Chamber Music", "Sonata", "Symphony", "Booty Bass", "Primus", "Porn Groove", "Satire", "Slow Jam",
+    "Club", "Tango", "Samba", "Folklore", "Ballad", "Power Ballad", "Rhythmic Soul", "Freestyle",
+    "Duet", "Punk Rock", "Drum Solo", "A capella", "Euro-House", "Dance Hall",
+    // 126–141: Winamp 1.91
+    "Goa", "Drum & Bass", "Club-House", "Hardcore Techno", "Terror", "Indie", "BritPop", "Negerpunk",
+    "Polsk Punk", "Beat", "Christian Gangsta Rap", "Heavy Metal", "Black Metal", "Crossover", "Contemporary Christian", "Christian Rock",
+    // 142–147: Winamp 1.92
+    "Merengue", "Salsa", "Thrash Metal", "Anime", "Jpop", "Synthpop",
+    // 148–191: Winamp 5.6
+    "Abstract", "Art Rock", "Baroque", "Bhangra", "Big Beat", "Breakbeat", "Chillout", "Downtempo",
+    "Dub", "EBM", "Eclectic", "Electro", "Electroclash", "Emo", "Experimental", "Garage",
+    "Global", "IDM", "Illbient", "Industro-Goth", "Jam Band", "Krautrock", "Leftfield", "Lounge",
+    "Math Rock", "New Romantic", "Nu-Breakz", "Post-Punk", "Post-Rock", "Psytrance", "Shoegaze", "Space Rock",
+    "Trop Rock", "World Music", "Neoclassical", "Audiobook", "Audio Theatre", "Neue Deutsche Welle", "Podcast", "Indie Rock",
+    "G-Funk", "Dubstep", "Garage Rock", "Psybient",
+];
+
+/// Genre byte of an ID3v1 tag, also referenced by number from ID3v2 TCON frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Genre(pub u8);
+
+impl Genre {
+    /// Byte written when the genre is unknown or unset
+    pub const NONE: Genre = Genre(255);
+
+    /// Name of the genre, `None` for bytes outside the table (including 255)
+    pub fn name(self) -> Option<&'static str> {
+        GENRES.get(self.0 as usize).copied()
+    }
+
+    /// Look up a genre by its exact name, ignoring ASCII case
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        GENRES
+            .iter()
+            .position(|genre| genre.eq_ignore_ascii_case(name))
+            .map(|index| Genre(index as u8))
+    }
+
+    /// Resolve a user supplied genre leniently
+    ///
+    /// Tries the exact name, a genre number such as `17` or `(17)`, common
+    /// aliases, and finally the name ignoring case, spaces and punctuation.
+    pub fn lookup(name: &str) -> Option<Self> {
+        if let Some(genre) = Self::from_name(name) {
+            return Some(genre);
+        }
+
+        let name = name.trim();
+        let number = name.strip_prefix('(').and_then(|n| n.strip_suffix(')')).unwrap_or(name);
+        if let Ok(index) = number.parse::<u8>() {
+            return Genre(index).name().map(|_| Genre(index));
+        }
+
+        let key = normalize(name);
+        if let Some(&(_, genre)) = ALIASES.iter().find(|(alias, _)| *alias == key) {
+            return Self::from_name(genre);
+        }
+        GENRES
+            .iter()
+            .position(|genre| normalize(genre) == key)
+            .map(|index| Genre(index as u8))
+    }
+}
+
+/// Alternative spellings, keyed by their normalized form
+const ALIASES: &[(&str, &str)] = &[
+    ("randb", "R&B"),
+    ("rnb", "R&B"),
+    ("rhythmandblues", "R&B"),
+    ("altrock", "AlternRock"),
+    ("alternativerock", "AlternRock"),
+    ("rockandroll", "Rock & Roll"),
+    ("rocknroll", "Rock & Roll"),
+    ("drumandbass", "Drum & Bass"),
+    ("dnb", "Drum & Bass"),
+    ("psychedelic", "Psychadelic"),
+    ("acapella", "A capella"),
+    ("electronica", "Electronic"),
+    ("jazzfunk", "Jazz+Funk"),
+    ("popfunk", "Pop/Funk"),
+    ("bebop", "Bebob"),
+    ("humor", "Humour"),
+    ("ost", "Soundtrack"),
+    ("score", "Soundtrack"),
+    ("hiphoprap", "Hip-Hop"),
+    ("world", "World Music"),
+    ("spokenword", "Speech"),
+    ("audiobooks", "Audiobook"),
+];
+
+/// Lowercase ASCII letters and digits only, so "Hip Hop" matches "Hip-Hop"
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+impl fmt::Display for Genre {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => f.write_str(name),
+            None => write!(f, "({})", self.0),
+        }
+    }
+}
+
+/// How the ID3v2 writer stores a genre found in the genre table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenreFormat {
+    /// The reference only: "(17)", or "17" in ID3v2.4
+    Numeric,
+    /// The name only: "Rock"
+    Text,
+    /// Reference and name: "(17)Rock", or "17" and "Rock" as two values in ID3v2.4
+    Combined,
+}
+
+/// Genre names of a TCON value, resolving numeric references
+///
+/// Handles the ID3v2.3 forms "(13)", "(13)Pop" and "(13)(17)", whose refinement
+/// text takes precedence over the references, the "RX" (Remix) and "CR" (Cover)
+/// keywords, "((" escapes, and the null separated values of ID3v2.4. Duplicate
+/// names are dropped.
+pub fn parse_tcon(value: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for value in value.split('\0').map(str::trim).filter(|value| !value.is_empty()) {
+        let mut references = Vec::new();
+        let mut rest = value;
+        while let Some(inner) = rest.strip_prefix('(').filter(|inner| !inner.starts_with('(')) {
+            match inner.split_once(')') {
+                Some((reference, after)) => {
+                    references.push(reference);
+                    rest = after;
+                }
+                None => break,
+            }
+        }
+        let refinement = rest.strip_prefix('(').filter(|rest| rest.starts_with('(')).unwrap_or(rest).trim();
+
+        let resolved: Vec<String> = if !refinement.is_empty() {
+            vec![resolve_reference(refinement).unwrap_or_else(|| refinement.to_string())]
+        } else {
+            references
+                .into_iter()
+                .map(|reference| resolve_reference(reference).unwrap_or_else(|| format!("({})", reference)))
+                .collect()
+        };
+        for name in resolved {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// Name of a bare reference: a genre number, "RX" or "CR"
+fn resolve_reference(reference: &str) -> Option<String> {
+    match reference {
+        "RX" => Some("Remix".to_string()),
+        "CR" => Some("Cover".to_string()),
+        _ => reference.parse::<u8>().ok().and_then(|index| Genre(index).name()).map(str::to_string),
+    }
+}
+
+/// TCON value for a genre name in the given format
+///
+/// Names outside the genre table are always stored as text.
+pub fn format_tcon(name: &str, format: GenreFormat, version: Version) -> String {
+    let genre = match Genre::lookup(name) {
+        Some(genre) => genre,
+        None => return name.to_string(),
+    };
+    let canonical = genre.name().unwrap_or(name);
+    match (format, version) {
+        (GenreFormat::Text, _) => canonical.to_string(),
+        (GenreFormat::Numeric, Version::V4) => genre.0.to_string(),
+        (GenreFormat::Numeric, _) => format!("({})", genre.0),
+        (GenreFormat::Combined, Version::V4) => format!("{}\0{}", genre.0, canonical),
+        (GenreFormat::Combined, _) => format!("({}){}", genre.0, canonical),
+    }
+}
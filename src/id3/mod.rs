@@ -1,7 +1,12 @@
 pub mod constants;
+pub mod genre;
 pub mod v1;
 pub mod v2;
+pub mod sync;
 
-pub use v1::tag::{TagReader as Id3v1TagReader, TagWriter as Id3v1TagWriter};
-pub use v2::tag::{TagReader as Id3v2TagReader, TagWriter as Id3v2TagWriter};
+pub use v1::tag::{remove_id3v1_tag, Tag as Id3v1Tag, TagReader as Id3v1TagReader, TagWriter as Id3v1TagWriter};
+pub use v2::tag::{Tag as Id3v2Tag, TagReader as Id3v2TagReader, TagWriter as Id3v2TagWriter};
+pub use v2::convert::convert_version;
+pub use v2::stream::FrameIter;
 pub use v2::version::Version as Id3v2Version;
+pub use sync::{sync_tags, SyncDirection, SyncReport};
@@ -0,0 +1,89 @@
+//! Copy the core fields between the ID3v1 and ID3v2 tag of a file
+
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::id3::v1::report::WriteReport;
+use crate::id3::v1::tag::{TagReader as V1Reader, TagWriter as V1Writer};
+use crate::id3::v2::tag::{TagReader as V2Reader, TagWriter as V2Writer};
+use crate::meta_entry::MetaEntry;
+use crate::tag::{TagReaderStrategy, TagWriterStrategy};
+
+/// Fields present in both ID3v1 and ID3v2
+pub const SYNCED_ENTRIES: [MetaEntry; 6] = [
+    MetaEntry::Title,
+    MetaEntry::Artist,
+    MetaEntry::Album,
+    MetaEntry::Year,
+    MetaEntry::Comment,
+    MetaEntry::Genre,
+];
+
+/// Which tag is the source of a sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncDirection {
+    /// Copy the ID3v1 fields into the ID3v2 tag
+    V1ToV2,
+    /// Copy the ID3v2 fields into the ID3v1 tag, creating it if needed
+    V2ToV1,
+}
+
+/// Outcome of `sync_tags`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Entries copied from the source tag
+    pub copied: Vec<MetaEntry>,
+    /// Values truncated or dropped by the ID3v1 tag (only for `V2ToV1`)
+    pub lossy: WriteReport,
+}
+
+/// Copy title, artist, album, year, comment and genre from one tag to the other
+///
+/// Entries missing from the source are left alone in the target. Fails with
+/// `TagNotFound` when the file has no source tag.
+pub fn sync_tags<P: AsRef<Path>>(path: P, direction: SyncDirection) -> Result<SyncReport> {
+    let path = path.as_ref();
+    let values = match direction {
+        SyncDirection::V1ToV2 => read_entries(&mut V1Reader::new(), path)?,
+        SyncDirection::V2ToV1 => read_entries(&mut V2Reader::new(), path)?,
+    };
+
+    let mut report = SyncReport {
+        copied: values.iter().map(|(entry, _)| entry.clone()).collect(),
+        ..Default::default()
+    };
+    match direction {
+        SyncDirection::V1ToV2 => {
+            let mut writer = V2Writer::new();
+            writer.init(path)?;
+            writer.set_deferred(true);
+            for (entry, value) in &values {
+                writer.set_meta_entry(entry, value)?;
+            }
+            writer.save()?;
+        }
+        SyncDirection::V2ToV1 => {
+            let mut writer = V1Writer::new();
+            writer.init(path)?;
+            for (entry, value) in &values {
+                writer.set_meta_entry(entry, value)?;
+            }
+            writer.save()?;
+            report.lossy = writer.take_report();
+        }
+    }
+    Ok(report)
+}
+
+fn read_entries(reader: &mut dyn TagReaderStrategy, path: &Path) -> Result<Vec<(MetaEntry, String)>> {
+    reader.init(path)?;
+    let mut values = Vec::new();
+    for entry in SYNCED_ENTRIES {
+        match reader.get_meta_entry(path, &entry) {
+            Ok(value) if !value.is_empty() => values.push((entry, value)),
+            Ok(_) | Err(Error::EntryNotFound) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(values)
+}
@@ -0,0 +1,117 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::id3::constants::{ID3V1_IDENTIFIER, ID3V1_TAG_SIZE};
+use crate::id3::v1::tag::field_text;
+
+/// Identifier of the enhanced ("TAG+") block
+pub const ENHANCED_IDENTIFIER: &[u8] = b"TAG+";
+/// Size of the enhanced block, which directly precedes the ID3v1 tag
+pub const ENHANCED_TAG_SIZE: usize = 227;
+/// Bytes each of title, artist and album continue by
+pub const ENHANCED_TEXT_SIZE: usize = 60;
+/// Size of the free-form genre field
+pub const ENHANCED_GENRE_SIZE: usize = 30;
+
+const TITLE_RANGE: std::ops::Range<usize> = 4..64;
+const ARTIST_RANGE: std::ops::Range<usize> = 64..124;
+const ALBUM_RANGE: std::ops::Range<usize> = 124..184;
+const SPEED_OFFSET: usize = 184;
+const GENRE_RANGE: std::ops::Range<usize> = 185..215;
+const START_TIME_RANGE: std::ops::Range<usize> = 215..221;
+const END_TIME_RANGE: std::ops::Range<usize> = 221..227;
+
+/// Enhanced ID3v1 block ("TAG+")
+///
+/// Title, artist and album continue the 30-byte ID3v1 fields with up to 60
+/// more bytes each. Times are `mmm:ss` strings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnhancedTag {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    /// 0 unset, 1 slow, 2 medium, 3 fast, 4 hardcore
+    pub speed: u8,
+    /// Free-form genre name
+    pub genre: String,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+impl EnhancedTag {
+    /// Parse an enhanced block, `None` if it does not start with `TAG+`
+    pub fn parse(data: &[u8; ENHANCED_TAG_SIZE]) -> Option<Self> {
+        if &data[..ENHANCED_IDENTIFIER.len()] != ENHANCED_IDENTIFIER {
+            return None;
+        }
+        Some(Self {
+            title: field_text(&data[TITLE_RANGE]),
+            artist: field_text(&data[ARTIST_RANGE]),
+            album: field_text(&data[ALBUM_RANGE]),
+            speed: data[SPEED_OFFSET],
+            genre: field_text(&data[GENRE_RANGE]),
+            start_time: field_text(&data[START_TIME_RANGE]),
+            end_time: field_text(&data[END_TIME_RANGE]),
+        })
+    }
+
+    /// Serialize the block, cutting fields that do not fit
+    pub fn to_bytes(&self) -> [u8; ENHANCED_TAG_SIZE] {
+        let mut data = [0u8; ENHANCED_TAG_SIZE];
+        data[..ENHANCED_IDENTIFIER.len()].copy_from_slice(ENHANCED_IDENTIFIER);
+        copy_field(&mut data[TITLE_RANGE], &self.title);
+        copy_field(&mut data[ARTIST_RANGE], &self.artist);
+        copy_field(&mut data[ALBUM_RANGE], &self.album);
+        data[SPEED_OFFSET] = self.speed;
+        copy_field(&mut data[GENRE_RANGE], &self.genre);
+        copy_field(&mut data[START_TIME_RANGE], &self.start_time);
+        copy_field(&mut data[END_TIME_RANGE], &self.end_time);
+        data
+    }
+
+    /// Read the enhanced block in front of the file's ID3v1 tag, if there is one
+    pub fn read_from_file(path: &Path) -> Result<Option<Self>> {
+        let mut file = File::open(path)?;
+        let file_len = file.seek(SeekFrom::End(0))?;
+        let block_len = (ENHANCED_TAG_SIZE + ID3V1_TAG_SIZE) as u64;
+        if file_len < block_len {
+            return Ok(None);
+        }
+
+        let mut data = [0u8; ENHANCED_TAG_SIZE];
+        let mut identifier = [0u8; 3];
+        file.seek(SeekFrom::End(-(block_len as i64)))?;
+        file.read_exact(&mut data)?;
+        file.read_exact(&mut identifier)?;
+        if identifier != ID3V1_IDENTIFIER {
+            return Ok(None);
+        }
+        Ok(Self::parse(&data))
+    }
+
+    /// Overwrite the TAG+ block in front of the file's ID3v1 tag
+    ///
+    /// Fails with `TagNotFound` when the file has no such block; a new one is
+    /// never inserted.
+    pub fn write_to_file(&self, path: &Path) -> Result<()> {
+        if Self::read_from_file(path)?.is_none() {
+            return Err(Error::TagNotFound);
+        }
+        let mut file = OpenOptions::new().write(true).open(path)?;
+        file.seek(SeekFrom::End(-((ENHANCED_TAG_SIZE + ID3V1_TAG_SIZE) as i64)))?;
+        file.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+/// Whether the file has a TAG+ block in front of its ID3v1 tag
+pub fn has_enhanced_tag(path: &Path) -> Result<bool> {
+    Ok(EnhancedTag::read_from_file(path)?.is_some())
+}
+
+fn copy_field(field: &mut [u8], text: &str) {
+    let len = text.len().min(field.len());
+    field[..len].copy_from_slice(&text.as_bytes()[..len]);
+}
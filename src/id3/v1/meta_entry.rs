@@ -8,6 +8,7 @@ pub fn supported_entries() -> Vec<MetaEntry> {
         MetaEntry::Album,
         MetaEntry::Year,
         MetaEntry::Comment,
+        MetaEntry::Genre,
         // Note: ID3v1 doesn't support the extended entries like Date, TextWriter, etc.
     ]
 }
@@ -19,6 +20,7 @@ pub fn is_supported(entry: &MetaEntry) -> bool {
         MetaEntry::Artist |
         MetaEntry::Album |
         MetaEntry::Year |
-        MetaEntry::Comment
+        MetaEntry::Comment |
+        MetaEntry::Genre
     )
 }
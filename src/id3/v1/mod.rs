@@ -1,6 +1,11 @@
 pub mod constants;
+pub mod enhanced;
 pub mod meta_entry;
+pub mod report;
 pub mod tag;
 
 pub use tag::{TagReader, TagWriter};
+pub use super::genre::{self, Genre};
+pub use enhanced::EnhancedTag;
+pub use report::{FieldIssue, WriteReport};
 pub use constants::*;
@@ -0,0 +1,48 @@
+use crate::meta_entry::MetaEntry;
+
+/// How a value was altered or dropped when written to an ID3v1 tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldIssue {
+    /// The value was cut to fit the fixed-size field
+    Truncated { original: String, written: String },
+    /// The genre is not in the genre table and was stored as "no genre"
+    UnknownGenre(String),
+    /// ID3v1 has no field for the entry, so the value was not written
+    Unsupported(String),
+    /// ID3v1 fields cannot be removed, so the entry was blanked instead
+    Blanked,
+}
+
+/// Values that could not be stored losslessly by an ID3v1 writer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteReport {
+    pub issues: Vec<(MetaEntry, FieldIssue)>,
+}
+
+impl WriteReport {
+    /// Whether every value was written unchanged
+    pub fn is_lossless(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Entries whose values were truncated
+    pub fn truncated(&self) -> impl Iterator<Item = &MetaEntry> {
+        self.issues
+            .iter()
+            .filter(|(_, issue)| matches!(issue, FieldIssue::Truncated { .. }))
+            .map(|(entry, _)| entry)
+    }
+
+    pub(crate) fn push(&mut self, entry: &MetaEntry, issue: FieldIssue) {
+        self.issues.push((entry.clone(), issue));
+    }
+}
+
+/// Longest prefix of `value` that fits in `max` bytes without splitting a character
+pub fn truncate_to_boundary(value: &str, max: usize) -> &str {
+    if value.len() <= max {
+        return value;
+    }
+    let end = (0..=max).rev().find(|&i| value.is_char_boundary(i)).unwrap_or(0);
+    &value[..end]
+}
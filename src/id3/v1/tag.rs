@@ -2,10 +2,16 @@ use std::path::{Path, PathBuf};
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Write, Seek, SeekFrom};
 
+use crate::date::RecordingDate;
 use crate::error::{Error, Result};
+use crate::file_access::FileLock;
 use crate::meta_entry::MetaEntry;
 use crate::tag::{TagType, TagReaderStrategy, TagWriterStrategy};
 use crate::id3::constants::{ID3V1_TAG_SIZE, ID3V1_IDENTIFIER};
+use crate::id3::v1::enhanced::{EnhancedTag, ENHANCED_GENRE_SIZE, ENHANCED_TAG_SIZE, ENHANCED_TEXT_SIZE};
+use crate::id3::v1::report::{truncate_to_boundary, FieldIssue, WriteReport};
+use crate::id3::genre::Genre;
+use crate::util::{self, PreserveOptions};
 
 // ID3v1 field sizes
 const TITLE_SIZE: usize = 30;
@@ -34,16 +40,53 @@ pub fn has_id3v1_tag(path: &std::path::Path) -> crate::Result<bool> {
     Ok(tag == ID3V1_IDENTIFIER)
 }
 
+/// Remove the ID3v1 tag by truncating the file, returning whether a tag was present
+///
+/// Only a block starting with the `TAG` identifier is removed, together with a
+/// TAG+ block in front of it; files without one are left untouched.
+pub fn remove_id3v1_tag(path: &Path) -> Result<bool> {
+    let _lock = FileLock::acquire(path)?;
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let file_len = file.seek(SeekFrom::End(0))?;
+    if file_len < ID3V1_TAG_SIZE as u64 {
+        return Ok(false);
+    }
+
+    let mut identifier = [0u8; IDENTIFIER_SIZE];
+    file.seek(SeekFrom::End(-(ID3V1_TAG_SIZE as i64)))?;
+    file.read_exact(&mut identifier)?;
+    if identifier != ID3V1_IDENTIFIER {
+        return Ok(false);
+    }
+
+    let mut removed = ID3V1_TAG_SIZE as u64;
+    if EnhancedTag::read_from_file(path)?.is_some() {
+        removed += ENHANCED_TAG_SIZE as u64;
+    }
+    file.set_len(file_len - removed)?;
+    Ok(true)
+}
+
+/// Text of a fixed-size field without its NUL or space padding
+pub(crate) fn field_text(field: &[u8]) -> String {
+    String::from_utf8_lossy(field).trim_end_matches(['\0', ' ']).to_string()
+}
+
 #[derive(Debug)]
 pub struct TagReader {
     path: PathBuf,
     tag: Option<Tag>,
+    enhanced: Option<EnhancedTag>,
 }
 
 #[derive(Debug)]
 pub struct TagWriter {
     path: PathBuf,
     tag: Option<Tag>,
+    enhanced: Option<EnhancedTag>,
+    report: WriteReport,
+    /// Only `mtime` applies, as the tag is written in place
+    preserve: PreserveOptions,
 }
 
 /// ID3v1 tag implementation
@@ -68,8 +111,14 @@ impl TagReader {
         Self {
             path: PathBuf::new(),
             tag: None,
+            enhanced: None,
         }
     }
+
+    /// The TAG+ block read by `init`, if the file has one
+    pub fn enhanced(&self) -> Option<&EnhancedTag> {
+        self.enhanced.as_ref()
+    }
 }
 
 impl Default for TagWriter {
@@ -83,27 +132,87 @@ impl TagWriter {
         Self {
             path: PathBuf::new(),
             tag: None,
+            enhanced: None,
+            report: WriteReport::default(),
+            preserve: PreserveOptions::default(),
         }
     }
+
+    /// Choose which attributes of the file survive a write; the tag is written
+    /// in place, so only the modification time can change
+    pub fn set_preserve_options(&mut self, preserve: PreserveOptions) {
+        self.preserve = preserve;
+    }
+
+    /// Values truncated or dropped by `set_meta_entry` since `init`
+    pub fn report(&self) -> &WriteReport {
+        &self.report
+    }
+
+    /// Take the report, leaving an empty one behind
+    pub fn take_report(&mut self) -> WriteReport {
+        std::mem::take(&mut self.report)
+    }
 }
 
 impl TagReaderStrategy for TagReader {
     fn init(&mut self, path: &Path) -> Result<()> {
         self.path = path.to_path_buf();
+        self.tag = None;
+        self.enhanced = None;
         if has_id3v1_tag(path).unwrap_or(false) {
             self.tag = Some(Tag::read_from_file(path)?);
+            self.enhanced = EnhancedTag::read_from_file(path)?;
+        }
+        Ok(())
+    }
+
+    fn init_from_file(&mut self, path: &Path, file: &mut File) -> Result<()> {
+        self.path = path.to_path_buf();
+        // The tag and a TAG+ block in front of it are all that is needed
+        let file_len = file.seek(SeekFrom::End(0))?;
+        let tail_len = file_len.min((ENHANCED_TAG_SIZE + ID3V1_TAG_SIZE) as u64);
+        let mut tail = vec![0u8; tail_len as usize];
+        file.seek(SeekFrom::End(-(tail_len as i64)))?;
+        file.read_exact(&mut tail)?;
+        self.init_from_bytes(path, &tail)
+    }
+
+    fn init_from_bytes(&mut self, _path: &Path, data: &[u8]) -> Result<()> {
+        self.tag = None;
+        self.enhanced = None;
+        let Some(tag_start) = data.len().checked_sub(ID3V1_TAG_SIZE) else {
+            return Ok(());
+        };
+        if let Ok(tag) = Tag::parse(data[tag_start..].try_into().unwrap()) {
+            self.tag = Some(tag);
+            self.enhanced = tag_start
+                .checked_sub(ENHANCED_TAG_SIZE)
+                .and_then(|start| EnhancedTag::parse(data[start..tag_start].try_into().unwrap()));
         }
         Ok(())
     }
 
     fn get_meta_entry(&self, _path: &Path, entry: &MetaEntry) -> Result<String> {
         if let Some(tag) = &self.tag {
+            // TAG+ continues title, artist and album where the ID3v1 fields end
+            let extended = |field: &[u8], more: fn(&EnhancedTag) -> &str| {
+                let mut text = field_text(field);
+                if let Some(enhanced) = &self.enhanced {
+                    text.push_str(more(enhanced));
+                }
+                text
+            };
             match entry {
-                MetaEntry::Title => Ok(String::from_utf8_lossy(&tag.title).trim_end().to_string()),
-                MetaEntry::Artist => Ok(String::from_utf8_lossy(&tag.artist).trim_end().to_string()),
-                MetaEntry::Album => Ok(String::from_utf8_lossy(&tag.album).trim_end().to_string()),
-                MetaEntry::Year => Ok(String::from_utf8_lossy(&tag.year).trim_end().to_string()),
-                MetaEntry::Comment => Ok(String::from_utf8_lossy(&tag.comment).trim_end().to_string()),
+                MetaEntry::Title => Ok(extended(&tag.title, |e| &e.title)),
+                MetaEntry::Artist => Ok(extended(&tag.artist, |e| &e.artist)),
+                MetaEntry::Album => Ok(extended(&tag.album, |e| &e.album)),
+                MetaEntry::Year => Ok(field_text(&tag.year)),
+                MetaEntry::Comment => Ok(field_text(&tag.comment)),
+                MetaEntry::Genre => match &self.enhanced {
+                    Some(enhanced) if !enhanced.genre.is_empty() => Ok(enhanced.genre.clone()),
+                    _ => Genre(tag.genre[0]).name().map(str::to_string).ok_or(Error::EntryNotFound),
+                },
                 _ => Err(Error::EntryNotFound),
             }
         } else {
@@ -119,30 +228,96 @@ impl TagReaderStrategy for TagReader {
 impl TagWriterStrategy for TagWriter {
     fn init(&mut self, path: &Path) -> Result<()> {
         self.path = path.to_path_buf();
+        self.report = WriteReport::default();
         if has_id3v1_tag(path).unwrap_or(false) {
             self.tag = Some(Tag::read_from_file(path)?);
+            self.enhanced = EnhancedTag::read_from_file(path)?;
         } else {
             self.tag = Some(Tag::new());
+            self.enhanced = None;
         }
         Ok(())
     }
 
     fn set_meta_entry(&mut self, entry: &MetaEntry, value: &str) -> Result<()> {
         let tag = self.tag.get_or_insert_with(Tag::new);
-        match entry {
-            MetaEntry::Title => tag.title[..value.len().min(TITLE_SIZE)].copy_from_slice(value.as_bytes()),
-            MetaEntry::Artist => tag.artist[..value.len().min(ARTIST_SIZE)].copy_from_slice(value.as_bytes()),
-            MetaEntry::Album => tag.album[..value.len().min(ALBUM_SIZE)].copy_from_slice(value.as_bytes()),
-            MetaEntry::Year => tag.year[..value.len().min(YEAR_SIZE)].copy_from_slice(value.as_bytes()),
-            MetaEntry::Comment => tag.comment[..value.len().min(COMMENT_SIZE)].copy_from_slice(value.as_bytes()),
-            _ => return Ok(()),
+        let (field, continuation): (&mut [u8], Option<&mut String>) = match entry {
+            MetaEntry::Title => (&mut tag.title, self.enhanced.as_mut().map(|e| &mut e.title)),
+            MetaEntry::Artist => (&mut tag.artist, self.enhanced.as_mut().map(|e| &mut e.artist)),
+            MetaEntry::Album => (&mut tag.album, self.enhanced.as_mut().map(|e| &mut e.album)),
+            MetaEntry::Year => (&mut tag.year, None),
+            MetaEntry::Comment => (&mut tag.comment, None),
+            MetaEntry::Genre => {
+                // Unknown names are stored as "no genre"; TAG+ keeps the name itself
+                let genre = Genre::lookup(value);
+                tag.genre = [genre.unwrap_or(Genre::NONE).0];
+                match &mut self.enhanced {
+                    Some(enhanced) => enhanced.genre = truncate_to_boundary(value, ENHANCED_GENRE_SIZE).to_string(),
+                    None if genre.is_none() && !value.is_empty() => {
+                        self.report.push(entry, FieldIssue::UnknownGenre(value.to_string()));
+                    }
+                    None => {}
+                }
+                return Ok(());
+            }
+            _ => {
+                if !value.is_empty() {
+                    self.report.push(entry, FieldIssue::Unsupported(value.to_string()));
+                }
+                return Ok(());
+            }
+        };
+
+        let head = truncate_to_boundary(value, field.len());
+        field.fill(0);
+        field[..head.len()].copy_from_slice(head.as_bytes());
+        let mut written = head.len();
+        // With a TAG+ block the text continues there
+        if let Some(continuation) = continuation {
+            let tail = truncate_to_boundary(&value[head.len()..], ENHANCED_TEXT_SIZE);
+            *continuation = tail.to_string();
+            written += tail.len();
+        }
+
+        if written < value.len() {
+            self.report.push(entry, FieldIssue::Truncated {
+                original: value.to_string(),
+                written: value[..written].to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// ID3v1 fields cannot be removed, so supported entries are blanked and reported
+    fn remove_meta_entry(&mut self, entry: &MetaEntry) -> Result<()> {
+        let supported = matches!(
+            entry,
+            MetaEntry::Title | MetaEntry::Artist | MetaEntry::Album | MetaEntry::Year | MetaEntry::Comment | MetaEntry::Genre
+        );
+        if !supported {
+            return Ok(());
         }
+        self.set_meta_entry(entry, "")?;
+        self.report.push(entry, FieldIssue::Blanked);
         Ok(())
     }
 
+    /// The year field holds only the year of a date
+    fn set_date(&mut self, date: &RecordingDate) -> Result<()> {
+        self.set_meta_entry(&MetaEntry::Year, &format!("{:04}", date.year))
+    }
+
     fn save(&mut self) -> Result<()> {
         if let Some(tag) = &self.tag {
+            let _lock = FileLock::acquire(&self.path)?;
+            let metadata = std::fs::metadata(&self.path)?;
             tag.write_to_file(&self.path)?;
+            if let Some(enhanced) = &self.enhanced {
+                enhanced.write_to_file(&self.path)?;
+            }
+            if self.preserve.mtime {
+                util::restore_times(&OpenOptions::new().write(true).open(&self.path)?, &metadata)?;
+            }
         }
         Ok(())
     }
@@ -153,8 +328,12 @@ impl TagWriterStrategy for TagWriter {
 }
 
 impl Tag {
+    /// An empty tag with the genre unset
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            genre: [Genre::NONE.0],
+            ..Self::default()
+        }
     }
 
     pub fn read_from_file(path: &Path) -> Result<Self> {
@@ -169,7 +348,11 @@ impl Tag {
         
         let mut tag_data = [0u8; ID3V1_TAG_SIZE];
         file.read_exact(&mut tag_data)?;
-        
+        Self::parse(&tag_data)
+    }
+
+    /// Parse the 128 bytes of a tag, failing with `TagNotFound` without the "TAG" identifier
+    pub fn parse(tag_data: &[u8; ID3V1_TAG_SIZE]) -> Result<Self> {
         if &tag_data[IDENTIFIER_OFFSET..IDENTIFIER_OFFSET + IDENTIFIER_SIZE] != ID3V1_IDENTIFIER {
             return Err(Error::TagNotFound);
         }
@@ -185,17 +368,21 @@ impl Tag {
         Ok(tag)
     }
 
+    /// Write the tag over the existing ID3v1 tag, or append it when the file has none
     pub fn write_to_file(&self, path: &Path) -> Result<()> {
         let mut file = OpenOptions::new()
+            .read(true)
             .write(true)
             .open(path)?;
         let file_len = file.seek(SeekFrom::End(0))?;
         
-        if file_len < ID3V1_TAG_SIZE as u64 {
-            return Err(Error::TagNotFound);
+        if file_len >= ID3V1_TAG_SIZE as u64 {
+            let mut identifier = [0u8; IDENTIFIER_SIZE];
+            file.seek(SeekFrom::End(-(ID3V1_TAG_SIZE as i64)))?;
+            file.read_exact(&mut identifier)?;
+            let offset = if identifier == ID3V1_IDENTIFIER { -(ID3V1_TAG_SIZE as i64) } else { 0 };
+            file.seek(SeekFrom::End(offset))?;
         }
-
-        file.seek(SeekFrom::End(-(ID3V1_TAG_SIZE as i64)))?;
         
         let mut tag_data = [0u8; ID3V1_TAG_SIZE];
         tag_data[IDENTIFIER_OFFSET..IDENTIFIER_OFFSET + IDENTIFIER_SIZE].copy_from_slice(ID3V1_IDENTIFIER);
@@ -0,0 +1,232 @@
+use crate::error::{Error, Result};
+use crate::id3::v2::encoding::{split_terminated, TextEncoding};
+use crate::id3::v2::frame::Frame;
+use crate::id3::v2::picture::Picture;
+use crate::id3::v2::version::Version;
+
+/// Byte offsets equal to this value are unused; times are authoritative
+const UNUSED_OFFSET: u32 = 0xFFFF_FFFF;
+
+const FLAG_ORDERED: u8 = 0x01;
+const FLAG_TOP_LEVEL: u8 = 0x02;
+
+/// Chapter (CHAP) with its embedded sub-frames, e.g. TIT2 title and APIC image
+#[derive(Debug, Clone)]
+pub struct Chapter {
+    /// Identifier referenced from tables of contents
+    pub element_id: String,
+    /// Start time in milliseconds
+    pub start_time: u32,
+    /// End time in milliseconds
+    pub end_time: u32,
+    /// Byte offset of the first audio frame, if the chapter uses offsets
+    pub start_offset: Option<u32>,
+    /// Byte offset of the first audio frame after the chapter
+    pub end_offset: Option<u32>,
+    pub sub_frames: Vec<Frame>,
+}
+
+impl Chapter {
+    pub fn new(element_id: &str, start_time: u32, end_time: u32) -> Self {
+        Self {
+            element_id: element_id.to_string(),
+            start_time,
+            end_time,
+            start_offset: None,
+            end_offset: None,
+            sub_frames: Vec::new(),
+        }
+    }
+
+    /// Chapter title from the TIT2 sub-frame
+    pub fn title(&self) -> Option<String> {
+        sub_frame_text(&self.sub_frames, "TIT2")
+    }
+
+    /// Set the TIT2 sub-frame
+    pub fn set_title(&mut self, title: &str, version: Version) {
+        set_sub_frame(&mut self.sub_frames, Frame::new_text("TIT2", title, version));
+    }
+
+    /// Chapter description from the TIT3 sub-frame
+    pub fn description(&self) -> Option<String> {
+        sub_frame_text(&self.sub_frames, "TIT3")
+    }
+
+    /// Set the TIT3 sub-frame
+    pub fn set_description(&mut self, description: &str, version: Version) {
+        set_sub_frame(&mut self.sub_frames, Frame::new_text("TIT3", description, version));
+    }
+
+    /// Chapter image from the APIC sub-frame
+    pub fn picture(&self, version: Version) -> Option<Picture> {
+        self.sub_frames
+            .iter()
+            .filter(|frame| frame.id == "APIC")
+            .find_map(|frame| Picture::parse(frame.data(), version).ok())
+    }
+
+    /// Set the APIC sub-frame
+    pub fn set_picture(&mut self, picture: &Picture, version: Version) {
+        set_sub_frame(&mut self.sub_frames, picture.to_frame(version));
+    }
+
+    /// Parse the payload of a CHAP frame
+    pub fn parse(data: &[u8], version: Version) -> Result<Self> {
+        let (element_id, rest) = split_terminated(data, TextEncoding::Latin1);
+        if rest.len() < 16 {
+            return Err(Error::ContentLengthExceedsFrameArea);
+        }
+        let read_u32 = |pos: usize| u32::from_be_bytes([rest[pos], rest[pos + 1], rest[pos + 2], rest[pos + 3]]);
+        let offset = |value: u32| if value == UNUSED_OFFSET { None } else { Some(value) };
+
+        Ok(Self {
+            element_id: TextEncoding::Latin1.decode(element_id),
+            start_time: read_u32(0),
+            end_time: read_u32(4),
+            start_offset: offset(read_u32(8)),
+            end_offset: offset(read_u32(12)),
+            sub_frames: parse_sub_frames(&rest[16..], version),
+        })
+    }
+
+    /// Serialize into a CHAP frame payload
+    pub fn to_frame_data(&self, version: Version) -> Vec<u8> {
+        let mut data = TextEncoding::Latin1.encode_terminated(&self.element_id);
+        data.extend_from_slice(&self.start_time.to_be_bytes());
+        data.extend_from_slice(&self.end_time.to_be_bytes());
+        data.extend_from_slice(&self.start_offset.unwrap_or(UNUSED_OFFSET).to_be_bytes());
+        data.extend_from_slice(&self.end_offset.unwrap_or(UNUSED_OFFSET).to_be_bytes());
+        for frame in &self.sub_frames {
+            data.extend_from_slice(&frame.to_bytes(version));
+        }
+        data
+    }
+
+    /// Build the CHAP frame
+    pub fn to_frame(&self, version: Version) -> Frame {
+        Frame::from_data("CHAP", self.to_frame_data(version))
+    }
+}
+
+/// Table of contents (CTOC) listing chapters or nested tables of contents
+#[derive(Debug, Clone)]
+pub struct TableOfContents {
+    pub element_id: String,
+    /// Whether this is the root of the table of contents tree
+    pub top_level: bool,
+    /// Whether the child elements are in playback order
+    pub ordered: bool,
+    /// Element IDs of the child chapters or tables of contents
+    pub child_element_ids: Vec<String>,
+    pub sub_frames: Vec<Frame>,
+}
+
+impl TableOfContents {
+    /// Create an ordered, top level table of contents
+    pub fn new(element_id: &str, child_element_ids: Vec<String>) -> Self {
+        Self {
+            element_id: element_id.to_string(),
+            top_level: true,
+            ordered: true,
+            child_element_ids,
+            sub_frames: Vec::new(),
+        }
+    }
+
+    /// Title from the TIT2 sub-frame
+    pub fn title(&self) -> Option<String> {
+        sub_frame_text(&self.sub_frames, "TIT2")
+    }
+
+    /// Set the TIT2 sub-frame
+    pub fn set_title(&mut self, title: &str, version: Version) {
+        set_sub_frame(&mut self.sub_frames, Frame::new_text("TIT2", title, version));
+    }
+
+    /// Parse the payload of a CTOC frame
+    pub fn parse(data: &[u8], version: Version) -> Result<Self> {
+        let (element_id, rest) = split_terminated(data, TextEncoding::Latin1);
+        if rest.len() < 2 {
+            return Err(Error::ContentLengthExceedsFrameArea);
+        }
+        let flags = rest[0];
+        let entry_count = rest[1];
+        let mut rest = &rest[2..];
+
+        let mut child_element_ids = Vec::with_capacity(entry_count as usize);
+        for _ in 0..entry_count {
+            if rest.is_empty() {
+                return Err(Error::ContentLengthExceedsFrameArea);
+            }
+            let (child, after) = split_terminated(rest, TextEncoding::Latin1);
+            child_element_ids.push(TextEncoding::Latin1.decode(child));
+            rest = after;
+        }
+
+        Ok(Self {
+            element_id: TextEncoding::Latin1.decode(element_id),
+            top_level: flags & FLAG_TOP_LEVEL != 0,
+            ordered: flags & FLAG_ORDERED != 0,
+            child_element_ids,
+            sub_frames: parse_sub_frames(rest, version),
+        })
+    }
+
+    /// Serialize into a CTOC frame payload
+    pub fn to_frame_data(&self, version: Version) -> Result<Vec<u8>> {
+        if self.child_element_ids.len() > u8::MAX as usize {
+            return Err(Error::Other(format!(
+                "Table of contents has {} entries, at most 255 are allowed",
+                self.child_element_ids.len()
+            )));
+        }
+        let mut data = TextEncoding::Latin1.encode_terminated(&self.element_id);
+        let mut flags = 0;
+        if self.top_level {
+            flags |= FLAG_TOP_LEVEL;
+        }
+        if self.ordered {
+            flags |= FLAG_ORDERED;
+        }
+        data.push(flags);
+        data.push(self.child_element_ids.len() as u8);
+        for child in &self.child_element_ids {
+            data.extend_from_slice(&TextEncoding::Latin1.encode_terminated(child));
+        }
+        for frame in &self.sub_frames {
+            data.extend_from_slice(&frame.to_bytes(version));
+        }
+        Ok(data)
+    }
+
+    /// Build the CTOC frame
+    pub fn to_frame(&self, version: Version) -> Result<Frame> {
+        Ok(Frame::from_data("CTOC", self.to_frame_data(version)?))
+    }
+}
+
+/// Parse embedded frames, stopping at padding or the first malformed frame
+fn parse_sub_frames(mut data: &[u8], version: Version) -> Vec<Frame> {
+    let mut frames = Vec::new();
+    while data.len() >= Frame::header_size(version) && data[0] != 0 {
+        match Frame::parse(data, version) {
+            Ok(frame) => {
+                let size = Frame::declared_size(data, version).unwrap_or_default();
+                data = &data[Frame::header_size(version) + size..];
+                frames.push(frame);
+            }
+            Err(_) => break,
+        }
+    }
+    frames
+}
+
+fn sub_frame_text(frames: &[Frame], id: &str) -> Option<String> {
+    frames.iter().find(|frame| frame.id == id).and_then(Frame::text)
+}
+
+fn set_sub_frame(frames: &mut Vec<Frame>, frame: Frame) {
+    frames.retain(|existing| existing.id != frame.id);
+    frames.push(frame);
+}
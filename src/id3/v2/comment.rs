@@ -0,0 +1,46 @@
+use crate::error::Result;
+use crate::id3::v2::frame::Frame;
+use crate::id3::v2::lyrics::{known_language, language_text_data, parse_language_text};
+use crate::id3::v2::version::Version;
+
+/// Comment (COMM, or COM in ID3v2.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    /// ISO-639-2 language code
+    pub lang: String,
+    /// Short content description; several comments may differ only by description
+    pub description: String,
+    pub text: String,
+}
+
+impl Comment {
+    pub fn new(lang: &str, description: &str, text: &str) -> Self {
+        Self {
+            lang: lang.to_string(),
+            description: description.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    /// Normalized language code, `None` when undetermined or malformed
+    pub fn language(&self) -> Option<String> {
+        known_language(&self.lang)
+    }
+
+    /// Parse the payload of a COMM frame
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let (lang, description, text) = parse_language_text(data)?;
+        Ok(Self { lang, description, text })
+    }
+
+    /// Serialize the comment into a COMM frame payload
+    pub fn to_frame_data(&self, version: Version) -> Vec<u8> {
+        language_text_data(&self.lang, &self.description, &self.text, version)
+    }
+
+    /// Build the frame holding this comment
+    pub fn to_frame(&self, version: Version) -> Frame {
+        let id = if version == Version::V2 { "COM" } else { "COMM" };
+        Frame::from_data(id, self.to_frame_data(version))
+    }
+}
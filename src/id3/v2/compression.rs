@@ -0,0 +1,51 @@
+//! zlib compression of ID3v2.3 and ID3v2.4 frames, available with the `compression` feature
+
+use crate::error::{Error, Result};
+
+/// Whether this build can read and write compressed frames
+pub const SUPPORTED: bool = cfg!(feature = "compression");
+
+/// Largest inflated frame accepted, whatever size the frame declares
+pub const MAX_INFLATED_SIZE: usize = 16 * 1024 * 1024;
+
+/// Inflate a zlib stream, failing when it inflates to more than `limit` bytes
+///
+/// The limit is capped at `MAX_INFLATED_SIZE`, so a small hostile stream
+/// cannot allocate more than that.
+#[cfg(feature = "compression")]
+pub fn inflate(data: &[u8], limit: usize) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let limit = limit.min(MAX_INFLATED_SIZE);
+    let mut inflated = Vec::new();
+    flate2::read::ZlibDecoder::new(data)
+        .take(limit as u64 + 1)
+        .read_to_end(&mut inflated)
+        .map_err(|e| Error::Other(format!("Invalid zlib data: {}", e)))?;
+    if inflated.len() > limit {
+        return Err(Error::Other(format!("Compressed data inflates to more than {} bytes", limit)));
+    }
+    Ok(inflated)
+}
+
+/// Inflate a zlib stream, failing when it inflates to more than `limit` bytes
+#[cfg(not(feature = "compression"))]
+pub fn inflate(_data: &[u8], _limit: usize) -> Result<Vec<u8>> {
+    Err(Error::Other("Compressed frames require the `compression` feature".to_string()))
+}
+
+/// Compress data into a zlib stream
+#[cfg(feature = "compression")]
+pub fn deflate(data: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+/// Compress data into a zlib stream
+#[cfg(not(feature = "compression"))]
+pub fn deflate(_data: &[u8]) -> Result<Vec<u8>> {
+    Err(Error::Other("Compressed frames require the `compression` feature".to_string()))
+}
@@ -0,0 +1,59 @@
+//! Conversion of tags between ID3v2.3 and ID3v2.4
+
+use std::path::Path;
+
+use crate::error::Result;
+use crate::id3::v2::tag::TagWriter;
+use crate::id3::v2::version::Version;
+use crate::tag::TagWriterStrategy;
+
+/// Rewrite the ID3v2 tag of a file in another version
+///
+/// Date frames are remapped (TYER/TDAT/TIME and TDRC, TORY and TDOR) and text
+/// is re-encoded where the target version does not support its encoding.
+/// ID3v2.4 frames without an ID3v2.3 counterpart are dropped when converting
+/// to ID3v2.3. Only ID3v2.3 and ID3v2.4 tags can be converted.
+pub fn convert_version<P: AsRef<Path>>(path: P, target: Version) -> Result<()> {
+    let mut writer = TagWriter::new();
+    writer.init(path.as_ref())?;
+    writer.convert_version(target)
+}
+
+/// ID3v2.4 frames that ID3v2.3 has no counterpart for
+pub(crate) const V4_ONLY_FRAMES: &[&str] = &[
+    "ASPI", "EQU2", "RVA2", "SEEK", "SIGN", "TDEN", "TDRL", "TDTG", "TMOO", "TPRO", "TSST",
+];
+
+/// Frames other than text frames whose payload starts with a text encoding byte
+pub(crate) const ENCODED_FRAMES: &[&str] = &[
+    "APIC", "COMM", "COMR", "GEOB", "IPLS", "OWNE", "SYLT", "USER", "USLT", "WXXX",
+];
+
+/// Combine ID3v2.3 TYER ("YYYY"), TDAT ("DDMM") and TIME ("HHMM") into an
+/// ID3v2.4 timestamp ("YYYY-MM-DDTHH:MM")
+pub(crate) fn v3_date_to_timestamp(year: &str, date: Option<&str>, time: Option<&str>) -> String {
+    let mut timestamp = year.trim().to_string();
+    let is_digits = |value: &str| value.len() == 4 && value.bytes().all(|b| b.is_ascii_digit());
+
+    if let Some(date) = date.map(str::trim).filter(|date| is_digits(date)) {
+        timestamp.push_str(&format!("-{}-{}", &date[2..4], &date[0..2]));
+        if let Some(time) = time.map(str::trim).filter(|time| is_digits(time)) {
+            timestamp.push_str(&format!("T{}:{}", &time[0..2], &time[2..4]));
+        }
+    }
+    timestamp
+}
+
+/// Split an ID3v2.4 timestamp into ID3v2.3 TYER, TDAT and TIME values
+pub(crate) fn timestamp_to_v3_date(timestamp: &str) -> (String, Option<String>, Option<String>) {
+    let timestamp = timestamp.trim();
+    let part = |range: std::ops::Range<usize>| timestamp.get(range).filter(|p| p.bytes().all(|b| b.is_ascii_digit()));
+
+    let year = part(0..4).unwrap_or(timestamp).to_string();
+    let date = part(5..7).zip(part(8..10)).map(|(month, day)| format!("{}{}", day, month));
+    let time = date
+        .as_ref()
+        .and(part(11..13).zip(part(14..16)))
+        .map(|(hour, minute)| format!("{}{}", hour, minute));
+    (year, date, time)
+}
@@ -0,0 +1,160 @@
+use crate::id3::v2::version::Version;
+use crate::util::decode_latin1;
+
+/// Text encodings declared by the first byte of ID3v2 text-bearing frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextEncoding {
+    /// ISO-8859-1
+    #[default]
+    Latin1,
+    /// UTF-16 with byte order mark
+    Utf16,
+    /// UTF-16 big endian without BOM (ID3v2.4 only)
+    Utf16Be,
+    /// UTF-8 (ID3v2.4 only)
+    Utf8,
+}
+
+impl TextEncoding {
+    /// Parse the encoding byte of a frame
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Latin1),
+            1 => Some(Self::Utf16),
+            2 => Some(Self::Utf16Be),
+            3 => Some(Self::Utf8),
+            _ => None,
+        }
+    }
+
+    /// The encoding byte written in front of the frame payload
+    pub fn as_byte(self) -> u8 {
+        match self {
+            Self::Latin1 => 0,
+            Self::Utf16 => 1,
+            Self::Utf16Be => 2,
+            Self::Utf8 => 3,
+        }
+    }
+
+    /// Whether the encoding may be used in a tag of the given version
+    pub fn is_supported_by(self, version: Version) -> bool {
+        match self {
+            Self::Latin1 | Self::Utf16 => true,
+            Self::Utf16Be | Self::Utf8 => version == Version::V4,
+        }
+    }
+
+    /// Default encoding for `text` in the given version
+    ///
+    /// ISO-8859-1 is only chosen for ASCII text, since many players decode
+    /// encoding 0 with the system code page; anything else uses Unicode.
+    pub fn for_text(text: &str, version: Version) -> Self {
+        if text.is_ascii() {
+            Self::Latin1
+        } else if version == Version::V4 {
+            Self::Utf8
+        } else {
+            Self::Utf16
+        }
+    }
+
+    /// Whether every character of `text` can be represented in this encoding
+    pub fn can_encode(self, text: &str) -> bool {
+        match self {
+            Self::Latin1 => text.chars().all(|c| (c as u32) <= 0xFF),
+            Self::Utf16 | Self::Utf16Be | Self::Utf8 => true,
+        }
+    }
+
+    /// String terminator for this encoding
+    pub fn terminator(self) -> &'static [u8] {
+        match self {
+            Self::Latin1 | Self::Utf8 => &[0],
+            Self::Utf16 | Self::Utf16Be => &[0, 0],
+        }
+    }
+
+    /// Decode bytes in this encoding, dropping trailing terminators
+    pub fn decode(self, bytes: &[u8]) -> String {
+        let text = match self {
+            Self::Latin1 => decode_latin1(bytes),
+            Self::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Self::Utf16 => match bytes {
+                [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, false),
+                [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, true),
+                // No BOM: assume little endian, as written by most Windows taggers
+                _ => decode_utf16(bytes, false),
+            },
+            Self::Utf16Be => decode_utf16(bytes, true),
+        };
+        text.trim_end_matches('\0').to_string()
+    }
+
+    /// Encode text in this encoding, without a terminator
+    ///
+    /// UTF-16 output is little endian with a byte order mark. Characters that
+    /// ISO-8859-1 cannot represent are replaced with '?'.
+    pub fn encode(self, text: &str) -> Vec<u8> {
+        match self {
+            Self::Latin1 => text
+                .chars()
+                .map(|c| if (c as u32) <= 0xFF { c as u8 } else { b'?' })
+                .collect(),
+            Self::Utf8 => text.as_bytes().to_vec(),
+            Self::Utf16 => {
+                let mut bytes = vec![0xFF, 0xFE];
+                for unit in text.encode_utf16() {
+                    bytes.extend_from_slice(&unit.to_le_bytes());
+                }
+                bytes
+            }
+            Self::Utf16Be => text.encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect(),
+        }
+    }
+
+    /// Encode text followed by the terminator
+    pub fn encode_terminated(self, text: &str) -> Vec<u8> {
+        let mut bytes = self.encode(text);
+        bytes.extend_from_slice(self.terminator());
+        bytes
+    }
+}
+
+fn decode_utf16(bytes: &[u8], big_endian: bool) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| {
+            if big_endian {
+                u16::from_be_bytes([pair[0], pair[1]])
+            } else {
+                u16::from_le_bytes([pair[0], pair[1]])
+            }
+        })
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Split `bytes` at the first string terminator of the given encoding
+///
+/// Returns the string bytes and the remainder after the terminator. UTF-16
+/// terminators are only matched at even offsets. When no terminator exists the
+/// whole buffer is the string and the remainder is empty.
+pub fn split_terminated(bytes: &[u8], encoding: TextEncoding) -> (&[u8], &[u8]) {
+    match encoding {
+        TextEncoding::Latin1 | TextEncoding::Utf8 => match bytes.iter().position(|&b| b == 0) {
+            Some(pos) => (&bytes[..pos], &bytes[pos + 1..]),
+            None => (bytes, &[]),
+        },
+        TextEncoding::Utf16 | TextEncoding::Utf16Be => {
+            let mut pos = 0;
+            while pos + 1 < bytes.len() {
+                if bytes[pos] == 0 && bytes[pos + 1] == 0 {
+                    return (&bytes[..pos], &bytes[pos + 2..]);
+                }
+                pos += 2;
+            }
+            (bytes, &[])
+        }
+    }
+}
@@ -1,4 +1,17 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+
 use crate::error::{Error, Result};
+use crate::id3::v2::compression;
+use crate::id3::v2::encoding::TextEncoding;
+use crate::id3::v2::util::{int_to_synchsafe, synchsafe_to_int};
+use crate::id3::v2::version::Version;
+use crate::tag::TagType;
+use crate::telemetry::{self, AnomalyKind};
+
+pub use crate::id3::v2::lyrics::{SyncedContentType, SyncedLine, SynchronisedLyrics, TimestampFormat};
 
 /// ID3v2 frame flags
 #[derive(Debug, Clone, Copy)]
@@ -10,6 +23,64 @@ pub struct FrameFlags {
     pub compression: bool,
     pub encryption: bool,
     pub grouping_identity: bool,
+    /// The payload is unsynchronised (ID3v2.4 only)
+    pub unsynchronisation: bool,
+    /// A 4 byte data length indicator precedes the payload (ID3v2.4 only)
+    pub data_length_indicator: bool,
+}
+
+impl FrameFlags {
+    /// Decode the two flag bytes of an ID3v2.3 or ID3v2.4 frame header
+    pub fn from_bytes(bytes: [u8; 2], version: Version) -> Self {
+        let [status, format] = bytes;
+        match version {
+            Version::V2 => Self::default(),
+            Version::V3 => Self {
+                tag_alter_preservation: status & 0x80 != 0,
+                file_alter_preservation: status & 0x40 != 0,
+                read_only: status & 0x20 != 0,
+                compression: format & 0x80 != 0,
+                encryption: format & 0x40 != 0,
+                grouping_identity: format & 0x20 != 0,
+                unsynchronisation: false,
+                data_length_indicator: false,
+            },
+            Version::V4 => Self {
+                tag_alter_preservation: status & 0x40 != 0,
+                file_alter_preservation: status & 0x20 != 0,
+                read_only: status & 0x10 != 0,
+                grouping_identity: format & 0x40 != 0,
+                compression: format & 0x08 != 0,
+                encryption: format & 0x04 != 0,
+                unsynchronisation: format & 0x02 != 0,
+                data_length_indicator: format & 0x01 != 0,
+            },
+        }
+    }
+
+    /// Encode the flags into the two flag bytes of a frame header
+    pub fn to_bytes(self, version: Version) -> [u8; 2] {
+        let bit = |set: bool, mask: u8| if set { mask } else { 0 };
+        match version {
+            Version::V2 => [0, 0],
+            Version::V3 => [
+                bit(self.tag_alter_preservation, 0x80)
+                    | bit(self.file_alter_preservation, 0x40)
+                    | bit(self.read_only, 0x20),
+                bit(self.compression, 0x80) | bit(self.encryption, 0x40) | bit(self.grouping_identity, 0x20),
+            ],
+            Version::V4 => [
+                bit(self.tag_alter_preservation, 0x40)
+                    | bit(self.file_alter_preservation, 0x20)
+                    | bit(self.read_only, 0x10),
+                bit(self.grouping_identity, 0x40)
+                    | bit(self.compression, 0x08)
+                    | bit(self.encryption, 0x04)
+                    | bit(self.unsynchronisation, 0x02)
+                    | bit(self.data_length_indicator, 0x01),
+            ],
+        }
+    }
 }
 
 /// ID3v2 frame implementation
@@ -17,71 +88,417 @@ pub struct FrameFlags {
 pub struct Frame {
     pub id: String,
     pub content: String,
-    data: Vec<u8>,
+    /// Flags read from the frame header; set `compression` to compress on write
+    pub flags: FrameFlags,
+    data: Payload,
+    /// Group identifier of a frame with the grouping identity flag
+    group_id: Option<u8>,
+    /// Header flag bytes of a frame whose payload could not be decoded (e.g.
+    /// encrypted); `data` then holds the payload as stored and is written back as is
+    raw_flags: Option<[u8; 2]>,
 }
 
 impl Frame {
-    pub fn parse(data: &[u8], _version: u8) -> Result<Self> {
-        if data.len() < 10 {
-            return Err(Error::InvalidHeader);
+    /// Parse a frame from the start of `data`
+    ///
+    /// ID3v2.2 frames have a 6 byte header with a 3 byte size, ID3v2.3 frames a
+    /// 10 byte header with a big-endian size and ID3v2.4 frames a syncsafe size.
+    pub fn parse(data: &[u8], version: Version) -> Result<Self> {
+        Self::parse_in(data, 0, None, version)
+    }
+
+    /// Parse the frame at `offset` of a tag buffer shared by all frames of the tag
+    ///
+    /// Payloads that need no decoding refer to the buffer instead of being
+    /// copied; the ID and decoded `content` are still owned by the frame.
+    pub(crate) fn parse_shared(buffer: &Arc<[u8]>, offset: usize, version: Version) -> Result<Self> {
+        Self::parse_in(buffer, offset, Some(buffer), version)
+    }
+
+    fn parse_in(buffer: &[u8], offset: usize, shared: Option<&Arc<[u8]>>, version: Version) -> Result<Self> {
+        let data = &buffer[offset..];
+        let header_size = Self::header_size(version);
+        let size = Self::declared_size(data, version).ok_or(Error::InvalidHeader)?;
+
+        let id = String::from_utf8_lossy(&data[..Self::id_size(version)]).to_string();
+        if size > data.len() - header_size {
+            return Err(Error::FrameLengthExceedsTagLength);
         }
+        let body = &data[header_size..header_size + size];
+        let body_end = offset + header_size + size;
+
+        let flag_bytes = if version == Version::V2 { [0, 0] } else { [data[8], data[9]] };
+        let flags = FrameFlags::from_bytes(flag_bytes, version);
+        let (frame_data, group_id, raw_flags) = match decode_payload(&id, body, flags, version) {
+            Some((payload, group_id)) => (payload, group_id, None),
+            None => (Cow::Borrowed(body), None, Some(flag_bytes)),
+        };
+        let frame_data = match (frame_data, shared) {
+            // A borrowed payload is what remains of the body after the data added by the flags
+            (Cow::Borrowed(payload), Some(buffer)) => Payload::Shared(buffer.clone(), body_end - payload.len()..body_end),
+            (payload, _) => Payload::Owned(payload.into_owned()),
+        };
         
-        let mut header = [0u8; 10];
-        header.copy_from_slice(&data[..10]);
-        
-        // Parse frame header manually since FrameHeader doesn't exist yet
-        let id = String::from_utf8_lossy(&header[0..4]).to_string();
-        let size = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
-        let frame_data = data[10..10 + size as usize].to_vec();
-        
-        // ID3v2 text frames start with a text encoding byte
-        let content = if frame_data.is_empty() {
+        // ID3v2 text frames start with a text encoding byte, URL frames hold ISO-8859-1 without one
+        let content = if frame_data.is_empty() || raw_flags.is_some() || !is_text_frame_id(&id) {
             String::new()
+        } else if is_url_frame_id(&id) {
+            TextEncoding::Latin1.decode(&frame_data)
         } else {
-            // Skip the first byte (text encoding) and parse the rest as text
-            String::from_utf8_lossy(&frame_data[1..]).to_string()
+            decode_text_content(&id, frame_data[0], &frame_data[1..])
         };
         
         Ok(Self {
             id,
             content,
+            flags,
             data: frame_data,
+            group_id,
+            raw_flags,
         })
     }
 
-    pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::with_capacity(10 + self.data.len());
-        let mut header = [0u8; 10];
-        header[0..4].copy_from_slice(self.id.as_bytes());
-        let size_bytes = (self.data.len() as u32).to_be_bytes();
-        header[4..8].copy_from_slice(&size_bytes);
-        // flags are already 0
-        bytes.extend_from_slice(&header);
-        bytes.extend_from_slice(&self.data);
+    /// Serialize the frame with a header in the layout of the given version
+    pub fn to_bytes(&self, version: Version) -> Vec<u8> {
+        let (flag_bytes, payload) = match self.raw_flags {
+            Some(flag_bytes) => (flag_bytes, self.data.to_vec()),
+            None => self.encode_payload(version),
+        };
+
+        let mut bytes = Vec::with_capacity(Self::header_size(version) + payload.len());
+        let mut id = self.id.as_bytes().to_vec();
+        id.resize(Self::id_size(version), 0);
+        bytes.extend_from_slice(&id);
+
+        let size = payload.len() as u32;
+        match version {
+            Version::V2 => bytes.extend_from_slice(&size.to_be_bytes()[1..]),
+            Version::V3 => bytes.extend_from_slice(&size.to_be_bytes()),
+            Version::V4 => bytes.extend_from_slice(&int_to_synchsafe(size)),
+        }
+        if version != Version::V2 {
+            bytes.extend_from_slice(&flag_bytes);
+        }
+        bytes.extend_from_slice(&payload);
         bytes
     }
 
+    /// Flag bytes and stored payload, compressed when requested and worthwhile
+    fn encode_payload(&self, version: Version) -> ([u8; 2], Vec<u8>) {
+        let mut flags = self.flags;
+        if version == Version::V2 {
+            return ([0, 0], self.data.to_vec());
+        }
+
+        // The payload is always written plain, unencrypted and unsynchronised
+        flags.encryption = false;
+        flags.unsynchronisation = false;
+        flags.grouping_identity = flags.grouping_identity && self.group_id.is_some();
+
+        let compressed = if flags.compression && compression::SUPPORTED {
+            compression::deflate(&self.data)
+                .ok()
+                .filter(|compressed| compressed.len() + 4 < self.data.len())
+        } else {
+            None
+        };
+        flags.compression = compressed.is_some();
+
+        let inflated_size = self.data.len() as u32;
+        let mut payload = Vec::new();
+        match version {
+            Version::V3 => {
+                if flags.compression {
+                    payload.extend_from_slice(&inflated_size.to_be_bytes());
+                }
+                payload.extend(self.group_id.filter(|_| flags.grouping_identity));
+            }
+            _ => {
+                // Compressed frames need the inflated size in the data length indicator
+                flags.data_length_indicator |= flags.compression;
+                payload.extend(self.group_id.filter(|_| flags.grouping_identity));
+                if flags.data_length_indicator {
+                    payload.extend_from_slice(&int_to_synchsafe(inflated_size));
+                }
+            }
+        }
+        payload.extend_from_slice(compressed.as_deref().unwrap_or(&self.data));
+        (flags.to_bytes(version), payload)
+    }
+
+    /// Group identifier of a frame with the grouping identity flag
+    pub fn group_id(&self) -> Option<u8> {
+        self.group_id
+    }
+
+    /// Length of a frame header in the given version
+    pub fn header_size(version: Version) -> usize {
+        match version {
+            Version::V2 => 6,
+            Version::V3 | Version::V4 => 10,
+        }
+    }
+
+    /// Length of a frame ID in the given version
+    pub fn id_size(version: Version) -> usize {
+        match version {
+            Version::V2 => 3,
+            Version::V3 | Version::V4 => 4,
+        }
+    }
+
+    /// Payload size declared by the frame header at the start of `data`
+    pub fn declared_size(data: &[u8], version: Version) -> Option<usize> {
+        if data.len() < Self::header_size(version) {
+            return None;
+        }
+        let size = match version {
+            Version::V2 => u32::from_be_bytes([0, data[3], data[4], data[5]]),
+            Version::V3 => u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+            Version::V4 => synchsafe_to_int(&data[4..8]),
+        };
+        Some(size as usize)
+    }
+
+    /// Create a text frame valid in every ID3v2 version (ISO-8859-1 or UTF-16)
     pub fn new(id: &str, content: &str) -> Self {
-        // ID3v2 text frames start with a text encoding byte (0x00 = ISO-8859-1)
-        let mut data = vec![0x00];
-        data.extend_from_slice(content.as_bytes());
+        Self::new_text(id, content, Version::V3)
+    }
+
+    /// Create a frame from a raw payload, e.g. a binary APIC frame
+    pub fn from_data(id: &str, data: Vec<u8>) -> Self {
         Self {
             id: id.to_string(),
-            content: content.to_string(),
-            data,
+            content: String::new(),
+            flags: FrameFlags::default(),
+            data: Payload::Owned(data),
+            group_id: None,
+            raw_flags: None,
         }
     }
 
+    /// Create a text frame using the narrowest encoding the version allows
+    pub fn new_text(id: &str, text: &str, version: Version) -> Self {
+        Self::new_text_with_encoding(id, text, TextEncoding::for_text(text, version))
+    }
+
+    /// Create a text frame in the given encoding
+    ///
+    /// Characters the encoding cannot represent are replaced, see `TextEncoding::encode`.
+    /// URL frames other than WXXX have no encoding byte and are always ISO-8859-1.
+    pub fn new_text_with_encoding(id: &str, text: &str, encoding: TextEncoding) -> Self {
+        let data = if is_url_frame_id(id) {
+            TextEncoding::Latin1.encode(text)
+        } else {
+            let mut data = vec![encoding.as_byte()];
+            data.extend_from_slice(&encoding.encode(text));
+            data
+        };
+        Self {
+            id: id.to_string(),
+            content: text.to_string(),
+            flags: FrameFlags::default(),
+            data: Payload::Owned(data),
+            group_id: None,
+            raw_flags: None,
+        }
+    }
+
+    /// Decode the payload of a text frame according to its encoding byte, or of a URL frame
+    pub fn text(&self) -> Option<String> {
+        if is_url_frame_id(&self.id) {
+            return Some(TextEncoding::Latin1.decode(&self.data));
+        }
+        let (&encoding_byte, text) = self.data.split_first()?;
+        TextEncoding::from_byte(encoding_byte).map(|encoding| encoding.decode(text))
+    }
+
+    /// Create a text frame holding several values
+    ///
+    /// ID3v2.4 separates values with a null character. Earlier versions have no
+    /// multi-value support, so the values are joined with `separator`.
+    pub fn new_text_values(id: &str, values: &[String], version: Version, separator: &str) -> Self {
+        Self::new_text(id, &join_text_values(values, version, separator), version)
+    }
+
+    /// Decode a text frame into its null separated values
+    pub fn text_values(&self) -> Vec<String> {
+        if is_url_frame_id(&self.id) {
+            return vec![TextEncoding::Latin1.decode(&self.data)];
+        }
+        let (&encoding_byte, text) = match self.data.split_first() {
+            Some(split) => split,
+            None => return Vec::new(),
+        };
+        let encoding = match TextEncoding::from_byte(encoding_byte) {
+            Some(encoding) => encoding,
+            None => return Vec::new(),
+        };
+        encoding
+            .decode(text)
+            .split('\0')
+            // Every UTF-16 value may start with its own byte order mark
+            .map(|value| value.trim_start_matches('\u{FEFF}').to_string())
+            .collect()
+    }
+
+    /// Raw frame payload, including the text encoding byte of text frames
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
     pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
+}
 
-    pub fn total_size(&self) -> usize {
-        10 + self.data.len() // Header size (10) + data size
+
+/// Strip the data that frame flags add before the payload and inflate compressed payloads
+///
+/// Returns `None` when the payload cannot be decoded and has to be kept as stored.
+fn decode_payload<'a>(id: &str, body: &'a [u8], flags: FrameFlags, version: Version) -> Option<(Cow<'a, [u8]>, Option<u8>)> {
+    if flags.encryption {
+        return None;
     }
 
-    pub fn size(&self) -> usize {
-        10 + self.data.len() // Header (10 bytes) + data
+    // Size of the decoded payload, when the frame declares it
+    let mut declared_size = None;
+    let (group_id, payload) = match version {
+        Version::V2 => (None, body),
+        Version::V3 => {
+            // Inflated size, then the group identifier
+            if flags.compression {
+                declared_size = body.get(..4).map(|size| u32::from_be_bytes([size[0], size[1], size[2], size[3]]) as usize);
+            }
+            let rest = body.get(4 * flags.compression as usize..)?;
+            if flags.grouping_identity {
+                let (&group_id, rest) = rest.split_first()?;
+                (Some(group_id), rest)
+            } else {
+                (None, rest)
+            }
+        }
+        Version::V4 => {
+            // Group identifier, then the data length indicator
+            let (group_id, rest) = if flags.grouping_identity {
+                let (&group_id, rest) = body.split_first()?;
+                (Some(group_id), rest)
+            } else {
+                (None, body)
+            };
+            if flags.data_length_indicator {
+                declared_size = rest.get(..4).map(|size| synchsafe_to_int(size) as usize);
+            }
+            (group_id, rest.get(4 * flags.data_length_indicator as usize..)?)
+        }
+    };
+
+    let payload = if flags.unsynchronisation {
+        Cow::Owned(remove_unsynchronisation(payload))
+    } else {
+        Cow::Borrowed(payload)
+    };
+    if !flags.compression {
+        return Some((payload, group_id));
+    }
+
+    match compression::inflate(&payload, declared_size.unwrap_or(compression::MAX_INFLATED_SIZE)) {
+        Ok(inflated) => Some((Cow::Owned(inflated), group_id)),
+        Err(e) => {
+            telemetry::report(
+                AnomalyKind::UndecodableFrame,
+                TagType::Id3v2,
+                None,
+                format!("Compressed frame '{}' kept as is: {}", id, e),
+            );
+            None
+        }
     }
 }
 
+/// Payload of a frame, either its own or a range of the tag buffer it was parsed from
+///
+/// Sharing the buffer saves a copy of the payload per frame when reading
+/// tags; the buffer lives as long as any frame parsed from it.
+#[derive(Clone)]
+enum Payload {
+    Owned(Vec<u8>),
+    Shared(Arc<[u8]>, Range<usize>),
+}
+
+impl std::ops::Deref for Payload {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Payload::Owned(data) => data,
+            Payload::Shared(buffer, range) => &buffer[range.clone()],
+        }
+    }
+}
+
+impl fmt::Debug for Payload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Undo unsynchronisation by dropping the zero byte inserted after every 0xFF
+fn remove_unsynchronisation(data: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(data.len());
+    let mut previous = 0u8;
+    for &byte in data {
+        if !(previous == 0xFF && byte == 0x00) {
+            result.push(byte);
+        }
+        previous = byte;
+    }
+    result
+}
+
+/// Join values for a text frame: null separated in ID3v2.4, `separator` before that
+pub fn join_text_values(values: &[String], version: Version, separator: &str) -> String {
+    if version == Version::V4 {
+        values.join("\0")
+    } else {
+        values.join(separator)
+    }
+}
+
+/// Decode the text of a frame according to its encoding byte, reporting malformed text
+fn decode_text_content(id: &str, encoding_byte: u8, text: &[u8]) -> String {
+    let report_invalid = |message: String| {
+        telemetry::report(AnomalyKind::InvalidText, TagType::Id3v2, None, message);
+    };
+
+    match TextEncoding::from_byte(encoding_byte) {
+        Some(encoding @ (TextEncoding::Utf16 | TextEncoding::Utf16Be)) => {
+            if !text.len().is_multiple_of(2) {
+                report_invalid(format!("Frame '{}' has UTF-16 text of odd length {}", id, text.len()));
+            }
+            encoding.decode(text)
+        }
+        Some(TextEncoding::Utf8) => {
+            if std::str::from_utf8(text).is_err() {
+                report_invalid(format!("Frame '{}' contains invalid UTF-8", id));
+            }
+            TextEncoding::Utf8.decode(text)
+        }
+        // Every byte is a valid ISO-8859-1 character, so there is nothing to report
+        Some(TextEncoding::Latin1) => TextEncoding::Latin1.decode(text),
+        None => {
+            report_invalid(format!("Frame '{}' declares unknown text encoding {}", id, encoding_byte));
+            String::from_utf8_lossy(text).to_string()
+        }
+    }
+}
+
+/// Whether the frame payload is text that can be exposed as `content`
+fn is_text_frame_id(id: &str) -> bool {
+    id.starts_with('T') || id.starts_with('W') || matches!(id, "COMM" | "USLT" | "COM" | "ULT")
+}
+
+/// Whether the frame is a URL link frame, whose payload is ISO-8859-1 without an encoding byte
+pub(crate) fn is_url_frame_id(id: &str) -> bool {
+    id.starts_with('W') && !matches!(id, "WXXX" | "WXX")
+}
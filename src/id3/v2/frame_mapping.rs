@@ -25,76 +25,38 @@ pub mod v3_v4 {
         "FileType" => "TFLT",
         "BandOrchestra" => "TPE2",
         "AttachedPicture" => "APIC",
-        "AudioSeekPointIndex" => "ASPI",
-        "CommercialFrame" => "COMR",
-        "EncryptionMethodRegistration" => "ENCR",
-        "Equalisation2" => "EQU2",
-        "EventTimingCodes" => "ETCO",
-        "GeneralEncapsulatedObject" => "GEOB",
-        "GroupIdentificationRegistration" => "GRID",
-        "LinkedInformation" => "LINK",
-        "MusicCDIdentifier" => "MCDI",
-        "MPEGLocationLookupTable" => "MLLT",
-        "OwnershipFrame" => "OWNE",
-        "PrivateFrame" => "PRIV",
-        "PlayCounter" => "PCNT",
-        "Popularimeter" => "POPM",
-        "PositionSynchronisationFrame" => "POSS",
-        "RecommendedBufferSize" => "RBUF",
-        "RelativeVolumeAdjustment2" => "RVA2",
-        "Reverb" => "RVRB",
-        "SeekFrame" => "SEEK",
-        "SignatureFrame" => "SIGN",
-        "SynchronisedLyricText" => "SYLT",
-        "SynchronisedTempoCodes" => "SYTC",
+        "Lyrics" => "USLT",
+        "PodcastDescription" => "TDES",
+        "PodcastId" => "TGID",
+        "PodcastFeedUrl" => "WFED",
+        "PodcastCategory" => "TCAT",
+        "PodcastKeywords" => "TKWD",
+        "Compilation" => "TCMP",
+        "AlbumArtistSortOrder" => "TSO2",
+        "ComposerSortOrder" => "TSOC",
+        "TitleSortOrder" => "TSOT",
+        "ArtistSortOrder" => "TSOP",
+        "AlbumSortOrder" => "TSOA",
+        "PartOfSet" => "TPOS",
         "BeatsPerMinute" => "TBPM",
-        "CopyrightMessage" => "TCOP",
-        "EncodingTime" => "TDEN",
-        "PlaylistDelay" => "TDLY",
-        "OriginalReleaseTime" => "TDOR",
-        "RecordingTime" => "TDRC",
-        "ReleaseTime" => "TDRL",
-        "TaggingTime" => "TDTG",
-        "EncodedBy" => "TENC",
-        "InvolvedPeopleList" => "TIPL",
-        "ContentGroupDescription" => "TIT1",
-        "SubtitleDescriptionRefinement" => "TIT3",
         "InitialKey" => "TKEY",
-        "Length" => "TLEN",
-        "MusicianCreditsList" => "TMCL",
-        "MediaType" => "TMED",
-        "Mood" => "TMOO",
-        "OriginalAlbumMovieShowTitle" => "TOAL",
-        "OriginalLyricistTextWriter" => "TOLY",
-        "OriginalArtistPerformer" => "TOPE",
-        "FileOwnerLicensee" => "TOWN",
-        "ConductorPerformerRefinement" => "TPE3",
-        "InterpretedRemixedModifiedBy" => "TPE4",
-        "PartOfSet" => "TPOS",
-        "ProducedNotice" => "TPRO",
+        "Isrc" => "TSRC",
         "Publisher" => "TPUB",
-        "InternetRadioStationName" => "TRSN",
-        "InternetRadioStationOwner" => "TRSO",
-        "AlbumSortOrder" => "TSOA",
-        "PerformerSortOrder" => "TSOP",
-        "TitleSortOrder" => "TSOT",
-        "ISRC" => "TSRC",
-        "SoftwareHardwareSettings" => "TSSE",
-        "SetSubtitle" => "TSST",
-        "UserDefinedTextInformation" => "TXXX",
-        "UniqueFileIdentifier" => "UFID",
-        "TermsOfUse" => "USER",
-        "UnsynchronisedLyricTextTranscription" => "USLT",
-        "CommercialInformation" => "WCOM",
-        "CopyrightLegalInformation" => "WCOP",
-        "OfficialAudioFileWebpage" => "WOAF",
-        "OfficialArtistPerformerWebpage" => "WOAR",
-        "OfficialAudioSourceWebpage" => "WOAS",
-        "OfficialInternetRadioStationHomepage" => "WORS",
-        "Payment" => "WPAY",
-        "PublishersOfficialWebpage" => "WPUB",
-        "UserDefinedURLLink" => "WXXX",
+        "Copyright" => "TCOP",
+        "EncodedBy" => "TENC",
+        "Conductor" => "TPE3",
+        "Remixer" => "TPE4",
+        "Rating" => "POPM",
     };
+
+    /// Frames without a `MetaEntry` that are still parsed and written back
+    static OTHER_FRAMES: &[&str] = &[
+        "ASPI", "COMR", "CHAP", "CTOC", "ENCR", "EQU2", "ETCO", "GEOB", "GRID", "IPLS", "LINK", "MCDI", "MLLT",
+        "OWNE", "PCNT", "PCST", "POSS", "PRIV", "RBUF", "RVA2", "RVRB", "SEEK", "SIGN", "SYLT", "SYTC", "TDEN",
+        "TDLY", "TDOR", "TDRC", "TDRL", "TDTG", "TIPL", "TIT1", "TIT3", "TLEN", "TMCL", "TMED", "TMOO", "TOAL",
+        "TOLY", "TOPE", "TORY", "TOWN", "TPRO", "TRSN", "TRSO", "TSSE", "TSST", "TXXX", "UFID", "USER", "WCOM",
+        "WCOP", "WOAF", "WOAR", "WOAS", "WORS", "WPAY", "WPUB", "WXXX",
+    ];
     
     fn get_frame_map() -> &'static Map<&'static str, &'static str> {
         &FRAME_MAP
@@ -112,7 +74,7 @@ pub mod v3_v4 {
     
     /// Check if a frame ID is supported in ID3v2.3/v2.4
     pub fn is_supported_frame(frame_id: &str) -> bool {
-        get_frame_map().values().any(|&id| id == frame_id)
+        get_frame_map().values().any(|&id| id == frame_id) || OTHER_FRAMES.contains(&frame_id)
     }
 }
 
@@ -126,68 +88,45 @@ pub mod v2_0 {
         "Title" => "TIT",
         "Artist" => "TP1",
         "Album" => "TAL",
-        "Date" => "TDA",
+        "Year" => "TYE",
         "Genre" => "TCO",
+        "Comment" => "COM",
+        "Composer" => "TCM",
+        "Track" => "TRK",
+        "Date" => "TDA",
         "TextWriter" => "TXT",
         "AudioEncryption" => "CRA",
         "Language" => "TLA",
         "Time" => "TIM",
-        "Composer" => "TCM",
+        "OriginalFilename" => "TOF",
         "FileType" => "TFT",
         "BandOrchestra" => "TP2",
-        "RecommendedBufferSize" => "BUF",
-        "PlayCounter" => "CNT",
-        "Comments" => "COM",
-        "EncryptedMetaFrame" => "CRM",
-        "EventTimingCodes" => "ETC",
-        "Equalization" => "EQU",
-        "GeneralEncapsulatedObject" => "GEO",
-        "InvolvedPeopleList" => "IPL",
-        "LinkedInformation" => "LNK",
-        "MusicCDIdentifier" => "MCI",
-        "MPEGLocationLookupTable" => "MLL",
         "AttachedPicture" => "PIC",
-        "Popularimeter" => "POP",
-        "Reverb" => "REV",
-        "RelativeVolumeAdjustment" => "RVA",
-        "SynchronizedLyricText" => "SLT",
-        "SyncedTempoCodes" => "STC",
+        "Lyrics" => "ULT",
+        "Compilation" => "TCP",
+        "AlbumArtistSortOrder" => "TS2",
+        "ComposerSortOrder" => "TSC",
+        "TitleSortOrder" => "TST",
+        "ArtistSortOrder" => "TSP",
+        "AlbumSortOrder" => "TSA",
+        "PartOfSet" => "TPA",
         "BeatsPerMinute" => "TBP",
-        "CopyrightMessage" => "TCR",
-        "PlaylistDelay" => "TDY",
-        "EncodedBy" => "TEN",
         "InitialKey" => "TKE",
-        "Length" => "TLE",
-        "MediaType" => "TMT",
-        "OriginalArtistPerformer" => "TOA",
-        "OriginalFilename" => "TOF",
-        "OriginalLyricistTextWriter" => "TOL",
-        "OriginalReleaseYear" => "TOR",
-        "OriginalAlbumMovieShowTitle" => "TOT",
-        "ConductorPerformerRefinement" => "TP3",
-        "InterpretedRemixedModifiedBy" => "TP4",
-        "PartOfSet" => "TPA",
+        "Isrc" => "TRC",
         "Publisher" => "TPB",
-        "ISRC" => "TRC",
-        "RecordingDates" => "TRD",
-        "TrackNumberPositionInSet" => "TRK",
-        "Size" => "TSI",
-        "SoftwareHardwareSettings" => "TSS",
-        "ContentGroupDescription" => "TT1",
-        "TitleSongnameContentDescription" => "TT2",
-        "SubtitleDescriptionRefinement" => "TT3",
-        "UserDefinedTextInformation" => "TXX",
-        "Year" => "TYE",
-        "UniqueFileIdentifier" => "UFI",
-        "UnsynchronizedLyricTextTranscription" => "ULT",
-        "OfficialAudioFileWebpage" => "WAF",
-        "OfficialArtistPerformerWebpage" => "WAR",
-        "OfficialAudioSourceWebpage" => "WAS",
-        "CommercialInformation" => "WCM",
-        "CopyrightLegalInformation" => "WCP",
-        "PublishersOfficialWebpage" => "WPB",
-        "UserDefinedURLLink" => "WXX",
+        "Copyright" => "TCR",
+        "EncodedBy" => "TEN",
+        "Conductor" => "TP3",
+        "Remixer" => "TP4",
+        "Rating" => "POP",
     };
+
+    /// Frames without a `MetaEntry` that are still parsed and written back
+    static OTHER_FRAMES: &[&str] = &[
+        "BUF", "CNT", "CRM", "EQU", "ETC", "GEO", "IPL", "LNK", "MCI", "MLL", "REV", "RVA", "SLT", "STC", "TDY",
+        "TLE", "TMT", "TOA", "TOL", "TOR", "TOT", "TRD", "TSI", "TSS", "TT1", "TT2", "TT3", "TXX", "UFI", "WAF",
+        "WAR", "WAS", "WCM", "WCP", "WPB", "WXX",
+    ];
     
     fn get_frame_map() -> &'static Map<&'static str, &'static str> {
         &FRAME_MAP
@@ -205,6 +144,6 @@ pub mod v2_0 {
     
     /// Check if a frame ID is supported in ID3v2.0
     pub fn is_supported_frame(frame_id: &str) -> bool {
-        get_frame_map().values().any(|&id| id == frame_id)
+        get_frame_map().values().any(|&id| id == frame_id) || OTHER_FRAMES.contains(&frame_id)
     }
 }
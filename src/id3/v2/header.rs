@@ -1,12 +1,31 @@
 use crate::id3::v2::util::{int_to_synchsafe, synchsafe_to_int};
-use crate::error::Result;
+use crate::id3::v2::version::Version;
+use crate::error::{Error, Result};
+use std::io::{Read, Seek, SeekFrom};
+
+/// Header flag: an extended header follows the tag header (ID3v2.3 and later)
+pub const FLAG_EXTENDED_HEADER: u8 = 0x40;
+
+/// Header flag: a footer follows the tag (ID3v2.4 only)
+pub const FLAG_FOOTER: u8 = 0x10;
+
+/// ID3v2.3 extended header flag: a CRC-32 follows the padding size
+const V3_FLAG_CRC: u16 = 0x8000;
+/// ID3v2.4 extended header flags
+const V4_FLAG_UPDATE: u16 = 0x40;
+const V4_FLAG_CRC: u16 = 0x20;
+const V4_FLAG_RESTRICTIONS: u16 = 0x10;
 
 /// Extended header for ID3v2 tags
 #[derive(Debug)]
 pub struct ExtendedHeader {
+    /// Length of the extended header in bytes, including the size field
     pub size: u32,
     pub flags: u16,
+    /// Padding size declared by an ID3v2.3 extended header
     pub padding_size: u32,
+    /// CRC-32 of the tag data, when present
+    pub crc: Option<u32>,
 }
 
 /// ID3v2 header implementation
@@ -62,6 +81,28 @@ impl Header {
     pub fn is_valid(&self) -> bool {
         self.version <= 4 && self.size > 0
     }
+
+    /// Whether an extended header follows this header
+    pub fn has_extended_header(&self) -> bool {
+        // ID3v2.2 uses this bit for compression instead
+        self.version >= 3 && self.flags & FLAG_EXTENDED_HEADER != 0
+    }
+}
+
+/// Bytes taken by the ID3v2 tag at the start of a file, including header and
+/// footer, or `None` when the file does not start with a tag
+pub fn existing_tag_size<R: Read + Seek>(reader: &mut R) -> Result<Option<u64>> {
+    let mut buffer = [0u8; 10];
+    reader.seek(SeekFrom::Start(0))?;
+    if reader.read(&mut buffer)? < buffer.len() {
+        return Ok(None);
+    }
+    let header = match Header::parse(&buffer) {
+        Ok(header) => header,
+        Err(_) => return Ok(None),
+    };
+    let footer_size = if header.version == 4 && header.flags & FLAG_FOOTER != 0 { 10 } else { 0 };
+    Ok(Some(10 + header.size as u64 + footer_size))
 }
 
 impl Default for ExtendedHeader {
@@ -76,6 +117,118 @@ impl ExtendedHeader {
             size: 0,
             flags: 0,
             padding_size: 0,
+            crc: None,
+        }
+    }
+
+    /// Create an extended header carrying a CRC-32
+    pub fn with_crc(crc: u32) -> Self {
+        Self {
+            crc: Some(crc),
+            ..Self::new()
+        }
+    }
+
+    /// Parse the extended header at the start of the tag data
+    pub fn parse(data: &[u8], version: Version) -> Result<Self> {
+        match version {
+            Version::V2 => Err(Error::InvalidTagVersion("ID3v2.2 has no extended header".to_string())),
+            Version::V3 => Self::parse_v3(data),
+            Version::V4 => Self::parse_v4(data),
+        }
+    }
+
+    fn parse_v3(data: &[u8]) -> Result<Self> {
+        if data.len() < 10 {
+            return Err(Error::InvalidTagSize);
+        }
+        // The size field excludes itself and is either 6 or 10
+        let size = u32::from_be_bytes([data[0], data[1], data[2], data[3]]).saturating_add(4);
+        let flags = u16::from_be_bytes([data[4], data[5]]);
+        let padding_size = u32::from_be_bytes([data[6], data[7], data[8], data[9]]);
+        if size as usize > data.len() {
+            return Err(Error::InvalidTagSize);
+        }
+
+        let crc = if flags & V3_FLAG_CRC != 0 {
+            if size < 14 {
+                return Err(Error::InvalidTagSize);
+            }
+            Some(u32::from_be_bytes([data[10], data[11], data[12], data[13]]))
+        } else {
+            None
+        };
+
+        Ok(Self { size, flags, padding_size, crc })
+    }
+
+    fn parse_v4(data: &[u8]) -> Result<Self> {
+        if data.len() < 6 {
+            return Err(Error::InvalidTagSize);
+        }
+        let size = synchsafe_to_int(&data[0..4]);
+        if (size as usize) < 6 || size as usize > data.len() {
+            return Err(Error::InvalidTagSize);
+        }
+        let data = &data[..size as usize];
+        let flags = data[5] as u16;
+
+        // Each set flag is followed by a length byte and its data, in flag order
+        let mut pos = 6;
+        let mut crc = None;
+        for flag in [V4_FLAG_UPDATE, V4_FLAG_CRC, V4_FLAG_RESTRICTIONS] {
+            if flags & flag == 0 {
+                continue;
+            }
+            let len = *data.get(pos).ok_or(Error::InvalidTagSize)? as usize;
+            let flag_data = data.get(pos + 1..pos + 1 + len).ok_or(Error::InvalidTagSize)?;
+            if flag == V4_FLAG_CRC {
+                // 35 bit syncsafe integer, of which only the low 32 bits are used
+                crc = Some(synchsafe_to_int(flag_data));
+            }
+            pos += 1 + len;
+        }
+
+        Ok(Self { size, flags, padding_size: 0, crc })
+    }
+
+    /// Serialize the extended header, writing only the CRC flag
+    pub fn to_bytes(&self, version: Version) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        match (version, self.crc) {
+            (Version::V4, Some(crc)) => {
+                buffer.extend_from_slice(&int_to_synchsafe(12));
+                buffer.extend_from_slice(&[1, V4_FLAG_CRC as u8, 5]);
+                for shift in [28, 21, 14, 7, 0] {
+                    buffer.push(((crc >> shift) & 0x7F) as u8);
+                }
+            }
+            (Version::V4, None) => {
+                buffer.extend_from_slice(&int_to_synchsafe(6));
+                buffer.extend_from_slice(&[1, 0]);
+            }
+            (_, Some(crc)) => {
+                buffer.extend_from_slice(&10u32.to_be_bytes());
+                buffer.extend_from_slice(&V3_FLAG_CRC.to_be_bytes());
+                buffer.extend_from_slice(&self.padding_size.to_be_bytes());
+                buffer.extend_from_slice(&crc.to_be_bytes());
+            }
+            (_, None) => {
+                buffer.extend_from_slice(&6u32.to_be_bytes());
+                buffer.extend_from_slice(&[0, 0]);
+                buffer.extend_from_slice(&self.padding_size.to_be_bytes());
+            }
+        }
+        buffer
+    }
+
+    /// Part of the data following the extended header that the CRC covers
+    ///
+    /// ID3v2.3 excludes the padding, ID3v2.4 covers everything up to the footer.
+    pub fn crc_data<'a>(&self, data: &'a [u8], version: Version) -> &'a [u8] {
+        match version {
+            Version::V3 => &data[..data.len().saturating_sub(self.padding_size as usize)],
+            _ => data,
         }
     }
 }
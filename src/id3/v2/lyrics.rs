@@ -0,0 +1,265 @@
+use crate::error::{Error, Result};
+use crate::id3::v2::encoding::{split_terminated, TextEncoding};
+use crate::id3::v2::frame::Frame;
+use crate::id3::v2::version::Version;
+use crate::validation::normalize_language;
+
+/// ISO 639-2 code for an undetermined language
+pub const UNKNOWN_LANGUAGE: &str = "und";
+
+/// Unsynchronised lyrics (USLT, or ULT in ID3v2.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lyrics {
+    /// ISO-639-2 language code
+    pub lang: String,
+    /// Content descriptor distinguishing several lyrics frames
+    pub description: String,
+    pub text: String,
+}
+
+impl Lyrics {
+    pub fn new(lang: &str, description: &str, text: &str) -> Self {
+        Self {
+            lang: lang.to_string(),
+            description: description.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    /// Normalized language code, `None` when undetermined or malformed
+    pub fn language(&self) -> Option<String> {
+        known_language(&self.lang)
+    }
+
+    /// Parse the payload of a USLT frame
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let (lang, description, text) = parse_language_text(data)?;
+        Ok(Self { lang, description, text })
+    }
+
+    /// Serialize the lyrics into a USLT frame payload
+    pub fn to_frame_data(&self, version: Version) -> Vec<u8> {
+        language_text_data(&self.lang, &self.description, &self.text, version)
+    }
+
+    /// Build the frame holding these lyrics
+    pub fn to_frame(&self, version: Version) -> Frame {
+        let id = if version == Version::V2 { "ULT" } else { "USLT" };
+        Frame::from_data(id, self.to_frame_data(version))
+    }
+}
+
+/// Normalized form of a stored language code, `None` when undetermined or malformed
+pub(crate) fn known_language(code: &str) -> Option<String> {
+    normalize_language(code).ok().filter(|code| code != UNKNOWN_LANGUAGE)
+}
+
+/// Parse the `encoding, language, description, text` layout shared by USLT and COMM
+pub(crate) fn parse_language_text(data: &[u8]) -> Result<(String, String, String)> {
+    if data.len() < 4 {
+        return Err(Error::ContentLengthExceedsFrameArea);
+    }
+    let encoding = TextEncoding::from_byte(data[0])
+        .ok_or_else(|| Error::Other(format!("Invalid text encoding: {}", data[0])))?;
+    let lang = TextEncoding::Latin1.decode(&data[1..4]);
+    let (description, text) = split_terminated(&data[4..], encoding);
+    Ok((lang, encoding.decode(description), encoding.decode(text)))
+}
+
+/// Serialize the `encoding, language, description, text` layout shared by USLT and COMM
+pub(crate) fn language_text_data(lang: &str, description: &str, text: &str, version: Version) -> Vec<u8> {
+    let encoding = TextEncoding::for_text(&format!("{}{}", description, text), version);
+    let mut data = vec![encoding.as_byte()];
+    let mut lang = lang.as_bytes().to_vec();
+    lang.resize(3, b' ');
+    data.extend_from_slice(&lang[..3]);
+    data.extend_from_slice(&encoding.encode_terminated(description));
+    data.extend_from_slice(&encoding.encode(text));
+    data
+}
+
+/// Unit of the timestamps in a SYLT frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// Absolute time in MPEG frames
+    MpegFrames,
+    /// Absolute time in milliseconds
+    Milliseconds,
+}
+
+impl TimestampFormat {
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            1 => Some(Self::MpegFrames),
+            2 => Some(Self::Milliseconds),
+            _ => None,
+        }
+    }
+
+    pub fn as_byte(self) -> u8 {
+        match self {
+            Self::MpegFrames => 1,
+            Self::Milliseconds => 2,
+        }
+    }
+}
+
+/// Content type byte of a SYLT frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncedContentType {
+    Other,
+    Lyrics,
+    TextTranscription,
+    Movement,
+    Events,
+    Chord,
+    Trivia,
+    WebpageUrls,
+    ImageUrls,
+    /// Value outside the range defined by the specification
+    Undefined(u8),
+}
+
+impl From<u8> for SyncedContentType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Other,
+            1 => Self::Lyrics,
+            2 => Self::TextTranscription,
+            3 => Self::Movement,
+            4 => Self::Events,
+            5 => Self::Chord,
+            6 => Self::Trivia,
+            7 => Self::WebpageUrls,
+            8 => Self::ImageUrls,
+            other => Self::Undefined(other),
+        }
+    }
+}
+
+impl From<SyncedContentType> for u8 {
+    fn from(content_type: SyncedContentType) -> Self {
+        match content_type {
+            SyncedContentType::Other => 0,
+            SyncedContentType::Lyrics => 1,
+            SyncedContentType::TextTranscription => 2,
+            SyncedContentType::Movement => 3,
+            SyncedContentType::Events => 4,
+            SyncedContentType::Chord => 5,
+            SyncedContentType::Trivia => 6,
+            SyncedContentType::WebpageUrls => 7,
+            SyncedContentType::ImageUrls => 8,
+            SyncedContentType::Undefined(value) => value,
+        }
+    }
+}
+
+/// A single line of synchronised text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncedLine {
+    /// Time at which the text starts, in the unit of the frame's `TimestampFormat`
+    pub timestamp: u32,
+    pub text: String,
+}
+
+/// Synchronised lyrics or text (SYLT, or SLT in ID3v2.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SynchronisedLyrics {
+    /// ISO-639-2 language code
+    pub lang: String,
+    pub timestamp_format: TimestampFormat,
+    pub content_type: SyncedContentType,
+    /// Content descriptor distinguishing several SYLT frames
+    pub description: String,
+    pub lines: Vec<SyncedLine>,
+}
+
+impl SynchronisedLyrics {
+    /// Create empty lyrics timed in milliseconds
+    pub fn new(lang: &str, description: &str) -> Self {
+        Self {
+            lang: lang.to_string(),
+            timestamp_format: TimestampFormat::Milliseconds,
+            content_type: SyncedContentType::Lyrics,
+            description: description.to_string(),
+            lines: Vec::new(),
+        }
+    }
+
+    /// Normalized language code, `None` when undetermined or malformed
+    pub fn language(&self) -> Option<String> {
+        known_language(&self.lang)
+    }
+
+    /// Append a timed line
+    pub fn add_line(&mut self, timestamp: u32, text: &str) {
+        self.lines.push(SyncedLine {
+            timestamp,
+            text: text.to_string(),
+        });
+    }
+
+    /// Parse the payload of a SYLT frame
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < 6 {
+            return Err(Error::ContentLengthExceedsFrameArea);
+        }
+        let encoding = TextEncoding::from_byte(data[0])
+            .ok_or_else(|| Error::Other(format!("Invalid text encoding: {}", data[0])))?;
+        let lang = TextEncoding::Latin1.decode(&data[1..4]);
+        let timestamp_format = TimestampFormat::from_byte(data[4])
+            .ok_or_else(|| Error::Other(format!("Invalid timestamp format: {}", data[4])))?;
+        let content_type = SyncedContentType::from(data[5]);
+        let (description, mut rest) = split_terminated(&data[6..], encoding);
+
+        let mut lines = Vec::new();
+        while !rest.is_empty() {
+            let (text, after_text) = split_terminated(rest, encoding);
+            if after_text.len() < 4 {
+                return Err(Error::ContentLengthExceedsFrameArea);
+            }
+            let timestamp = u32::from_be_bytes([after_text[0], after_text[1], after_text[2], after_text[3]]);
+            lines.push(SyncedLine {
+                timestamp,
+                text: encoding.decode(text),
+            });
+            rest = &after_text[4..];
+        }
+
+        Ok(Self {
+            lang,
+            timestamp_format,
+            content_type,
+            description: encoding.decode(description),
+            lines,
+        })
+    }
+
+    /// Serialize into a SYLT frame payload
+    pub fn to_frame_data(&self, version: Version) -> Vec<u8> {
+        let all_text = self
+            .lines
+            .iter()
+            .fold(self.description.clone(), |acc, line| acc + &line.text);
+        let encoding = TextEncoding::for_text(&all_text, version);
+
+        let mut data = vec![encoding.as_byte()];
+        let mut lang = self.lang.as_bytes().to_vec();
+        lang.resize(3, b' ');
+        data.extend_from_slice(&lang[..3]);
+        data.push(self.timestamp_format.as_byte());
+        data.push(self.content_type.into());
+        data.extend_from_slice(&encoding.encode_terminated(&self.description));
+        for line in &self.lines {
+            data.extend_from_slice(&encoding.encode_terminated(&line.text));
+            data.extend_from_slice(&line.timestamp.to_be_bytes());
+        }
+        data
+    }
+
+    /// Build the frame holding these lyrics
+    pub fn to_frame(&self, version: Version) -> Frame {
+        let id = if version == Version::V2 { "SLT" } else { "SYLT" };
+        Frame::from_data(id, self.to_frame_data(version))
+    }
+}
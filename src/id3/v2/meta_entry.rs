@@ -19,6 +19,29 @@ pub fn supported_entries() -> Vec<MetaEntry> {
         MetaEntry::OriginalFilename,
         MetaEntry::FileType,
         MetaEntry::BandOrchestra,
+        MetaEntry::AttachedPicture,
+        MetaEntry::Lyrics,
+        MetaEntry::PodcastDescription,
+        MetaEntry::PodcastId,
+        MetaEntry::PodcastFeedUrl,
+        MetaEntry::PodcastCategory,
+        MetaEntry::PodcastKeywords,
+        MetaEntry::Compilation,
+        MetaEntry::AlbumArtistSortOrder,
+        MetaEntry::ComposerSortOrder,
+        MetaEntry::TitleSortOrder,
+        MetaEntry::ArtistSortOrder,
+        MetaEntry::AlbumSortOrder,
+        MetaEntry::PartOfSet,
+        MetaEntry::BeatsPerMinute,
+        MetaEntry::InitialKey,
+        MetaEntry::Isrc,
+        MetaEntry::Publisher,
+        MetaEntry::Copyright,
+        MetaEntry::EncodedBy,
+        MetaEntry::Conductor,
+        MetaEntry::Remixer,
+        MetaEntry::Rating,
         // Custom entries are also supported
     ]
 }
@@ -42,6 +65,29 @@ pub fn is_supported(entry: &MetaEntry) -> bool {
         MetaEntry::OriginalFilename |
         MetaEntry::FileType |
         MetaEntry::BandOrchestra |
+        MetaEntry::AttachedPicture |
+        MetaEntry::Lyrics |
+        MetaEntry::PodcastDescription |
+        MetaEntry::PodcastId |
+        MetaEntry::PodcastFeedUrl |
+        MetaEntry::PodcastCategory |
+        MetaEntry::PodcastKeywords |
+        MetaEntry::Compilation |
+        MetaEntry::AlbumArtistSortOrder |
+        MetaEntry::ComposerSortOrder |
+        MetaEntry::TitleSortOrder |
+        MetaEntry::ArtistSortOrder |
+        MetaEntry::AlbumSortOrder |
+        MetaEntry::PartOfSet |
+        MetaEntry::BeatsPerMinute |
+        MetaEntry::InitialKey |
+        MetaEntry::Isrc |
+        MetaEntry::Publisher |
+        MetaEntry::Copyright |
+        MetaEntry::EncodedBy |
+        MetaEntry::Conductor |
+        MetaEntry::Remixer |
+        MetaEntry::Rating |
         MetaEntry::Custom(_)
     )
 }
@@ -1,7 +1,21 @@
+pub mod chapter;
+pub mod comment;
+pub mod compression;
+pub mod convert;
+pub mod encoding;
 pub mod frame;
 pub mod frame_mapping;
 pub mod header;
+pub mod lyrics;
+pub mod people;
 pub mod meta_entry;
+pub mod picture;
+pub mod play_counter;
+pub mod popularimeter;
+pub mod private;
+pub mod stream;
 pub mod tag;
+pub mod ufid;
+pub mod user_text;
 pub mod util;
 pub mod version;
@@ -0,0 +1,36 @@
+//! Involved people (TIPL, ID3v2.3 IPLS) and musician credits (TMCL) lists
+
+use crate::id3::v2::frame::Frame;
+use crate::id3::v2::version::Version;
+
+/// Frame ID of the involved people list in the given version
+pub fn involved_people_frame_id(version: Version) -> &'static str {
+    match version {
+        Version::V2 => "IPL",
+        Version::V3 => "IPLS",
+        Version::V4 => "TIPL",
+    }
+}
+
+/// Decode the role and name pairs of a people list frame
+///
+/// A trailing terminator is ignored and a role without a name gets an empty name.
+pub fn parse_people_list(frame: &Frame) -> Vec<(String, String)> {
+    let mut values = frame.text_values();
+    if values.len() % 2 == 1 && values.last().is_some_and(String::is_empty) {
+        values.pop();
+    }
+    values
+        .chunks(2)
+        .map(|pair| (pair[0].clone(), pair.get(1).cloned().unwrap_or_default()))
+        .collect()
+}
+
+/// Text of a people list frame; every version separates the values with null characters
+pub fn join_people_list(people: &[(String, String)]) -> String {
+    people
+        .iter()
+        .flat_map(|(role, name)| [role.as_str(), name.as_str()])
+        .collect::<Vec<_>>()
+        .join("\0")
+}
@@ -0,0 +1,235 @@
+use std::fmt;
+
+use crate::error::{Error, Result};
+use crate::id3::v2::encoding::{split_terminated, TextEncoding};
+use crate::id3::v2::frame::Frame;
+use crate::id3::v2::version::Version;
+
+/// Picture type byte of an APIC frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PictureType {
+    Other,
+    FileIcon,
+    OtherFileIcon,
+    CoverFront,
+    CoverBack,
+    Leaflet,
+    Media,
+    LeadArtist,
+    Artist,
+    Conductor,
+    Band,
+    Composer,
+    Lyricist,
+    RecordingLocation,
+    DuringRecording,
+    DuringPerformance,
+    ScreenCapture,
+    BrightColouredFish,
+    Illustration,
+    BandLogo,
+    PublisherLogo,
+    /// Value outside the range defined by the specification
+    Undefined(u8),
+}
+
+impl From<u8> for PictureType {
+    fn from(value: u8) -> Self {
+        match value {
+            0x00 => Self::Other,
+            0x01 => Self::FileIcon,
+            0x02 => Self::OtherFileIcon,
+            0x03 => Self::CoverFront,
+            0x04 => Self::CoverBack,
+            0x05 => Self::Leaflet,
+            0x06 => Self::Media,
+            0x07 => Self::LeadArtist,
+            0x08 => Self::Artist,
+            0x09 => Self::Conductor,
+            0x0A => Self::Band,
+            0x0B => Self::Composer,
+            0x0C => Self::Lyricist,
+            0x0D => Self::RecordingLocation,
+            0x0E => Self::DuringRecording,
+            0x0F => Self::DuringPerformance,
+            0x10 => Self::ScreenCapture,
+            0x11 => Self::BrightColouredFish,
+            0x12 => Self::Illustration,
+            0x13 => Self::BandLogo,
+            0x14 => Self::PublisherLogo,
+            other => Self::Undefined(other),
+        }
+    }
+}
+
+impl From<PictureType> for u8 {
+    fn from(picture_type: PictureType) -> Self {
+        match picture_type {
+            PictureType::Other => 0x00,
+            PictureType::FileIcon => 0x01,
+            PictureType::OtherFileIcon => 0x02,
+            PictureType::CoverFront => 0x03,
+            PictureType::CoverBack => 0x04,
+            PictureType::Leaflet => 0x05,
+            PictureType::Media => 0x06,
+            PictureType::LeadArtist => 0x07,
+            PictureType::Artist => 0x08,
+            PictureType::Conductor => 0x09,
+            PictureType::Band => 0x0A,
+            PictureType::Composer => 0x0B,
+            PictureType::Lyricist => 0x0C,
+            PictureType::RecordingLocation => 0x0D,
+            PictureType::DuringRecording => 0x0E,
+            PictureType::DuringPerformance => 0x0F,
+            PictureType::ScreenCapture => 0x10,
+            PictureType::BrightColouredFish => 0x11,
+            PictureType::Illustration => 0x12,
+            PictureType::BandLogo => 0x13,
+            PictureType::PublisherLogo => 0x14,
+            PictureType::Undefined(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for PictureType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Other => "Other",
+            Self::FileIcon => "File icon",
+            Self::OtherFileIcon => "Other file icon",
+            Self::CoverFront => "Cover (front)",
+            Self::CoverBack => "Cover (back)",
+            Self::Leaflet => "Leaflet page",
+            Self::Media => "Media",
+            Self::LeadArtist => "Lead artist",
+            Self::Artist => "Artist",
+            Self::Conductor => "Conductor",
+            Self::Band => "Band",
+            Self::Composer => "Composer",
+            Self::Lyricist => "Lyricist",
+            Self::RecordingLocation => "Recording location",
+            Self::DuringRecording => "During recording",
+            Self::DuringPerformance => "During performance",
+            Self::ScreenCapture => "Screen capture",
+            Self::BrightColouredFish => "A bright coloured fish",
+            Self::Illustration => "Illustration",
+            Self::BandLogo => "Band logo",
+            Self::PublisherLogo => "Publisher logo",
+            Self::Undefined(value) => return write!(f, "Undefined ({})", value),
+        };
+        f.write_str(name)
+    }
+}
+
+/// Attached picture (APIC, or PIC in ID3v2.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Picture {
+    pub mime_type: String,
+    pub picture_type: PictureType,
+    pub description: String,
+    pub data: Vec<u8>,
+}
+
+impl Picture {
+    pub fn new(mime_type: &str, picture_type: PictureType, description: &str, data: Vec<u8>) -> Self {
+        Self {
+            mime_type: mime_type.to_string(),
+            picture_type,
+            description: description.to_string(),
+            data,
+        }
+    }
+
+    /// Create a front cover picture, guessing the MIME type from the image bytes
+    pub fn front_cover(data: Vec<u8>) -> Self {
+        let mime_type = guess_mime_type(&data).unwrap_or("image/");
+        Self::new(mime_type, PictureType::CoverFront, "", data)
+    }
+
+    /// Parse the payload of an APIC (or v2.2 PIC) frame
+    pub fn parse(data: &[u8], version: Version) -> Result<Self> {
+        let (&encoding_byte, rest) = data.split_first().ok_or(Error::NoFramePayloadLength)?;
+        let encoding = TextEncoding::from_byte(encoding_byte)
+            .ok_or_else(|| Error::Other(format!("Invalid text encoding: {}", encoding_byte)))?;
+
+        let (mime_type, rest) = if version == Version::V2 {
+            // ID3v2.2 stores a three character image format instead of a MIME type
+            if rest.len() < 3 {
+                return Err(Error::ContentLengthExceedsFrameArea);
+            }
+            let format = String::from_utf8_lossy(&rest[..3]).to_ascii_lowercase();
+            let mime_type = match format.as_str() {
+                "jpg" => "image/jpeg".to_string(),
+                "-->" => "-->".to_string(),
+                other => format!("image/{}", other),
+            };
+            (mime_type, &rest[3..])
+        } else {
+            let (mime, rest) = split_terminated(rest, TextEncoding::Latin1);
+            (TextEncoding::Latin1.decode(mime), rest)
+        };
+
+        let (&type_byte, rest) = rest.split_first().ok_or(Error::ContentLengthExceedsFrameArea)?;
+        let (description, image) = split_terminated(rest, encoding);
+
+        Ok(Self {
+            mime_type,
+            picture_type: PictureType::from(type_byte),
+            description: encoding.decode(description),
+            data: image.to_vec(),
+        })
+    }
+
+    /// Serialize the picture into an APIC (or v2.2 PIC) frame payload
+    pub fn to_frame_data(&self, version: Version) -> Vec<u8> {
+        let encoding = TextEncoding::for_text(&self.description, version);
+        let mut data = vec![encoding.as_byte()];
+
+        if version == Version::V2 {
+            let format = match self.mime_type.as_str() {
+                "image/jpeg" | "image/jpg" => "JPG".to_string(),
+                mime => mime.trim_start_matches("image/").to_ascii_uppercase(),
+            };
+            let mut format = format.into_bytes();
+            format.resize(3, b' ');
+            data.extend_from_slice(&format);
+        } else {
+            data.extend_from_slice(&TextEncoding::Latin1.encode_terminated(&self.mime_type));
+        }
+
+        data.push(self.picture_type.into());
+        data.extend_from_slice(&encoding.encode_terminated(&self.description));
+        data.extend_from_slice(&self.data);
+        data
+    }
+
+    /// Build the frame holding this picture
+    pub fn to_frame(&self, version: Version) -> Frame {
+        let id = if version == Version::V2 { "PIC" } else { "APIC" };
+        Frame::from_data(id, self.to_frame_data(version))
+    }
+}
+
+impl fmt::Display for Picture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}, {} bytes)", self.mime_type, self.picture_type, self.data.len())?;
+        if !self.description.is_empty() {
+            write!(f, ": {}", self.description)?;
+        }
+        Ok(())
+    }
+}
+
+/// Guess an image MIME type from its magic bytes
+pub fn guess_mime_type(data: &[u8]) -> Option<&'static str> {
+    match data {
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [b'G', b'I', b'F', b'8', ..] => Some("image/gif"),
+        [b'B', b'M', ..] => Some("image/bmp"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ => None,
+    }
+}
@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use crate::error::Result;
+use crate::id3::v2::tag::TagWriter;
+use crate::tag::TagWriterStrategy;
+
+/// Decode a play counter (PCNT payload or POPM suffix)
+///
+/// The counter is big endian and at least four bytes long, growing as needed.
+/// Shorter counters written by other taggers are read as is and an empty one
+/// as zero. Values beyond 64 bits saturate at `u64::MAX`.
+pub fn decode_counter(data: &[u8]) -> u64 {
+    data.iter().fold(0u64, |count, &byte| {
+        count.checked_mul(256).map_or(u64::MAX, |count| count | byte as u64)
+    })
+}
+
+/// Encode a play counter big endian, using at least four bytes as the spec requires
+pub fn encode_counter(count: u64) -> Vec<u8> {
+    let bytes = count.to_be_bytes();
+    let first = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    bytes[first.min(4)..].to_vec()
+}
+
+/// Increment the PCNT play counter of a file and return the new count
+///
+/// The tag is read, bumped and rewritten in a single pass while holding an
+/// advisory lock on the file, so concurrent increments are not lost.
+/// A missing counter starts at zero; the counter saturates instead of wrapping.
+pub fn increment_play_count<P: AsRef<Path>>(path: P) -> Result<u64> {
+    let mut writer = TagWriter::new();
+    writer.init(path.as_ref())?;
+    writer.increment_play_count()
+}
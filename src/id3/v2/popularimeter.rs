@@ -0,0 +1,92 @@
+use crate::error::{Error, Result};
+use crate::id3::v2::encoding::{split_terminated, TextEncoding};
+use crate::id3::v2::frame::Frame;
+use crate::id3::v2::play_counter::{decode_counter, encode_counter};
+use crate::id3::v2::version::Version;
+
+/// Rating from a popularimeter frame (POPM, or POP in ID3v2.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rating {
+    /// Email identifying the user or player that set the rating
+    pub email: String,
+    /// Raw rating, 1 (worst) to 255 (best), 0 when unknown
+    pub rating_0_255: u8,
+    /// Number of times the file was played, if the frame carries a counter
+    pub play_count: Option<u64>,
+}
+
+impl Rating {
+    pub fn new(email: &str, rating_0_255: u8) -> Self {
+        Self {
+            email: email.to_string(),
+            rating_0_255,
+            play_count: None,
+        }
+    }
+
+    /// Create a rating from a 0–5 star value
+    pub fn from_stars(email: &str, stars: u8) -> Self {
+        Self::new(email, stars_to_rating(stars))
+    }
+
+    /// The rating on a 0–5 star scale
+    pub fn stars(&self) -> u8 {
+        rating_to_stars(self.rating_0_255)
+    }
+
+    /// Parse the payload of a POPM frame
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let (email, rest) = split_terminated(data, TextEncoding::Latin1);
+        let (&rating_0_255, counter) = rest.split_first().ok_or(Error::ContentLengthExceedsFrameArea)?;
+        let play_count = if counter.is_empty() {
+            None
+        } else {
+            Some(decode_counter(counter))
+        };
+        Ok(Self {
+            email: TextEncoding::Latin1.decode(email),
+            rating_0_255,
+            play_count,
+        })
+    }
+
+    /// Serialize into a POPM frame payload
+    pub fn to_frame_data(&self) -> Vec<u8> {
+        let mut data = TextEncoding::Latin1.encode_terminated(&self.email);
+        data.push(self.rating_0_255);
+        if let Some(count) = self.play_count {
+            data.extend_from_slice(&encode_counter(count));
+        }
+        data
+    }
+
+    /// Build the frame holding this rating
+    pub fn to_frame(&self, version: Version) -> Frame {
+        let id = if version == Version::V2 { "POP" } else { "POPM" };
+        Frame::from_data(id, self.to_frame_data())
+    }
+}
+
+/// Map a 0–255 rating to stars, using the ranges common players agree on
+pub fn rating_to_stars(rating: u8) -> u8 {
+    match rating {
+        0 => 0,
+        1..=31 => 1,
+        32..=95 => 2,
+        96..=159 => 3,
+        160..=223 => 4,
+        224..=255 => 5,
+    }
+}
+
+/// Map 0–5 stars to the 0–255 values written by Windows Media Player
+pub fn stars_to_rating(stars: u8) -> u8 {
+    match stars {
+        0 => 0,
+        1 => 1,
+        2 => 64,
+        3 => 128,
+        4 => 196,
+        _ => 255,
+    }
+}
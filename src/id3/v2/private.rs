@@ -0,0 +1,46 @@
+use crate::error::{Error, Result};
+use crate::id3::v2::encoding::{split_terminated, TextEncoding};
+use crate::id3::v2::frame::Frame;
+
+/// Private frame (PRIV): application specific binary data keyed by an owner
+///
+/// ID3v2.2 has no equivalent frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivateFrame {
+    /// URL or email identifying the application that owns the data
+    pub owner: String,
+    pub data: Vec<u8>,
+}
+
+impl PrivateFrame {
+    pub fn new(owner: &str, data: Vec<u8>) -> Self {
+        Self {
+            owner: owner.to_string(),
+            data,
+        }
+    }
+
+    /// Parse the payload of a PRIV frame
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let (owner, private_data) = split_terminated(data, TextEncoding::Latin1);
+        if owner.is_empty() {
+            return Err(Error::Other("PRIV frame without owner".to_string()));
+        }
+        Ok(Self {
+            owner: TextEncoding::Latin1.decode(owner),
+            data: private_data.to_vec(),
+        })
+    }
+
+    /// Serialize into a PRIV frame payload
+    pub fn to_frame_data(&self) -> Vec<u8> {
+        let mut data = TextEncoding::Latin1.encode_terminated(&self.owner);
+        data.extend_from_slice(&self.data);
+        data
+    }
+
+    /// Build the frame holding this data
+    pub fn to_frame(&self) -> Frame {
+        Frame::from_data("PRIV", self.to_frame_data())
+    }
+}
@@ -0,0 +1,148 @@
+//! Frame-by-frame reading of ID3v2 tags
+//!
+//! `Tag::parse` and `TagReader` read the whole tag into memory before parsing
+//! it. `FrameIter` holds one frame at a time, so a tag declaring hundreds of
+//! megabytes costs no more than its largest frame actually present in the file.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use log::warn;
+
+use crate::error::{Error, Result};
+use crate::id3::constants::{HEADER_SIZE, ID3V2_IDENTIFIER};
+use crate::id3::v2::frame::Frame;
+use crate::id3::v2::header::{ExtendedHeader, Header};
+use crate::id3::v2::util::synchsafe_to_int;
+use crate::id3::v2::version::Version;
+use crate::tag::TagType;
+use crate::telemetry::{self, AnomalyKind};
+
+/// Iterator over the frames of an ID3v2 tag, read from disk one at a time
+///
+/// Iteration ends at the padding, at the end of the tag, at an empty frame or
+/// at a frame whose declared size runs past the tag, and after an error.
+/// Unlike `Tag`, frames with unsupported IDs are yielded too, and the CRC of
+/// an extended header is not checked.
+#[derive(Debug)]
+pub struct FrameIter<R> {
+    reader: R,
+    version: Version,
+    /// Declared size of the tag, without its header
+    size: u64,
+    /// Bytes of the tag not read yet
+    remaining: u64,
+}
+
+impl FrameIter<BufReader<File>> {
+    /// Iterate over the frames of the ID3v2 tag at the start of a file
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> FrameIter<R> {
+    /// Read the header, and the extended header if any, of the tag at the position of `reader`
+    ///
+    /// Fails with `TagNotFound` when no ID3v2 header is found there.
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut buffer = [0u8; HEADER_SIZE];
+        match reader.read_exact(&mut buffer) {
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Err(Error::TagNotFound),
+            result => result?,
+        }
+        if !buffer.starts_with(ID3V2_IDENTIFIER) {
+            return Err(Error::TagNotFound);
+        }
+        let header = Header::parse(&buffer)?;
+        if !header.is_valid() {
+            return Err(Error::InvalidHeader);
+        }
+
+        let version = Version::from(header.version);
+        let mut frames = Self { reader, version, size: header.size as u64, remaining: header.size as u64 };
+        if header.has_extended_header() {
+            let mut data = frames.read_bytes(4)?;
+            let size = match version {
+                // The ID3v2.3 size field excludes itself
+                Version::V3 => u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as u64 + 4,
+                _ => synchsafe_to_int(&data) as u64,
+            };
+            data.extend(frames.read_bytes(size.saturating_sub(4))?);
+            ExtendedHeader::parse(&data, version)?;
+        }
+        Ok(frames)
+    }
+
+    /// Version of the tag
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Read `len` bytes of the tag, allocating only as much as the reader delivers
+    fn read_bytes(&mut self, len: u64) -> Result<Vec<u8>> {
+        if len > self.remaining {
+            return Err(Error::InvalidTagSize);
+        }
+        let mut data = Vec::new();
+        (&mut self.reader).take(len).read_to_end(&mut data)?;
+        if (data.len() as u64) < len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        self.remaining -= len;
+        Ok(data)
+    }
+
+    /// Read the next frame, `None` at the end of the frames
+    fn read_frame(&mut self) -> Result<Option<Frame>> {
+        let header_size = Frame::header_size(self.version) as u64;
+        if self.remaining < header_size {
+            return Ok(None);
+        }
+        let offset = self.size - self.remaining;
+        let mut data = self.read_bytes(header_size)?;
+        if data[..Frame::id_size(self.version)].iter().all(|&b| b == 0) {
+            // Padding
+            return Ok(None);
+        }
+
+        let size = Frame::declared_size(&data, self.version).ok_or(Error::InvalidHeader)? as u64;
+        if size > self.remaining {
+            warn!("Invalid frame size at offset {}", offset);
+            telemetry::report(
+                AnomalyKind::InvalidFrameSize,
+                TagType::Id3v2,
+                Some(offset),
+                format!("Frame size {} exceeds remaining tag size {}", size, self.remaining),
+            );
+            return Ok(None);
+        }
+        data.extend(self.read_bytes(size)?);
+
+        let frame = Frame::parse(&data, self.version)?;
+        if frame.is_empty() {
+            warn!("Empty frame found at offset {}", offset);
+            telemetry::report(
+                AnomalyKind::EmptyFrame,
+                TagType::Id3v2,
+                Some(offset),
+                format!("Frame '{}' has no payload", frame.id),
+            );
+            return Ok(None);
+        }
+        Ok(Some(frame))
+    }
+}
+
+impl<R: Read> Iterator for FrameIter<R> {
+    type Item = Result<Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frame = self.read_frame();
+        if !matches!(frame, Ok(Some(_))) {
+            self.remaining = 0;
+        }
+        frame.transpose()
+    }
+}
@@ -1,32 +1,74 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use log::{warn};
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::fs::OpenOptions;
 
+use crate::date::RecordingDate;
 use crate::error::{Error, Result};
+use crate::file_access::FileLock;
 use crate::id3::constants::*;
-use crate::id3::v2::frame::Frame;
+use crate::id3::genre::{format_tcon, parse_tcon, GenreFormat};
+use crate::id3::v2::chapter::{Chapter, TableOfContents};
+use crate::id3::v2::comment::Comment;
+use crate::id3::v2::compression;
+use crate::id3::v2::convert::{timestamp_to_v3_date, v3_date_to_timestamp, ENCODED_FRAMES, V4_ONLY_FRAMES};
+use crate::id3::v2::encoding::{split_terminated, TextEncoding};
+use crate::id3::v2::frame::{join_text_values, Frame};
 use crate::id3::v2::frame_mapping::{v2_0, v3_v4};
-use crate::id3::v2::header::Header;
-use crate::id3::v2::util::has_id3v2_tag;
+use crate::id3::v2::header::{existing_tag_size, ExtendedHeader, Header, FLAG_EXTENDED_HEADER, FLAG_FOOTER};
+use crate::id3::v2::people::{involved_people_frame_id, join_people_list, parse_people_list};
+use crate::id3::v2::lyrics::{Lyrics, SynchronisedLyrics, UNKNOWN_LANGUAGE};
+use crate::id3::v2::picture::Picture;
+use crate::id3::v2::play_counter::{decode_counter, encode_counter};
+use crate::id3::v2::popularimeter::Rating;
+use crate::id3::v2::private::PrivateFrame;
+use crate::id3::v2::ufid::{ufid_frame_id, UniqueFileId};
+use crate::id3::v2::user_text::{user_text_frame_id, UserText};
+use crate::id3::v2::util::{crc32, has_id3v2_tag, starts_with_id3v2_tag};
 use crate::id3::v2::version::Version;
 use crate::meta_entry::MetaEntry;
 use crate::tag::{TagReaderStrategy, TagType, TagWriterStrategy};
-
-const FRAME_HEADER_SIZE: usize = 10;
-const FRAME_ID_SIZE: usize = 4;
+use crate::telemetry::{self, AnomalyKind};
+use crate::util::{self, PreserveOptions};
+use crate::validation::{normalize_language, BaseValidator, StandardValidator};
 
 /// Template Method Pattern for ID3v2 tag parsing
 trait TagParser {
     /// Template method - defines the parsing algorithm
     fn parse_tag(&self, path: &Path) -> Result<Tag> {
         let mut file = self.open_file(path)?;
-        let header = self.read_and_parse_header(&mut file)?;
-        let tag_data = self.read_tag_data(&mut file, &header)?;
-        let frames = self.parse_frames(&tag_data, &header)?;
+        self.parse_from(&mut file)
+    }
+
+    /// Template method - parses the tag at the current position of an open file
+    fn parse_from(&self, file: &mut File) -> Result<Tag> {
+        let header = self.read_and_parse_header(file)?;
+        let tag_data = self.read_tag_data(file, &header)?;
+        self.parse_tag_data(header, &tag_data)
+    }
+
+    /// Template method - parses a tag held in memory, starting with its header
+    fn parse_bytes(&self, data: &[u8]) -> Result<Tag> {
+        if !data.starts_with(ID3V2_IDENTIFIER) {
+            return Err(Error::TagNotFound);
+        }
+        let header = Header::parse(data)?;
+        if !header.is_valid() {
+            return Err(Error::InvalidHeader);
+        }
+        let tag_data = data.get(HEADER_SIZE..HEADER_SIZE + header.size as usize).ok_or(Error::InvalidHeader)?;
+        self.parse_tag_data(header, tag_data)
+    }
+
+    /// Concrete method - parses the extended header and frames following the header
+    fn parse_tag_data(&self, header: Header, tag_data: &[u8]) -> Result<Tag> {
+        let frame_area = self.skip_extended_header(tag_data, &header)?;
+        let frames = self.parse_frames(frame_area, &header)?;
         self.build_tag(header, frames)
     }
 
@@ -55,17 +97,39 @@ trait TagParser {
         Ok(tag_buf)
     }
 
+    /// Concrete method - parses the extended header, if any, and returns the data following it
+    fn skip_extended_header<'a>(&self, tag_data: &'a [u8], header: &Header) -> Result<&'a [u8]> {
+        if !header.has_extended_header() {
+            return Ok(tag_data);
+        }
+        let version = Version::from(header.version);
+        let extended_header = ExtendedHeader::parse(tag_data, version)?;
+        let frame_area = &tag_data[extended_header.size as usize..];
+
+        if let Some(expected) = extended_header.crc.filter(|_| self.should_validate_crc()) {
+            let actual = crc32(extended_header.crc_data(frame_area, version));
+            if actual != expected {
+                return Err(Error::CrcMismatch(expected, actual));
+            }
+        }
+        Ok(frame_area)
+    }
+
     /// Concrete method - parses all frames from tag data
-    fn parse_frames(&self, tag_buf: &[u8], header: &Header) -> Result<HashMap<String, Vec<Frame>>> {
+    fn parse_frames(&self, frame_area: &[u8], header: &Header) -> Result<HashMap<String, Vec<Frame>>> {
+        // One copy of the frame area, shared by the payloads (not the IDs or text) of all frames
+        let tag_buf: Arc<[u8]> = Arc::from(frame_area);
         let mut frames = HashMap::new();
         let mut offset = 0;
         let tag_size = tag_buf.len();
 
         while offset < tag_size {
-            match self.parse_single_frame(tag_buf, &mut offset, header) {
+            let start = offset;
+            match self.parse_single_frame(&tag_buf, &mut offset, header) {
                 Ok(Some(frame)) => {
                     self.collect_frame(&mut frames, frame);
                 }
+                Ok(None) if offset > start => continue, // Unsupported frame skipped
                 Ok(None) => break, // End of frames
                 Err(e) => return Err(e),
             }
@@ -75,42 +139,60 @@ trait TagParser {
     }
 
     /// Parse a single frame at the given offset
-    fn parse_single_frame(&self, tag_buf: &[u8], offset: &mut usize, header: &Header) -> Result<Option<Frame>> {
+    fn parse_single_frame(&self, tag_buf: &Arc<[u8]>, offset: &mut usize, header: &Header) -> Result<Option<Frame>> {
+        let version = Version::from(header.version);
+        let header_size = Frame::header_size(version);
+
         // Check if we have enough bytes for a frame header
-        if *offset + FRAME_HEADER_SIZE > tag_buf.len() {
-            return Ok(None);
-        }
+        let frame_size = match Frame::declared_size(&tag_buf[*offset..], version) {
+            Some(size) => size,
+            None => return Ok(None),
+        };
 
         // Security: Check that the frame header is not pointing outside the tag
-        let size_bytes = [tag_buf[*offset + 4], tag_buf[*offset + 5], tag_buf[*offset + 6], tag_buf[*offset + 7]];
-        let frame_size = u32::from_be_bytes(size_bytes) as usize;
-        if *offset + FRAME_HEADER_SIZE + frame_size > tag_buf.len() {
+        if *offset + header_size + frame_size > tag_buf.len() {
             // The frame size is invalid, stop parsing
             warn!("Invalid frame size at offset {}", *offset);
+            telemetry::report(
+                AnomalyKind::InvalidFrameSize,
+                TagType::Id3v2,
+                Some(*offset as u64),
+                format!("Frame size {} exceeds remaining tag size {}", frame_size, tag_buf.len() - *offset - header_size),
+            );
             return Ok(None);
         }
 
         // Check for empty frame (all zeros) - can be overridden
-        if self.should_check_empty_frame_id() && tag_buf[*offset..*offset + FRAME_ID_SIZE].iter().all(|&b| b == 0) {
+        let id_end = *offset + Frame::id_size(version);
+        if self.should_check_empty_frame_id() && tag_buf[*offset..id_end].iter().all(|&b| b == 0) {
             warn!("Empty zeroed frame found at offset {}", *offset);
             return Ok(None);
         }
 
-        let frame = Frame::parse(&tag_buf[*offset..], header.version)?;
+        let frame = Frame::parse_shared(tag_buf, *offset, version)?;
         if frame.is_empty() {
             warn!("Empty frame found at offset {}", *offset);
+            telemetry::report(
+                AnomalyKind::EmptyFrame,
+                TagType::Id3v2,
+                Some(*offset as u64),
+                format!("Frame '{}' has no payload", frame.id),
+            );
             return Ok(None);
         }
 
-        let frame_size = frame.total_size();
-        if frame_size == 0 {
-            warn!("Invalid frame size at offset {}", *offset);
-            return Ok(None);
-        }
+        // The stored size, which differs from the payload size of compressed frames
+        let frame_size = header_size + frame_size;
 
         // Validate frame ID if validation is enabled
-        if self.should_validate_frame_ids() && !self.is_supported_frame(&frame.id, header.version.into()) {
+        if self.should_validate_frame_ids() && !self.is_supported_frame(&frame.id, version) {
             warn!("Unsupported frame ID '{}' found at offset {}", frame.id, *offset);
+            telemetry::report(
+                AnomalyKind::UnsupportedFrame,
+                TagType::Id3v2,
+                Some(*offset as u64),
+                format!("Unsupported frame ID '{}'", frame.id),
+            );
             *offset += frame_size;
             return Ok(None); // Skip unsupported frames
         }
@@ -124,6 +206,11 @@ trait TagParser {
         true
     }
 
+    /// Hook method - whether a CRC in the extended header must match the tag data
+    fn should_validate_crc(&self) -> bool {
+        true
+    }
+
     /// Hook method - whether to validate frame IDs before collecting
     fn should_validate_frame_ids(&self) -> bool {
         true
@@ -139,7 +226,13 @@ trait TagParser {
 
     /// Strategy method - how to collect/store parsed frames
     fn collect_frame(&self, frames: &mut HashMap<String, Vec<Frame>>, frame: Frame) {
-        frames.entry(frame.id.clone()).or_default().push(frame);
+        // The map key is only copied for the first frame with an ID
+        match frames.get_mut(&frame.id) {
+            Some(list) => list.push(frame),
+            None => {
+                frames.insert(frame.id.clone(), vec![frame]);
+            }
+        }
     }
 
     /// Concrete method - builds the final Tag struct
@@ -157,7 +250,7 @@ struct DefaultTagParser;
 
 impl TagParser for DefaultTagParser {}
 
-/// Parser for existing tags - uses different frame insertion strategy
+/// Parser for existing tags, used by the writer to preserve all frames
 struct ExistingTagParser;
 
 impl TagParser for ExistingTagParser {
@@ -166,9 +259,15 @@ impl TagParser for ExistingTagParser {
         false
     }
 
-    /// Use insert instead of entry().or_insert_with() to match original behavior
-    fn collect_frame(&self, frames: &mut HashMap<String, Vec<Frame>>, frame: Frame) {
-        frames.insert(frame.id.to_string(), vec![frame]);
+    /// The tag is rewritten with a fresh CRC, so a stale one must not block editing
+    fn should_validate_crc(&self) -> bool {
+        false
+    }
+
+    /// Frames without a mapping are written back too, as long as their ID is well-formed
+    fn is_supported_frame(&self, frame_id: &str, version: Version) -> bool {
+        frame_id.len() == Frame::id_size(version)
+            && frame_id.bytes().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
     }
 }
 
@@ -193,6 +292,106 @@ impl TagReader {
     pub fn new() -> Self {
         Self { tag: None }
     }
+
+    /// The tag read by `init`, if the file has one
+    pub fn tag(&self) -> Option<&Tag> {
+        self.tag.as_ref()
+    }
+
+    /// All attached pictures of the tag read by `init`
+    pub fn pictures(&self) -> Vec<Picture> {
+        self.tag.as_ref().map(Tag::pictures).unwrap_or_default()
+    }
+
+    /// Whether the tag read by `init` marks the file as a podcast episode (PCST)
+    pub fn is_podcast(&self) -> bool {
+        self.tag.as_ref().is_some_and(Tag::is_podcast)
+    }
+
+    /// Raw CD table of contents from the MCDI frame of the tag read by `init`
+    pub fn music_cd_identifier(&self) -> Option<&[u8]> {
+        self.tag.as_ref().and_then(Tag::music_cd_identifier)
+    }
+
+    /// All chapters of the tag read by `init`
+    pub fn chapters(&self) -> Vec<Chapter> {
+        self.tag.as_ref().map(Tag::chapters).unwrap_or_default()
+    }
+
+    /// All tables of contents of the tag read by `init`
+    pub fn tables_of_contents(&self) -> Vec<TableOfContents> {
+        self.tag.as_ref().map(Tag::tables_of_contents).unwrap_or_default()
+    }
+
+    /// All comments of the tag read by `init`
+    pub fn comments(&self) -> Vec<Comment> {
+        self.tag.as_ref().map(Tag::comments).unwrap_or_default()
+    }
+
+    /// The comment with the given description, in any language
+    pub fn comment(&self, description: &str) -> Option<Comment> {
+        self.tag.as_ref().and_then(|tag| tag.comment(description))
+    }
+
+    /// All user defined text frames of the tag read by `init`
+    pub fn user_texts(&self) -> Vec<UserText> {
+        self.tag.as_ref().map(Tag::user_texts).unwrap_or_default()
+    }
+
+    /// Value of the TXXX frame with the given description
+    pub fn user_text(&self, description: &str) -> Option<String> {
+        self.tag.as_ref().and_then(|tag| tag.user_text(description))
+    }
+
+    /// All unique file identifiers of the tag read by `init`
+    pub fn unique_file_ids(&self) -> Vec<UniqueFileId> {
+        self.tag.as_ref().map(Tag::unique_file_ids).unwrap_or_default()
+    }
+
+    /// The unique file identifier issued by `owner`
+    pub fn unique_file_id(&self, owner: &str) -> Option<UniqueFileId> {
+        self.tag.as_ref().and_then(|tag| tag.unique_file_id(owner))
+    }
+
+    /// All private frames of the tag read by `init`
+    pub fn private_frames(&self) -> Vec<PrivateFrame> {
+        self.tag.as_ref().map(Tag::private_frames).unwrap_or_default()
+    }
+
+    /// Play count of the tag read by `init`
+    pub fn play_count(&self) -> Option<u64> {
+        self.tag.as_ref().and_then(Tag::play_count)
+    }
+
+    /// All popularimeter ratings of the tag read by `init`
+    pub fn ratings(&self) -> Vec<Rating> {
+        self.tag.as_ref().map(Tag::ratings).unwrap_or_default()
+    }
+
+    /// The rating set by `email`
+    pub fn rating(&self, email: &str) -> Option<Rating> {
+        self.tag.as_ref().and_then(|tag| tag.rating(email))
+    }
+
+    /// Involved people of the tag read by `init`, as role and name pairs
+    pub fn involved_people(&self) -> Vec<(String, String)> {
+        self.tag.as_ref().map(Tag::involved_people).unwrap_or_default()
+    }
+
+    /// Musician credits of the tag read by `init`, as instrument and name pairs
+    pub fn musician_credits(&self) -> Vec<(String, String)> {
+        self.tag.as_ref().map(Tag::musician_credits).unwrap_or_default()
+    }
+
+    /// All unsynchronised lyrics of the tag read by `init`
+    pub fn lyrics(&self) -> Vec<Lyrics> {
+        self.tag.as_ref().map(Tag::lyrics).unwrap_or_default()
+    }
+
+    /// All synchronised lyrics of the tag read by `init`
+    pub fn synchronised_lyrics(&self) -> Vec<SynchronisedLyrics> {
+        self.tag.as_ref().map(Tag::synchronised_lyrics).unwrap_or_default()
+    }
 }
 
 impl TagReaderStrategy for TagReader {
@@ -205,9 +404,72 @@ impl TagReaderStrategy for TagReader {
         Ok(())
     }
 
+    fn init_from_file(&mut self, _path: &Path, file: &mut File) -> Result<()> {
+        file.seek(SeekFrom::Start(0))?;
+        self.tag = None;
+        if starts_with_id3v2_tag(file).unwrap_or(false) {
+            file.seek(SeekFrom::Start(0))?;
+            self.tag = Some(DefaultTagParser.parse_from(file)?);
+        }
+        Ok(())
+    }
+
+    fn init_from_bytes(&mut self, _path: &Path, data: &[u8]) -> Result<()> {
+        self.tag = match Tag::parse(data) {
+            Err(Error::TagNotFound) => None,
+            result => Some(result?),
+        };
+        Ok(())
+    }
+
     fn get_meta_entry(&self, _path: &Path, entry: &MetaEntry) -> Result<String> {
         // Use the cached tag info from init()
         let tag = self.tag.as_ref().ok_or(Error::TagNotFound)?;
+
+        match entry {
+            MetaEntry::AttachedPicture => {
+                return tag
+                    .pictures()
+                    .first()
+                    .map(|picture| picture.to_string())
+                    .ok_or(Error::EntryNotFound);
+            }
+            MetaEntry::Comment => {
+                return tag
+                    .comment("")
+                    .or_else(|| tag.comments().into_iter().next())
+                    .map(|comment| comment.text)
+                    .ok_or(Error::EntryNotFound);
+            }
+            MetaEntry::Lyrics => {
+                return tag
+                    .lyrics()
+                    .into_iter()
+                    .next()
+                    .map(|lyrics| lyrics.text)
+                    .ok_or(Error::EntryNotFound);
+            }
+            MetaEntry::Rating => {
+                // The rating without an email is the one written for the entry
+                return tag
+                    .rating("")
+                    .or_else(|| tag.ratings().into_iter().next())
+                    .map(|rating| rating.stars().to_string())
+                    .ok_or(Error::EntryNotFound);
+            }
+            MetaEntry::Custom(description) => {
+                return tag.user_text(description).ok_or(Error::EntryNotFound);
+            }
+            MetaEntry::Genre => {
+                // Numeric references such as "(13)" are resolved to names
+                let names = tag.genres();
+                if names.is_empty() {
+                    return Err(Error::EntryNotFound);
+                }
+                return Ok(names.join(DEFAULT_MULTI_VALUE_SEPARATOR));
+            }
+            _ => {}
+        }
         
         // Use the cached version instead of re-reading the file
         let frame_id = get_frame_id_for_version(entry, tag.version);
@@ -215,6 +477,10 @@ impl TagReaderStrategy for TagReader {
         if let Some(id) = frame_id {
             if let Some(frames) = tag.frames.get(id) {
                 if let Some(frame) = frames.first() {
+                    // ID3v2.4 values are null separated, each possibly with its own byte order mark
+                    if id.starts_with('T') {
+                        return Ok(frame.text_values().join(DEFAULT_MULTI_VALUE_SEPARATOR));
+                    }
                     return Ok(frame.content.clone());
                 }
             }
@@ -222,14 +488,56 @@ impl TagReaderStrategy for TagReader {
         Err(Error::EntryNotFound)
     }
 
+    fn get_meta_entry_values(&self, path: &Path, entry: &MetaEntry) -> Result<Vec<String>> {
+        let tag = self.tag.as_ref().ok_or(Error::TagNotFound)?;
+        if *entry == MetaEntry::Genre {
+            return Some(tag.genres()).filter(|names| !names.is_empty()).ok_or(Error::EntryNotFound);
+        }
+        let frame = get_frame_id_for_version(entry, tag.version)
+            .filter(|id| id.starts_with('T') && *id != "TXXX" && *id != "TXX")
+            .and_then(|id| tag.frames(id).first());
+        match frame {
+            Some(frame) => Ok(frame.text_values()),
+            None => self.get_meta_entry(path, entry).map(|value| vec![value]),
+        }
+    }
+
+    fn get_date(&self, _path: &Path) -> Result<RecordingDate> {
+        let tag = self.tag.as_ref().ok_or(Error::TagNotFound)?;
+        tag.recording_timestamp().ok_or(Error::EntryNotFound)?.parse()
+    }
+
     fn tag_type(&self) -> TagType {
         TagType::Id3v2
     }
 }
 
-#[derive(Debug)]
+/// Separator used to join multiple values in ID3v2.2/2.3 text frames
+pub const DEFAULT_MULTI_VALUE_SEPARATOR: &str = "/";
+
+#[derive(Debug, Clone)]
 pub struct TagWriter {
     path: PathBuf,
+    multi_value_separator: String,
+    /// Encoding for text frames; `None` picks one per value
+    text_encoding: Option<TextEncoding>,
+    /// Whether to write an extended header with a CRC-32
+    write_crc: bool,
+    /// Whether to zlib compress frames on write
+    compress_frames: bool,
+    /// Padding appended when the tag does not fit in the space of the existing one
+    padding: usize,
+    /// File attributes kept when the file is rewritten
+    preserve: PreserveOptions,
+    /// Version of tags created by the writer
+    version: Version,
+    /// Form of genres from the genre table, `None` to store values as given
+    genre_format: Option<GenreFormat>,
+    /// Whether modifications are kept in `pending` until `save`
+    deferred: bool,
+    pending: RefCell<Option<Tag>>,
+    /// Language of comments and lyrics set as meta entries
+    language: String,
 }
 
 impl Default for TagWriter {
@@ -242,7 +550,130 @@ impl TagWriter {
     pub fn new() -> Self {
         Self {
             path: PathBuf::new(),
+            multi_value_separator: DEFAULT_MULTI_VALUE_SEPARATOR.to_string(),
+            text_encoding: None,
+            write_crc: false,
+            compress_frames: false,
+            padding: ID3V2_PADDING_SIZE,
+            preserve: PreserveOptions::default(),
+            version: Version::V3,
+            genre_format: None,
+            deferred: false,
+            pending: RefCell::new(None),
+            language: UNKNOWN_LANGUAGE.to_string(),
+        }
+    }
+
+    /// ISO 639-2 language of comments and lyrics written with `set_meta_entry`, "und" by default
+    pub fn set_language(&mut self, code: &str) -> Result<()> {
+        self.language = normalize_language(code)?;
+        Ok(())
+    }
+
+    /// Store genres as a numeric reference, a name, or both, or `None` to keep values as given
+    ///
+    /// Values are first resolved like on read, so "(17)" and "Rock" both become
+    /// "(17)Rock" with `GenreFormat::Combined`.
+    pub fn set_genre_format(&mut self, format: Option<GenreFormat>) {
+        self.genre_format = format;
+    }
+
+    /// Keep modifications in memory and write them at once on `save`
+    ///
+    /// By default every modification rewrites the tag.
+    pub fn set_deferred(&mut self, deferred: bool) {
+        self.deferred = deferred;
+    }
+
+    /// Whether the file, or the modifications not yet written, have an ID3v2 tag
+    fn has_tag(&self) -> bool {
+        self.pending.borrow().is_some() || has_id3v2_tag(&self.path).unwrap_or(false)
+    }
+
+    /// Version of tags created on files without an ID3v2 tag (ID3v2.3 or ID3v2.4)
+    ///
+    /// Existing tags keep their version; see `convert_version`.
+    pub fn set_version(&mut self, version: Version) -> Result<()> {
+        if version == Version::V2 {
+            return Err(Error::InvalidTagVersion("ID3v2.2 tags cannot be created".to_string()));
         }
+        self.version = version;
+        Ok(())
+    }
+
+    /// Choose which attributes of the original file survive a write
+    ///
+    /// Restoring the mtime also applies to tags updated in place.
+    pub fn set_preserve_options(&mut self, preserve: PreserveOptions) {
+        self.preserve = preserve;
+    }
+
+    /// Padding appended after the frames when the tag cannot be updated in place
+    ///
+    /// Padding lets later edits grow the tag without rewriting the file. Tags
+    /// updated in place keep the space of the existing tag instead.
+    pub fn set_padding(&mut self, padding: usize) {
+        self.padding = padding;
+    }
+
+    /// Compress frames with zlib on write (ID3v2.3 and later)
+    ///
+    /// Frames that would not shrink are stored uncompressed. Requires the
+    /// `compression` feature.
+    pub fn set_compress_frames(&mut self, compress: bool) -> Result<()> {
+        if compress && !compression::SUPPORTED {
+            return Err(Error::Other("Compressed frames require the `compression` feature".to_string()));
+        }
+        self.compress_frames = compress;
+        Ok(())
+    }
+
+    /// Write an extended header holding a CRC-32 of the tag data (ID3v2.3 and later)
+    pub fn set_write_crc(&mut self, write_crc: bool) {
+        self.write_crc = write_crc;
+    }
+
+    /// Encoding used for text frames, or `None` to use ISO-8859-1 for ASCII values
+    /// and Unicode (UTF-16, or UTF-8 in ID3v2.4) otherwise
+    pub fn set_text_encoding(&mut self, encoding: Option<TextEncoding>) {
+        self.text_encoding = encoding;
+    }
+
+    /// Build a text frame in the configured encoding
+    fn text_frame(&self, id: &str, text: &str, version: Version) -> Result<Frame> {
+        let encoding = match self.text_encoding {
+            None => TextEncoding::for_text(text, version),
+            Some(encoding) if !encoding.is_supported_by(version) => {
+                return Err(Error::Other(format!(
+                    "Text encoding {:?} is not supported by ID3v2.{}",
+                    encoding,
+                    u8::from(version)
+                )));
+            }
+            Some(encoding) if !encoding.can_encode(text) => {
+                return Err(Error::Other(format!("Value of frame '{}' cannot be encoded as {:?}", id, encoding)));
+            }
+            Some(encoding) => encoding,
+        };
+        Ok(Frame::new_text_with_encoding(id, text, encoding))
+    }
+
+    /// Separator used to join multiple values when the tag is older than ID3v2.4
+    pub fn set_multi_value_separator(&mut self, separator: &str) {
+        self.multi_value_separator = separator.to_string();
+    }
+
+    /// Set a text entry to several values (null separated in ID3v2.4)
+    pub fn set_meta_entry_values(&self, entry: &MetaEntry, values: &[String]) -> Result<()> {
+        self.modify_tag(|tag| {
+            let frame_id = get_frame_id_for_version(entry, tag.version)
+                .filter(|id| id.starts_with('T') && *id != "TXXX" && *id != "TXX")
+                .ok_or_else(|| Error::Other(format!("Entry does not map to a text frame: {}", entry)))?;
+            let text = join_text_values(values, tag.version, &self.multi_value_separator);
+            let frame = self.text_frame(frame_id, &text, tag.version)?;
+            tag.set_frames(frame_id, vec![frame]);
+            Ok(())
+        })
     }
 
     fn write_tag(&self, tag: &Tag) -> Result<()> {
@@ -251,73 +682,642 @@ impl TagWriter {
             .write(true)
             .open(&self.path)?;
         
+        let metadata = file.metadata()?;
+        let existing = existing_tag_size(&mut file)?;
+        let bytes = self.render_tag(tag, existing);
+        
+        if existing == Some(bytes.len() as u64) {
+            // Same size as the old tag: overwrite it in place
+            file.seek(SeekFrom::Start(0))?;
+            file.write_all(&bytes)?;
+            if self.preserve.mtime {
+                util::restore_times(&file, &metadata)?;
+            }
+            return Ok(());
+        }
+        
+        // The tag grows (or is new): write it to a temporary file followed by the audio
+        let temp_path = util::get_temp_path(&self.path);
+        let result = (|| -> Result<()> {
+            let mut temp_file = File::create(&temp_path)?;
+            temp_file.write_all(&bytes)?;
+            file.seek(SeekFrom::Start(existing.unwrap_or(0)))?;
+            util::copy_file_range(&mut file, &mut temp_file)?;
+            temp_file.sync_all()?;
+            drop(file);
+            util::replace_file(&temp_path, &self.path, self.preserve)
+        })();
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        result
+    }
+
+    /// Serialize header, extended header, frames and padding
+    ///
+    /// When the tag fits in `available` bytes it is padded to exactly that size,
+    /// otherwise the configured padding is appended.
+    fn render_tag(&self, tag: &Tag, available: Option<u64>) -> Vec<u8> {
         let header = Header::new(tag.version.into());
         
         let mut frame_data = Vec::new();
         for frames in tag.frames.values() {
             for frame in frames {
-            frame_data.extend_from_slice(&frame.to_bytes());
+                if self.compress_frames {
+                    let mut frame = frame.clone();
+                    frame.flags.compression = true;
+                    frame_data.extend_from_slice(&frame.to_bytes(tag.version));
+                } else {
+                    frame_data.extend_from_slice(&frame.to_bytes(tag.version));
+                }
             }
         }
         
+        // Any extended header read from the file is dropped, its CRC would be stale
         let mut header = header;
-        header.size = frame_data.len() as u32;
-        header.flags = tag.flags;
-        
-        file.seek(SeekFrom::Start(0))?;
-        file.write_all(&header.to_bytes())?;
-        file.write_all(&frame_data)?;
+        header.flags = tag.flags & !(FLAG_EXTENDED_HEADER | FLAG_FOOTER);
+        let write_crc = self.write_crc && tag.version != Version::V2;
+        let extended_header_size = if write_crc {
+            ExtendedHeader::with_crc(0).to_bytes(tag.version).len()
+        } else {
+            0
+        };
+        let needed = HEADER_SIZE + extended_header_size + frame_data.len();
+
+        // Reuse the space of the existing tag when the new one fits, turning the rest into padding
+        let padding = match available {
+            Some(existing) if needed as u64 <= existing => existing as usize - needed,
+            _ => self.padding,
+        };
+
+        let mut tag_data = Vec::with_capacity(needed - HEADER_SIZE + padding);
+        if write_crc {
+            let mut extended_header = ExtendedHeader::new();
+            extended_header.padding_size = padding as u32;
+            let mut crc_data = frame_data.clone();
+            crc_data.resize(frame_data.len() + padding, 0);
+            extended_header.crc = Some(crc32(extended_header.crc_data(&crc_data, tag.version)));
+            tag_data = extended_header.to_bytes(tag.version);
+            header.flags |= FLAG_EXTENDED_HEADER;
+        }
+        tag_data.extend_from_slice(&frame_data);
+        tag_data.resize(tag_data.len() + padding, 0);
+        header.size = tag_data.len() as u32;
         
-        Ok(())
+        let mut bytes = header.to_bytes();
+        bytes.extend_from_slice(&tag_data);
+        bytes
+    }
+
+    /// The existing tag re-serialized without padding, or `None` when the file has no ID3v2 tag
+    pub(crate) fn compacted_tag(&self) -> Result<Option<Vec<u8>>> {
+        if !has_id3v2_tag(&self.path).unwrap_or(false) {
+            return Ok(None);
+        }
+        let tag = self.read_existing_tag()?;
+        let writer = Self {
+            padding: 0,
+            ..self.clone()
+        };
+        Ok(Some(writer.render_tag(&tag, None)))
     }
 
     fn read_existing_tag(&self) -> Result<Tag> {
         let parser = ExistingTagParser;
         parser.parse_tag(&self.path)
     }
+
+    /// Read the existing tag (or start a new one), apply `f` and write it back
+    ///
+    /// When deferred, `f` is applied to the pending tag, which `save` writes.
+    fn modify_tag<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Tag) -> Result<()>,
+    {
+        if self.deferred {
+            let mut pending = self.pending.borrow_mut();
+            let tag = match pending.take() {
+                Some(tag) => tag,
+                None if has_id3v2_tag(&self.path).unwrap_or(false) => self.read_existing_tag()?,
+                None => Tag::new(self.version),
+            };
+            // Keep the pending tag unchanged when `f` fails
+            let mut modified = tag.clone();
+            let result = f(&mut modified);
+            *pending = Some(if result.is_ok() { modified } else { tag });
+            return result;
+        }
+
+        let _lock = FileLock::acquire(&self.path)?;
+        let mut tag = if has_id3v2_tag(&self.path).unwrap_or(false) {
+            // Read existing tag to preserve other frames
+            self.read_existing_tag()?
+        } else {
+            Tag::new(self.version)
+        };
+        f(&mut tag)?;
+        self.write_tag(&tag)
+    }
+
+    /// Embed a picture, replacing any existing picture of the same type
+    pub fn set_picture(&self, picture: &Picture) -> Result<()> {
+        self.modify_tag(|tag| {
+            let version = tag.version;
+            tag.replace_frames_where(picture.to_frame(version), |frame| {
+                Picture::parse(frame.data(), version)
+                    .map(|existing| existing.picture_type == picture.picture_type)
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Embed an image file as the front cover, guessing its MIME type from the image bytes
+    pub fn set_picture_from_file<P: AsRef<Path>>(&self, image: P) -> Result<()> {
+        let data = std::fs::read(image)?;
+        self.set_picture(&Picture::front_cover(data))
+    }
+
+    /// Mark or unmark the file as a podcast episode with the iTunes PCST frame
+    pub fn set_podcast(&self, podcast: bool) -> Result<()> {
+        self.modify_tag(|tag| {
+            if podcast {
+                // iTunes writes a four byte zero payload; presence of the frame is the flag
+                tag.set_frames("PCST", vec![Frame::from_data("PCST", vec![0; 4])]);
+            } else {
+                tag.remove_frames("PCST");
+            }
+            Ok(())
+        })
+    }
+
+    /// Store a raw CD table of contents in the MCDI frame
+    pub fn set_music_cd_identifier(&self, toc: &[u8]) -> Result<()> {
+        if toc.is_empty() {
+            return Err(Error::Other("MCDI table of contents must not be empty".to_string()));
+        }
+        self.modify_tag(|tag| {
+            let id = music_cd_identifier_frame_id(tag.version);
+            tag.set_frames(id, vec![Frame::from_data(id, toc.to_vec())]);
+            Ok(())
+        })
+    }
+
+    /// Remove the MCDI frame
+    pub fn remove_music_cd_identifier(&self) -> Result<()> {
+        if !self.has_tag() {
+            return Ok(());
+        }
+        self.modify_tag(|tag| {
+            tag.remove_frames(music_cd_identifier_frame_id(tag.version));
+            Ok(())
+        })
+    }
+
+    /// Add a chapter, replacing any chapter with the same element ID
+    pub fn add_chapter(&self, chapter: &Chapter) -> Result<()> {
+        self.modify_tag(|tag| {
+            let version = tag.version;
+            tag.replace_frames_where(chapter.to_frame(version), |frame| {
+                Chapter::parse(frame.data(), version)
+                    .map(|existing| existing.element_id == chapter.element_id)
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Remove a chapter and its references from all tables of contents
+    pub fn remove_chapter(&self, element_id: &str) -> Result<()> {
+        if !self.has_tag() {
+            return Ok(());
+        }
+        self.modify_tag(|tag| {
+            let version = tag.version;
+            tag.remove_frames_where("CHAP", |frame| {
+                Chapter::parse(frame.data(), version)
+                    .map(|existing| existing.element_id == element_id)
+                    .unwrap_or(false)
+            });
+            let tables = tag
+                .tables_of_contents()
+                .into_iter()
+                .map(|mut toc| {
+                    toc.child_element_ids.retain(|child| child != element_id);
+                    toc.to_frame(version)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            tag.set_frames("CTOC", tables);
+            Ok(())
+        })
+    }
+
+    /// Set a table of contents, replacing any with the same element ID
+    pub fn set_table_of_contents(&self, toc: &TableOfContents) -> Result<()> {
+        self.modify_tag(|tag| {
+            let version = tag.version;
+            tag.replace_frames_where(toc.to_frame(version)?, |existing| {
+                TableOfContents::parse(existing.data(), version)
+                    .map(|existing| existing.element_id == toc.element_id)
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Store a comment, replacing any existing comment with the same language and description
+    ///
+    /// The language code is normalized, see `normalize_language`.
+    pub fn set_comment(&self, comment: &Comment) -> Result<()> {
+        let comment = Comment { lang: normalize_language(&comment.lang)?, ..comment.clone() };
+        self.modify_tag(|tag| {
+            tag.replace_frames_where(comment.to_frame(tag.version), |frame| {
+                Comment::parse(frame.data())
+                    .map(|existing| same_language(&existing.lang, &comment.lang) && existing.description == comment.description)
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Remove the comments with the given language and description
+    pub fn remove_comment(&self, lang: &str, description: &str) -> Result<()> {
+        let lang = normalize_language(lang)?;
+        self.remove_comments_where(|comment| same_language(&comment.lang, &lang) && comment.description == description)
+    }
+
+    fn remove_comments_where<F>(&self, matches: F) -> Result<()>
+    where
+        F: Fn(&Comment) -> bool,
+    {
+        if !self.has_tag() {
+            return Ok(());
+        }
+        self.modify_tag(|tag| {
+            tag.remove_frames_where(comment_frame_id(tag.version), |frame| {
+                Comment::parse(frame.data()).map(|existing| matches(&existing)).unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Set a TXXX frame, replacing any existing frame with the same description
+    pub fn set_user_text(&self, description: &str, value: &str) -> Result<()> {
+        let user_text = UserText::new(description, value);
+        self.modify_tag(|tag| {
+            tag.replace_frames_where(user_text.to_frame(tag.version), |frame| {
+                UserText::parse(frame.data())
+                    .map(|existing| existing.matches(description))
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Remove the TXXX frames with the given description
+    pub fn remove_user_text(&self, description: &str) -> Result<()> {
+        if !self.has_tag() {
+            return Ok(());
+        }
+        self.modify_tag(|tag| {
+            tag.remove_frames_where(user_text_frame_id(tag.version), |frame| {
+                UserText::parse(frame.data())
+                    .map(|existing| existing.matches(description))
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Set a UFID frame, replacing any existing identifier from the same owner
+    pub fn set_unique_file_id(&self, ufid: &UniqueFileId) -> Result<()> {
+        self.modify_tag(|tag| {
+            tag.replace_frames_where(ufid.to_frame(tag.version), |frame| {
+                UniqueFileId::parse(frame.data())
+                    .map(|existing| existing.owner == ufid.owner)
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Remove the UFID frames issued by `owner`
+    pub fn remove_unique_file_id(&self, owner: &str) -> Result<()> {
+        if !self.has_tag() {
+            return Ok(());
+        }
+        self.modify_tag(|tag| {
+            tag.remove_frames_where(ufid_frame_id(tag.version), |frame| {
+                UniqueFileId::parse(frame.data())
+                    .map(|existing| existing.owner == owner)
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Set a PRIV frame, replacing existing private data from the same owner
+    pub fn set_private_frame(&self, private: &PrivateFrame) -> Result<()> {
+        self.modify_tag(|tag| {
+            if tag.version == Version::V2 {
+                return Err(Error::Other("PRIV frames are not supported by ID3v2.2".to_string()));
+            }
+            tag.replace_frames_where(private.to_frame(), |frame| {
+                PrivateFrame::parse(frame.data())
+                    .map(|existing| existing.owner == private.owner)
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Remove the PRIV frames owned by `owner`
+    pub fn remove_private_frames(&self, owner: &str) -> Result<()> {
+        if !self.has_tag() {
+            return Ok(());
+        }
+        self.modify_tag(|tag| {
+            tag.remove_frames_where("PRIV", |frame| {
+                PrivateFrame::parse(frame.data())
+                    .map(|existing| existing.owner == owner)
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Set the PCNT play counter
+    pub fn set_play_count(&self, count: u64) -> Result<()> {
+        self.modify_tag(|tag| {
+            let id = play_counter_frame_id(tag.version);
+            tag.set_frames(id, vec![Frame::from_data(id, encode_counter(count))]);
+            Ok(())
+        })
+    }
+
+    /// Replace the involved people list with role and name pairs; an empty list removes it
+    pub fn set_involved_people(&self, people: &[(String, String)]) -> Result<()> {
+        self.modify_tag(|tag| {
+            let version = tag.version;
+            let id = involved_people_frame_id(version);
+            let frames = if people.is_empty() {
+                Vec::new()
+            } else {
+                vec![self.text_frame(id, &join_people_list(people), version)?]
+            };
+            tag.set_frames(id, frames);
+            Ok(())
+        })
+    }
+
+    /// Replace the TMCL musician credits with instrument and name pairs; an empty list removes them
+    ///
+    /// TMCL only exists in ID3v2.4; earlier versions keep musicians in the
+    /// involved people list.
+    pub fn set_musician_credits(&self, credits: &[(String, String)]) -> Result<()> {
+        self.modify_tag(|tag| {
+            let version = tag.version;
+            if version != Version::V4 && !credits.is_empty() {
+                return Err(Error::InvalidTagVersion(format!(
+                    "Musician credits require ID3v2.4, the tag is ID3v2.{}",
+                    u8::from(version)
+                )));
+            }
+            let frames = if credits.is_empty() {
+                Vec::new()
+            } else {
+                vec![self.text_frame("TMCL", &join_people_list(credits), version)?]
+            };
+            tag.set_frames("TMCL", frames);
+            Ok(())
+        })
+    }
+
+    /// Rewrite the tag in another version, see `Tag::convert_version`
+    pub fn convert_version(&self, target: Version) -> Result<()> {
+        if !self.has_tag() {
+            return Err(Error::TagNotFound);
+        }
+        self.modify_tag(|tag| tag.convert_version(target))
+    }
+
+    /// Increment the PCNT play counter in a single read-modify-write and return the new count
+    pub fn increment_play_count(&self) -> Result<u64> {
+        let mut new_count = 0;
+        self.modify_tag(|tag| {
+            new_count = tag.play_count().unwrap_or(0).saturating_add(1);
+            let id = play_counter_frame_id(tag.version);
+            tag.set_frames(id, vec![Frame::from_data(id, encode_counter(new_count))]);
+            Ok(())
+        })?;
+        Ok(new_count)
+    }
+
+    /// Set a POPM frame, replacing any existing rating from the same email
+    pub fn set_rating(&self, rating: &Rating) -> Result<()> {
+        self.modify_tag(|tag| {
+            tag.replace_frames_where(rating.to_frame(tag.version), |frame| {
+                Rating::parse(frame.data())
+                    .map(|existing| existing.email == rating.email)
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Remove the rating set by `email`
+    pub fn remove_rating(&self, email: &str) -> Result<()> {
+        if !self.has_tag() {
+            return Ok(());
+        }
+        self.modify_tag(|tag| {
+            tag.remove_frames_where(popularimeter_frame_id(tag.version), |frame| {
+                Rating::parse(frame.data())
+                    .map(|existing| existing.email == email)
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Store lyrics, replacing existing lyrics with the same language and description
+    ///
+    /// The language code is normalized, see `normalize_language`.
+    pub fn set_lyrics(&self, lyrics: &Lyrics) -> Result<()> {
+        let lyrics = Lyrics { lang: normalize_language(&lyrics.lang)?, ..lyrics.clone() };
+        self.modify_tag(|tag| {
+            tag.replace_frames_where(lyrics.to_frame(tag.version), |frame| {
+                Lyrics::parse(frame.data())
+                    .map(|existing| same_language(&existing.lang, &lyrics.lang) && existing.description == lyrics.description)
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Store synchronised lyrics, replacing existing ones with the same language and description
+    ///
+    /// The language code is normalized, see `normalize_language`.
+    pub fn set_synchronised_lyrics(&self, lyrics: &SynchronisedLyrics) -> Result<()> {
+        let lyrics = SynchronisedLyrics { lang: normalize_language(&lyrics.lang)?, ..lyrics.clone() };
+        self.modify_tag(|tag| {
+            tag.replace_frames_where(lyrics.to_frame(tag.version), |frame| {
+                SynchronisedLyrics::parse(frame.data())
+                    .map(|existing| same_language(&existing.lang, &lyrics.lang) && existing.description == lyrics.description)
+                    .unwrap_or(false)
+            });
+            Ok(())
+        })
+    }
+
+    /// Remove all attached pictures
+    pub fn remove_pictures(&self) -> Result<()> {
+        if !self.has_tag() {
+            return Ok(());
+        }
+        self.modify_tag(|tag| {
+            tag.remove_frames(picture_frame_id(tag.version));
+            Ok(())
+        })
+    }
+
+    /// Strip the ID3v2 tag from the start of the file
+    ///
+    /// Returns whether there was a tag to remove.
+    pub fn remove_tag(&self) -> Result<bool> {
+        self.pending.borrow_mut().take();
+        let _lock = FileLock::acquire(&self.path)?;
+        let mut file = File::open(&self.path)?;
+        let existing = match existing_tag_size(&mut file)? {
+            Some(size) => size,
+            None => return Ok(false),
+        };
+
+        let temp_path = util::get_temp_path(&self.path);
+        let result = (|| -> Result<()> {
+            let mut temp_file = File::create(&temp_path)?;
+            file.seek(SeekFrom::Start(existing))?;
+            util::copy_file_range(&mut file, &mut temp_file)?;
+            temp_file.sync_all()?;
+            drop(file);
+            util::replace_file(&temp_path, &self.path, self.preserve)
+        })();
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        result?;
+        Ok(true)
+    }
 }
 
 impl TagWriterStrategy for TagWriter {
     fn init(&mut self, path: &Path) -> Result<()> {
         self.path = path.to_path_buf();
+        self.pending.replace(None);
         Ok(())
     }
 
     fn set_meta_entry(&mut self, entry: &MetaEntry, value: &str) -> Result<()> {
-        let version = if has_id3v2_tag(&self.path).unwrap_or(false) {
-            // If a tag exists, read its version to ensure we don't downgrade it.
-            let existing_tag = self.read_existing_tag()?;
-            existing_tag.version
-        } else {
-            Version::V3
-        };
+        StandardValidator.validate_format(entry, value)?;
+        match entry {
+            MetaEntry::AttachedPicture => {
+                // Pictures are binary and set with `set_picture`; an empty value removes all pictures
+                if value.is_empty() {
+                    return self.remove_pictures();
+                }
+                return Err(Error::UnsupportedMetaEntry(entry.to_string()));
+            }
+            MetaEntry::Comment => {
+                return self.set_comment(&Comment::new(&self.language, "", value));
+            }
+            MetaEntry::Lyrics => {
+                return self.set_lyrics(&Lyrics::new(&self.language, "", value));
+            }
+            MetaEntry::Rating => {
+                let stars = value
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|stars| *stars <= 5)
+                    .ok_or_else(|| Error::Other(format!("Rating must be 0 to 5 stars: {:?}", value)))?;
+                return self.set_rating(&Rating::from_stars("", stars));
+            }
+            MetaEntry::Custom(description) => {
+                return self.set_user_text(description, value);
+            }
+            _ => {}
+        }
 
-        let frame_id = get_frame_id_for_version(entry, version)
-            .ok_or_else(|| Error::Other(format!("No frame mapping for entry: {}", entry)))?;
+        self.modify_tag(|tag| {
+            let frame_id = get_frame_id_for_version(entry, tag.version)
+                .ok_or_else(|| Error::Other(format!("No frame mapping for entry: {}", entry)))?;
+            let value = match self.genre_format {
+                Some(format) if *entry == MetaEntry::Genre => {
+                    let values: Vec<String> = parse_tcon(value).iter().map(|name| format_tcon(name, format, tag.version)).collect();
+                    join_text_values(&values, tag.version, &self.multi_value_separator)
+                }
+                _ => value.to_string(),
+            };
+            let frame = self.text_frame(frame_id, &value, tag.version)?;
+            tag.set_frames(frame_id, vec![frame]);
+            Ok(())
+        })
+    }
 
-        let frame = Frame::new(frame_id, value);
-        
-        // Read existing tag or create new one
-        let mut tag = if has_id3v2_tag(&self.path).unwrap_or(false) {
-            // Read existing tag to preserve other frames
-            self.read_existing_tag()?
-        } else {
-            // Create new tag if none exists
-            Tag {
-                version,
-                flags: 0,
-                frames: HashMap::new(),
+    fn remove_meta_entry(&mut self, entry: &MetaEntry) -> Result<()> {
+        match entry {
+            MetaEntry::AttachedPicture => return self.remove_pictures(),
+            MetaEntry::Comment => return self.remove_comments_where(|comment| comment.description.is_empty()),
+            MetaEntry::Rating => return self.remove_rating(""),
+            MetaEntry::Custom(description) => return self.remove_user_text(description),
+            _ => {}
+        }
+        if !self.has_tag() {
+            return Ok(());
+        }
+        self.modify_tag(|tag| {
+            if let Some(frame_id) = get_frame_id_for_version(entry, tag.version) {
+                tag.remove_frames(frame_id);
             }
-        };
+            Ok(())
+        })
+    }
 
-        // Update or insert the specific frame
-        tag.frames.insert(frame_id.to_string(), vec![frame]);
+    fn set_date(&mut self, date: &RecordingDate) -> Result<()> {
+        self.modify_tag(|tag| {
+            let version = tag.version;
+            let [year_id, date_id, time_id] = date_frame_ids(version);
+            if version == Version::V4 {
+                tag.set_frames("TDRC", vec![self.text_frame("TDRC", &date.to_string(), version)?]);
+                for id in [year_id, date_id, time_id] {
+                    tag.remove_frames(id);
+                }
+                return Ok(());
+            }
 
-        self.write_tag(&tag)
+            let (year, day_month, time) = timestamp_to_v3_date(&date.to_string());
+            tag.set_frames(year_id, vec![self.text_frame(year_id, &year, version)?]);
+            for (id, value) in [(date_id, day_month), (time_id, time)] {
+                match value {
+                    Some(value) => tag.set_frames(id, vec![self.text_frame(id, &value, version)?]),
+                    None => {
+                        tag.remove_frames(id);
+                    }
+                }
+            }
+            tag.remove_frames("TDRC");
+            Ok(())
+        })
     }
 
     fn save(&mut self) -> Result<()> {
-        Ok(())
+        match self.pending.take() {
+            Some(tag) => {
+                let _lock = FileLock::acquire(&self.path)?;
+                self.write_tag(&tag)
+            }
+            None => Ok(()),
+        }
     }
 
     fn tag_type(&self) -> TagType {
@@ -326,16 +1326,459 @@ impl TagWriterStrategy for TagWriter {
 }
 
 /// ID3v2 tag implementation
-#[derive(Debug)]
+///
+/// Tags are `Send + Sync`; wrap a parsed tag in an `Arc` to read it from several threads.
+#[derive(Debug, Clone)]
 pub struct Tag {
     version: Version,
     flags: u8,
     frames: HashMap<String, Vec<Frame>>,
 }
 
+impl Tag {
+    /// Create an empty tag
+    pub fn new(version: Version) -> Self {
+        Self {
+            version,
+            flags: 0,
+            frames: HashMap::new(),
+        }
+    }
+
+    /// Parse a tag from bytes starting with its header, such as the start of a file
+    ///
+    /// Frames are read as by `TagReader::init`; bytes behind the tag are ignored.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        DefaultTagParser.parse_bytes(data)
+    }
+
+    /// Serialize the tag as `TagWriter` writes a new one: header, frames and
+    /// the default padding of `ID3V2_PADDING_SIZE` bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        TagWriter::new().render_tag(self, None)
+    }
+
+    /// Convert between ID3v2.3 and ID3v2.4
+    ///
+    /// TYER/TDAT/TIME become TDRC, TORY becomes TDOR and IPLS becomes TIPL, or
+    /// the other way round, with TMCL merged into IPLS. Text, comment, lyrics, URL, terms of use and picture frames
+    /// are re-encoded when the target version does not support their encoding; other frames in such an encoding
+    /// are dropped and reported to telemetry.
+    ///
+    /// Going to ID3v2.3, multiple text values are joined with '/' and frames
+    /// that only exist in ID3v2.4, such as TDRL and TMOO, are dropped.
+    pub fn convert_version(&mut self, target: Version) -> Result<()> {
+        if self.version == Version::V2 || target == Version::V2 {
+            return Err(Error::InvalidTagVersion(
+                "Only ID3v2.3 and ID3v2.4 tags can be converted".to_string(),
+            ));
+        }
+        if self.version == target {
+            return Ok(());
+        }
+
+        if target == Version::V4 {
+            if let Some(year) = self.take_text("TYER") {
+                let date = self.take_text("TDAT");
+                let time = self.take_text("TIME");
+                let timestamp = v3_date_to_timestamp(&year, date.as_deref(), time.as_deref());
+                self.set_frames("TDRC", vec![Frame::new_text("TDRC", &timestamp, target)]);
+            }
+            if let Some(year) = self.take_text("TORY") {
+                self.set_frames("TDOR", vec![Frame::new_text("TDOR", &year, target)]);
+            }
+            if let Some(frame) = self.remove_frames("IPLS").first() {
+                let people = join_people_list(&parse_people_list(frame));
+                self.set_frames("TIPL", vec![Frame::new_text("TIPL", &people, target)]);
+            }
+        } else {
+            if let Some(timestamp) = self.take_text("TDRC") {
+                let (year, date, time) = timestamp_to_v3_date(&timestamp);
+                self.set_frames("TYER", vec![Frame::new_text("TYER", &year, target)]);
+                for (id, value) in [("TDAT", date), ("TIME", time)] {
+                    if let Some(value) = value {
+                        self.set_frames(id, vec![Frame::new_text(id, &value, target)]);
+                    }
+                }
+            }
+            if let Some(timestamp) = self.take_text("TDOR") {
+                let (year, _, _) = timestamp_to_v3_date(&timestamp);
+                self.set_frames("TORY", vec![Frame::new_text("TORY", &year, target)]);
+            }
+            // ID3v2.3 has a single list for involved people and musicians
+            let mut people = self.remove_frames("TIPL").first().map(parse_people_list).unwrap_or_default();
+            people.extend(self.remove_frames("TMCL").first().map(parse_people_list).unwrap_or_default());
+            if !people.is_empty() {
+                self.set_frames("IPLS", vec![Frame::new_text("IPLS", &join_people_list(&people), target)]);
+            }
+            for id in V4_ONLY_FRAMES {
+                self.frames.remove(*id);
+            }
+        }
+
+        let from = self.version;
+        for frames in self.frames.values_mut() {
+            frames.retain_mut(|frame| {
+                if let Some(converted) = reencode_frame(frame, from, target) {
+                    *frame = converted;
+                }
+                let keep = !has_unsupported_encoding(frame, target);
+                if !keep {
+                    telemetry::report(
+                        AnomalyKind::UnsupportedFrame,
+                        TagType::Id3v2,
+                        None,
+                        format!("Dropped {} frame whose text encoding ID3v2.{} does not support", frame.id, u8::from(target)),
+                    );
+                }
+                keep
+            });
+        }
+        self.frames.retain(|_, frames| !frames.is_empty());
+
+        // The footer flag only exists in ID3v2.4 and is never written
+        self.flags &= !FLAG_FOOTER;
+        self.version = target;
+        Ok(())
+    }
+
+    /// Remove the frames with the given ID and return the text of the first one
+    fn take_text(&mut self, id: &str) -> Option<String> {
+        self.frames.remove(id)?.first().and_then(Frame::text)
+    }
+
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// All frames with the given ID, in file order
+    pub fn frames(&self, id: &str) -> &[Frame] {
+        self.frames.get(id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// IDs of all frames present in the tag
+    pub fn frame_ids(&self) -> impl Iterator<Item = &str> {
+        self.frames.keys().map(String::as_str)
+    }
+
+    /// Replace all frames with the given ID
+    pub fn set_frames(&mut self, id: &str, frames: Vec<Frame>) {
+        if frames.is_empty() {
+            self.frames.remove(id);
+        } else {
+            self.frames.insert(id.to_string(), frames);
+        }
+    }
+
+    /// Append a frame, keeping existing frames with the same ID
+    pub fn add_frame(&mut self, frame: Frame) {
+        self.frames.entry(frame.id.clone()).or_default().push(frame);
+    }
+
+    /// Add `frame`, dropping existing frames with the same ID for which `matches` is true
+    pub fn replace_frames_where<F>(&mut self, frame: Frame, matches: F)
+    where
+        F: Fn(&Frame) -> bool,
+    {
+        let frames = self.frames.entry(frame.id.clone()).or_default();
+        frames.retain(|existing| !matches(existing));
+        frames.push(frame);
+    }
+
+    /// Remove the frames with the given ID for which `matches` is true
+    pub fn remove_frames_where<F>(&mut self, id: &str, matches: F)
+    where
+        F: Fn(&Frame) -> bool,
+    {
+        if let Some(frames) = self.frames.get_mut(id) {
+            frames.retain(|frame| !matches(frame));
+            if frames.is_empty() {
+                self.frames.remove(id);
+            }
+        }
+    }
+
+    /// Remove all frames with the given ID
+    pub fn remove_frames(&mut self, id: &str) -> Vec<Frame> {
+        self.frames.remove(id).unwrap_or_default()
+    }
+
+    /// Decode all attached picture frames, skipping malformed ones
+    pub fn pictures(&self) -> Vec<Picture> {
+        self.frames(picture_frame_id(self.version))
+            .iter()
+            .filter_map(|frame| Picture::parse(frame.data(), self.version).ok())
+            .collect()
+    }
+
+    /// Whether the iTunes PCST podcast flag is present
+    pub fn is_podcast(&self) -> bool {
+        !self.frames("PCST").is_empty()
+    }
+
+    /// Raw CD table of contents stored in the MCDI frame
+    pub fn music_cd_identifier(&self) -> Option<&[u8]> {
+        self.frames(music_cd_identifier_frame_id(self.version))
+            .first()
+            .map(Frame::data)
+    }
+
+    /// Decode all chapter frames, skipping malformed ones
+    pub fn chapters(&self) -> Vec<Chapter> {
+        self.frames("CHAP")
+            .iter()
+            .filter_map(|frame| Chapter::parse(frame.data(), self.version).ok())
+            .collect()
+    }
+
+    /// Decode all table of contents frames, skipping malformed ones
+    pub fn tables_of_contents(&self) -> Vec<TableOfContents> {
+        self.frames("CTOC")
+            .iter()
+            .filter_map(|frame| TableOfContents::parse(frame.data(), self.version).ok())
+            .collect()
+    }
+
+    /// Decode all comment frames, skipping malformed ones
+    pub fn comments(&self) -> Vec<Comment> {
+        self.frames(comment_frame_id(self.version))
+            .iter()
+            .filter_map(|frame| Comment::parse(frame.data()).ok())
+            .collect()
+    }
+
+    /// The first comment with the given description
+    pub fn comment(&self, description: &str) -> Option<Comment> {
+        self.comments().into_iter().find(|comment| comment.description == description)
+    }
+
+    /// Decode all user defined text frames, skipping malformed ones
+    pub fn user_texts(&self) -> Vec<UserText> {
+        self.frames(user_text_frame_id(self.version))
+            .iter()
+            .filter_map(|frame| UserText::parse(frame.data()).ok())
+            .collect()
+    }
+
+    /// Value of the first TXXX frame with the given description
+    pub fn user_text(&self, description: &str) -> Option<String> {
+        self.user_texts()
+            .into_iter()
+            .find(|user_text| user_text.matches(description))
+            .map(|user_text| user_text.value)
+    }
+
+    /// Decode all unique file identifier frames, skipping malformed ones
+    pub fn unique_file_ids(&self) -> Vec<UniqueFileId> {
+        self.frames(ufid_frame_id(self.version))
+            .iter()
+            .filter_map(|frame| UniqueFileId::parse(frame.data()).ok())
+            .collect()
+    }
+
+    /// The unique file identifier issued by `owner`
+    pub fn unique_file_id(&self, owner: &str) -> Option<UniqueFileId> {
+        self.unique_file_ids().into_iter().find(|ufid| ufid.owner == owner)
+    }
+
+    /// Decode all private frames, skipping malformed ones
+    pub fn private_frames(&self) -> Vec<PrivateFrame> {
+        self.frames("PRIV")
+            .iter()
+            .filter_map(|frame| PrivateFrame::parse(frame.data()).ok())
+            .collect()
+    }
+
+    /// Private frames owned by `owner`
+    pub fn private_frames_by_owner(&self, owner: &str) -> Vec<PrivateFrame> {
+        self.private_frames()
+            .into_iter()
+            .filter(|private| private.owner == owner)
+            .collect()
+    }
+
+    /// Value of the PCNT play counter
+    pub fn play_count(&self) -> Option<u64> {
+        self.frames(play_counter_frame_id(self.version))
+            .first()
+            .map(|frame| decode_counter(frame.data()))
+    }
+
+    /// Decode all popularimeter frames, skipping malformed ones
+    pub fn ratings(&self) -> Vec<Rating> {
+        let id = popularimeter_frame_id(self.version);
+        self.frames(id)
+            .iter()
+            .filter_map(|frame| Rating::parse(frame.data()).ok())
+            .collect()
+    }
+
+    /// The rating set by `email`
+    pub fn rating(&self, email: &str) -> Option<Rating> {
+        self.ratings().into_iter().find(|rating| rating.email == email)
+    }
+
+    /// Recording time as an ID3v2.4 timestamp, from TDRC or combined from
+    /// the ID3v2.3 TYER, TDAT and TIME frames
+    pub fn recording_timestamp(&self) -> Option<String> {
+        let first_text = |id: &str| self.frames(id).first().and_then(|frame| frame.text_values().into_iter().next());
+        if let Some(timestamp) = first_text("TDRC") {
+            return Some(timestamp);
+        }
+        let [year, date, time] = date_frame_ids(self.version);
+        let year = first_text(year)?;
+        Some(v3_date_to_timestamp(&year, first_text(date).as_deref(), first_text(time).as_deref()))
+    }
+
+    /// Role and name pairs of the involved people list (TIPL, or IPLS before ID3v2.4)
+    pub fn involved_people(&self) -> Vec<(String, String)> {
+        ["TIPL", "IPLS", "IPL"]
+            .iter()
+            .find_map(|id| self.frames(id).first())
+            .map(parse_people_list)
+            .unwrap_or_default()
+    }
+
+    /// Instrument and musician pairs of the TMCL musician credits list
+    pub fn musician_credits(&self) -> Vec<(String, String)> {
+        self.frames("TMCL").first().map(parse_people_list).unwrap_or_default()
+    }
+
+    /// Genre names of the TCON frame, with numeric references resolved
+    pub fn genres(&self) -> Vec<String> {
+        let id = if self.version == Version::V2 { "TCO" } else { "TCON" };
+        self.frames(id)
+            .first()
+            .map(|frame| parse_tcon(&frame.text_values().join("\0")))
+            .unwrap_or_default()
+    }
+
+    /// Decode all unsynchronised lyrics frames, skipping malformed ones
+    pub fn lyrics(&self) -> Vec<Lyrics> {
+        let id = if self.version == Version::V2 { "ULT" } else { "USLT" };
+        self.frames(id)
+            .iter()
+            .filter_map(|frame| Lyrics::parse(frame.data()).ok())
+            .collect()
+    }
+
+    /// Decode all synchronised lyrics frames, skipping malformed ones
+    pub fn synchronised_lyrics(&self) -> Vec<SynchronisedLyrics> {
+        let id = if self.version == Version::V2 { "SLT" } else { "SYLT" };
+        self.frames(id)
+            .iter()
+            .filter_map(|frame| SynchronisedLyrics::parse(frame.data()).ok())
+            .collect()
+    }
+}
+
+fn comment_frame_id(version: Version) -> &'static str {
+    match version {
+        Version::V2 => "COM",
+        Version::V3 | Version::V4 => "COMM",
+    }
+}
+
+/// Whether a stored language code matches a normalized one, e.g. "XXX" and "und"
+fn same_language(stored: &str, normalized: &str) -> bool {
+    normalize_language(stored).is_ok_and(|code| code == normalized)
+}
+
+/// IDs of the year, date and time frames, which ID3v2.4 replaces with TDRC
+fn date_frame_ids(version: Version) -> [&'static str; 3] {
+    if version == Version::V2 {
+        ["TYE", "TDA", "TIM"]
+    } else {
+        ["TYER", "TDAT", "TIME"]
+    }
+}
+
+fn popularimeter_frame_id(version: Version) -> &'static str {
+    match version {
+        Version::V2 => "POP",
+        Version::V3 | Version::V4 => "POPM",
+    }
+}
+
+fn play_counter_frame_id(version: Version) -> &'static str {
+    match version {
+        Version::V2 => "CNT",
+        Version::V3 | Version::V4 => "PCNT",
+    }
+}
+
+fn music_cd_identifier_frame_id(version: Version) -> &'static str {
+    match version {
+        Version::V2 => "MCI",
+        Version::V3 | Version::V4 => "MCDI",
+    }
+}
+
+fn picture_frame_id(version: Version) -> &'static str {
+    match version {
+        Version::V2 => "PIC",
+        Version::V3 | Version::V4 => "APIC",
+    }
+}
+
 fn get_frame_id_for_version(entry: &MetaEntry, version: Version) -> Option<&'static str> {
     match version {
         Version::V2 => v2_0::get_frame_id(entry),
         Version::V3 | Version::V4 => v3_v4::get_frame_id(entry),
     }
 }
+
+/// Re-encode a frame whose text encoding is not supported by the target version
+///
+/// Null separated text values, which only ID3v2.4 supports, are joined with '/'.
+fn reencode_frame(frame: &Frame, from: Version, target: Version) -> Option<Frame> {
+    let encoding = TextEncoding::from_byte(*frame.data().first()?)?;
+    let is_text = frame.id.starts_with('T') && frame.id != "TXXX";
+    let joins_values = is_text && target != Version::V4 && frame.text_values().len() > 1;
+    if encoding.is_supported_by(target) && !joins_values {
+        return None;
+    }
+
+    let mut converted = match frame.id.as_str() {
+        "TXXX" => UserText::parse(frame.data()).ok()?.to_frame(target),
+        "COMM" => Comment::parse(frame.data()).ok()?.to_frame(target),
+        "USLT" => Lyrics::parse(frame.data()).ok()?.to_frame(target),
+        "APIC" => Picture::parse(frame.data(), from).ok()?.to_frame(target),
+        "SYLT" => SynchronisedLyrics::parse(frame.data()).ok()?.to_frame(target),
+        "WXXX" => {
+            let (description, url) = split_terminated(&frame.data()[1..], encoding);
+            let description = encoding.decode(description);
+            let target_encoding = TextEncoding::for_text(&description, target);
+            let mut data = vec![target_encoding.as_byte()];
+            data.extend_from_slice(&target_encoding.encode_terminated(&description));
+            data.extend_from_slice(url);
+            Frame::from_data("WXXX", data)
+        }
+        "USER" => {
+            let lang = frame.data().get(1..4)?;
+            let text = encoding.decode(&frame.data()[4..]);
+            let target_encoding = TextEncoding::for_text(&text, target);
+            let mut data = vec![target_encoding.as_byte()];
+            data.extend_from_slice(lang);
+            data.extend_from_slice(&target_encoding.encode(&text));
+            Frame::from_data("USER", data)
+        }
+        id if id.starts_with('T') => {
+            Frame::new_text_values(id, &frame.text_values(), target, DEFAULT_MULTI_VALUE_SEPARATOR)
+        }
+        _ => return None,
+    };
+    converted.flags = frame.flags;
+    Some(converted)
+}
+
+/// Whether a frame declares a text encoding that the target version does not support
+fn has_unsupported_encoding(frame: &Frame, target: Version) -> bool {
+    let is_encoded = frame.id.starts_with('T') || ENCODED_FRAMES.contains(&frame.id.as_str());
+    is_encoded
+        && frame
+            .data()
+            .first()
+            .and_then(|&byte| TextEncoding::from_byte(byte))
+            .is_some_and(|encoding| !encoding.is_supported_by(target))
+}
@@ -0,0 +1,72 @@
+use crate::error::{Error, Result};
+use crate::id3::v2::encoding::{split_terminated, TextEncoding};
+use crate::id3::v2::frame::Frame;
+use crate::id3::v2::version::Version;
+
+/// Maximum identifier length allowed by the specification
+pub const MAX_IDENTIFIER_LENGTH: usize = 64;
+
+/// Unique file identifier (UFID, or UFI in ID3v2.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UniqueFileId {
+    /// URL or email of the organisation issuing the identifier,
+    /// e.g. "http://musicbrainz.org"
+    pub owner: String,
+    /// Binary identifier, at most 64 bytes
+    pub identifier: Vec<u8>,
+}
+
+impl UniqueFileId {
+    pub fn new(owner: &str, identifier: &[u8]) -> Result<Self> {
+        if owner.is_empty() {
+            return Err(Error::Other("UFID owner must not be empty".to_string()));
+        }
+        if identifier.len() > MAX_IDENTIFIER_LENGTH {
+            return Err(Error::Other(format!(
+                "UFID identifier of {} bytes exceeds {} bytes",
+                identifier.len(),
+                MAX_IDENTIFIER_LENGTH
+            )));
+        }
+        Ok(Self {
+            owner: owner.to_string(),
+            identifier: identifier.to_vec(),
+        })
+    }
+
+    /// Parse the payload of a UFID frame
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let (owner, identifier) = split_terminated(data, TextEncoding::Latin1);
+        if owner.is_empty() {
+            return Err(Error::Other("UFID frame without owner".to_string()));
+        }
+        Ok(Self {
+            owner: TextEncoding::Latin1.decode(owner),
+            identifier: identifier.to_vec(),
+        })
+    }
+
+    /// The identifier as text, when it is valid UTF-8 (as MusicBrainz IDs are)
+    pub fn identifier_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.identifier).ok()
+    }
+
+    /// Serialize into a UFID frame payload
+    pub fn to_frame_data(&self) -> Vec<u8> {
+        let mut data = TextEncoding::Latin1.encode_terminated(&self.owner);
+        data.extend_from_slice(&self.identifier);
+        data
+    }
+
+    /// Build the frame holding this identifier
+    pub fn to_frame(&self, version: Version) -> Frame {
+        Frame::from_data(ufid_frame_id(version), self.to_frame_data())
+    }
+}
+
+pub(crate) fn ufid_frame_id(version: Version) -> &'static str {
+    match version {
+        Version::V2 => "UFI",
+        Version::V3 | Version::V4 => "UFID",
+    }
+}
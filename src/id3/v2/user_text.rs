@@ -0,0 +1,59 @@
+use crate::error::{Error, Result};
+use crate::id3::v2::encoding::{split_terminated, TextEncoding};
+use crate::id3::v2::frame::Frame;
+use crate::id3::v2::version::Version;
+
+/// User defined text (TXXX, or TXX in ID3v2.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserText {
+    /// Key identifying the value, e.g. "MOOD"
+    pub description: String,
+    pub value: String,
+}
+
+impl UserText {
+    pub fn new(description: &str, value: &str) -> Self {
+        Self {
+            description: description.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    /// Parse the payload of a TXXX frame
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let (&encoding_byte, rest) = data.split_first().ok_or(Error::NoFramePayloadLength)?;
+        let encoding = TextEncoding::from_byte(encoding_byte)
+            .ok_or_else(|| Error::Other(format!("Invalid text encoding: {}", encoding_byte)))?;
+        let (description, value) = split_terminated(rest, encoding);
+        Ok(Self {
+            description: encoding.decode(description),
+            value: encoding.decode(value),
+        })
+    }
+
+    /// Serialize into a TXXX frame payload
+    pub fn to_frame_data(&self, version: Version) -> Vec<u8> {
+        let encoding = TextEncoding::for_text(&format!("{}{}", self.description, self.value), version);
+        let mut data = vec![encoding.as_byte()];
+        data.extend_from_slice(&encoding.encode_terminated(&self.description));
+        data.extend_from_slice(&encoding.encode(&self.value));
+        data
+    }
+
+    /// Build the frame holding this value
+    pub fn to_frame(&self, version: Version) -> Frame {
+        Frame::from_data(user_text_frame_id(version), self.to_frame_data(version))
+    }
+
+    /// Whether this frame is addressed by `description` (compared case-insensitively)
+    pub fn matches(&self, description: &str) -> bool {
+        self.description.eq_ignore_ascii_case(description)
+    }
+}
+
+pub(crate) fn user_text_frame_id(version: Version) -> &'static str {
+    match version {
+        Version::V2 => "TXX",
+        Version::V3 | Version::V4 => "TXXX",
+    }
+}
@@ -17,12 +17,34 @@ pub fn int_to_synchsafe(val: u32) -> [u8; 4] {
     bytes
 }
 
+/// CRC-32 (ISO 3309, as used by the ID3v2 extended header)
+pub fn crc32(data: &[u8]) -> u32 {
+    crc32_update(0, data)
+}
+
+/// Continue the CRC-32 `crc` of preceding data with `data`; 0 starts a new one
+pub fn crc32_update(crc: u32, data: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
 use std::io::Read;
 
 pub fn has_id3v2_tag(path: &std::path::Path) -> crate::Result<bool> {
-    let mut file = std::fs::File::open(path)?;
+    starts_with_id3v2_tag(&mut std::fs::File::open(path)?)
+}
+
+/// Whether the data read from `reader` starts with an ID3v2 header
+pub(crate) fn starts_with_id3v2_tag<R: Read>(reader: &mut R) -> crate::Result<bool> {
     let mut header = [0; 10];
-    if file.read(&mut header)? < 10 {
+    if reader.read(&mut header)? < 10 {
         return Ok(false);
     }
     Ok(&header[0..3] == crate::id3::constants::ID3V2_IDENTIFIER)
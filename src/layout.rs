@@ -0,0 +1,279 @@
+//! Map of the structures that make up a file
+//!
+//! `layout` lists the tags, padding and audio stream of a file with their
+//! positions, for diagnostics and for tools that splice files.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::ape::common::{constants::APE_TAG_FOOTER_SIZE, ApeTagHeader};
+use crate::audio::{find_first_frame, read_stream_head};
+use crate::error::Result;
+use crate::id3::constants::{ID3V1_IDENTIFIER, ID3V1_TAG_SIZE};
+use crate::id3::v1::enhanced::ENHANCED_TAG_SIZE;
+use crate::id3::v2::frame::Frame;
+use crate::id3::v2::header::{ExtendedHeader, Header, FLAG_FOOTER};
+use crate::id3::v2::version::Version;
+use crate::tag::{id3v1_revision, DetectedTag, TagPresence, TagVersion};
+
+/// Lyrics3v1 tags hold at most 5100 bytes of lyrics between their markers
+const LYRICS3V1_MAX_SIZE: u64 = 5100 + 11 + 9;
+
+/// Kind of a structure found by `layout`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StructureKind {
+    /// ID3v2 tag, at the start of the file or appended with a footer, without its padding
+    Id3v2,
+    /// Zero bytes at the end of an ID3v2 tag or between the tags and the audio
+    Padding,
+    /// MPEG audio frames
+    Audio,
+    /// APE tag, including its header when present
+    Ape,
+    /// Lyrics3v1 or Lyrics3v2 tag
+    Lyrics3,
+    /// TAG+ block in front of an ID3v1 tag
+    EnhancedId3v1,
+    Id3v1,
+    /// Data that is none of the above, such as junk in front of the first frame
+    Unknown,
+}
+
+/// A structure of a file and its position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Structure {
+    pub kind: StructureKind,
+    pub offset: u64,
+    pub size: u64,
+}
+
+impl Structure {
+    /// Offset of the first byte behind the structure
+    pub fn end(&self) -> u64 {
+        self.offset + self.size
+    }
+}
+
+/// The structures of a file in file order, covering it without gaps
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileLayout {
+    pub file_size: u64,
+    pub structures: Vec<Structure>,
+}
+
+impl FileLayout {
+    /// The structures of the given kind, in file order
+    pub fn of_kind(&self, kind: StructureKind) -> impl Iterator<Item = &Structure> {
+        self.structures.iter().filter(move |structure| structure.kind == kind)
+    }
+
+    /// The audio frames, if any were found
+    pub fn audio(&self) -> Option<&Structure> {
+        self.of_kind(StructureKind::Audio).next()
+    }
+}
+
+/// List the tags, padding and audio of a file with their byte offsets and sizes
+///
+/// ID3v2 tags are split into the tag and its trailing padding. Zero bytes
+/// between the leading tags and the first audio frame count as padding, other
+/// data there as `Unknown`; without any audio frame the whole region between
+/// the tags is `Unknown`.
+pub fn layout<P: AsRef<Path>>(path: P) -> Result<FileLayout> {
+    layout_of(&mut File::open(path.as_ref())?)
+}
+
+/// The layout of a file and the first tag of each type in it, from one pass over the file
+///
+/// The tags are the ones `detect_tags` reports, except that ID3v2 sizes leave out the padding.
+pub(crate) fn layout_with_tags<P: AsRef<Path>>(path: P) -> Result<(FileLayout, TagPresence)> {
+    let mut file = File::open(path.as_ref())?;
+    let layout = layout_of(&mut file)?;
+    let mut presence = TagPresence::default();
+    let mut previous: Option<&Structure> = None;
+    for structure in &layout.structures {
+        let end = structure.end();
+        let version = match structure.kind {
+            StructureKind::Id3v2 => read_at(&mut file, structure.offset, end, 10)?
+                .and_then(|data| Header::parse(&data).ok())
+                .map(|header| TagVersion::Id3v2(header.version.into())),
+            StructureKind::Ape => {
+                let footer = end.saturating_sub(APE_TAG_FOOTER_SIZE as u64).max(structure.offset);
+                read_at(&mut file, footer, end, APE_TAG_FOOTER_SIZE)?
+                    .and_then(|data| ApeTagHeader::from_buffer(&data).ok())
+                    .map(|footer| TagVersion::Ape(footer.version))
+            }
+            StructureKind::Id3v1 => read_at(&mut file, structure.offset, end, ID3V1_TAG_SIZE)?
+                .map(|tag| TagVersion::Id3v1 { revision: id3v1_revision(&tag) }),
+            _ => None,
+        };
+        if let Some(version) = version.filter(|version| !presence.has(version.tag_type())) {
+            // A TAG+ block counts as part of the ID3v1 tag behind it
+            let start = match previous {
+                Some(block) if block.kind == StructureKind::EnhancedId3v1 => block.offset,
+                _ => structure.offset,
+            };
+            presence.tags.push(DetectedTag { tag_type: version.tag_type(), version, offset: start, size: end - start });
+        }
+        previous = Some(structure);
+    }
+    Ok((layout, presence))
+}
+
+/// The layout of an open file
+fn layout_of(file: &mut File) -> Result<FileLayout> {
+    let file_size = file.metadata()?.len();
+    let mut structures = Vec::new();
+
+    let leading = leading_tags(file, file_size)?;
+    for tag in &leading {
+        let padding = id3v2_padding(file, tag)?;
+        push(&mut structures, StructureKind::Id3v2, tag.offset, tag.size - padding);
+        push(&mut structures, StructureKind::Padding, tag.end() - padding, padding);
+    }
+
+    let start = leading.last().map_or(0, Structure::end).min(file_size);
+    let trailing = trailing_tags(file, start, file_size)?;
+    let end = trailing.first().map_or(file_size, |tag| tag.offset);
+    let head = read_stream_head(file, &(start..end))?;
+    match find_first_frame(&head, start, end) {
+        Some((offset, _)) => {
+            let gap = if head[..offset].iter().all(|&b| b == 0) { StructureKind::Padding } else { StructureKind::Unknown };
+            push(&mut structures, gap, start, offset as u64);
+            push(&mut structures, StructureKind::Audio, start + offset as u64, end - start - offset as u64);
+        }
+        None => push(&mut structures, StructureKind::Unknown, start, end - start),
+    }
+    structures.extend(trailing);
+
+    Ok(FileLayout { file_size, structures })
+}
+
+/// Add a structure unless it is empty
+fn push(structures: &mut Vec<Structure>, kind: StructureKind, offset: u64, size: u64) {
+    if size > 0 {
+        structures.push(Structure { kind, offset, size });
+    }
+}
+
+/// The ID3v2 tags stacked at the start of a file, including their padding
+pub(crate) fn leading_tags(file: &mut File, file_size: u64) -> Result<Vec<Structure>> {
+    let mut tags = Vec::new();
+    let mut offset = 0;
+    while let Some(header) = read_at(file, offset, file_size, 10)?.and_then(|data| Header::parse(&data).ok()) {
+        let footer = if header.version == 4 && header.flags & FLAG_FOOTER != 0 { 10 } else { 0 };
+        let size = (10 + header.size as u64 + footer).min(file_size - offset);
+        tags.push(Structure { kind: StructureKind::Id3v2, offset, size });
+        offset += size;
+    }
+    Ok(tags)
+}
+
+/// The tags stacked at the end of the region `start..end`, in file order
+pub(crate) fn trailing_tags(file: &mut File, start: u64, mut end: u64) -> Result<Vec<Structure>> {
+    let mut tags = Vec::new();
+    while end > start {
+        let Some((kind, size)) = trailing_tag(file, start, end)? else {
+            break;
+        };
+        end -= size;
+        tags.push(Structure { kind, offset: end, size });
+        // An enhanced TAG+ block directly precedes the ID3v1 tag
+        let block = ENHANCED_TAG_SIZE as u64;
+        if kind == StructureKind::Id3v1
+            && end - start >= block
+            && read_at(file, end - block, end, 4)?.is_some_and(|data| data == b"TAG+")
+        {
+            end -= block;
+            tags.push(Structure { kind: StructureKind::EnhancedId3v1, offset: end, size: block });
+        }
+    }
+    tags.reverse();
+    Ok(tags)
+}
+
+/// Kind and size of the tag ending at `end`, if any
+fn trailing_tag(file: &mut File, start: u64, end: u64) -> Result<Option<(StructureKind, u64)>> {
+    let available = end - start;
+    let Some(tail) = read_at(file, end.saturating_sub(32).max(start), end, 32.min(available) as usize)? else {
+        return Ok(None);
+    };
+    let tag = if available >= ID3V1_TAG_SIZE as u64
+        && read_at(file, end - ID3V1_TAG_SIZE as u64, end, 3)?.is_some_and(|data| data == ID3V1_IDENTIFIER)
+    {
+        Some((StructureKind::Id3v1, ID3V1_TAG_SIZE as u64))
+    } else if tail.len() == APE_TAG_FOOTER_SIZE && tail.starts_with(b"APETAGEX") {
+        ApeTagHeader::from_buffer(&tail).ok().filter(|footer| !footer.is_header()).map(|footer| {
+            let header = if footer.has_header() { APE_TAG_FOOTER_SIZE as u64 } else { 0 };
+            (StructureKind::Ape, footer.size as u64 + header)
+        })
+    } else if tail.ends_with(b"LYRICS200") && tail.len() >= 15 {
+        // Lyrics3v2 stores its size, without the size and end marker, as six digits
+        std::str::from_utf8(&tail[tail.len() - 15..tail.len() - 9])
+            .ok()
+            .and_then(|digits| digits.parse::<u64>().ok())
+            .map(|size| (StructureKind::Lyrics3, size + 15))
+    } else if tail.ends_with(b"LYRICSEND") {
+        let search_start = end.saturating_sub(LYRICS3V1_MAX_SIZE).max(start);
+        let data = read_at(file, search_start, end, (end - search_start) as usize)?.unwrap_or_default();
+        data.windows(11)
+            .rposition(|window| window == b"LYRICSBEGIN")
+            .map(|position| (StructureKind::Lyrics3, end - search_start - position as u64))
+    } else if tail.len() >= 10 && tail[tail.len() - 10..].starts_with(b"3DI") {
+        // Footer of an appended ID3v2.4 tag, which repeats the header
+        let mut header = tail[tail.len() - 10..].to_vec();
+        header[..3].copy_from_slice(b"ID3");
+        Header::parse(&header).ok().map(|header| (StructureKind::Id3v2, header.size as u64 + 20))
+    } else {
+        None
+    };
+    Ok(tag.filter(|&(_, size)| size > 0 && size <= available))
+}
+
+/// Size of the zero padding behind the frames of an ID3v2 tag
+///
+/// Tags with a footer have no padding; tags whose frames cannot be walked,
+/// such as unsynchronised ID3v2.3 tags, count as having none.
+fn id3v2_padding(file: &mut File, tag: &Structure) -> Result<u64> {
+    let Some(data) = read_at(file, tag.offset, tag.end(), tag.size as usize)? else {
+        return Ok(0);
+    };
+    let Ok(header) = Header::parse(&data) else {
+        return Ok(0);
+    };
+    if header.flags & FLAG_FOOTER != 0 || !(2..=4).contains(&header.version) {
+        return Ok(0);
+    }
+    let version = Version::from(header.version);
+    let mut position = 10;
+    if header.has_extended_header() {
+        match ExtendedHeader::parse(&data[10..], version) {
+            Ok(extended) => position += extended.size as usize,
+            Err(_) => return Ok(0),
+        }
+    }
+    let id_size = Frame::id_size(version);
+    while position + id_size <= data.len() && data[position] != 0 {
+        let id = &data[position..position + id_size];
+        let Some(size) = Frame::declared_size(&data[position..], version)
+            .filter(|_| id.iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit()))
+        else {
+            return Ok(0);
+        };
+        position += Frame::header_size(version) + size;
+    }
+    let padding = data.get(position..).unwrap_or_default();
+    Ok(if padding.iter().all(|&b| b == 0) { padding.len() as u64 } else { 0 })
+}
+
+/// Read `len` bytes at `offset`, `None` when they would extend past `limit`
+fn read_at(file: &mut File, offset: u64, limit: u64, len: usize) -> Result<Option<Vec<u8>>> {
+    if offset + len as u64 > limit {
+        return Ok(None);
+    }
+    let mut data = vec![0u8; len];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut data)?;
+    Ok(Some(data))
+}
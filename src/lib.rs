@@ -11,10 +11,39 @@ pub mod id3;
 pub mod ape;
 pub mod validation;
 pub mod file_access;
+pub mod telemetry;
+pub mod artwork;
+pub mod replaygain;
+pub mod musicbrainz;
+pub mod vacuum;
+pub mod backup;
+pub mod convert;
+pub mod metadata;
+pub mod strip;
+pub mod editor;
+pub mod position;
+pub mod date;
+pub mod audio;
+pub mod layout;
+pub mod batch;
+pub mod progress;
+pub mod library;
+#[cfg(feature = "watch")]
+pub mod watch;
+pub mod cache;
 
 pub use error::{Error, Result};
 pub use meta_entry::MetaEntry;
-pub use tag::{TagReader, TagWriter, TagType};
+pub use editor::Tag;
+pub use metadata::Metadata;
+pub use position::Position;
+pub use date::RecordingDate;
+pub use audio::{
+    audio_crc32, audio_md5, audio_properties, audio_range, gapless_info, stream_info, AudioProperties, BitrateMode, GaplessInfo,
+    StreamInfo,
+};
+pub use layout::{layout, FileLayout, Structure, StructureKind};
+pub use tag::{detect_tags, DetectedTag, MergePolicy, TagPresence, TagReader, TagVersion, TagWriter, TagWriterBuilder, TagType};
 
 // Re-export common tag operations for convenience
 pub use tag::{
@@ -0,0 +1,660 @@
+//! Index of the tags of a music library
+//!
+//! `Library::scan` walks a root directory, reads the tags of every MP3 file
+//! and keeps them with the file's modification time and size. The index can
+//! be saved and loaded again; `rescan` then only reads the files that changed.
+//!
+//! ```no_run
+//! use mp3tags_r::library::Library;
+//!
+//! let mut library = match Library::load("library.idx") {
+//!     Ok(library) => library,
+//!     Err(_) => Library::new("music"),
+//! };
+//! let report = library.rescan()?;
+//! println!("{} added, {} updated, {} removed", report.added, report.updated, report.removed);
+//! library.save("library.idx")?;
+//!
+//! for entry in library.find().artist_contains("Miles").year_between(1950, 1970).entries() {
+//!     println!("{}: {:?}", entry.path.display(), entry.metadata.title);
+//! }
+//! # Ok::<(), mp3tags_r::Error>(())
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::batch::{is_mp3, Batch};
+use crate::error::{Error, Result};
+use crate::layout::{layout_with_tags, StructureKind};
+use crate::metadata::Metadata;
+use crate::tag::{TagType, TagVersion};
+
+/// First line of a saved index
+const INDEX_HEADER: &str = "mp3tags_r library 2";
+
+/// A file of the library with the tags read from it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryEntry {
+    /// Path of the file, starting with the root of the library
+    pub path: PathBuf,
+    /// Modification time of the file when it was read
+    pub modified: SystemTime,
+    /// Size of the file when it was read
+    pub size: u64,
+    /// Fields of the first tag found, without pictures; empty for untagged files
+    pub metadata: Metadata,
+    /// Format revisions of the tags found, one per tag type, in file order
+    pub tags: Vec<TagVersion>,
+    /// Bytes of padding in ID3v2 tags and between the tags and the audio
+    pub padding: u64,
+}
+
+/// Changes found by `Library::rescan`
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    /// Files new to the index
+    pub added: usize,
+    /// Files read again because their modification time or size changed
+    pub updated: usize,
+    /// Files no longer found, or no longer readable
+    pub removed: usize,
+    /// Files that could not be read, or whose tags could not be, with their errors
+    pub failed: Vec<(PathBuf, Error)>,
+}
+
+/// Change of a single file, reported by `Library::refresh`
+#[derive(Debug)]
+pub enum LibraryEvent {
+    Added(LibraryEntry),
+    Updated(LibraryEntry),
+    /// The file is gone, or is no longer an MP3 file
+    Removed(PathBuf),
+    /// The tags of the file could not be read; it was dropped from the index
+    Failed(PathBuf, Error),
+}
+
+/// The indexed MP3 files below a root directory, by path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Library {
+    root: PathBuf,
+    entries: BTreeMap<PathBuf, LibraryEntry>,
+}
+
+impl Library {
+    /// An empty index of the files below `root`; fill it with `rescan`
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self { root: root.as_ref().to_path_buf(), entries: BTreeMap::new() }
+    }
+
+    /// Index the files below `root`
+    ///
+    /// Files whose tags cannot be read are left out; use `new` and `rescan`
+    /// to find out which.
+    pub fn scan<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let mut library = Self::new(root);
+        library.rescan()?;
+        Ok(library)
+    }
+
+    /// Bring the index up to date with the files below the root
+    ///
+    /// Only new files and files whose modification time or size changed are
+    /// read, in parallel; files that disappeared are dropped.
+    pub fn rescan(&mut self) -> Result<ScanReport> {
+        let batch = Batch::from_directory(&self.root)?;
+        Ok(self.rescan_files(batch.files()))
+    }
+
+    /// Index `files`, the sorted files below the root, dropping all other entries
+    pub(crate) fn rescan_files(&mut self, files: &[PathBuf]) -> ScanReport {
+        let mut report = ScanReport::default();
+        let mut current = BTreeMap::new();
+        let mut changed = Vec::new();
+        for path in files {
+            let stat = fs::metadata(path).and_then(|metadata| Ok((metadata.modified()?, metadata.len())));
+            let (modified, size) = match stat {
+                Ok(stat) => stat,
+                Err(error) => {
+                    // E.g. deleted since the directory was listed; an indexed entry counts as removed
+                    report.failed.push((path.clone(), error.into()));
+                    continue;
+                }
+            };
+            match self.entries.remove(path) {
+                Some(entry) if entry.modified == modified && entry.size == size => {
+                    current.insert(path.clone(), entry);
+                }
+                previous => {
+                    if previous.is_some() {
+                        report.updated += 1;
+                    } else {
+                        report.added += 1;
+                    }
+                    changed.push((path.clone(), modified, size, previous.is_some()));
+                }
+            }
+        }
+        report.removed = self.entries.len();
+
+        let summary = Batch::new(changed.iter().map(|(path, ..)| path.clone())).run(read_file);
+        for ((path, result), (_, modified, size, existed)) in summary.results.into_iter().zip(changed) {
+            match result {
+                Ok((metadata, tags, padding)) => {
+                    current.insert(path.clone(), LibraryEntry { path, modified, size, metadata, tags, padding });
+                }
+                Err(error) => {
+                    if existed {
+                        report.updated -= 1;
+                        report.removed += 1;
+                    } else {
+                        report.added -= 1;
+                    }
+                    report.failed.push((path, error));
+                }
+            }
+        }
+        self.entries = current;
+        report
+    }
+
+    /// Bring the entries of a file, or of the files below a directory, up to date
+    ///
+    /// Unlike `rescan` this only looks at `path`, for callers that know which
+    /// files changed. Unchanged files report no event; a directory that cannot
+    /// be read reports a single `Failed` event and leaves its entries alone.
+    pub fn refresh<P: AsRef<Path>>(&mut self, path: P) -> Vec<LibraryEvent> {
+        let path = path.as_ref();
+        let mut events = Vec::new();
+        // Symbolic links are not followed, as when scanning
+        let files = match fs::symlink_metadata(path).map(|metadata| metadata.file_type()) {
+            Ok(file_type) if file_type.is_dir() => match Batch::from_directory(path) {
+                Ok(batch) => batch.files().to_vec(),
+                // The entries below are kept; the directory was not read, not emptied
+                Err(error) => return vec![LibraryEvent::Failed(path.to_path_buf(), error)],
+            },
+            Ok(file_type) if file_type.is_file() && is_mp3(path) => vec![path.to_path_buf()],
+            _ => Vec::new(),
+        };
+
+        let gone: Vec<PathBuf> = self
+            .entries
+            .range(path.to_path_buf()..)
+            .take_while(|(indexed, _)| indexed.starts_with(path))
+            .filter(|(indexed, _)| files.binary_search(indexed).is_err())
+            .map(|(indexed, _)| indexed.clone())
+            .collect();
+        for indexed in gone {
+            self.entries.remove(&indexed);
+            events.push(LibraryEvent::Removed(indexed));
+        }
+
+        for file in files {
+            let stat = fs::metadata(&file).and_then(|metadata| Ok((metadata.modified()?, metadata.len())));
+            let previous = self.entries.remove(&file);
+            let result = match (stat, previous) {
+                (Ok((modified, size)), Some(entry)) if entry.modified == modified && entry.size == size => {
+                    self.entries.insert(file, entry);
+                    continue;
+                }
+                (Ok((modified, size)), previous) => read_file(&file).map(|(metadata, tags, padding)| {
+                    let entry = LibraryEntry { path: file.clone(), modified, size, metadata, tags, padding };
+                    (entry, previous.is_some())
+                }),
+                (Err(error), _) => Err(error.into()),
+            };
+            events.push(match result {
+                Ok((entry, existed)) => {
+                    self.entries.insert(file, entry.clone());
+                    if existed {
+                        LibraryEvent::Updated(entry)
+                    } else {
+                        LibraryEvent::Added(entry)
+                    }
+                }
+                Err(error) => LibraryEvent::Failed(file, error),
+            });
+        }
+        events
+    }
+
+    /// The directory the library covers
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The indexed files, in path order
+    pub fn entries(&self) -> impl Iterator<Item = &LibraryEntry> {
+        self.entries.values()
+    }
+
+    /// The entry of a file, by the path it was found at, starting with the root
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&LibraryEntry> {
+        self.entries.get(path.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Statistics of the indexed files, as of their last scan
+    pub fn stats(&self) -> LibraryStats {
+        let mut stats = LibraryStats { files: self.entries.len(), ..LibraryStats::default() };
+        for entry in self.entries.values() {
+            stats.add(&entry.metadata, &entry.tags, entry.padding);
+        }
+        stats
+    }
+
+    /// Start a query over the indexed files; without conditions it matches all of them
+    pub fn find(&self) -> Query<'_> {
+        Query { library: self, conditions: Vec::new() }
+    }
+
+    /// Write the index to a file, replacing it at once
+    ///
+    /// The index is UTF-8 text with one line per field; paths below the root
+    /// are stored relative to it.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut out = format!("{}\nroot\t{}\n", INDEX_HEADER, escape(path_text(&self.root)?));
+        for entry in self.entries.values() {
+            let relative = entry.path.strip_prefix(&self.root).unwrap_or(&entry.path);
+            let modified = entry.modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "file\t{}\t{}\t{}\t{}",
+                escape(path_text(relative)?),
+                modified.as_secs(),
+                modified.subsec_nanos(),
+                entry.size
+            );
+            write_metadata(&mut out, &entry.metadata);
+            for tag in &entry.tags {
+                let _ = match tag {
+                    TagVersion::Id3v1 { revision } => writeln!(out, "tag\tid3v1\t{}", revision),
+                    TagVersion::Id3v2(version) => writeln!(out, "tag\tid3v2\t{}", u8::from(*version)),
+                    TagVersion::Ape(version) => writeln!(out, "tag\tape\t{}", version),
+                };
+            }
+            if entry.padding > 0 {
+                let _ = writeln!(out, "padding\t{}", entry.padding);
+            }
+        }
+
+        let path = path.as_ref();
+        let temp_path = crate::util::get_temp_path(path);
+        fs::write(&temp_path, out)?;
+        fs::rename(&temp_path, path).inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })?;
+        Ok(())
+    }
+
+    /// Read an index written by `save`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let mut lines = text.lines().enumerate();
+        if lines.next().map(|(_, line)| line) != Some(INDEX_HEADER) {
+            return Err(Error::Other("Not a library index".to_string()));
+        }
+
+        let mut library: Option<Self> = None;
+        let mut entry: Option<LibraryEntry> = None;
+        for (number, line) in lines {
+            let invalid = || Error::Other(format!("Invalid library index line {}", number + 1));
+            let fields: Vec<String> = line.split('\t').map(unescape).collect();
+            match (fields[0].as_str(), &mut library) {
+                ("root", None) if fields.len() == 2 => library = Some(Self::new(&fields[1])),
+                ("file", Some(library)) if fields.len() == 5 => {
+                    let number = |field: &String| field.parse::<u64>().map_err(|_| invalid());
+                    let nanos = fields[3].parse::<u32>().ok().filter(|nanos| *nanos < 1_000_000_000).ok_or_else(invalid)?;
+                    let modified = UNIX_EPOCH.checked_add(Duration::new(number(&fields[2])?, nanos)).ok_or_else(invalid)?;
+                    let path = library.root.join(&fields[1]);
+                    let size = number(&fields[4])?;
+                    let file = LibraryEntry { path, modified, size, metadata: Metadata::default(), tags: Vec::new(), padding: 0 };
+                    if let Some(previous) = entry.replace(file) {
+                        library.entries.insert(previous.path.clone(), previous);
+                    }
+                }
+                ("tag", Some(_)) if fields.len() == 3 => {
+                    let version = read_tag_version(&fields[1], &fields[2]).ok_or_else(invalid)?;
+                    entry.as_mut().ok_or_else(invalid)?.tags.push(version);
+                }
+                ("padding", Some(_)) if fields.len() == 2 => {
+                    entry.as_mut().ok_or_else(invalid)?.padding = fields[1].parse().map_err(|_| invalid())?;
+                }
+                (field, Some(_)) => {
+                    let metadata = &mut entry.as_mut().ok_or_else(invalid)?.metadata;
+                    read_metadata_field(metadata, field, &fields[1..]).ok_or_else(invalid)?;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        let mut library = library.ok_or_else(|| Error::Other("Library index without root".to_string()))?;
+        if let Some(entry) = entry {
+            library.entries.insert(entry.path.clone(), entry);
+        }
+        Ok(library)
+    }
+}
+
+/// A condition of a `Query`
+#[derive(Debug, Clone)]
+enum Condition {
+    /// The text field contains the lowercase text, ignoring case
+    Contains(Field, String),
+    /// The text field equals the lowercase text, ignoring case
+    Is(Field, String),
+    YearBetween(u32, u32),
+    /// The custom value of the key equals the text
+    Custom(String, String),
+    Missing(Field),
+}
+
+/// Text fields a `Query` can test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    Artist,
+    Album,
+    AlbumArtist,
+    Genre,
+    Comment,
+}
+
+impl Field {
+    fn value(self, metadata: &Metadata) -> Option<&str> {
+        match self {
+            Field::Title => metadata.title.as_deref(),
+            Field::Artist => metadata.artist.as_deref(),
+            Field::Album => metadata.album.as_deref(),
+            Field::AlbumArtist => metadata.album_artist.as_deref(),
+            Field::Genre => metadata.genre.as_deref(),
+            Field::Comment => metadata.comment.as_deref(),
+        }
+    }
+}
+
+impl Condition {
+    fn matches(&self, metadata: &Metadata) -> bool {
+        match self {
+            Condition::Contains(field, text) => field.value(metadata).is_some_and(|value| value.to_lowercase().contains(text)),
+            Condition::Is(field, text) => field.value(metadata).is_some_and(|value| value.to_lowercase() == *text),
+            Condition::YearBetween(from, to) => metadata.year.is_some_and(|year| (*from..=*to).contains(&year)),
+            Condition::Custom(key, text) => metadata.custom.get(key) == Some(text),
+            Condition::Missing(field) => field.value(metadata).is_none(),
+        }
+    }
+}
+
+/// Files of a `Library` matching all of a set of conditions
+///
+/// Text is compared ignoring case; files without the tested field never match,
+/// except for `missing`.
+#[derive(Debug, Clone)]
+pub struct Query<'a> {
+    library: &'a Library,
+    conditions: Vec<Condition>,
+}
+
+impl<'a> Query<'a> {
+    fn with(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Files whose `field` contains `text`
+    pub fn contains(self, field: Field, text: &str) -> Self {
+        self.with(Condition::Contains(field, text.to_lowercase()))
+    }
+
+    /// Files whose `field` equals `text`
+    pub fn is(self, field: Field, text: &str) -> Self {
+        self.with(Condition::Is(field, text.to_lowercase()))
+    }
+
+    /// Files without a value for `field`
+    pub fn missing(self, field: Field) -> Self {
+        self.with(Condition::Missing(field))
+    }
+
+    pub fn title_contains(self, text: &str) -> Self {
+        self.contains(Field::Title, text)
+    }
+
+    pub fn artist_contains(self, text: &str) -> Self {
+        self.contains(Field::Artist, text)
+    }
+
+    pub fn album_contains(self, text: &str) -> Self {
+        self.contains(Field::Album, text)
+    }
+
+    pub fn genre_is(self, genre: &str) -> Self {
+        self.is(Field::Genre, genre)
+    }
+
+    /// Files whose year lies between `from` and `to`, both included
+    pub fn year_between(self, from: u32, to: u32) -> Self {
+        self.with(Condition::YearBetween(from, to))
+    }
+
+    /// Files whose custom value for `key` equals `value` exactly
+    pub fn custom(self, key: &str, value: &str) -> Self {
+        self.with(Condition::Custom(key.to_string(), value.to_string()))
+    }
+
+    /// The matching files, in path order
+    pub fn entries(&self) -> impl Iterator<Item = &'a LibraryEntry> + '_ {
+        self.library.entries().filter(|entry| self.conditions.iter().all(|condition| condition.matches(&entry.metadata)))
+    }
+
+    /// The paths of the matching files, in path order
+    pub fn paths(&self) -> Vec<&'a Path> {
+        self.entries().map(|entry| entry.path.as_path()).collect()
+    }
+
+    /// Number of matching files
+    pub fn count(&self) -> usize {
+        self.entries().count()
+    }
+}
+
+/// Number of files lacking each field
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MissingFields {
+    pub title: usize,
+    pub artist: usize,
+    pub album: usize,
+    pub album_artist: usize,
+    pub year: usize,
+    pub track_number: usize,
+    pub genre: usize,
+}
+
+/// State of the tags of a set of files, reported by `stats`
+#[derive(Debug, Default)]
+pub struct LibraryStats {
+    /// Files examined, including the ones that failed
+    pub files: usize,
+    /// Files without any tag
+    pub untagged: usize,
+    /// Files holding a tag of each type
+    pub tag_types: HashMap<TagType, usize>,
+    /// Files holding a tag of each format revision, such as ID3v2.3
+    pub tag_versions: HashMap<TagVersion, usize>,
+    /// Files of each genre, as read by `Metadata::read`
+    pub genres: HashMap<String, usize>,
+    pub missing: MissingFields,
+    /// Bytes of padding in ID3v2 tags and between the tags and the audio
+    pub padding: u64,
+    /// Files that could not be examined, with their errors
+    pub failed: Vec<(PathBuf, Error)>,
+}
+
+/// Count the tag types and versions, genres, missing fields and padding of files, in parallel
+///
+/// Field counts follow `Metadata::read`, so they describe the first tag found;
+/// untagged files lack every field.
+pub fn stats<I, P>(paths: I) -> LibraryStats
+where
+    I: IntoIterator<Item = P>,
+    P: Into<PathBuf>,
+{
+    let summary = Batch::new(paths).run(read_file);
+
+    let mut stats = LibraryStats { files: summary.results.len(), ..LibraryStats::default() };
+    for (path, result) in summary.results {
+        match result {
+            Ok((metadata, tags, padding)) => stats.add(&metadata, &tags, padding),
+            Err(error) => stats.failed.push((path, error)),
+        }
+    }
+    stats
+}
+
+impl LibraryStats {
+    /// Count a file with the given fields, tags and padding
+    fn add(&mut self, metadata: &Metadata, tags: &[TagVersion], padding: u64) {
+        if tags.is_empty() {
+            self.untagged += 1;
+        }
+        for &tag in tags {
+            *self.tag_types.entry(tag.tag_type()).or_default() += 1;
+            *self.tag_versions.entry(tag).or_default() += 1;
+        }
+        if let Some(genre) = &metadata.genre {
+            *self.genres.entry(genre.clone()).or_default() += 1;
+        }
+
+        let missing = &mut self.missing;
+        missing.title += metadata.title.is_none() as usize;
+        missing.artist += metadata.artist.is_none() as usize;
+        missing.album += metadata.album.is_none() as usize;
+        missing.album_artist += metadata.album_artist.is_none() as usize;
+        missing.year += metadata.year.is_none() as usize;
+        missing.track_number += metadata.track_number.is_none() as usize;
+        missing.genre += metadata.genre.is_none() as usize;
+        self.padding += padding;
+    }
+}
+
+/// The fields of a file, the format revisions of its tags and its padding
+fn read_file(path: &Path) -> Result<(Metadata, Vec<TagVersion>, u64)> {
+    let (layout, presence) = layout_with_tags(path)?;
+    let padding = layout.of_kind(StructureKind::Padding).map(|padding| padding.size).sum();
+    let tags = presence.tags.iter().map(|tag| tag.version).collect();
+    Ok((read_metadata(path)?, tags, padding))
+}
+
+/// The fields of a file, empty when it has no tag
+fn read_metadata(path: &Path) -> Result<Metadata> {
+    match Metadata::read_fields(path) {
+        Err(Error::TagNotFound) => Ok(Metadata::default()),
+        result => result,
+    }
+}
+
+/// A tag revision as written by `Library::save`
+fn read_tag_version(kind: &str, number: &str) -> Option<TagVersion> {
+    let number: u32 = number.parse().ok()?;
+    match kind {
+        "id3v1" if number <= 1 => Some(TagVersion::Id3v1 { revision: number as u8 }),
+        "id3v2" if (2..=4).contains(&number) => Some(TagVersion::Id3v2((number as u8).into())),
+        "ape" => Some(TagVersion::Ape(number)),
+        _ => None,
+    }
+}
+
+fn path_text(path: &Path) -> Result<&str> {
+    path.to_str().ok_or_else(|| Error::Other(format!("Path is not valid UTF-8: {}", path.display())))
+}
+
+/// Append the fields that are set, one per line
+fn write_metadata(out: &mut String, metadata: &Metadata) {
+    let mut field = |name: &str, value: Option<String>| {
+        if let Some(value) = value {
+            let _ = writeln!(out, "{}\t{}", name, escape(&value));
+        }
+    };
+    field("title", metadata.title.clone());
+    field("artist", metadata.artist.clone());
+    field("album", metadata.album.clone());
+    field("album_artist", metadata.album_artist.clone());
+    field("year", metadata.year.map(|year| year.to_string()));
+    field("track_number", metadata.track_number.map(|number| number.to_string()));
+    field("track_total", metadata.track_total.map(|total| total.to_string()));
+    field("disc_number", metadata.disc_number.map(|number| number.to_string()));
+    field("disc_total", metadata.disc_total.map(|total| total.to_string()));
+    field("genre", metadata.genre.clone());
+    field("comment", metadata.comment.clone());
+    for (key, value) in &metadata.custom {
+        let _ = writeln!(out, "custom\t{}\t{}", escape(key), escape(value));
+    }
+}
+
+/// Set the field written by `write_metadata`, `None` for an unknown or malformed field
+fn read_metadata_field(metadata: &mut Metadata, name: &str, values: &[String]) -> Option<()> {
+    let text = || values.first().cloned();
+    let number = || values.first()?.parse().ok();
+    match (name, values.len()) {
+        ("title", 1) => metadata.title = text(),
+        ("artist", 1) => metadata.artist = text(),
+        ("album", 1) => metadata.album = text(),
+        ("album_artist", 1) => metadata.album_artist = text(),
+        ("year", 1) => metadata.year = Some(number()?),
+        ("track_number", 1) => metadata.track_number = Some(number()?),
+        ("track_total", 1) => metadata.track_total = Some(number()?),
+        ("disc_number", 1) => metadata.disc_number = Some(number()?),
+        ("disc_total", 1) => metadata.disc_total = Some(number()?),
+        ("genre", 1) => metadata.genre = text(),
+        ("comment", 1) => metadata.comment = text(),
+        ("custom", 2) => {
+            metadata.custom.insert(values[0].clone(), values[1].clone());
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Escape the characters that separate fields and lines
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
@@ -31,12 +31,69 @@ pub enum MetaEntry {
     Time,
     OriginalFilename,
     FileType,
+    /// Band or orchestra, commonly used for the album artist (ID3v2 TPE2);
+    /// `MetaEntry::AlbumArtist` names the same entry
     BandOrchestra,
+    /// Embedded picture such as cover art (ID3v2 APIC)
+    AttachedPicture,
+    /// Unsynchronised lyrics (ID3v2 USLT)
+    Lyrics,
+    /// Podcast episode description (ID3v2 TDES)
+    PodcastDescription,
+    /// Podcast episode GUID (ID3v2 TGID)
+    PodcastId,
+    /// Podcast feed URL (ID3v2 WFED)
+    PodcastFeedUrl,
+    /// Podcast category (ID3v2 TCAT)
+    PodcastCategory,
+    /// Podcast keywords (ID3v2 TKWD)
+    PodcastKeywords,
+    /// iTunes compilation flag, "1" for compilations (ID3v2 TCMP)
+    Compilation,
+    /// iTunes album artist sort order (ID3v2 TSO2)
+    AlbumArtistSortOrder,
+    /// iTunes composer sort order (ID3v2 TSOC)
+    ComposerSortOrder,
+    /// Title sort order (ID3v2 TSOT)
+    TitleSortOrder,
+    /// Artist sort order (ID3v2 TSOP)
+    ArtistSortOrder,
+    /// Album sort order (ID3v2 TSOA)
+    AlbumSortOrder,
+    /// Disc number, optionally with the total as "1/2" (ID3v2 TPOS);
+    /// `MetaEntry::DiscNumber` names the same entry
+    PartOfSet,
+    /// Tempo in beats per minute (ID3v2 TBPM)
+    BeatsPerMinute,
+    /// Musical key such as "Am" or "F#" (ID3v2 TKEY)
+    InitialKey,
+    /// International Standard Recording Code (ID3v2 TSRC)
+    Isrc,
+    /// Publisher or label (ID3v2 TPUB)
+    Publisher,
+    /// Copyright message (ID3v2 TCOP)
+    Copyright,
+    /// Person or organisation that encoded the file (ID3v2 TENC)
+    EncodedBy,
+    /// Conductor (ID3v2 TPE3)
+    Conductor,
+    /// Remixer or other modifier (ID3v2 TPE4)
+    Remixer,
+    /// Rating on a 0–5 star scale (ID3v2 POPM)
+    Rating,
     
-    /// Custom entry with user-defined key
+    /// Custom entry with user-defined key (ID3v2 TXXX description, APE item key)
     Custom(String),
 }
 
+#[allow(non_upper_case_globals)]
+impl MetaEntry {
+    /// Album artist, stored as `BandOrchestra`
+    pub const AlbumArtist: MetaEntry = MetaEntry::BandOrchestra;
+    /// Disc number, stored as `PartOfSet`
+    pub const DiscNumber: MetaEntry = MetaEntry::PartOfSet;
+}
+
 impl fmt::Display for MetaEntry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -56,6 +113,29 @@ impl fmt::Display for MetaEntry {
             Self::OriginalFilename => write!(f, "OriginalFilename"),
             Self::FileType => write!(f, "FileType"),
             Self::BandOrchestra => write!(f, "BandOrchestra"),
+            Self::AttachedPicture => write!(f, "AttachedPicture"),
+            Self::Lyrics => write!(f, "Lyrics"),
+            Self::PodcastDescription => write!(f, "PodcastDescription"),
+            Self::PodcastId => write!(f, "PodcastId"),
+            Self::PodcastFeedUrl => write!(f, "PodcastFeedUrl"),
+            Self::PodcastCategory => write!(f, "PodcastCategory"),
+            Self::PodcastKeywords => write!(f, "PodcastKeywords"),
+            Self::Compilation => write!(f, "Compilation"),
+            Self::AlbumArtistSortOrder => write!(f, "AlbumArtistSortOrder"),
+            Self::ComposerSortOrder => write!(f, "ComposerSortOrder"),
+            Self::TitleSortOrder => write!(f, "TitleSortOrder"),
+            Self::ArtistSortOrder => write!(f, "ArtistSortOrder"),
+            Self::AlbumSortOrder => write!(f, "AlbumSortOrder"),
+            Self::PartOfSet => write!(f, "PartOfSet"),
+            Self::BeatsPerMinute => write!(f, "BeatsPerMinute"),
+            Self::InitialKey => write!(f, "InitialKey"),
+            Self::Isrc => write!(f, "Isrc"),
+            Self::Publisher => write!(f, "Publisher"),
+            Self::Copyright => write!(f, "Copyright"),
+            Self::EncodedBy => write!(f, "EncodedBy"),
+            Self::Conductor => write!(f, "Conductor"),
+            Self::Remixer => write!(f, "Remixer"),
+            Self::Rating => write!(f, "Rating"),
             Self::Custom(key) => write!(f, "{}", key),
         }
     }
@@ -80,5 +160,28 @@ pub fn all_standard_entries() -> Vec<MetaEntry> {
         MetaEntry::OriginalFilename,
         MetaEntry::FileType,
         MetaEntry::BandOrchestra,
+        MetaEntry::AttachedPicture,
+        MetaEntry::Lyrics,
+        MetaEntry::PodcastDescription,
+        MetaEntry::PodcastId,
+        MetaEntry::PodcastFeedUrl,
+        MetaEntry::PodcastCategory,
+        MetaEntry::PodcastKeywords,
+        MetaEntry::Compilation,
+        MetaEntry::AlbumArtistSortOrder,
+        MetaEntry::ComposerSortOrder,
+        MetaEntry::TitleSortOrder,
+        MetaEntry::ArtistSortOrder,
+        MetaEntry::AlbumSortOrder,
+        MetaEntry::PartOfSet,
+        MetaEntry::BeatsPerMinute,
+        MetaEntry::InitialKey,
+        MetaEntry::Isrc,
+        MetaEntry::Publisher,
+        MetaEntry::Copyright,
+        MetaEntry::EncodedBy,
+        MetaEntry::Conductor,
+        MetaEntry::Remixer,
+        MetaEntry::Rating,
     ]
 }
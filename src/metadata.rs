@@ -0,0 +1,150 @@
+//! Typed view of the common tag fields
+//!
+//! `Metadata` gathers the fields most applications care about from any tag
+//! type, with numbers parsed, and writes them back to any tag type. With the
+//! `serde` feature it implements `Serialize` and `Deserialize`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::artwork::Picture;
+use crate::convert::{read_source, write_target};
+use crate::error::{Error, Result};
+use crate::id3::v1::report::WriteReport;
+use crate::meta_entry::MetaEntry;
+use crate::position::Position;
+use crate::tag::TagType;
+
+/// Common tag fields; `None` and empty fields are absent from the tag
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    /// Stored as the band/orchestra entry (ID3v2 TPE2)
+    pub album_artist: Option<String>,
+    pub year: Option<u32>,
+    pub track_number: Option<u32>,
+    pub track_total: Option<u32>,
+    pub disc_number: Option<u32>,
+    pub disc_total: Option<u32>,
+    pub genre: Option<String>,
+    /// The comment without a description; ID3v2 comments with a description
+    /// are only available through the ID3v2 `TagReader::comments` and
+    /// `TagWriter::set_comment`
+    pub comment: Option<String>,
+    /// Embedded pictures
+    pub pictures: Vec<Picture>,
+    /// ID3v2 TXXX values and unmapped APE items, by key
+    pub custom: BTreeMap<String, String>,
+}
+
+impl Metadata {
+    /// Load from the first tag found, trying ID3v2, then APE, then ID3v1
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::read_first(path.as_ref(), true)
+    }
+
+    /// Load like `read`, leaving out the pictures
+    pub(crate) fn read_fields(path: &Path) -> Result<Self> {
+        Self::read_first(path, false)
+    }
+
+    fn read_first(path: &Path, with_pictures: bool) -> Result<Self> {
+        for tag_type in [TagType::Id3v2, TagType::Ape, TagType::Id3v1] {
+            match Self::read_tag(path, tag_type, with_pictures) {
+                Err(Error::TagNotFound) => continue,
+                result => return result,
+            }
+        }
+        Err(Error::TagNotFound)
+    }
+
+    /// Load from the tag of the given type
+    ///
+    /// Fails with `TagNotFound` when the file has no such tag.
+    pub fn read_from<P: AsRef<Path>>(path: P, tag_type: TagType) -> Result<Self> {
+        Self::read_tag(path.as_ref(), tag_type, true)
+    }
+
+    fn read_tag(path: &Path, tag_type: TagType, with_pictures: bool) -> Result<Self> {
+        let (values, pictures) = read_source(path, tag_type, with_pictures)?;
+        let mut metadata = Self::from_entries(&values.into_iter().collect());
+        metadata.pictures = pictures;
+        Ok(metadata)
+    }
+
+    /// Write the fields into the tag of the given type, creating it if needed
+    ///
+    /// Fields that are `None` or empty leave the tag's value alone. The report
+    /// lists values an ID3v1 tag could not hold.
+    pub fn write_to<P: AsRef<Path>>(&self, path: P, tag_type: TagType) -> Result<WriteReport> {
+        write_target(path.as_ref(), tag_type, &self.to_entries(), &self.pictures)
+    }
+
+    /// Build from text entries, such as those of `TagReader::get_all_meta_entries`
+    ///
+    /// Values that do not parse as numbers, or as a `Position`, are dropped
+    /// from numeric fields.
+    pub fn from_entries(entries: &HashMap<MetaEntry, String>) -> Self {
+        let text = |entry: MetaEntry| entries.get(&entry).filter(|value| !value.is_empty()).cloned();
+        let position = |entry: MetaEntry| entries.get(&entry).and_then(|value| value.parse::<Position>().ok());
+        let (track_number, track_total) = position(MetaEntry::Track).map_or((None, None), |p| (Some(p.number), p.total));
+        let (disc_number, disc_total) = position(MetaEntry::PartOfSet).map_or((None, None), |p| (Some(p.number), p.total));
+
+        Self {
+            title: text(MetaEntry::Title),
+            artist: text(MetaEntry::Artist),
+            album: text(MetaEntry::Album),
+            album_artist: text(MetaEntry::BandOrchestra),
+            year: entries.get(&MetaEntry::Year).and_then(|value| parse_year(value)),
+            track_number,
+            track_total,
+            disc_number,
+            disc_total,
+            genre: text(MetaEntry::Genre),
+            comment: text(MetaEntry::Comment),
+            pictures: Vec::new(),
+            custom: entries
+                .iter()
+                .filter_map(|(entry, value)| match entry {
+                    MetaEntry::Custom(key) if !value.is_empty() => Some((key.clone(), value.clone())),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Text entries for the fields that are set, custom entries last
+    pub fn to_entries(&self) -> Vec<(MetaEntry, String)> {
+        let mut entries = Vec::new();
+        let mut push = |entry: MetaEntry, value: Option<String>| {
+            if let Some(value) = value.filter(|value| !value.is_empty()) {
+                entries.push((entry, value));
+            }
+        };
+        push(MetaEntry::Title, self.title.clone());
+        push(MetaEntry::Artist, self.artist.clone());
+        push(MetaEntry::Album, self.album.clone());
+        push(MetaEntry::BandOrchestra, self.album_artist.clone());
+        push(MetaEntry::Year, self.year.map(|year| year.to_string()));
+        // A total without a number is not written
+        let position = |number: Option<u32>, total| number.map(|number| Position::new(number, total).to_string());
+        push(MetaEntry::Track, position(self.track_number, self.track_total));
+        push(MetaEntry::PartOfSet, position(self.disc_number, self.disc_total));
+        push(MetaEntry::Genre, self.genre.clone());
+        push(MetaEntry::Comment, self.comment.clone());
+        for (key, value) in &self.custom {
+            push(MetaEntry::Custom(key.clone()), Some(value.clone()));
+        }
+        entries
+    }
+}
+
+/// Year at the start of a year or timestamp value ("2004", "2004-05-17")
+pub(crate) fn parse_year(value: &str) -> Option<u32> {
+    let digits = value.trim().get(..4)?;
+    digits.bytes().all(|b| b.is_ascii_digit()).then(|| digits.parse().ok())?
+}
@@ -0,0 +1,119 @@
+//! MusicBrainz identifiers in their conventional per-format locations.
+//!
+//! Follows the MusicBrainz Picard mapping: the recording ID lives in a UFID
+//! frame owned by `http://musicbrainz.org`, the other IDs in TXXX frames, and
+//! APE tags use `MUSICBRAINZ_*` keys.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::ape::{ApeReader, ApeWriter};
+use crate::id3::v2::tag::{TagReader as Id3v2Reader, TagWriter as Id3v2Writer};
+use crate::id3::v2::ufid::UniqueFileId;
+use crate::tag::{TagReaderStrategy, TagWriterStrategy};
+use crate::{Error, MetaEntry, Result, TagType};
+
+/// UFID owner used for MusicBrainz recording IDs
+pub const UFID_OWNER: &str = "http://musicbrainz.org";
+
+/// Kind of MusicBrainz identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MusicBrainzId {
+    /// Recording ("track") ID
+    Track,
+    /// Track ID within a specific release
+    ReleaseTrack,
+    /// Release ("album") ID
+    Release,
+    Artist,
+    AlbumArtist,
+    ReleaseGroup,
+    Work,
+}
+
+impl MusicBrainzId {
+    /// TXXX description used in ID3v2, `None` for the recording ID stored in UFID
+    pub fn id3v2_description(self) -> Option<&'static str> {
+        match self {
+            Self::Track => None,
+            Self::ReleaseTrack => Some("MusicBrainz Release Track Id"),
+            Self::Release => Some("MusicBrainz Album Id"),
+            Self::Artist => Some("MusicBrainz Artist Id"),
+            Self::AlbumArtist => Some("MusicBrainz Album Artist Id"),
+            Self::ReleaseGroup => Some("MusicBrainz Release Group Id"),
+            Self::Work => Some("MusicBrainz Work Id"),
+        }
+    }
+
+    /// APE item key
+    pub fn ape_key(self) -> &'static str {
+        match self {
+            Self::Track => "MUSICBRAINZ_TRACKID",
+            Self::ReleaseTrack => "MUSICBRAINZ_RELEASETRACKID",
+            Self::Release => "MUSICBRAINZ_ALBUMID",
+            Self::Artist => "MUSICBRAINZ_ARTISTID",
+            Self::AlbumArtist => "MUSICBRAINZ_ALBUMARTISTID",
+            Self::ReleaseGroup => "MUSICBRAINZ_RELEASEGROUPID",
+            Self::Work => "MUSICBRAINZ_WORKID",
+        }
+    }
+}
+
+/// Read an identifier, preferring ID3v2 over APE
+pub fn read<P: AsRef<Path>>(path: P, kind: MusicBrainzId) -> Result<Option<String>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Err(Error::FileNotFound(path.display().to_string()));
+    }
+
+    let mut id3v2 = Id3v2Reader::new();
+    if id3v2.init(path).is_ok() {
+        let value = match kind.id3v2_description() {
+            Some(description) => id3v2.user_text(description),
+            None => id3v2
+                .unique_file_id(UFID_OWNER)
+                .and_then(|ufid| ufid.identifier_str().map(str::to_string)),
+        };
+        if value.is_some() {
+            return Ok(value);
+        }
+    }
+
+    Ok(ApeReader::new()
+        .read_tag(path)
+        .ok()
+        .and_then(|tag| tag.get_item_text(kind.ape_key()).ok()))
+}
+
+/// Write an identifier to the given tag type after checking it is a well-formed MBID
+pub fn write<P: AsRef<Path>>(path: P, kind: MusicBrainzId, mbid: &str, tag_type: TagType) -> Result<()> {
+    let path = path.as_ref();
+    if !is_valid_mbid(mbid) {
+        return Err(Error::Other(format!("Invalid MusicBrainz identifier: {}", mbid)));
+    }
+    match tag_type {
+        TagType::Id3v2 => {
+            let mut writer = Id3v2Writer::new();
+            writer.init(path)?;
+            match kind.id3v2_description() {
+                Some(description) => writer.set_user_text(description, mbid),
+                None => writer.set_unique_file_id(&UniqueFileId::new(UFID_OWNER, mbid.as_bytes())?),
+            }
+        }
+        TagType::Ape => {
+            let entries = HashMap::from([(MetaEntry::Custom(kind.ape_key().to_string()), mbid.to_string())]);
+            ApeWriter::new().set_meta_entries(path, &entries)
+        }
+        TagType::Id3v1 => Err(Error::UnsupportedMetaEntry(format!("{:?}", kind))),
+        TagType::Custom(_) => Err(Error::InvalidTagType),
+    }
+}
+
+/// Whether `mbid` is a lowercase or uppercase hyphenated UUID
+pub fn is_valid_mbid(mbid: &str) -> bool {
+    mbid.len() == 36
+        && mbid.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
@@ -0,0 +1,56 @@
+//! Track and disc positions such as "3/12"
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// Position of a track on its disc, or of a disc in its set, with an optional total
+///
+/// Stored in ID3v2 TRCK/TPOS frames and APE TRACK/DISC items as "3/12", or "3"
+/// without a total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub number: u32,
+    pub total: Option<u32>,
+}
+
+impl Position {
+    pub fn new(number: u32, total: Option<u32>) -> Self {
+        Self { number, total }
+    }
+
+    /// Fail on a zero number or total, or a number beyond the total
+    pub fn validate(&self) -> Result<()> {
+        if self.number == 0 {
+            return Err(Error::Other("Positions start at 1".to_string()));
+        }
+        match self.total {
+            Some(total) if total < self.number => Err(Error::Other(format!("Position {} exceeds total {}", self.number, total))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl FromStr for Position {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid = || Error::Other(format!("Invalid position: {:?}", value));
+        let (number, total) = match value.split_once('/') {
+            Some((number, total)) => (number, Some(total.trim().parse().map_err(|_| invalid())?)),
+            None => (value, None),
+        };
+        let number = number.trim().parse().map_err(|_| invalid())?;
+        Ok(Self { number, total })
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.total {
+            Some(total) => write!(f, "{}/{}", self.number, total),
+            None => write!(f, "{}", self.number),
+        }
+    }
+}
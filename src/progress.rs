@@ -0,0 +1,180 @@
+//! Progress reporting and cancellation of long operations
+//!
+//! A `Progress` given to `Batch::progress` counts the files processed and the
+//! bytes copied by file rewrites, and stops the batch once its
+//! `CancellationToken` is cancelled. Single rewrites, such as saving a tag that
+//! no longer fits in place, report to the `Progress` set with `with_progress`:
+//!
+//! ```no_run
+//! use mp3tags_r::progress::{with_progress, Progress};
+//! use mp3tags_r::Tag;
+//!
+//! let progress = Progress::new().on_update(|update| println!("{} bytes copied", update.bytes_copied));
+//! with_progress(&progress, || Tag::open("song.mp3")?.title("Title").save())?;
+//! # Ok::<(), mp3tags_r::Error>(())
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+
+/// State of an operation, passed to the progress callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProgressUpdate {
+    /// Files processed so far, successfully or not
+    pub files_done: usize,
+    /// Files in the batch; zero outside of a batch
+    pub files_total: usize,
+    /// Bytes copied by file rewrites so far
+    pub bytes_copied: u64,
+}
+
+/// Callback invoked when files are processed or bytes are copied
+pub type ProgressHook = Arc<dyn Fn(&ProgressUpdate) + Send + Sync>;
+
+/// Flag to abort an operation from another thread
+///
+/// Clones share the flag. A cancelled batch reports `Error::Cancelled` for
+/// the files it did not process; a cancelled rewrite leaves the file as it was.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request the operations using this token to stop
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    files_done: AtomicUsize,
+    files_total: AtomicUsize,
+    bytes_copied: AtomicU64,
+}
+
+/// Progress callback and cancellation token of an operation
+///
+/// Clones share their counters, so one `Progress` can follow work spread
+/// over several threads.
+#[derive(Clone, Default)]
+pub struct Progress {
+    hook: Option<ProgressHook>,
+    token: CancellationToken,
+    counters: Arc<Counters>,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `hook` after every processed file and every chunk copied by a rewrite
+    pub fn on_update<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&ProgressUpdate) + Send + Sync + 'static,
+    {
+        self.hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Stop when `token` is cancelled
+    pub fn with_token(mut self, token: CancellationToken) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// The token that cancels the operation
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// The current state of the operation
+    pub fn update(&self) -> ProgressUpdate {
+        ProgressUpdate {
+            files_done: self.counters.files_done.load(Ordering::Relaxed),
+            files_total: self.counters.files_total.load(Ordering::Relaxed),
+            bytes_copied: self.counters.bytes_copied.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Fail with `Error::Cancelled` once the token is cancelled
+    pub(crate) fn check(&self) -> Result<()> {
+        if self.token.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_files_total(&self, files: usize) {
+        self.counters.files_total.store(files, Ordering::Relaxed);
+    }
+
+    pub(crate) fn file_done(&self) {
+        self.counters.files_done.fetch_add(1, Ordering::Relaxed);
+        self.notify();
+    }
+
+    fn bytes_copied(&self, bytes: u64) {
+        self.counters.bytes_copied.fetch_add(bytes, Ordering::Relaxed);
+        self.notify();
+    }
+
+    fn notify(&self) {
+        if let Some(hook) = &self.hook {
+            hook(&self.update());
+        }
+    }
+}
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Progress")
+            .field("update", &self.update())
+            .field("cancelled", &self.token.is_cancelled())
+            .finish()
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Vec<Progress>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Pops the progress pushed by `with_progress`, even when the closure panics
+struct ProgressGuard;
+
+impl Drop for ProgressGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| {
+            current.borrow_mut().pop();
+        });
+    }
+}
+
+/// Run `f` with `progress` receiving the bytes copied by the rewrites it does on this thread
+pub fn with_progress<T>(progress: &Progress, f: impl FnOnce() -> T) -> T {
+    CURRENT.with(|current| current.borrow_mut().push(progress.clone()));
+    let _guard = ProgressGuard;
+    f()
+}
+
+/// Report `bytes` copied by a rewrite, failing with `Error::Cancelled` once cancelled
+pub(crate) fn copied(bytes: usize) -> Result<()> {
+    // Cloned out so that the callback may start rewrites of its own
+    let Some(progress) = CURRENT.with(|current| current.borrow().last().cloned()) else {
+        return Ok(());
+    };
+    progress.bytes_copied(bytes as u64);
+    progress.check()
+}
@@ -0,0 +1,138 @@
+//! ReplayGain values stored as ID3v2 TXXX frames or APE items.
+//!
+//! Both formats use the same keys (`REPLAYGAIN_TRACK_GAIN` etc.) with values
+//! such as "-6.54 dB" for gains and "0.988547" for peaks.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::ape::{ApeReader, ApeWriter};
+use crate::id3::v2::tag::{TagReader as Id3v2Reader, TagWriter as Id3v2Writer};
+use crate::tag::{TagReaderStrategy, TagWriterStrategy};
+use crate::{Error, MetaEntry, Result, TagType};
+
+pub const TRACK_GAIN: &str = "REPLAYGAIN_TRACK_GAIN";
+pub const TRACK_PEAK: &str = "REPLAYGAIN_TRACK_PEAK";
+pub const ALBUM_GAIN: &str = "REPLAYGAIN_ALBUM_GAIN";
+pub const ALBUM_PEAK: &str = "REPLAYGAIN_ALBUM_PEAK";
+
+/// ReplayGain values; gains are in dB, peaks are linear sample amplitudes
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReplayGain {
+    pub track_gain: Option<f32>,
+    pub track_peak: Option<f32>,
+    pub album_gain: Option<f32>,
+    pub album_peak: Option<f32>,
+}
+
+impl ReplayGain {
+    /// Whether no value is set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Values as (key, formatted value) pairs, skipping unset ones
+    fn to_items(self) -> Vec<(&'static str, String)> {
+        [
+            (TRACK_GAIN, self.track_gain.map(format_gain)),
+            (TRACK_PEAK, self.track_peak.map(format_peak)),
+            (ALBUM_GAIN, self.album_gain.map(format_gain)),
+            (ALBUM_PEAK, self.album_peak.map(format_peak)),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (key, value)))
+        .collect()
+    }
+
+    /// Build from a key lookup, parsing each value
+    fn from_lookup<F>(lookup: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        Self {
+            track_gain: lookup(TRACK_GAIN).and_then(|v| parse_value(&v)),
+            track_peak: lookup(TRACK_PEAK).and_then(|v| parse_value(&v)),
+            album_gain: lookup(ALBUM_GAIN).and_then(|v| parse_value(&v)),
+            album_peak: lookup(ALBUM_PEAK).and_then(|v| parse_value(&v)),
+        }
+    }
+
+    /// Fill unset values from `other`
+    fn or(self, other: Self) -> Self {
+        Self {
+            track_gain: self.track_gain.or(other.track_gain),
+            track_peak: self.track_peak.or(other.track_peak),
+            album_gain: self.album_gain.or(other.album_gain),
+            album_peak: self.album_peak.or(other.album_peak),
+        }
+    }
+}
+
+/// Read ReplayGain values, preferring ID3v2 TXXX frames over APE items
+pub fn read<P: AsRef<Path>>(path: P) -> Result<ReplayGain> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Err(Error::FileNotFound(path.display().to_string()));
+    }
+
+    let mut id3v2 = Id3v2Reader::new();
+    let from_id3v2 = match id3v2.init(path) {
+        Ok(()) => ReplayGain::from_lookup(|key| id3v2.user_text(key)),
+        Err(_) => ReplayGain::default(),
+    };
+    let from_ape = match ApeReader::new().read_tag(path) {
+        Ok(tag) => ReplayGain::from_lookup(|key| tag.get_item_text(key).ok()),
+        Err(_) => ReplayGain::default(),
+    };
+
+    Ok(from_id3v2.or(from_ape))
+}
+
+/// Write the set ReplayGain values to the given tag type
+///
+/// Unset values are left untouched. ID3v1 has no place for ReplayGain data.
+pub fn write<P: AsRef<Path>>(path: P, values: &ReplayGain, tag_type: TagType) -> Result<()> {
+    let path = path.as_ref();
+    let items = values.to_items();
+    match tag_type {
+        TagType::Id3v2 => {
+            let mut writer = Id3v2Writer::new();
+            writer.init(path)?;
+            for (key, value) in items {
+                writer.set_user_text(key, &value)?;
+            }
+            Ok(())
+        }
+        TagType::Ape => {
+            let entries: HashMap<MetaEntry, String> = items
+                .into_iter()
+                .map(|(key, value)| (MetaEntry::Custom(key.to_string()), value))
+                .collect();
+            ApeWriter::new().set_meta_entries(path, &entries)
+        }
+        TagType::Id3v1 => Err(Error::UnsupportedMetaEntry("ReplayGain".to_string())),
+        TagType::Custom(_) => Err(Error::InvalidTagType),
+    }
+}
+
+/// Parse "-6.54 dB", "+1.2dB" or "0.988547"
+pub fn parse_value(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let number = value
+        .strip_suffix("dB")
+        .or_else(|| value.strip_suffix("db"))
+        .or_else(|| value.strip_suffix("DB"))
+        .unwrap_or(value)
+        .trim();
+    number.parse::<f32>().ok().filter(|v| v.is_finite())
+}
+
+/// Format a gain the way common scanners do, e.g. "-6.54 dB"
+pub fn format_gain(gain: f32) -> String {
+    format!("{:+.2} dB", gain)
+}
+
+/// Format a peak with six decimals, e.g. "0.988547"
+pub fn format_peak(peak: f32) -> String {
+    format!("{:.6}", peak)
+}
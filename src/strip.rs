@@ -0,0 +1,58 @@
+//! Physical removal of selected tags from a file
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::error::Result;
+use crate::file_access::FileLock;
+use crate::tag::{detect_tags, TagType};
+use crate::util::{copy_file_range, get_temp_path, replace_file, PreserveOptions};
+
+/// Remove the tags of the given types from a file, leaving the other tags intact
+///
+/// The file is rewritten once without the selected tags; an ID3v1 tag is
+/// removed together with its TAG+ block. Returns the types that were found and
+/// removed, in file order. The file is left untouched when none are present.
+pub fn strip_tags<P: AsRef<Path>>(path: P, tag_types: &[TagType]) -> Result<Vec<TagType>> {
+    strip_tags_preserving(path, tag_types, PreserveOptions::default())
+}
+
+/// Remove tags like `strip_tags`, carrying the selected attributes over to the rewritten file
+pub fn strip_tags_preserving<P: AsRef<Path>>(path: P, tag_types: &[TagType], preserve: PreserveOptions) -> Result<Vec<TagType>> {
+    let path = path.as_ref();
+    let _lock = FileLock::acquire(path)?;
+    let stripped: Vec<_> = detect_tags(path)?
+        .tags
+        .into_iter()
+        .filter(|tag| tag_types.contains(&tag.tag_type))
+        .collect();
+    if stripped.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut file = File::open(path)?;
+    let temp_path = get_temp_path(path);
+    let result = (|| -> Result<()> {
+        let mut temp = OpenOptions::new().write(true).create(true).truncate(true).open(&temp_path)?;
+        // Copy everything between the stripped tags, which are in file order
+        let mut position = 0;
+        for tag in &stripped {
+            if tag.offset > position {
+                file.seek(SeekFrom::Start(position))?;
+                copy_file_range(&mut (&mut file).take(tag.offset - position), &mut temp)?;
+            }
+            position = position.max(tag.offset + tag.size);
+        }
+        file.seek(SeekFrom::Start(position))?;
+        copy_file_range(&mut file, &mut temp)?;
+        temp.sync_all()?;
+        drop(file);
+        replace_file(&temp_path, path, preserve)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result?;
+    Ok(stripped.into_iter().map(|tag| tag.tag_type).collect())
+}
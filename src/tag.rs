@@ -1,10 +1,19 @@
+use std::fs::File;
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
 use crate::{Result, MetaEntry, Error};
-use crate::file_access::{FileManager};
+use crate::artwork::{self, Picture};
+use crate::file_access::{FileAccessStrategy, FileManager};
+use crate::id3::v2::encoding::TextEncoding;
+use crate::id3::v2::version::Version;
+use crate::date::RecordingDate;
+use crate::position::Position;
+use crate::telemetry::{self, AnomalyHook, ParseAnomaly};
+use crate::validation::{normalize_isrc, BaseValidator, StandardValidator};
+use crate::util::PreserveOptions;
 
 /// Represents the type of tag
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TagType {
     /// ID3v1 tag
     Id3v1,
@@ -12,30 +21,225 @@ pub enum TagType {
     Id3v2,
     /// APE tag
     Ape,
+    /// Tag format of a strategy added with `TagReader::add_strategy` or
+    /// `TagWriter::add_strategy`, by name
+    Custom(&'static str),
+}
+
+/// Format revision of a detected tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TagVersion {
+    /// ID3v1.0, or ID3v1.1 when the comment field holds a track number
+    Id3v1 { revision: u8 },
+    Id3v2(crate::id3::v2::version::Version),
+    /// APE version number, 1000 or 2000
+    Ape(u32),
+}
+
+impl TagVersion {
+    /// The tag type this is a revision of
+    pub fn tag_type(self) -> TagType {
+        match self {
+            Self::Id3v1 { .. } => TagType::Id3v1,
+            Self::Id3v2(_) => TagType::Id3v2,
+            Self::Ape(_) => TagType::Ape,
+        }
+    }
+}
+
+impl std::fmt::Display for TagVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id3v1 { revision } => write!(f, "ID3v1.{}", revision),
+            Self::Id3v2(version) => write!(f, "ID3v2.{}", u8::from(*version)),
+            Self::Ape(version) => write!(f, "APEv{}", version / 1000),
+        }
+    }
+}
+
+/// A tag found by `detect_tags`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetectedTag {
+    pub tag_type: TagType,
+    pub version: TagVersion,
+    /// Offset of the first byte of the tag
+    pub offset: u64,
+    /// Size in bytes, including headers, footers, padding and any TAG+ block
+    pub size: u64,
+}
+
+/// Which tags a file holds, in file order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagPresence {
+    pub tags: Vec<DetectedTag>,
+}
+
+impl TagPresence {
+    /// The tag of the given type, if present
+    pub fn get(&self, tag_type: TagType) -> Option<&DetectedTag> {
+        self.tags.iter().find(|tag| tag.tag_type == tag_type)
+    }
+
+    /// Whether a tag of the given type is present
+    pub fn has(&self, tag_type: TagType) -> bool {
+        self.get(tag_type).is_some()
+    }
+
+    /// Whether the file has no tags
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+}
+
+/// Detect the ID3v2, APE and ID3v1 tags of a file with their versions and positions
+pub fn detect_tags<P: AsRef<Path>>(path: P) -> Result<TagPresence> {
+    use crate::id3::constants::{ID3V1_IDENTIFIER, ID3V1_TAG_SIZE};
+    use crate::id3::v1::enhanced::{has_enhanced_tag, ENHANCED_TAG_SIZE};
+    use crate::id3::v2::header::{existing_tag_size, Header};
+    use std::io::{Read, Seek, SeekFrom};
+
+    let path = path.as_ref();
+    let mut file = std::fs::File::open(path)?;
+    let file_size = file.metadata()?.len();
+    let mut tags = Vec::new();
+
+    if let Some(size) = existing_tag_size(&mut file)? {
+        let mut header = [0u8; 10];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut header)?;
+        tags.push(DetectedTag {
+            tag_type: TagType::Id3v2,
+            version: TagVersion::Id3v2(Header::parse(&header)?.version.into()),
+            offset: 0,
+            size,
+        });
+    }
+
+    if let Some((header, span)) = crate::ape::ApeReader::new().locate(&mut file)? {
+        tags.push(DetectedTag {
+            tag_type: TagType::Ape,
+            version: TagVersion::Ape(header.version),
+            offset: span.offset,
+            size: span.len,
+        });
+    }
+
+    if file_size >= ID3V1_TAG_SIZE as u64 {
+        let mut tag = [0u8; ID3V1_TAG_SIZE];
+        file.seek(SeekFrom::End(-(ID3V1_TAG_SIZE as i64)))?;
+        file.read_exact(&mut tag)?;
+        if &tag[..3] == ID3V1_IDENTIFIER {
+            let revision = id3v1_revision(&tag);
+            let size = if has_enhanced_tag(path)? { ID3V1_TAG_SIZE + ENHANCED_TAG_SIZE } else { ID3V1_TAG_SIZE } as u64;
+            tags.push(DetectedTag {
+                tag_type: TagType::Id3v1,
+                version: TagVersion::Id3v1 { revision },
+                offset: file_size - size,
+                size,
+            });
+        }
+    }
+
+    tags.sort_by_key(|tag| tag.offset);
+    Ok(TagPresence { tags })
+}
+
+/// Revision of an ID3v1 tag, given its 128 bytes
+pub(crate) fn id3v1_revision(tag: &[u8]) -> u8 {
+    // ID3v1.1 ends the comment early with a zero byte followed by the track
+    if tag[125] == 0 && tag[126] != 0 { 1 } else { 0 }
 }
 
+/// How `TagReader` resolves an entry stored in more than one tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergePolicy {
+    /// First tag with a value, in the reader's strategy order (ID3v2, ID3v1,
+    /// APE unless set with `TagReader::with_strategies`)
+    #[default]
+    PreferId3v2,
+    /// First tag with a value, newest format revision first: ID3v2.4, APEv2,
+    /// ID3v2.3, ID3v2.2, APEv1, ID3v1
+    PreferNewest,
+    /// The distinct values of every tag, in the reader's strategy order
+    ///
+    /// `get_meta_entry` joins them with `COLLECTED_VALUE_SEPARATOR`.
+    CollectAll,
+}
+
+/// Order in which `TagReader::new` consults the tags
+const DEFAULT_READ_ORDER: [TagType; 3] = [TagType::Id3v2, TagType::Id3v1, TagType::Ape];
+
+/// Separator between the values of different tags under `MergePolicy::CollectAll`
+pub const COLLECTED_VALUE_SEPARATOR: &str = "; ";
+
 /// Simple trait for tag readers
-pub trait TagReaderStrategy {
+///
+/// Implement it for other tag formats and add them with `TagReader::add_strategy`.
+/// Like the built-in strategies, `init` should succeed on a file without such a
+/// tag and leave `get_meta_entry` failing with `TagNotFound`; it only fails when
+/// the file cannot be read. Strategies are `Send + Sync` so that a `TagReader`
+/// holding them can be shared between threads.
+pub trait TagReaderStrategy: Send + Sync {
     /// Initialize the tag reader
     fn init(&mut self, path: &Path) -> Result<()>;
+
+    /// Initialize the tag reader from `data`, the whole contents of the file at `path`
+    ///
+    /// By default the file is read again with `init`.
+    fn init_from_bytes(&mut self, path: &Path, _data: &[u8]) -> Result<()> {
+        self.init(path)
+    }
+
+    /// Initialize the tag reader from `file`, the file at `path` opened once for all strategies
+    ///
+    /// The position of `file` is unspecified. By default the file is opened again with `init`.
+    fn init_from_file(&mut self, path: &Path, _file: &mut File) -> Result<()> {
+        self.init(path)
+    }
         
     /// Get a meta entry from the tag
     fn get_meta_entry(&self, path: &Path, entry: &MetaEntry) -> Result<String>;
+
+    /// Get all values of a meta entry; formats without multi-value support return one value
+    fn get_meta_entry_values(&self, path: &Path, entry: &MetaEntry) -> Result<Vec<String>> {
+        self.get_meta_entry(path, entry).map(|value| vec![value])
+    }
+
+    /// Get the recording date; by default parsed from the year entry
+    fn get_date(&self, path: &Path) -> Result<RecordingDate> {
+        self.get_meta_entry(path, &MetaEntry::Year)?.parse()
+    }
     
     /// Get the tag type
     fn tag_type(&self) -> TagType;
 }
 
 /// Simple trait for tag writers
+///
+/// Implement it for other tag formats and add them with `TagWriter::add_strategy`.
 pub trait TagWriterStrategy {
     /// Initialize the tag writer
     fn init(&mut self, path: &Path) -> Result<()>;
     
     /// Set a meta entry in the tag
     fn set_meta_entry(&mut self, entry: &MetaEntry, value: &str) -> Result<()>;
+
+    /// Remove a meta entry from the tag; formats without removal write an empty value
+    fn remove_meta_entry(&mut self, entry: &MetaEntry) -> Result<()> {
+        self.set_meta_entry(entry, "")
+    }
+
+    /// Set the recording date; by default written as a timestamp to the year entry
+    fn set_date(&mut self, date: &RecordingDate) -> Result<()> {
+        self.set_meta_entry(&MetaEntry::Year, &date.to_string())
+    }
     
     /// Save changes to the tag
     fn save(&mut self) -> Result<()>;
+
+    /// Keep the tag when its last entry is removed; ignored by formats that
+    /// never remove a tag on save
+    fn set_keep_empty_tag(&mut self, _keep: bool) {}
     
     /// Get the tag type
     fn tag_type(&self) -> TagType;
@@ -49,51 +253,357 @@ struct ReaderStrategy {
 struct WriterStrategy {
     selected: Box<dyn TagWriterStrategy>,
     initialized: bool,
+    /// Whether entries were set since the last save
+    modified: bool,
 }
 
 /// Main tag reader class that uses the strategy pattern
+///
+/// A reader is `Send + Sync`: its tags are parsed when it is built and only
+/// read afterwards, so one reader can serve several threads behind an `Arc`.
 pub struct TagReader {
     path: PathBuf,
 
     //pair of strategy and initialized flag
-    strategies: Vec<ReaderStrategy>
+    strategies: Vec<ReaderStrategy>,
+
+    /// Per-reader callback for recoverable parse anomalies
+    anomaly_hook: Option<AnomalyHook>,
+
+    merge_policy: MergePolicy,
+    /// Indices into `strategies` in the order the merge policy consults them
+    order: Vec<usize>,
 }
 
 impl TagReader {
     /// Create a new tag reader for the given path
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let path = path.as_ref().to_path_buf();
+        Self::build(path.as_ref(), None, &DEFAULT_READ_ORDER, None)
+    }
+
+    /// Create a new tag reader that reads the file once through `access`
+    ///
+    /// The built-in strategies parse their tags from the contents returned by
+    /// `FileAccessStrategy::read_contents` instead of probing the file
+    /// separately; with `MappedFileAccess` (feature `mmap`) the file is mapped
+    /// into memory rather than read.
+    pub fn with_file_access<P: AsRef<Path>>(path: P, access: &dyn FileAccessStrategy) -> Result<Self> {
+        Self::build(path.as_ref(), None, &DEFAULT_READ_ORDER, Some(access))
+    }
+
+    /// Create a new tag reader that consults only the given tags, in the given order
+    ///
+    /// `TagReader::with_strategies(path, &[TagType::Ape, TagType::Id3v2, TagType::Id3v1])`
+    /// trusts APE tags over ID3 ones. Repeated types are ignored.
+    pub fn with_strategies<P: AsRef<Path>>(path: P, tag_types: &[TagType]) -> Result<Self> {
+        if tag_types.is_empty() {
+            return Err(Error::Other("At least one tag type is required".to_string()));
+        }
+        Self::build(path.as_ref(), None, tag_types, None)
+    }
+
+    /// Create a new tag reader that reports recoverable parse anomalies to `hook`
+    ///
+    /// The hook is called in addition to the global hook installed with
+    /// `telemetry::set_global_hook`.
+    pub fn with_anomaly_hook<P, F>(path: P, hook: F) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        F: Fn(&ParseAnomaly) + Send + Sync + 'static,
+    {
+        Self::build(path.as_ref(), Some(std::sync::Arc::new(hook)), &DEFAULT_READ_ORDER, None)
+    }
+
+    fn build(
+        path: &Path,
+        anomaly_hook: Option<AnomalyHook>,
+        tag_types: &[TagType],
+        access: Option<&dyn FileAccessStrategy>,
+    ) -> Result<Self> {
+        let path = path.to_path_buf();
         
         // Create file manager and validate file
         let file_manager = FileManager::with_default_strategy();
         file_manager.validate_file_path(&path)?;
         
         // Create strategies in order of preference
-        let mut strategies: Vec<ReaderStrategy> = vec![
-            ReaderStrategy { selected: Box::new(crate::id3::v2::tag::TagReader::new()), initialized: false },
-            ReaderStrategy { selected: Box::new(crate::id3::v1::tag::TagReader::new()), initialized: false },
-            ReaderStrategy { selected: Box::new(crate::ape::ApeReader::new()), initialized: false },
-        ];
-        
-        // Initialize all strategies
-        for strategy in &mut strategies {
-            let handle = strategy.selected.init(&path);
-            strategy.initialized = handle.is_ok();
+        let mut strategies: Vec<ReaderStrategy> = Vec::new();
+        for (i, &tag_type) in tag_types.iter().enumerate() {
+            if tag_types[..i].contains(&tag_type) {
+                continue;
+            }
+            let selected: Box<dyn TagReaderStrategy> = match tag_type {
+                TagType::Id3v2 => Box::new(crate::id3::v2::tag::TagReader::new()),
+                TagType::Id3v1 => Box::new(crate::id3::v1::tag::TagReader::new()),
+                TagType::Ape => Box::new(crate::ape::ApeReader::new()),
+                // Custom formats come with their own strategy, see `add_strategy`
+                TagType::Custom(_) => return Err(Error::InvalidTagType),
+            };
+            strategies.push(ReaderStrategy { selected, initialized: false });
         }
         
-        Ok(Self { path, strategies })
+        // Initialize all strategies, from the contents read through `access` if given
+        // and otherwise from a single handle shared by all of them
+        let contents = access.map(|access| access.read_contents(&path)).transpose()?;
+        let mut file = if contents.is_none() { File::open(&path).ok() } else { None };
+        telemetry::with_scope(&path, anomaly_hook.as_ref(), || {
+            for strategy in &mut strategies {
+                let handle = match (&contents, &mut file) {
+                    (Some(data), _) => strategy.selected.init_from_bytes(&path, data),
+                    (None, Some(file)) => strategy.selected.init_from_file(&path, file),
+                    (None, None) => strategy.selected.init(&path),
+                };
+                strategy.initialized = handle.is_ok();
+            }
+        });
+        
+        let order = (0..strategies.len()).collect();
+        Ok(Self { path, strategies, anomaly_hook, merge_policy: MergePolicy::default(), order })
+    }
+
+    /// Add a strategy for another tag format, consulted after the built-in ones
+    ///
+    /// The strategy is initialized with the reader's file; one that fails to
+    /// initialize is kept but never consulted, as for the built-in strategies.
+    pub fn add_strategy(&mut self, mut strategy: Box<dyn TagReaderStrategy>) {
+        let initialized = telemetry::with_scope(&self.path, self.anomaly_hook.as_ref(), || strategy.init(&self.path).is_ok());
+        self.strategies.push(ReaderStrategy { selected: strategy, initialized });
+        self.set_merge_policy(self.merge_policy);
+    }
+
+    /// Choose how entries stored in more than one tag are resolved
+    pub fn set_merge_policy(&mut self, policy: MergePolicy) {
+        self.merge_policy = policy;
+        self.order = (0..self.strategies.len()).collect();
+        if policy == MergePolicy::PreferNewest {
+            let presence = detect_tags(&self.path).unwrap_or_default();
+            let strategies = &self.strategies;
+            self.order.sort_by_key(|&i| std::cmp::Reverse(format_revision(&presence, strategies[i].selected.tag_type())));
+        }
+    }
+
+    /// The merge policy in effect
+    pub fn merge_policy(&self) -> MergePolicy {
+        self.merge_policy
+    }
+
+    /// Initialized strategies in the order of the merge policy
+    fn ordered_strategies(&self) -> impl Iterator<Item = &ReaderStrategy> {
+        self.order.iter().map(|&i| &self.strategies[i]).filter(|strategy| strategy.initialized)
     }
     
-    /// Get a meta entry from the tag
-    pub fn get_meta_entry(&self, entry: &MetaEntry) -> Result<String> {
-        for strategy in &self.strategies {
-            if strategy.initialized {
-                if let Ok(value) = strategy.selected.get_meta_entry(&self.path, entry) {
-                    return Ok(value);
+    /// Get a meta entry from the tag, `None` when no tag holds it
+    ///
+    /// Errors other than a missing tag or entry, such as I/O failures, are returned.
+    pub fn get_meta_entry(&self, entry: &MetaEntry) -> Result<Option<String>> {
+        if self.merge_policy == MergePolicy::CollectAll {
+            return self
+                .get_meta_entry_values(entry)
+                .map(|values| values.map(|values| values.join(COLLECTED_VALUE_SEPARATOR)));
+        }
+        telemetry::with_scope(&self.path, self.anomaly_hook.as_ref(), || {
+            for strategy in self.ordered_strategies() {
+                match strategy.selected.get_meta_entry(&self.path, entry) {
+                    Ok(value) => return Ok(Some(value)),
+                    Err(e) if is_missing(&e) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(None)
+        })
+    }
+
+    /// Get all values of a meta entry, e.g. each artist of a multi-value ID3v2.4 TPE1 frame
+    ///
+    /// Under `MergePolicy::CollectAll` the distinct values of every tag are returned.
+    pub fn get_meta_entry_values(&self, entry: &MetaEntry) -> Result<Option<Vec<String>>> {
+        telemetry::with_scope(&self.path, self.anomaly_hook.as_ref(), || {
+            let mut collected: Vec<String> = Vec::new();
+            for strategy in self.ordered_strategies() {
+                let values = match strategy.selected.get_meta_entry_values(&self.path, entry) {
+                    Ok(values) => values,
+                    Err(e) if is_missing(&e) => continue,
+                    Err(e) => return Err(e),
+                };
+                if self.merge_policy != MergePolicy::CollectAll {
+                    return Ok(Some(values));
+                }
+                for value in values {
+                    if !value.is_empty() && !collected.contains(&value) {
+                        collected.push(value);
+                    }
+                }
+            }
+            Ok(Some(collected).filter(|collected| !collected.is_empty()))
+        })
+    }
+
+    /// Track number, without the total of a "3/12" value
+    ///
+    /// Fails when the value is not a position.
+    pub fn get_track(&self) -> Result<Option<u32>> {
+        Ok(self.get_track_position()?.map(|position| position.number))
+    }
+
+    /// Track number and total
+    ///
+    /// Fails when the value is not a position.
+    pub fn get_track_position(&self) -> Result<Option<Position>> {
+        self.get_parsed(&MetaEntry::Track, |value| value.parse().ok())
+    }
+
+    /// Disc number and total
+    ///
+    /// Fails when the value is not a position.
+    pub fn get_disc_position(&self) -> Result<Option<Position>> {
+        self.get_parsed(&MetaEntry::PartOfSet, |value| value.parse().ok())
+    }
+
+    /// Year, also taken from the start of a timestamp such as "2004-05-17"
+    ///
+    /// Fails when the value does not start with a four-digit year.
+    pub fn get_year(&self) -> Result<Option<u16>> {
+        self.get_parsed(&MetaEntry::Year, |value| crate::metadata::parse_year(value).and_then(|year| year.try_into().ok()))
+    }
+
+    /// Recording date from the first tag holding one
+    ///
+    /// ID3v2.4 TDRC and ID3v2.3 TYER/TDAT/TIME frames are both understood;
+    /// other tags store the date in their year entry.
+    pub fn get_date(&self) -> Result<Option<RecordingDate>> {
+        telemetry::with_scope(&self.path, self.anomaly_hook.as_ref(), || {
+            for strategy in self.ordered_strategies() {
+                match strategy.selected.get_date(&self.path) {
+                    Ok(date) => return Ok(Some(date)),
+                    // A value that does not parse as a date
+                    Err(e @ Error::Other(_)) => return Err(e),
+                    Err(_) => continue,
+                }
+            }
+            Ok(None)
+        })
+    }
+
+    /// Tempo in beats per minute, rounded when stored with a fraction
+    ///
+    /// Fails when the value is not a number.
+    pub fn get_bpm(&self) -> Result<Option<u16>> {
+        self.get_parsed(&MetaEntry::BeatsPerMinute, |value| {
+            let bpm: f64 = value.trim().parse().ok()?;
+            (0.0..=u16::MAX as f64).contains(&bpm).then(|| bpm.round() as u16)
+        })
+    }
+
+    /// Whether the file is part of a compilation, false when the flag is absent
+    ///
+    /// Fails when the flag is not "1", "0", "true" or "false".
+    pub fn is_compilation(&self) -> Result<bool> {
+        let flag = self.get_parsed(&MetaEntry::Compilation, |value| match value.trim().to_ascii_lowercase().as_str() {
+            "1" | "true" => Some(true),
+            "0" | "false" => Some(false),
+            _ => None,
+        })?;
+        Ok(flag.unwrap_or(false))
+    }
+
+    /// Musical key, such as "Am" or the Camelot "8A"
+    pub fn get_initial_key(&self) -> Result<Option<String>> {
+        Ok(self.get_meta_entry(&MetaEntry::InitialKey)?.filter(|key| !key.trim().is_empty()))
+    }
+
+    /// International Standard Recording Code, as 12 uppercase characters
+    ///
+    /// Fails when the value is not an ISRC.
+    pub fn get_isrc(&self) -> Result<Option<String>> {
+        self.get_parsed(&MetaEntry::Isrc, |value| normalize_isrc(value).ok())
+    }
+
+    /// Parse an entry with `parse`; empty values count as absent
+    fn get_parsed<T>(&self, entry: &MetaEntry, parse: impl Fn(&str) -> Option<T>) -> Result<Option<T>> {
+        match self.get_meta_entry(entry)? {
+            Some(value) if !value.trim().is_empty() => parse(&value)
+                .map(Some)
+                .ok_or_else(|| Error::Other(format!("Invalid {} value: {:?}", entry, value))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Get a meta entry from the tag of the given type only, `None` when the tag lacks it
+    ///
+    /// Fails with `TagNotFound` when the file has no such tag.
+    pub fn get_meta_entry_from(&self, tag_type: TagType, entry: &MetaEntry) -> Result<Option<String>> {
+        let strategy = self.strategy_for(tag_type)?;
+        telemetry::with_scope(&self.path, self.anomaly_hook.as_ref(), || {
+            match strategy.selected.get_meta_entry(&self.path, entry) {
+                Ok(value) => Ok(Some(value)),
+                Err(Error::EntryNotFound) | Err(Error::UnsupportedMetaEntry(_)) => Ok(None),
+                Err(e) => Err(e),
+            }
+        })
+    }
+
+    /// Get all standard meta entries from the tag of the given type only
+    ///
+    /// Fails with `TagNotFound` when the file has no such tag.
+    pub fn get_all_from(&self, tag_type: TagType) -> Result<HashMap<MetaEntry, String>> {
+        let strategy = self.strategy_for(tag_type)?;
+        telemetry::with_scope(&self.path, self.anomaly_hook.as_ref(), || {
+            let mut entries = HashMap::new();
+            for entry in crate::meta_entry::all_standard_entries() {
+                match strategy.selected.get_meta_entry(&self.path, &entry) {
+                    Ok(value) => {
+                        entries.insert(entry, value);
+                    }
+                    Err(Error::TagNotFound) => return Err(Error::TagNotFound),
+                    Err(_) => {}
                 }
             }
+            Ok(entries)
+        })
+    }
+
+    fn strategy_for(&self, tag_type: TagType) -> Result<&ReaderStrategy> {
+        self.strategies
+            .iter()
+            .find(|strategy| strategy.initialized && strategy.selected.tag_type() == tag_type)
+            .ok_or(Error::TagNotFound)
+    }
+
+    /// The value of an entry in each tag that has one, in the order of the merge policy
+    pub fn get_meta_entry_by_tag(&self, entry: &MetaEntry) -> Vec<(TagType, String)> {
+        telemetry::with_scope(&self.path, self.anomaly_hook.as_ref(), || {
+            self.ordered_strategies()
+                .filter_map(|strategy| {
+                    let value = strategy.selected.get_meta_entry(&self.path, entry).ok()?;
+                    Some((strategy.selected.tag_type(), value))
+                })
+                .collect()
+        })
+    }
+
+    /// Whether the tags hold different non-empty values for an entry
+    pub fn has_conflict(&self, entry: &MetaEntry) -> bool {
+        let values = self.get_meta_entry_by_tag(entry);
+        let mut values = values.iter().map(|(_, value)| value.trim()).filter(|value| !value.is_empty());
+        match values.next() {
+            Some(first) => values.any(|value| value != first),
+            None => false,
         }
-        Err(Error::EntryNotFound)
+    }
+
+    /// Standard entries whose values differ between the tags
+    pub fn conflicting_entries(&self) -> Vec<MetaEntry> {
+        crate::meta_entry::all_standard_entries()
+            .into_iter()
+            .filter(|entry| self.has_conflict(entry))
+            .collect()
+    }
+
+    /// Get every embedded picture (ID3v2 APIC frames and APE cover art items)
+    pub fn pictures(&self) -> Result<Vec<Picture>> {
+        telemetry::with_scope(&self.path, self.anomaly_hook.as_ref(), || {
+            artwork::extract_pictures(&self.path)
+        })
     }
       
     /// Get all meta entries from the tag
@@ -101,7 +611,7 @@ impl TagReader {
         let mut entries = HashMap::new();
         
         for entry in crate::meta_entry::all_standard_entries() {
-            if let Ok(value) = self.get_meta_entry(&entry) {
+            if let Ok(Some(value)) = self.get_meta_entry(&entry) {
                 entries.insert(entry, value);
             }
         }
@@ -110,61 +620,506 @@ impl TagReader {
     }
 }
 
+/// Whether a strategy error only means that its tag lacks the entry
+fn is_missing(error: &Error) -> bool {
+    matches!(error, Error::EntryNotFound | Error::TagNotFound | Error::UnsupportedMetaEntry(_))
+}
+
+/// Rank of the tag format revision in the file, higher is newer
+fn format_revision(presence: &TagPresence, tag_type: TagType) -> u8 {
+    use crate::id3::v2::version::Version;
+    match presence.get(tag_type).map(|tag| tag.version) {
+        Some(TagVersion::Id3v2(Version::V4)) => 5,
+        Some(TagVersion::Ape(version)) if version >= 2000 => 4,
+        Some(TagVersion::Id3v2(Version::V3)) => 3,
+        Some(TagVersion::Id3v2(Version::V2)) => 2,
+        Some(TagVersion::Ape(_)) => 1,
+        _ => 0,
+    }
+}
+
+/// What a dry-run change does to an entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Replaced,
+    Removed,
+}
+
+/// One entry change collected by a dry-run `TagWriter`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedChange {
+    pub tag_type: TagType,
+    pub entry: MetaEntry,
+    pub kind: ChangeKind,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+/// Changes a dry-run `TagWriter` would make
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeSet {
+    pub changes: Vec<PlannedChange>,
+    /// Estimated number of bytes written when the changes are applied
+    ///
+    /// An ID3v2 tag that still fits its padding is rewritten in place; otherwise,
+    /// as for APE tags, the whole file is rewritten.
+    pub bytes_to_rewrite: u64,
+}
+
+impl ChangeSet {
+    /// Whether applying the changes would leave the file as it is
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
 /// Main tag writer class that uses the strategy pattern
 pub struct TagWriter {
+    path: PathBuf,
     strategies: Vec<WriterStrategy>,
     preferred_tag_type: TagType,
+    /// Whether to copy the file to a `.bak` file before the first modification
+    backup: bool,
+    backed_up: bool,
+    /// Whether changes are only collected instead of written
+    dry_run: bool,
+    planned: Vec<PlannedChange>,
+    /// Whether `remove_all_meta_entries` leaves an empty tag behind
+    keep_empty_tags: bool,
+    /// Whether entries written to another tag are mirrored into the ID3v1 tag
+    sync_id3v1: bool,
+    /// Whether another tag is written when the preferred one is unavailable
+    fallback: bool,
 }
 
-impl TagWriter {
-    /// Create a new tag writer for the given path
-    pub fn new<P: AsRef<Path>>(path: P, preferred_tag_type: TagType) -> Result<Self> {
-        let path = path.as_ref().to_path_buf();
-        
+/// Builder for a `TagWriter` with non-default settings
+///
+/// ```no_run
+/// use mp3tags_r::{TagType, TagWriter};
+/// use mp3tags_r::id3::v2::encoding::TextEncoding;
+/// use mp3tags_r::id3::v2::version::Version;
+///
+/// let writer = TagWriter::builder("song.mp3")
+///     .tag_type(TagType::Id3v2)
+///     .version(Version::V4)
+///     .encoding(TextEncoding::Utf16)
+///     .padding(4096)
+///     .build()?;
+/// # Ok::<(), mp3tags_r::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct TagWriterBuilder {
+    path: PathBuf,
+    tag_type: TagType,
+    version: Option<Version>,
+    encoding: Option<TextEncoding>,
+    padding: Option<usize>,
+    deferred: bool,
+    sync_id3v1: bool,
+    fallback: bool,
+    sort_ape_items: bool,
+    preserve: PreserveOptions,
+}
+
+impl TagWriterBuilder {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            tag_type: TagType::Id3v2,
+            version: None,
+            encoding: None,
+            padding: None,
+            deferred: false,
+            sync_id3v1: false,
+            fallback: true,
+            sort_ape_items: false,
+            preserve: PreserveOptions::default(),
+        }
+    }
+
+    /// Tag written by `set_meta_entry` (ID3v2 by default)
+    pub fn tag_type(mut self, tag_type: TagType) -> Self {
+        self.tag_type = tag_type;
+        self
+    }
+
+    /// Version of newly created ID3v2 tags (ID3v2.3 by default)
+    pub fn version(mut self, version: Version) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Encoding of ID3v2 text frames, instead of one picked per value
+    pub fn encoding(mut self, encoding: TextEncoding) -> Self {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Padding of rewritten ID3v2 tags
+    pub fn padding(mut self, padding: usize) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+
+    /// Write ID3v2 changes once on `save` instead of on every change
+    pub fn deferred(mut self, deferred: bool) -> Self {
+        self.deferred = deferred;
+        self
+    }
+
+    /// Mirror entries into the ID3v1 tag, which is written on `save`
+    pub fn sync_id3v1(mut self, sync: bool) -> Self {
+        self.sync_id3v1 = sync;
+        self
+    }
+
+    /// Write another tag when the preferred one cannot be initialized (enabled by default)
+    pub fn fallback(mut self, fallback: bool) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Write APE items sorted by size instead of in their existing order, see `ApeWriter::set_sort_items`
+    pub fn sort_ape_items(mut self, sort: bool) -> Self {
+        self.sort_ape_items = sort;
+        self
+    }
+
+    /// Attributes of the file kept when a tag is written, for every tag type
+    pub fn preserve(mut self, preserve: PreserveOptions) -> Self {
+        self.preserve = preserve;
+        self
+    }
+
+    /// Create the writer, failing on an invalid path or an unsupported version
+    pub fn build(self) -> Result<TagWriter> {
+        let path = self.path;
+
         // Create file manager and validate file
         let file_manager = FileManager::with_default_strategy();
         file_manager.validate_file_path(&path)?;
-        
+
+        let mut id3v2 = crate::id3::v2::tag::TagWriter::new();
+        if let Some(version) = self.version {
+            id3v2.set_version(version)?;
+        }
+        if let Some(encoding) = self.encoding {
+            id3v2.set_text_encoding(Some(encoding));
+        }
+        if let Some(padding) = self.padding {
+            id3v2.set_padding(padding);
+        }
+        id3v2.set_deferred(self.deferred);
+        id3v2.set_preserve_options(self.preserve);
+        let mut id3v1 = crate::id3::v1::tag::TagWriter::new();
+        id3v1.set_preserve_options(self.preserve);
+        let mut ape = crate::ape::ApeWriter::new();
+        ape.set_sort_items(self.sort_ape_items);
+        ape.set_preserve_options(self.preserve);
+
         // Create strategies in order of preference
         let mut strategies: Vec<WriterStrategy> = vec![
-            WriterStrategy { selected: Box::new(crate::id3::v2::tag::TagWriter::new()), initialized: false },
-            WriterStrategy { selected: Box::new(crate::id3::v1::tag::TagWriter::new()), initialized: false },
-            WriterStrategy { selected: Box::new(crate::ape::ApeWriter::new()), initialized: false },
+            WriterStrategy { selected: Box::new(id3v2), initialized: false, modified: false },
+            WriterStrategy { selected: Box::new(id3v1), initialized: false, modified: false },
+            WriterStrategy { selected: Box::new(ape), initialized: false, modified: false },
         ];
-        
+
         // Initialize all strategies
         for strategy in &mut strategies {
             let handle = strategy.selected.init(&path);
             strategy.initialized = handle.is_ok();
         }
-        
-        Ok(Self {  
+
+        Ok(TagWriter {
+            path,
             strategies,
-            preferred_tag_type,
+            preferred_tag_type: self.tag_type,
+            backup: false,
+            backed_up: false,
+            dry_run: false,
+            planned: Vec::new(),
+            keep_empty_tags: false,
+            sync_id3v1: self.sync_id3v1,
+            fallback: self.fallback,
         })
     }
+}
+
+impl TagWriter {
+    /// Create a new tag writer for the given path
+    pub fn new<P: AsRef<Path>>(path: P, preferred_tag_type: TagType) -> Result<Self> {
+        Self::builder(path).tag_type(preferred_tag_type).build()
+    }
+
+    /// Add a strategy for another tag format
+    ///
+    /// It is written when its tag type is the preferred one, or as a fallback
+    /// after the built-in strategies, and saved by `save`.
+    pub fn add_strategy(&mut self, mut strategy: Box<dyn TagWriterStrategy>) {
+        let initialized = strategy.init(&self.path).is_ok();
+        self.strategies.push(WriterStrategy { selected: strategy, initialized, modified: false });
+    }
+
+    /// Start building a writer with non-default settings
+    pub fn builder<P: AsRef<Path>>(path: P) -> TagWriterBuilder {
+        TagWriterBuilder::new(path.as_ref().to_path_buf())
+    }
+
+    /// Collect changes instead of writing them, see `change_set`
+    ///
+    /// In dry-run mode `set_meta_entry` and `save` never touch the file.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    /// The changes collected in dry-run mode, with an estimate of the bytes to rewrite
+    pub fn change_set(&self) -> Result<ChangeSet> {
+        let mut bytes_to_rewrite = 0;
+        for tag_type in self.strategies.iter().map(|s| s.selected.tag_type()) {
+            let changes: Vec<&PlannedChange> = self.planned.iter().filter(|change| change.tag_type == tag_type).collect();
+            if !changes.is_empty() {
+                bytes_to_rewrite += estimate_rewrite(&self.path, tag_type, &changes)?;
+            }
+        }
+        Ok(ChangeSet {
+            changes: self.planned.clone(),
+            bytes_to_rewrite,
+        })
+    }
+
+    /// Record the change `set_meta_entry` would make, merging repeated changes of an entry
+    fn plan_change(&mut self, entry: &MetaEntry, value: &str) -> Result<()> {
+        let strategy = self
+            .strategies
+            .iter()
+            .find(|s| s.initialized && s.selected.tag_type() == self.preferred_tag_type)
+            .or_else(|| self.strategies.iter().find(|s| s.initialized))
+            .ok_or_else(|| Error::Other("Failed to set meta entry with any available strategy".to_string()))?;
+        let tag_type = strategy.selected.tag_type();
+
+        let position = self.planned.iter().position(|change| change.tag_type == tag_type && change.entry == *entry);
+        let old_value = match position {
+            Some(index) => self.planned.remove(index).old_value,
+            None => TagReader::new(&self.path)?
+                .get_meta_entry_from(tag_type, entry)
+                .ok()
+                .flatten()
+                .filter(|value| !value.is_empty()),
+        };
+        let new_value = Some(value.to_string()).filter(|value| !value.is_empty());
+        let kind = match (&old_value, &new_value) {
+            (old, new) if old == new => return Ok(()),
+            (None, _) => ChangeKind::Added,
+            (Some(_), None) => ChangeKind::Removed,
+            (Some(_), Some(_)) => ChangeKind::Replaced,
+        };
+        self.planned.push(PlannedChange {
+            tag_type,
+            entry: entry.clone(),
+            kind,
+            old_value,
+            new_value,
+        });
+        Ok(())
+    }
+
+    /// Write the entries set on tags that are not written immediately (ID3v1 and APE)
+    ///
+    /// Does nothing in dry-run mode.
+    pub fn save(&mut self) -> Result<()> {
+        if self.dry_run {
+            return Ok(());
+        }
+        for strategy in self.strategies.iter_mut().filter(|s| s.initialized && s.modified) {
+            strategy.selected.save()?;
+            strategy.modified = false;
+        }
+        Ok(())
+    }
+
+    /// Copy the file to `<file>.bak` before it is first modified by this writer
+    ///
+    /// The backup can be restored with `backup::rollback`.
+    pub fn set_backup(&mut self, backup: bool) {
+        self.backup = backup;
+    }
+
+    /// Keep an empty tag when all entries are removed instead of stripping it
+    ///
+    /// Some players expect a tag to be present once it has been written.
+    pub fn set_keep_empty_tags(&mut self, keep: bool) {
+        self.keep_empty_tags = keep;
+        for strategy in &mut self.strategies {
+            strategy.selected.set_keep_empty_tag(keep);
+        }
+    }
+
+    fn ensure_backup(&mut self) -> Result<()> {
+        if self.backup && !self.backed_up {
+            crate::backup::create_backup(&self.path)?;
+            self.backed_up = true;
+        }
+        Ok(())
+    }
     
     /// Set a meta entry in the tag
     pub fn set_meta_entry(&mut self, entry: &MetaEntry, value: &str) -> Result<()> {
-        // First, try to find and use the preferred strategy if it's initialized.
-        if let Some(strategy) = self.strategies.iter_mut().find(|s| s.initialized && 
-                s.selected.tag_type() == self.preferred_tag_type) {
-            return strategy.selected.set_meta_entry(entry, value);
+        if self.dry_run {
+            return self.plan_change(entry, value);
         }
+        self.ensure_backup()?;
+        self.apply(|strategy| strategy.set_meta_entry(entry, value), "set")
+    }
+    
+    /// Set the track number
+    ///
+    /// The total of the current value is kept.
+    pub fn set_track(&mut self, track: u32) -> Result<()> {
+        let total = self.current_position(&MetaEntry::Track)?.and_then(|position| position.total);
+        self.set_track_position(Position::new(track, total))
+    }
 
-        // If the preferred strategy is not available or fails, try any other initialized strategy.
-        for strategy in self.strategies.iter_mut().filter(|s| s.initialized) {
-            if strategy.selected.set_meta_entry(entry, value).is_ok() {
-                return Ok(());
-            }
+    /// Set the track number and total
+    pub fn set_track_position(&mut self, position: Position) -> Result<()> {
+        position.validate()?;
+        self.set_meta_entry(&MetaEntry::Track, &position.to_string())
+    }
+
+    /// Set the disc number, keeping the total of the current value
+    pub fn set_disc(&mut self, disc: u32) -> Result<()> {
+        let total = self.current_position(&MetaEntry::PartOfSet)?.and_then(|position| position.total);
+        self.set_disc_position(Position::new(disc, total))
+    }
+
+    /// Set the disc number and total
+    pub fn set_disc_position(&mut self, position: Position) -> Result<()> {
+        position.validate()?;
+        self.set_meta_entry(&MetaEntry::PartOfSet, &position.to_string())
+    }
+
+    /// The position stored in the preferred tag, including changes planned in dry-run mode
+    ///
+    /// Values that do not parse count as absent, so setters can replace them.
+    fn current_position(&self, entry: &MetaEntry) -> Result<Option<Position>> {
+        let planned = self
+            .planned
+            .iter()
+            .find(|change| change.tag_type == self.preferred_tag_type && change.entry == *entry);
+        let value = match planned {
+            Some(change) => change.new_value.clone(),
+            None => match TagReader::new(&self.path)?.get_meta_entry_from(self.preferred_tag_type, entry) {
+                Ok(value) => value,
+                Err(Error::TagNotFound) => None,
+                Err(e) => return Err(e),
+            },
+        };
+        Ok(value.and_then(|value| value.parse().ok()))
+    }
+
+    /// Set the year, which has at most four digits
+    pub fn set_year(&mut self, year: u16) -> Result<()> {
+        if year > 9999 {
+            return Err(Error::Other(format!("Invalid year: {}", year)));
         }
-        
-        Err(Error::Other("Failed to set meta entry with any available strategy".to_string()))
+        self.set_meta_entry(&MetaEntry::Year, &format!("{:04}", year))
     }
-    
+
+    /// Set the recording date
+    ///
+    /// ID3v2.4 tags store it in TDRC and ID3v2.3 tags in TYER, TDAT and TIME,
+    /// removing the frames of the other version.
+    pub fn set_date(&mut self, date: &RecordingDate) -> Result<()> {
+        if self.dry_run {
+            return self.plan_change(&MetaEntry::Year, &date.to_string());
+        }
+        self.ensure_backup()?;
+        self.apply(|strategy| strategy.set_date(date), "set")
+    }
+
+    /// Mark the file as part of a compilation, or remove the flag
+    pub fn set_compilation(&mut self, compilation: bool) -> Result<()> {
+        if compilation {
+            self.set_meta_entry(&MetaEntry::Compilation, "1")
+        } else {
+            self.remove_meta_entry(&MetaEntry::Compilation)
+        }
+    }
+
+    /// Set the tempo in beats per minute
+    pub fn set_bpm(&mut self, bpm: u16) -> Result<()> {
+        self.set_meta_entry(&MetaEntry::BeatsPerMinute, &bpm.to_string())
+    }
+
+    /// Set the musical key, in ID3v2 notation ("C#m", "Bb", "o" for off key)
+    /// or as a Camelot ("8A") or Open Key ("1m") position
+    pub fn set_initial_key(&mut self, key: &str) -> Result<()> {
+        StandardValidator.validate_format(&MetaEntry::InitialKey, key)?;
+        self.set_meta_entry(&MetaEntry::InitialKey, key)
+    }
+
+    /// Set the International Standard Recording Code
+    ///
+    /// Case and dashes are normalized, so "us-rc1-76-07839" is stored as "USRC17607839".
+    pub fn set_isrc(&mut self, isrc: &str) -> Result<()> {
+        let isrc = normalize_isrc(isrc)?;
+        self.set_meta_entry(&MetaEntry::Isrc, &isrc)
+    }
+
     /// Remove a meta entry from the tag
+    ///
+    /// ID3v2 frames and APE items are deleted; ID3v1 fields are blanked, since
+    /// the format has no notion of an absent field. Only the ID3v1 writer
+    /// itself reports blanked fields, see `id3::v1::tag::TagWriter::report`.
     pub fn remove_meta_entry(&mut self, entry: &MetaEntry) -> Result<()> {
-        self.set_meta_entry(entry, "")
+        if self.dry_run {
+            return self.plan_change(entry, "");
+        }
+        self.ensure_backup()?;
+        self.apply(|strategy| strategy.remove_meta_entry(entry), "remove")
+    }
+
+    /// Run `op` on the preferred strategy, or on the first other one that accepts
+    /// it when fallback is enabled, then on the ID3v1 strategy when syncing
+    ///
+    /// A sync failure is returned as `Error::PartialWrite`, since the first tag already holds the change.
+    fn apply<F>(&mut self, mut op: F, action: &str) -> Result<()>
+    where
+        F: FnMut(&mut dyn TagWriterStrategy) -> Result<()>,
+    {
+        let preferred = self.preferred_tag_type;
+        let mut written = None;
+
+        // First, try to find and use the preferred strategy if it's initialized.
+        if let Some(strategy) = self.strategies.iter_mut().find(|s| s.initialized && s.selected.tag_type() == preferred) {
+            op(strategy.selected.as_mut())?;
+            strategy.modified = true;
+            written = Some(preferred);
+        } else if self.fallback {
+            // If the preferred strategy is not available, try any other initialized strategy.
+            for strategy in self.strategies.iter_mut().filter(|s| s.initialized) {
+                if op(strategy.selected.as_mut()).is_ok() {
+                    strategy.modified = true;
+                    written = Some(strategy.selected.tag_type());
+                    break;
+                }
+            }
+        }
+        let Some(written) = written else {
+            return Err(Error::Other(format!("Failed to {} meta entry with any available strategy", action)));
+        };
+
+        if self.sync_id3v1 && written != TagType::Id3v1 {
+            if let Some(strategy) = self.strategies.iter_mut().find(|s| s.initialized && s.selected.tag_type() == TagType::Id3v1) {
+                op(strategy.selected.as_mut()).map_err(|e| Error::PartialWrite {
+                    written,
+                    failed: TagType::Id3v1,
+                    source: Box::new(e),
+                })?;
+                strategy.modified = true;
+            }
+        }
+        Ok(())
     }
     
     /// Remove multiple meta entries from the tag
@@ -176,51 +1131,109 @@ impl TagWriter {
     }
     
     /// Remove all meta entries from the tag
+    ///
+    /// The preferred tag, and the ID3v1 tag when syncing, is stripped from the
+    /// file, reclaiming its space, unless `set_keep_empty_tags` was enabled.
     pub fn remove_all_meta_entries(&mut self) -> Result<()> {
-        let all_entries = crate::meta_entry::all_standard_entries();
-        self.remove_meta_entries(&all_entries)
+        if self.dry_run || self.keep_empty_tags {
+            let all_entries = crate::meta_entry::all_standard_entries();
+            return self.remove_meta_entries(&all_entries);
+        }
+        self.ensure_backup()?;
+
+        let mut tag_types = vec![self.preferred_tag_type];
+        if self.sync_id3v1 && self.preferred_tag_type != TagType::Id3v1 {
+            tag_types.push(TagType::Id3v1);
+        }
+        crate::strip::strip_tags(&self.path, &tag_types)?;
+        // Pending changes of the stripped tags are dropped with them
+        for strategy in self.strategies.iter_mut().filter(|s| tag_types.contains(&s.selected.tag_type())) {
+            strategy.initialized = strategy.selected.init(&self.path).is_ok();
+            strategy.modified = false;
+        }
+        Ok(())
+    }
+}
+
+/// Estimated bytes written when the changes of one tag are applied
+fn estimate_rewrite(path: &Path, tag_type: TagType, changes: &[&PlannedChange]) -> Result<u64> {
+    let file_size = std::fs::metadata(path)?.len();
+    let presence = detect_tags(path)?;
+    match tag_type {
+        // Written in place, or appended
+        TagType::Id3v1 => Ok(presence
+            .get(TagType::Id3v1)
+            .map(|tag| tag.size)
+            .unwrap_or(crate::id3::constants::ID3V1_TAG_SIZE as u64)),
+        // The APE writer always rewrites the file, and custom formats are assumed to
+        TagType::Ape | TagType::Custom(_) => Ok(file_size),
+        TagType::Id3v2 => {
+            // Frame header plus text encoding byte
+            const FRAME_OVERHEAD: i64 = 11;
+            let growth: i64 = changes
+                .iter()
+                .map(|change| {
+                    let old = change.old_value.as_ref().map(|value| value.len() as i64 + FRAME_OVERHEAD).unwrap_or(0);
+                    let new = change.new_value.as_ref().map(|value| value.len() as i64 + FRAME_OVERHEAD).unwrap_or(0);
+                    new - old
+                })
+                .sum();
+            if let Some(existing) = presence.get(TagType::Id3v2) {
+                let mut writer = crate::id3::v2::tag::TagWriter::new();
+                writer.init(path)?;
+                let used = writer.compacted_tag()?.map(|tag| tag.len() as i64).unwrap_or(0);
+                if used + growth <= existing.size as i64 {
+                    return Ok(existing.size);
+                }
+            }
+            let padding = crate::id3::constants::ID3V2_PADDING_SIZE as i64;
+            Ok((file_size as i64 + growth + padding).max(0) as u64)
+        }
     }
 }
+
 // Convenience functions
+//
+// Each returns `Ok(None)` when the file has no value for the entry.
 
 /// Get the title of an MP3 file
-pub fn get_title<P: AsRef<Path>>(path: P) -> Result<String> {
+pub fn get_title<P: AsRef<Path>>(path: P) -> Result<Option<String>> {
     let reader = TagReader::new(path)?;
     reader.get_meta_entry(&MetaEntry::Title)
 }
 
 /// Get the artist of an MP3 file
-pub fn get_artist<P: AsRef<Path>>(path: P) -> Result<String> {
+pub fn get_artist<P: AsRef<Path>>(path: P) -> Result<Option<String>> {
     let reader = TagReader::new(path)?;
     reader.get_meta_entry(&MetaEntry::Artist)
 }
 
 /// Get the album of an MP3 file
-pub fn get_album<P: AsRef<Path>>(path: P) -> Result<String> {
+pub fn get_album<P: AsRef<Path>>(path: P) -> Result<Option<String>> {
     let reader = TagReader::new(path)?;
     reader.get_meta_entry(&MetaEntry::Album)
 }
 
 /// Get the year of an MP3 file
-pub fn get_year<P: AsRef<Path>>(path: P) -> Result<String> {
+pub fn get_year<P: AsRef<Path>>(path: P) -> Result<Option<String>> {
     let reader = TagReader::new(path)?;
     reader.get_meta_entry(&MetaEntry::Year)
 }
 
 /// Get the genre of an MP3 file
-pub fn get_genre<P: AsRef<Path>>(path: P) -> Result<String> {
+pub fn get_genre<P: AsRef<Path>>(path: P) -> Result<Option<String>> {
     let reader = TagReader::new(path)?;
     reader.get_meta_entry(&MetaEntry::Genre)
 }
 
 /// Get the comment of an MP3 file
-pub fn get_comment<P: AsRef<Path>>(path: P) -> Result<String> {
+pub fn get_comment<P: AsRef<Path>>(path: P) -> Result<Option<String>> {
     let reader = TagReader::new(path)?;
     reader.get_meta_entry(&MetaEntry::Comment)
 }
 
 /// Get the composer of an MP3 file
-pub fn get_composer<P: AsRef<Path>>(path: P) -> Result<String> {
+pub fn get_composer<P: AsRef<Path>>(path: P) -> Result<Option<String>> {
     let reader = TagReader::new(path)?;
     reader.get_meta_entry(&MetaEntry::Composer)
 }
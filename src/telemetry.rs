@@ -0,0 +1,132 @@
+//! Parse anomaly reporting.
+//!
+//! Parsers report recoverable problems (bad frame sizes, invalid text, oversized
+//! items) here in addition to logging them. Applications can install a global hook,
+//! or attach one to a single `TagReader`, to aggregate corruption statistics across
+//! a library.
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use crate::tag::TagType;
+
+/// Kind of recoverable anomaly encountered while parsing a tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnomalyKind {
+    /// A frame or item declares a size that does not fit inside the tag
+    InvalidFrameSize,
+    /// A frame without any payload
+    EmptyFrame,
+    /// A frame ID that is not known for the tag version
+    UnsupportedFrame,
+    /// Text that is not valid in its declared encoding
+    InvalidText,
+    /// An item larger than the allowed limit
+    OversizedItem,
+    /// An item key that is too long, unterminated or not valid UTF-8
+    InvalidKey,
+    /// A compressed frame whose payload could not be inflated
+    UndecodableFrame,
+}
+
+/// Structured details about a single parse anomaly
+#[derive(Debug, Clone)]
+pub struct ParseAnomaly {
+    /// What went wrong
+    pub kind: AnomalyKind,
+    /// Tag type being parsed when the anomaly was found
+    pub tag_type: TagType,
+    /// File being parsed, when known
+    pub path: Option<PathBuf>,
+    /// Byte offset of the anomaly, relative to the start of the tag data
+    pub offset: Option<u64>,
+    /// Human readable description
+    pub message: String,
+}
+
+/// Callback invoked for every reported anomaly
+pub type AnomalyHook = Arc<dyn Fn(&ParseAnomaly) + Send + Sync>;
+
+static GLOBAL_HOOK: RwLock<Option<AnomalyHook>> = RwLock::new(None);
+
+struct Scope {
+    path: PathBuf,
+    hook: Option<AnomalyHook>,
+}
+
+thread_local! {
+    static SCOPES: RefCell<Vec<Scope>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Install a hook that receives anomalies from every parse in the process
+pub fn set_global_hook<F>(hook: F)
+where
+    F: Fn(&ParseAnomaly) + Send + Sync + 'static,
+{
+    let mut global = GLOBAL_HOOK.write().unwrap_or_else(|e| e.into_inner());
+    *global = Some(Arc::new(hook));
+}
+
+/// Remove the global hook
+pub fn clear_global_hook() {
+    let mut global = GLOBAL_HOOK.write().unwrap_or_else(|e| e.into_inner());
+    *global = None;
+}
+
+/// Pops the scope pushed by `with_scope`, even when the closure panics
+struct ScopeGuard;
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        SCOPES.with(|scopes| {
+            scopes.borrow_mut().pop();
+        });
+    }
+}
+
+/// Run `f` with `path` and an optional per-reader hook attached to every anomaly it reports
+pub(crate) fn with_scope<T>(path: &Path, hook: Option<&AnomalyHook>, f: impl FnOnce() -> T) -> T {
+    SCOPES.with(|scopes| {
+        scopes.borrow_mut().push(Scope {
+            path: path.to_path_buf(),
+            hook: hook.cloned(),
+        });
+    });
+    let _guard = ScopeGuard;
+    f()
+}
+
+/// Report a recoverable anomaly to the scoped and global hooks
+pub(crate) fn report(kind: AnomalyKind, tag_type: TagType, offset: Option<u64>, message: impl Into<String>) {
+    let (path, scoped_hook) = SCOPES.with(|scopes| {
+        scopes
+            .borrow()
+            .last()
+            .map(|scope| (Some(scope.path.clone()), scope.hook.clone()))
+            .unwrap_or((None, None))
+    });
+    let global_hook = GLOBAL_HOOK
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+
+    if scoped_hook.is_none() && global_hook.is_none() {
+        return;
+    }
+
+    let anomaly = ParseAnomaly {
+        kind,
+        tag_type,
+        path,
+        offset,
+        message: message.into(),
+    };
+
+    if let Some(hook) = scoped_hook {
+        hook(&anomaly);
+    }
+    if let Some(hook) = global_hook {
+        hook(&anomaly);
+    }
+}
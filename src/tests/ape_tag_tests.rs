@@ -0,0 +1,315 @@
+use crate::ape::{ApeReader, ApeTagPlacement, ApeWriter, ItemFlags, ItemValue};
+use crate::MetaEntry;
+use std::collections::HashMap;
+use tempfile::tempdir;
+
+/// Serialize APE items followed by a footer of the given version, without a header
+fn ape_tag_without_header(version: u32, items: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (key, value) in items {
+        body.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        body.extend_from_slice(&0u32.to_le_bytes());
+        body.extend_from_slice(key.as_bytes());
+        body.push(0);
+        body.extend_from_slice(value);
+    }
+    let mut footer = b"APETAGEX".to_vec();
+    footer.extend_from_slice(&version.to_le_bytes());
+    footer.extend_from_slice(&(body.len() as u32 + 32).to_le_bytes());
+    footer.extend_from_slice(&(items.len() as u32).to_le_bytes());
+    footer.extend_from_slice(&[0u8; 12]);
+    body.extend(footer);
+    body
+}
+
+#[test]
+fn test_apev1_tag_round_trip() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("apev1.mp3");
+    let mut data = vec![0xABu8; 300];
+    data.extend(ape_tag_without_header(1000, &[("Title", b"Caf\xe9"), ("Artist", b"Someone")]));
+    std::fs::write(&test_file, &data).unwrap();
+
+    let tag = ApeReader::new().read_tag(&test_file).unwrap();
+    assert!(tag.is_v1());
+    assert!(tag.header.is_none());
+    assert_eq!(tag.get_item_text("TITLE").unwrap(), "Café");
+
+    let entries = HashMap::from([(MetaEntry::Album, "Album".to_string())]);
+    ApeWriter::new().set_meta_entries(&test_file, &entries).unwrap();
+
+    let data = std::fs::read(&test_file).unwrap();
+    let footer = &data[data.len() - 32..];
+    assert_eq!(&footer[..8], b"APETAGEX");
+    assert_eq!(u32::from_le_bytes(footer[8..12].try_into().unwrap()), 1000);
+    assert_eq!(u32::from_le_bytes(footer[20..24].try_into().unwrap()), 0);
+
+    let tag = ApeReader::new().read_tag(&test_file).unwrap();
+    assert!(tag.is_v1());
+    assert_eq!(tag.get_item_text("ALBUM").unwrap(), "Album");
+    assert_eq!(tag.get_item_text("ARTIST").unwrap(), "Someone");
+}
+
+#[test]
+fn test_binary_item_round_trip() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("cover.mp3");
+    std::fs::write(&test_file, vec![0xABu8; 300]).unwrap();
+
+    let mut cover = b"cover.jpg\0".to_vec();
+    cover.extend_from_slice(&[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x80]);
+    let writer = ApeWriter::new();
+    writer.set_binary_item(&test_file, "Cover Art (Front)", &cover).unwrap();
+    writer.set_meta_entries(&test_file, &HashMap::from([(MetaEntry::Title, "Title".to_string())])).unwrap();
+
+    let reader = ApeReader::new();
+    assert_eq!(reader.get_binary_item(&test_file, "cover art (front)").unwrap(), cover);
+    assert!(reader.get_binary_item(&test_file, "Title").is_err());
+    let entries = reader.read_tag(&test_file).unwrap().get_meta_entries();
+    assert_eq!(entries, HashMap::from([(MetaEntry::Title, "Title".to_string())]));
+
+    writer.remove_item(&test_file, "Cover Art (Front)").unwrap();
+    assert!(matches!(
+        reader.get_binary_item(&test_file, "Cover Art (Front)"),
+        Err(crate::Error::EntryNotFound)
+    ));
+}
+
+#[test]
+fn test_read_only_items_need_force() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("read_only.mp3");
+    std::fs::write(&test_file, vec![0xABu8; 300]).unwrap();
+
+    let title = |value: &str| HashMap::from([(MetaEntry::Title, value.to_string())]);
+    let mut writer = ApeWriter::new();
+    writer.set_meta_entries(&test_file, &title("Locked")).unwrap();
+    let read_only = ItemFlags { read_only: true, ..Default::default() };
+    writer.set_item_flags(&test_file, "Title", read_only).unwrap();
+
+    let tag = ApeReader::new().read_tag(&test_file).unwrap();
+    assert_eq!(tag.item_flags("TITLE"), Some(read_only));
+    assert_eq!(tag.get_item_text("TITLE").unwrap(), "Locked");
+
+    assert!(matches!(
+        writer.set_meta_entries(&test_file, &title("Changed")),
+        Err(crate::Error::ReadOnlyItem(_))
+    ));
+    assert!(writer.remove_meta_entries(&test_file, &[MetaEntry::Title]).is_err());
+    // Other items stay writable
+    writer.set_meta_entries(&test_file, &HashMap::from([(MetaEntry::Album, "Album".to_string())])).unwrap();
+
+    writer.set_force(true);
+    writer.set_meta_entries(&test_file, &title("Changed")).unwrap();
+    let tag = ApeReader::new().read_tag(&test_file).unwrap();
+    assert_eq!(tag.get_item_text("TITLE").unwrap(), "Changed");
+    assert_eq!(ItemFlags::from_bits(4), ItemFlags { external: true, ..Default::default() });
+}
+
+#[test]
+fn test_item_order_preserved_unless_sorting() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("order.mp3");
+    let mut data = vec![0xABu8; 300];
+    data.extend(ape_tag_without_header(2000, &[("Comment", b"a rather long comment"), ("Year", b"2001"), ("Title", b"Mid title")]));
+    std::fs::write(&test_file, &data).unwrap();
+
+    let keys = || -> Vec<String> {
+        ApeReader::new().read_tag(&test_file).unwrap().items.iter().map(|item| item.key.clone()).collect()
+    };
+    let mut writer = ApeWriter::new();
+    writer.set_meta_entries(&test_file, &HashMap::from([(MetaEntry::Year, "2002".to_string())])).unwrap();
+    assert_eq!(keys(), ["Comment", "Year", "Title"]);
+
+    writer.set_sort_items(true);
+    writer.set_meta_entries(&test_file, &HashMap::from([(MetaEntry::Album, "An album".to_string())])).unwrap();
+    assert_eq!(keys(), ["Year", "ALBUM", "Title", "Comment"]);
+
+    // The option reaches the APE writer through the facade
+    std::fs::write(&test_file, &data).unwrap();
+    let mut writer = crate::TagWriter::builder(&test_file).tag_type(crate::TagType::Ape).sort_ape_items(true).build().unwrap();
+    writer.set_meta_entry(&MetaEntry::Album, "An album").unwrap();
+    writer.save().unwrap();
+    assert_eq!(keys(), ["Year", "ALBUM", "Title", "Comment"]);
+}
+
+#[test]
+fn test_header_first_tag_round_trip() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("start.mpc");
+    // Turn a footer-only v2 tag into a header-only one at the start of the file
+    let mut tag = ape_tag_without_header(2000, &[("Title", b"At the start")]);
+    let footer_start = tag.len() - 32;
+    let mut header = tag.split_off(footer_start);
+    header[20..24].copy_from_slice(&(0xA000_0000u32 | 0x4000_0000).to_le_bytes());
+    let size = u32::from_le_bytes(header[12..16].try_into().unwrap()) - 32;
+    header[12..16].copy_from_slice(&size.to_le_bytes());
+    let audio = vec![0xABu8; 300];
+    let mut data = header;
+    data.extend(tag);
+    data.extend_from_slice(&audio);
+    std::fs::write(&test_file, &data).unwrap();
+
+    let reader = ApeReader::new();
+    let tag = reader.read_tag(&test_file).unwrap();
+    assert_eq!(tag.placement, ApeTagPlacement::Start);
+    assert_eq!(tag.get_item_text("Title").unwrap(), "At the start");
+
+    let writer = ApeWriter::new();
+    writer.set_meta_entries(&test_file, &HashMap::from([(MetaEntry::Artist, "Artist".to_string())])).unwrap();
+    let span = reader.locate_tag(&test_file).unwrap().unwrap();
+    assert_eq!(span.placement, ApeTagPlacement::Start);
+    let data = std::fs::read(&test_file).unwrap();
+    assert_eq!(&data[span.end() as usize..], audio.as_slice());
+    let tag = reader.read_tag(&test_file).unwrap();
+    assert_eq!(tag.get_item_text("Title").unwrap(), "At the start");
+    assert_eq!(tag.get_item_text("Artist").unwrap(), "Artist");
+
+    writer.remove_tag(&test_file).unwrap();
+    assert_eq!(std::fs::read(&test_file).unwrap(), audio);
+}
+
+#[test]
+fn test_rewrite_replaces_old_tag() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("rewrite.mp3");
+    let audio = vec![0xABu8; 300];
+    let mut id3v1 = vec![0u8; 128];
+    id3v1[..3].copy_from_slice(b"TAG");
+
+    for trailer in [Vec::new(), id3v1] {
+        let mut original = audio.clone();
+        original.extend_from_slice(&trailer);
+        std::fs::write(&test_file, &original).unwrap();
+
+        let writer = ApeWriter::new();
+        for title in ["One", "Two", "Six"] {
+            writer.set_meta_entries(&test_file, &HashMap::from([(MetaEntry::Title, title.to_string())])).unwrap();
+        }
+        let span = ApeReader::new().locate_tag(&test_file).unwrap().unwrap();
+        let data = std::fs::read(&test_file).unwrap();
+        assert_eq!(span.offset as usize, audio.len());
+        assert_eq!(data.len(), audio.len() + span.len as usize + trailer.len());
+        assert_eq!(&data[span.end() as usize..], trailer.as_slice());
+        assert_eq!(ApeReader::new().read_tag(&test_file).unwrap().get_item_text("Title").unwrap(), "Six");
+
+        writer.remove_tag(&test_file).unwrap();
+        assert_eq!(std::fs::read(&test_file).unwrap(), original);
+    }
+}
+
+#[test]
+fn test_locator_items() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("locator.mp3");
+    std::fs::write(&test_file, vec![0xABu8; 300]).unwrap();
+
+    let writer = ApeWriter::new();
+    writer.set_locator_item(&test_file, "Lyrics", "https://example.com/lyrics.txt").unwrap();
+    writer.set_meta_entries(&test_file, &HashMap::from([(MetaEntry::Title, "Title".to_string())])).unwrap();
+
+    let tag = ApeReader::new().read_tag(&test_file).unwrap();
+    assert_eq!(
+        tag.get_item_value("Lyrics").unwrap(),
+        ItemValue::Locator("https://example.com/lyrics.txt".to_string())
+    );
+    assert_eq!(tag.get_item_value("Title").unwrap(), ItemValue::Text("Title".to_string()));
+    assert!(tag.item_flags("Lyrics").unwrap().external);
+    assert!(tag.get_item_text("Lyrics").is_err());
+    assert!(!tag.get_meta_entries().contains_key(&MetaEntry::Lyrics));
+}
+
+#[test]
+fn test_lazy_item_access_skips_large_values() {
+    use crate::tag::TagReaderStrategy;
+    use std::io::Read;
+
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("large.mp3");
+    let cover = vec![0x5Au8; 17 * 1024 * 1024];
+    let mut data = vec![0xABu8; 300];
+    data.extend(ape_tag_without_header(2000, &[("Cover Art (Front)", &cover), ("Title", b"Small")]));
+    std::fs::write(&test_file, &data).unwrap();
+
+    let reader = ApeReader::new();
+    // Loading the whole tag refuses the oversized value...
+    assert!(reader.read_tag(&test_file).is_err());
+    // ...but the title can still be read without touching it
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Title).unwrap(), "Small");
+
+    let index = reader.read_item_index(&test_file).unwrap();
+    assert_eq!(index.len(), 2);
+    assert_eq!(index[0].len as usize, cover.len());
+    let mut head = [0u8; 4];
+    index[0].open_value(&test_file).unwrap().read_exact(&mut head).unwrap();
+    assert_eq!(head, [0x5A; 4]);
+    assert_eq!(index[1].read_value(&test_file).unwrap(), b"Small");
+}
+
+#[test]
+fn test_items_are_bounded_by_the_tag() {
+    let many: Vec<(String, Vec<u8>)> = (0..200).map(|i| (format!("Key{}", i), format!("Value {}", i).into_bytes())).collect();
+    let items: Vec<(&str, &[u8])> = many.iter().map(|(key, value)| (key.as_str(), value.as_slice())).collect();
+    let mut data = vec![0xABu8; 300];
+    data.extend(ape_tag_without_header(2000, &items));
+    let tag = crate::ape::ApeTag::parse(&data).unwrap();
+    assert_eq!(tag.items.len(), 200);
+    assert_eq!(tag.get_item_text("Key199").unwrap(), "Value 199");
+
+    // A value running into the footer is rejected rather than read from it
+    let mut data = ape_tag_without_header(2000, &[("Title", b"Short")]);
+    data[..4].copy_from_slice(&40u32.to_le_bytes());
+    assert!(crate::ape::ApeTag::parse(&data).is_err());
+}
+
+#[test]
+fn test_item_iterator() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("items.mp3");
+    let mut data = vec![0xABu8; 300];
+    data.extend(ape_tag_without_header(2000, &[("Title", b"First"), ("Artist", b"Second")]));
+    std::fs::write(&test_file, &data).unwrap();
+
+    let items: Vec<_> = ApeReader::new().iter_items(&test_file).unwrap().collect::<crate::Result<_>>().unwrap();
+    assert_eq!(items.iter().map(|item| (item.key.as_str(), item.value.as_slice())).collect::<Vec<_>>(), [("Title", &b"First"[..]), ("Artist", &b"Second"[..])]);
+
+    // An oversized value ends the iteration with an error instead of being loaded
+    let mut data = ape_tag_without_header(2000, &[("Title", b"First"), ("Cover Art (Front)", b"Art")]);
+    let second = 8 + "Title".len() + 1 + 5;
+    data[second..second + 4].copy_from_slice(&(17u32 * 1024 * 1024).to_le_bytes());
+    std::fs::write(&test_file, &data).unwrap();
+    let mut items = ApeReader::new().iter_items(&test_file).unwrap();
+    assert_eq!(items.next().unwrap().unwrap().key, "Title");
+    assert!(items.next().unwrap().is_err());
+    assert!(items.next().is_none());
+}
+
+#[test]
+fn test_save_without_items_removes_tag() {
+    use crate::tag::TagWriterStrategy;
+
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("empty.mp3");
+    let audio = vec![0xABu8; 300];
+    let mut data = audio.clone();
+    data.extend(ape_tag_without_header(2000, &[("Title", b"Title")]));
+    std::fs::write(&test_file, &data).unwrap();
+
+    let mut writer = ApeWriter::new();
+    writer.init(&test_file).unwrap();
+    writer.remove_meta_entry(&MetaEntry::Title).unwrap();
+    writer.save().unwrap();
+    assert_eq!(std::fs::read(&test_file).unwrap(), audio);
+
+    // A file without a tag is left alone when nothing was set
+    let mut writer = ApeWriter::new();
+    writer.init(&test_file).unwrap();
+    writer.remove_meta_entry(&MetaEntry::Title).unwrap();
+    writer.save().unwrap();
+    assert_eq!(std::fs::read(&test_file).unwrap(), audio);
+
+    // Unless empty tags are kept
+    writer.set_keep_empty_tag(true);
+    writer.save().unwrap();
+    assert!(ApeReader::new().locate_tag(&test_file).unwrap().is_some());
+}
@@ -0,0 +1,31 @@
+use crate::ape::{ApeItem, ApeTag, ApeWriter};
+use crate::artwork::{self, PictureType};
+use crate::TagReader;
+use tempfile::tempdir;
+
+const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+#[test]
+fn test_extract_ape_cover_art() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("art.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let mut value = b"cover.png\0".to_vec();
+    value.extend_from_slice(PNG);
+    let mut tag = ApeTag::new(2000);
+    tag.set_item(ApeItem::new("Cover Art (Back)", value, 2));
+    tag.set_item(ApeItem::new_text("Title", "Not a picture"));
+    ApeWriter::new().write_tag(&test_file, &tag).unwrap();
+
+    let pictures = TagReader::new(&test_file).unwrap().pictures().unwrap();
+    let picture = pictures.iter().find(|p| p.picture_type == PictureType::CoverBack).unwrap();
+    assert_eq!(picture.mime_type, "image/png");
+    assert_eq!(picture.description, "cover.png");
+    assert_eq!(picture.data, PNG);
+}
+
+#[test]
+fn test_extract_pictures_missing_file() {
+    assert!(artwork::extract_pictures("does/not/exist.mp3").is_err());
+}
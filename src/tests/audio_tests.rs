@@ -0,0 +1,320 @@
+use crate::audio::gapless::parse_itunsmpb;
+use crate::audio::{
+    audio_crc32, audio_md5, audio_properties, audio_range, gapless_info, stream_info, GaplessSource, BitrateMode, ChannelMode, Emphasis, FrameHeader,
+    LameHeader, Layer, MpegVersion, StreamInfo, VbrHeaderKind,
+};
+use crate::id3::v2::comment::Comment;
+use crate::id3::v2::tag::TagWriter as Id3v2Writer;
+use crate::id3::v2::util::crc32;
+use crate::tag::{TagType, TagWriter, TagWriterStrategy};
+use crate::MetaEntry;
+use std::time::Duration;
+use tempfile::tempdir;
+
+#[test]
+fn test_frame_header_parsing() {
+    // MPEG-1 Layer III, 128 kbit/s, 44.1 kHz, padded, joint stereo
+    let header = FrameHeader::parse(&[0xFF, 0xFB, 0x92, 0x44]).unwrap();
+    assert_eq!(header.version, MpegVersion::V1);
+    assert_eq!(header.layer, Layer::III);
+    assert_eq!(header.bitrate, 128);
+    assert_eq!(header.sample_rate, 44100);
+    assert_eq!(header.channel_mode, ChannelMode::JointStereo);
+    assert_eq!(header.frame_length(), 418);
+
+    // MPEG-2 Layer III, 64 kbit/s, 22.05 kHz, mono
+    let header = FrameHeader::parse(&[0xFF, 0xF3, 0x80, 0xC0]).unwrap();
+    assert_eq!((header.version, header.bitrate, header.sample_rate), (MpegVersion::V2, 64, 22050));
+    assert_eq!(header.samples_per_frame(), 576);
+    assert_eq!(header.channel_mode.channels(), 1);
+
+    // Free format, bad bitrate, reserved sample rate and layer
+    for bytes in [[0xFF, 0xFB, 0x00, 0x00], [0xFF, 0xFB, 0xF0, 0x00], [0xFF, 0xFB, 0x9C, 0x00], [0xFF, 0xF9, 0x90, 0x00]] {
+        assert_eq!(FrameHeader::parse(&bytes), None);
+    }
+}
+
+#[test]
+fn test_audio_properties_of_sample_files() {
+    for (file, bitrate, channels) in [
+        ("audio_files/mp3_44100Hz_128kbps_stereo.mp3", 128, 2),
+        ("audio_files/mp3_44100Hz_320kbps_stereo.mp3", 320, 2),
+        ("audio_files/mp3_44100Hz_64kbps_mono.mp3", 64, 1),
+    ] {
+        let properties = audio_properties(file).unwrap();
+        assert_eq!(properties.version, MpegVersion::V1, "{}", file);
+        assert_eq!(properties.layer, Layer::III, "{}", file);
+        assert_eq!(properties.sample_rate, 44100, "{}", file);
+        assert_eq!(properties.bitrate, bitrate, "{}", file);
+        assert_eq!(properties.channels(), channels, "{}", file);
+
+        // LAME writes an Info header with the frame count into constant bitrate files
+        let vbr_header = properties.vbr_header.clone().unwrap();
+        assert_eq!(vbr_header.kind, VbrHeaderKind::Info);
+        let lame = vbr_header.lame.unwrap();
+        assert_eq!(lame.encoder, "LAME3.100");
+        assert_eq!(lame.vbr_method, 1);
+        assert_eq!((lame.encoder_delay, lame.encoder_padding), (576, 1107));
+        assert!(lame.replay_gain.is_empty());
+        assert_eq!(vbr_header.frames, Some(properties.frame_count));
+        assert_eq!(properties.bitrate_mode, BitrateMode::Constant);
+        assert_eq!(properties.average_bitrate, bitrate);
+        let size = std::fs::metadata(file).unwrap().len();
+        let estimate = size as f64 * 8.0 / (bitrate as f64 * 1000.0);
+        assert!((properties.duration.as_secs_f64() - estimate).abs() < 0.2, "{}", file);
+    }
+}
+
+/// An MPEG-1 Layer III frame at 128 kbit/s and 44.1 kHz, stereo
+fn frame_with(offset: usize, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0xFF, 0xFB, 0x90, 0x04];
+    frame.resize(417, 0);
+    frame[offset..offset + payload.len()].copy_from_slice(payload);
+    frame
+}
+
+#[test]
+fn test_duration_from_vbr_headers() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("vbr.mp3");
+    let audio = frame_with(0, &[]).repeat(4);
+    // Enough audio for the frame counts of the headers
+    let vbr_audio = frame_with(0, &[]).repeat(12);
+
+    // Xing header after the 32 bytes of stereo side information
+    let mut data = frame_with(36, b"Xing\0\0\0\x03\0\0\x03\xE8\0\x01\0\0");
+    data.extend(&vbr_audio);
+    std::fs::write(&path, &data).unwrap();
+    let properties = audio_properties(&path).unwrap();
+    let vbr_header = properties.vbr_header.clone().unwrap();
+    assert_eq!((vbr_header.kind, vbr_header.frames, vbr_header.bytes), (VbrHeaderKind::Xing, Some(1000), Some(65536)));
+    assert_eq!(vbr_header.lame, None);
+    assert_eq!(properties.frame_count, 1000);
+    assert_eq!(properties.bitrate_mode, BitrateMode::Variable);
+    assert_eq!(properties.average_bitrate, 20);
+    assert_eq!(properties.duration, Duration::from_nanos(1000 * 1152 * 1_000_000_000 / 44100));
+
+    // A frame count that cannot fit in the file falls back to the estimate from the size
+    let mut data = frame_with(36, b"Xing\0\0\0\x03\xFF\xFF\xFF\xFF\0\x01\0\0");
+    data.extend(&vbr_audio);
+    std::fs::write(&path, &data).unwrap();
+    let properties = audio_properties(&path).unwrap();
+    assert_eq!(properties.vbr_header.clone().unwrap().frames, Some(u32::MAX));
+    assert_eq!(properties.frame_count, 12);
+    assert_eq!(properties.bitrate_mode, BitrateMode::Constant);
+
+    // VBRI header at a fixed offset
+    let mut data = frame_with(36, b"VBRI\0\x01\0\0\0\x4B\0\x01\0\0\0\0\x01\xF4");
+    data.extend(&vbr_audio);
+    std::fs::write(&path, &data).unwrap();
+    let properties = audio_properties(&path).unwrap();
+    assert_eq!(properties.vbr_header.unwrap().kind, VbrHeaderKind::Vbri);
+    assert_eq!(properties.frame_count, 500);
+    assert_eq!(properties.bitrate_mode, BitrateMode::Variable);
+
+    // Without a header the duration follows from the size, excluding trailing tags
+    let mut data = audio.clone();
+    let mut id3v1 = vec![0u8; 128];
+    id3v1[..3].copy_from_slice(b"TAG");
+    data.extend(id3v1);
+    std::fs::write(&path, &data).unwrap();
+    let properties = audio_properties(&path).unwrap();
+    assert_eq!(properties.vbr_header, None);
+    assert_eq!(properties.bitrate_mode, BitrateMode::Constant);
+    assert_eq!(properties.frame_count, 4);
+    assert!((properties.duration.as_secs_f64() - 4.0 * 1152.0 / 44100.0).abs() < 0.001);
+}
+
+#[test]
+fn test_variable_bitrate_from_sampled_frames() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("sampled.mp3");
+    // Alternating 128 and 160 kbit/s frames without a VBR header
+    let mut fast = vec![0xFF, 0xFB, 0xA0, 0x04];
+    fast.resize(522, 0);
+    let data = [frame_with(0, &[]), fast].concat().repeat(10);
+    std::fs::write(&path, &data).unwrap();
+
+    let properties = audio_properties(&path).unwrap();
+    assert_eq!(properties.bitrate, 128);
+    assert_eq!(properties.bitrate_mode, BitrateMode::Variable);
+    assert_eq!(properties.average_bitrate, 144);
+    assert_eq!(properties.frame_count, 20);
+}
+
+#[test]
+fn test_audio_properties_skip_false_syncs() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("junk.mp3");
+    let frame = {
+        let mut frame = vec![0xFF, 0xFB, 0x90, 0xC4];
+        frame.resize(417, 0);
+        frame
+    };
+    // A lone sync pattern in junk data before three frames
+    let mut data = vec![0x00, 0xFF, 0xFB, 0x90, 0x00, 0x12];
+    data.extend(frame.repeat(3));
+    std::fs::write(&path, &data).unwrap();
+
+    let properties = audio_properties(&path).unwrap();
+    assert_eq!(properties.first_frame_offset, 6);
+    assert_eq!(properties.channel_mode, ChannelMode::Mono);
+
+    std::fs::write(&path, [0u8; 2048]).unwrap();
+    assert!(audio_properties(&path).is_err());
+}
+
+#[test]
+fn test_lame_header_fields() {
+    let mut data = b"LAME3.99r".to_vec();
+    data.push(0x14); // Revision 1, VBR method 4
+    data.push(195); // 19.5 kHz lowpass
+    data.extend((1u32 << 22).to_be_bytes()); // Peak 0.5
+    data.extend(0x2C47u16.to_be_bytes()); // Radio gain, set by the user, +7.1 dB
+    data.extend(0x4E07u16.to_be_bytes()); // Audiophile gain, set by the user, -0.7 dB
+    data.extend([0, 0]);
+    data.extend([0x24, 0x02, 0x10]); // Delay 576, padding 528
+    data.resize(36, 0);
+
+    let lame = LameHeader::parse(&data).unwrap();
+    assert_eq!(lame.encoder, "LAME3.99r");
+    assert_eq!((lame.revision, lame.vbr_method), (1, 4));
+    assert_eq!(lame.lowpass, Some(19500));
+    assert_eq!((lame.encoder_delay, lame.encoder_padding), (576, 528));
+    assert_eq!(lame.replay_gain.track_peak, Some(0.5));
+    assert_eq!(lame.replay_gain.track_gain, Some(7.1));
+    assert_eq!(lame.replay_gain.album_gain, Some(-0.7));
+
+    assert_eq!(LameHeader::parse(&data[..20]), None);
+    data[0] = 0;
+    assert_eq!(LameHeader::parse(&data), None);
+}
+
+#[test]
+fn test_audio_range_excludes_tags() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("tagged.mp3");
+    let audio = frame_with(0, &[]).repeat(8);
+
+    // Two stacked ID3v2 tags in front
+    let mut data = Vec::new();
+    for _ in 0..2 {
+        data.extend_from_slice(&[b'I', b'D', b'3', 3, 0, 0, 0, 0, 0, 20]);
+        data.extend_from_slice(&[0; 20]);
+    }
+    let start = data.len() as u64;
+    data.extend_from_slice(&audio);
+    let end = data.len() as u64;
+
+    // APE tag with header, Lyrics3v2, TAG+ and ID3v1 behind
+    let mut ape = |flags: u32| {
+        let mut block = b"APETAGEX".to_vec();
+        block.extend_from_slice(&2000u32.to_le_bytes());
+        block.extend_from_slice(&32u32.to_le_bytes());
+        block.extend_from_slice(&0u32.to_le_bytes());
+        block.extend_from_slice(&flags.to_le_bytes());
+        block.extend_from_slice(&[0; 8]);
+        data.extend_from_slice(&block);
+    };
+    ape(0xA000_0000);
+    ape(0x8000_0000);
+    let lyrics = b"LYRICSBEGININD00003110";
+    data.extend_from_slice(lyrics);
+    data.extend_from_slice(format!("{:06}LYRICS200", lyrics.len()).as_bytes());
+    let mut enhanced = vec![0u8; 227];
+    enhanced[..4].copy_from_slice(b"TAG+");
+    data.extend_from_slice(&enhanced);
+    let mut id3v1 = vec![0u8; 128];
+    id3v1[..3].copy_from_slice(b"TAG");
+    data.extend_from_slice(&id3v1);
+    std::fs::write(&path, &data).unwrap();
+
+    assert_eq!(audio_range(&path).unwrap(), start..end);
+    let properties = audio_properties(&path).unwrap();
+    assert_eq!(properties.first_frame_offset, start);
+    assert_eq!(properties.frame_count, 8);
+
+    // Lyrics3v1 directly before ID3v1, and a file without tags
+    let mut data = audio.clone();
+    data.extend_from_slice(b"LYRICSBEGINSome words\nLYRICSEND");
+    data.extend_from_slice(&id3v1);
+    std::fs::write(&path, &data).unwrap();
+    assert_eq!(audio_range(&path).unwrap(), 0..audio.len() as u64);
+    std::fs::write(&path, &audio).unwrap();
+    assert_eq!(audio_range(&path).unwrap(), 0..audio.len() as u64);
+}
+
+#[test]
+fn test_audio_checksums_survive_tag_edits() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("checksum.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &path).unwrap();
+    let data = std::fs::read(&path).unwrap();
+    let range = audio_range(&path).unwrap();
+    let audio = &data[range.start as usize..range.end as usize];
+    let (md5, crc) = (audio_md5(&path).unwrap(), audio_crc32(&path).unwrap());
+    assert_eq!(crc, crc32(audio));
+    assert_eq!(md5, md5::compute(audio).0);
+
+    for tag_type in [TagType::Id3v2, TagType::Ape, TagType::Id3v1] {
+        let mut writer = TagWriter::new(&path, tag_type).unwrap();
+        writer.set_meta_entry(&MetaEntry::Title, "Checksum").unwrap();
+        writer.save().unwrap();
+    }
+    assert_ne!(std::fs::read(&path).unwrap(), data);
+    assert_eq!(audio_md5(&path).unwrap(), md5);
+    assert_eq!(audio_crc32(&path).unwrap(), crc);
+}
+
+#[test]
+fn test_stream_info() {
+    // Joint stereo Layer III with mid/side stereo, original, no CRC
+    let info = StreamInfo::from(&FrameHeader::parse(&[0xFF, 0xFB, 0x90, 0x64]).unwrap());
+    assert!(info.mid_side_stereo && !info.intensity_stereo);
+    assert!(info.original && !info.copyright && !info.crc_protected);
+    assert_eq!((info.emphasis, info.samples_per_frame), (Emphasis::None, 1152));
+
+    // Joint stereo Layer II with CRC, copyright and CCITT J.17 emphasis
+    let info = StreamInfo::from(&FrameHeader::parse(&[0xFF, 0xFC, 0x90, 0x4B]).unwrap());
+    assert_eq!(info.layer, Layer::II);
+    assert!(info.intensity_stereo && !info.mid_side_stereo);
+    assert!(info.copyright && !info.original && info.crc_protected);
+    assert_eq!(info.emphasis, Emphasis::CcittJ17);
+
+    // Stereo tools only apply to joint stereo
+    let info = StreamInfo::from(&FrameHeader::parse(&[0xFF, 0xFB, 0x90, 0x34]).unwrap());
+    assert_eq!(info.channel_mode, ChannelMode::Stereo);
+    assert!(!info.mid_side_stereo && !info.intensity_stereo);
+
+    let info = stream_info("audio_files/mp3_44100Hz_64kbps_mono.mp3").unwrap();
+    assert_eq!((info.version, info.layer, info.channel_mode), (MpegVersion::V1, Layer::III, ChannelMode::Mono));
+    assert_eq!(info.samples_per_frame, 1152);
+    assert!(stream_info("Cargo.toml").is_err());
+}
+
+#[test]
+fn test_gapless_info() {
+    // From the LAME header
+    let file = "audio_files/mp3_44100Hz_128kbps_stereo.mp3";
+    let info = gapless_info(file).unwrap().unwrap();
+    assert_eq!(info.source, GaplessSource::Lame);
+    assert_eq!((info.encoder_delay, info.encoder_padding), (576, 1107));
+    let frames = audio_properties(file).unwrap().frame_count as u64;
+    assert_eq!(info.total_samples, Some(frames * 1152 - 576 - 1107));
+
+    // From an iTunSMPB comment, and nothing without one
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("itunes.mp3");
+    std::fs::write(&path, frame_with(0, &[]).repeat(4)).unwrap();
+    assert_eq!(gapless_info(&path).unwrap(), None);
+    let mut writer = Id3v2Writer::new();
+    writer.init(&path).unwrap();
+    let value = " 00000000 00000840 000001CC 0000000000000E34 00000000 00000000";
+    writer.set_comment(&Comment::new("eng", "iTunSMPB", value)).unwrap();
+    let info = gapless_info(&path).unwrap().unwrap();
+    assert_eq!(info.source, GaplessSource::ITunSmpb);
+    assert_eq!((info.encoder_delay, info.encoder_padding, info.total_samples), (2112, 460, Some(3636)));
+
+    assert_eq!(parse_itunsmpb("00000000 00000840"), None);
+    assert_eq!(parse_itunsmpb("0 840 1CC 0").unwrap().total_samples, None);
+}
@@ -0,0 +1,27 @@
+use crate::backup::{backup_path, rollback};
+use crate::{MetaEntry, TagReader, TagType, TagWriter};
+use tempfile::tempdir;
+
+#[test]
+fn test_backup_and_rollback() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("backup.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+    let original = std::fs::read(&test_file).unwrap();
+
+    let mut writer = TagWriter::new(&test_file, TagType::Id3v2).unwrap();
+    writer.set_backup(true);
+    writer.set_meta_entry(&MetaEntry::Title, "First edit").unwrap();
+    writer.set_meta_entry(&MetaEntry::Title, "Second edit").unwrap();
+
+    // The backup holds the file as it was before the writer touched it
+    assert_eq!(backup_path(&test_file), temp_dir.path().join("backup.mp3.bak"));
+    assert_eq!(std::fs::read(backup_path(&test_file)).unwrap(), original);
+    let reader = TagReader::new(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Second edit"));
+
+    rollback(&test_file).unwrap();
+    assert_eq!(std::fs::read(&test_file).unwrap(), original);
+    assert!(!backup_path(&test_file).exists());
+    assert!(rollback(&test_file).is_err());
+}
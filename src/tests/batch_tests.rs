@@ -0,0 +1,91 @@
+use crate::batch::Batch;
+use tempfile::tempdir;
+
+#[test]
+fn test_batch_edit_and_read() {
+    let temp_dir = tempdir().unwrap();
+    std::fs::create_dir(temp_dir.path().join("disc 2")).unwrap();
+    let names = ["a.mp3", "b.MP3", "disc 2/c.mp3", "d.mp3", "e.mp3"];
+    for name in names {
+        std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", temp_dir.path().join(name)).unwrap();
+    }
+    std::fs::write(temp_dir.path().join("cover.jpg"), b"not audio").unwrap();
+
+    let batch = Batch::from_directory(temp_dir.path()).unwrap().threads(2);
+    assert_eq!(batch.files().len(), names.len());
+    let summary = batch.edit(|tag| tag.album("Batch Album"));
+    assert!(summary.is_success(), "{:?}", summary.failures().collect::<Vec<_>>());
+
+    let summary = batch.read();
+    assert_eq!(summary.succeeded(), names.len());
+    let files: Vec<_> = summary.results.iter().map(|(path, _)| path.clone()).collect();
+    assert_eq!(files, batch.files());
+    for (_, metadata) in &summary.results {
+        assert_eq!(metadata.as_ref().unwrap().album.as_deref(), Some("Batch Album"));
+    }
+
+    // Failures are reported per file without stopping the others
+    let mut files = batch.files().to_vec();
+    files.insert(1, temp_dir.path().join("missing.mp3"));
+    let summary = Batch::new(files).run(|path| crate::get_title(path));
+    assert_eq!((summary.succeeded(), summary.failed()), (names.len(), 1));
+    assert_eq!(summary.failures().next().unwrap().0, temp_dir.path().join("missing.mp3"));
+
+    // A link back to the directory is not followed
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(temp_dir.path(), temp_dir.path().join("disc 2/loop")).unwrap();
+        assert_eq!(Batch::from_directory(temp_dir.path()).unwrap().files().len(), names.len());
+    }
+}
+
+#[test]
+fn test_progress_and_cancellation() {
+    use crate::progress::{with_progress, CancellationToken, Progress};
+    use crate::{Error, Tag, TagType};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let temp_dir = tempdir().unwrap();
+    let audio = vec![0x55u8; 200_000];
+    let path = temp_dir.path().join("untagged.mp3");
+    std::fs::write(&path, &audio).unwrap();
+
+    // Adding a tag rewrites the file, copying all of the audio
+    let progress = Progress::new();
+    with_progress(&progress, || Tag::open_as(&path, TagType::Id3v2).unwrap().title("Title").save()).unwrap();
+    assert_eq!(progress.update().bytes_copied, audio.len() as u64);
+
+    // A cancelled rewrite leaves the file and no temporary file behind
+    let other = temp_dir.path().join("other.mp3");
+    std::fs::write(&other, &audio).unwrap();
+    let cancelled = Progress::new();
+    cancelled.token().cancel();
+    let result = with_progress(&cancelled, || Tag::open_as(&other, TagType::Id3v2).unwrap().title("Title").save());
+    assert!(matches!(result, Err(Error::Cancelled)));
+    assert_eq!(std::fs::read(&other).unwrap(), audio);
+    assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+
+    // Cancelling a batch from its callback stops it after the current file
+    let files: Vec<_> = (0..4).map(|i| temp_dir.path().join(format!("{}.mp3", i))).collect();
+    for file in &files {
+        std::fs::write(file, &audio).unwrap();
+    }
+    let token = CancellationToken::new();
+    let updates = Arc::new(AtomicUsize::new(0));
+    let progress = Progress::new().with_token(token.clone()).on_update({
+        let updates = updates.clone();
+        move |update| {
+            updates.fetch_add(1, Ordering::Relaxed);
+            if update.files_done == 1 {
+                token.cancel();
+            }
+        }
+    });
+    let summary = Batch::new(files).threads(1).progress(progress.clone()).edit(|tag| tag.title("Title"));
+    assert_eq!(summary.succeeded(), 1);
+    assert!(summary.failures().all(|(_, error)| matches!(error, Error::Cancelled)));
+    assert_eq!(progress.update().files_done, 1);
+    assert_eq!(progress.update().files_total, 4);
+    assert!(updates.load(Ordering::Relaxed) > 1);
+}
@@ -3,6 +3,7 @@ use std::fs::{write};
 use tempfile::tempdir;
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod blackbox_security_tests {
     use super::*;
 
@@ -28,18 +29,12 @@ mod blackbox_security_tests {
             let writer_result = TagWriter::new(&test_file, TagType::Id3v2);
             
             // Should either succeed or return proper error - no panics
-            match reader_result {
-                Ok(reader) => {
-                    let _ = reader.get_meta_entry(&MetaEntry::Title);
-                }
-                Err(_) => {} // Expected for malformed files
+            if let Ok(reader) = reader_result {
+                let _ = reader.get_meta_entry(&MetaEntry::Title);
             }
 
-            match writer_result {
-                Ok(mut writer) => {
-                    let _ = writer.set_meta_entry(&MetaEntry::Title, "test");
-                }
-                Err(_) => {} // Expected for malformed files
+            if let Ok(mut writer) = writer_result {
+                let _ = writer.set_meta_entry(&MetaEntry::Title, "test");
             }
         }
     }
@@ -140,7 +135,7 @@ mod blackbox_security_tests {
                 
                 // Verify data integrity after write
                 if let Ok(reader) = TagReader::new(&test_file) {
-                    if let Ok(title) = reader.get_meta_entry(&MetaEntry::Title) {
+                    if let Ok(Some(title)) = reader.get_meta_entry(&MetaEntry::Title) {
                         // Data should be preserved exactly or sanitized predictably
                         assert!(title.len() <= payload.len() * 4); // UTF-8 expansion max
                     }
@@ -0,0 +1,139 @@
+use crate::ape::{ApeReader, ApeWriter};
+use crate::artwork::{self, Picture, PictureType};
+use crate::convert::{convert_tag, copy_tags, ConvertOptions, CopyOptions};
+use crate::id3::v1::report::FieldIssue;
+use crate::id3::v1::tag::has_id3v1_tag;
+use crate::id3::v2::tag::{TagReader as Id3v2Reader, TagWriter as Id3v2Writer};
+use crate::id3::v2::util::has_id3v2_tag;
+use crate::tag::{TagReaderStrategy, TagType, TagWriterStrategy};
+use crate::{Error, MetaEntry};
+use std::collections::HashMap;
+use tempfile::tempdir;
+
+const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+fn test_file(dir: &tempfile::TempDir) -> std::path::PathBuf {
+    let path = dir.path().join("convert.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &path).unwrap();
+    path
+}
+
+#[test]
+fn test_convert_id3v2_to_ape_with_artwork() {
+    let temp_dir = tempdir().unwrap();
+    let path = test_file(&temp_dir);
+    let mut writer = Id3v2Writer::new();
+    writer.init(&path).unwrap();
+    writer.set_meta_entry(&MetaEntry::Title, "Converted").unwrap();
+    writer.set_meta_entry(&MetaEntry::Genre, "(17)Rock").unwrap();
+    writer.set_picture(&Picture::new("image/png", PictureType::CoverBack, "", PNG.to_vec())).unwrap();
+
+    let options = ConvertOptions { remove_source: true, ..Default::default() };
+    let report = convert_tag(&path, TagType::Id3v2, TagType::Ape, options).unwrap();
+    assert!(report.copied.contains(&MetaEntry::Title));
+    assert_eq!(report.pictures, 1);
+    assert!(report.source_removed);
+    assert!(!has_id3v2_tag(&path).unwrap());
+
+    let reader = ApeReader::new();
+    assert_eq!(reader.get_meta_entry(&path, &MetaEntry::Title).unwrap(), "Converted");
+    assert_eq!(reader.get_meta_entry(&path, &MetaEntry::Genre).unwrap(), "Rock");
+    let pictures = artwork::extract_pictures(&path).unwrap();
+    assert_eq!(pictures.len(), 1);
+    assert_eq!(pictures[0].picture_type, PictureType::CoverBack);
+    assert_eq!(pictures[0].description, "cover.png");
+    assert_eq!(pictures[0].data, PNG);
+}
+
+#[test]
+fn test_convert_ape_to_id3v1_reports_losses() {
+    let temp_dir = tempdir().unwrap();
+    let path = test_file(&temp_dir);
+    let entries = HashMap::from([
+        (MetaEntry::Title, "A title that is far too long for ID3v1".to_string()),
+        (MetaEntry::Genre, "Hip Hop".to_string()),
+        (MetaEntry::Composer, "Someone".to_string()),
+    ]);
+    ApeWriter::new().set_meta_entries(&path, &entries).unwrap();
+
+    let report = convert_tag(&path, TagType::Ape, TagType::Id3v1, ConvertOptions::default()).unwrap();
+    assert!(has_id3v1_tag(&path).unwrap());
+    assert!(report.lossy.truncated().any(|entry| *entry == MetaEntry::Title));
+    assert!(report
+        .lossy
+        .issues
+        .iter()
+        .any(|(entry, issue)| *entry == MetaEntry::Composer && matches!(issue, FieldIssue::Unsupported(_))));
+
+    let mut reader = crate::id3::v1::tag::TagReader::new();
+    reader.init(&path).unwrap();
+    assert_eq!(reader.get_meta_entry(&path, &MetaEntry::Genre).unwrap(), "Hip-Hop");
+    // The source tag is kept by default
+    assert_eq!(ApeReader::new().get_meta_entry(&path, &MetaEntry::Composer).unwrap(), "Someone");
+}
+
+#[test]
+fn test_convert_id3v1_to_id3v2_and_errors() {
+    let temp_dir = tempdir().unwrap();
+    let path = test_file(&temp_dir);
+    assert!(matches!(
+        convert_tag(&path, TagType::Id3v1, TagType::Id3v2, ConvertOptions::default()),
+        Err(Error::TagNotFound)
+    ));
+    assert!(convert_tag(&path, TagType::Ape, TagType::Ape, ConvertOptions::default()).is_err());
+
+    let mut tag = vec![0u8; 128];
+    tag[..3].copy_from_slice(b"TAG");
+    tag[3..8].copy_from_slice(b"Title");
+    tag[127] = 8;
+    let mut data = std::fs::read(&path).unwrap();
+    data.extend(tag);
+    std::fs::write(&path, data).unwrap();
+
+    let options = ConvertOptions { remove_source: true, ..Default::default() };
+    convert_tag(&path, TagType::Id3v1, TagType::Id3v2, options).unwrap();
+    assert!(!has_id3v1_tag(&path).unwrap());
+    let mut reader = Id3v2Reader::new();
+    reader.init(&path).unwrap();
+    assert_eq!(reader.get_meta_entry(&path, &MetaEntry::Title).unwrap(), "Title");
+    assert_eq!(reader.get_meta_entry(&path, &MetaEntry::Genre).unwrap(), "Jazz");
+}
+
+#[test]
+fn test_copy_tags_between_files() {
+    let temp_dir = tempdir().unwrap();
+    let src = test_file(&temp_dir);
+    let dst = temp_dir.path().join("reencoded.mp3");
+    std::fs::write(&dst, [0xFF, 0xFB, 0x90, 0x44, 0x00, 0x01, 0x02, 0x03].repeat(64)).unwrap();
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&src).unwrap();
+    writer.set_meta_entry(&MetaEntry::Title, "Copied").unwrap();
+    writer.set_user_text("MOOD", "Calm").unwrap();
+    writer.set_picture(&Picture::new("image/png", PictureType::CoverFront, "", PNG.to_vec())).unwrap();
+    let entries = HashMap::from([(MetaEntry::Composer, "Ape Composer".to_string())]);
+    ApeWriter::new().set_meta_entries(&src, &entries).unwrap();
+
+    // Mirror every source tag
+    let report = copy_tags(&src, &dst, CopyOptions::default()).unwrap();
+    assert!(report.written.contains(&TagType::Id3v2));
+    assert!(report.written.contains(&TagType::Ape));
+    let mut reader = Id3v2Reader::new();
+    reader.init(&dst).unwrap();
+    assert_eq!(reader.get_meta_entry(&dst, &MetaEntry::Title).unwrap(), "Copied");
+    assert_eq!(reader.user_text("MOOD").as_deref(), Some("Calm"));
+    assert_eq!(reader.pictures().len(), 1);
+    assert_eq!(ApeReader::new().get_meta_entry(&dst, &MetaEntry::Composer).unwrap(), "Ape Composer");
+
+    // Merge everything into a single APE tag
+    let merged = temp_dir.path().join("merged.mp3");
+    std::fs::write(&merged, [0xFF, 0xFB, 0x90, 0x44].repeat(64)).unwrap();
+    let options = CopyOptions { tag_type: Some(TagType::Ape), ..Default::default() };
+    assert_eq!(copy_tags(&src, &merged, options).unwrap().written, [TagType::Ape]);
+    assert!(!has_id3v2_tag(&merged).unwrap());
+    let reader = ApeReader::new();
+    assert_eq!(reader.get_meta_entry(&merged, &MetaEntry::Title).unwrap(), "Copied");
+    assert_eq!(reader.get_meta_entry(&merged, &MetaEntry::Composer).unwrap(), "Ape Composer");
+    assert_eq!(reader.get_meta_entry(&merged, &MetaEntry::Custom("MOOD".to_string())).unwrap(), "Calm");
+    assert_eq!(artwork::extract_pictures(&merged).unwrap().len(), 1);
+}
@@ -0,0 +1,65 @@
+use crate::ape::ApeWriter;
+use crate::id3::v2::tag::TagWriter as Id3v2Writer;
+use crate::id3::v2::util::has_id3v2_tag;
+use crate::tag::{detect_tags, TagReader, TagType, TagWriterStrategy};
+use crate::{MetaEntry, Tag};
+use std::collections::HashMap;
+use tempfile::tempdir;
+
+const AUDIO: [u8; 8] = [0xFF, 0xFB, 0x90, 0x44, 0x00, 0x01, 0x02, 0x03];
+
+#[test]
+fn test_fluent_editor() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("edit.mp3");
+    std::fs::write(&path, AUDIO.repeat(16)).unwrap();
+
+    let tag = Tag::open(&path).unwrap().title("X").artist("Y").comment("Temporary");
+    assert_eq!(tag.tag_type(), TagType::Id3v2);
+    // Nothing is written before `save`
+    assert!(!has_id3v2_tag(&path).unwrap());
+    tag.remove(MetaEntry::Comment).save().unwrap();
+
+    let reader = TagReader::new(&path).unwrap();
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("X"));
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap().as_deref(), Some("Y"));
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Comment).unwrap(), None);
+    assert!(Tag::open(temp_dir.path().join("missing.mp3")).is_err());
+}
+
+#[test]
+fn test_editor_keeps_existing_tag_type() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("ape.mp3");
+    std::fs::write(&path, AUDIO.repeat(16)).unwrap();
+    let entries = HashMap::from([(MetaEntry::Title, "Ape".to_string())]);
+    ApeWriter::new().set_meta_entries(&path, &entries).unwrap();
+
+    let tag = Tag::open(&path).unwrap();
+    assert_eq!(tag.tag_type(), TagType::Ape);
+    tag.album("Album").save().unwrap();
+    let presence = detect_tags(&path).unwrap();
+    assert!(!presence.has(TagType::Id3v2));
+    let reader = TagReader::new(&path).unwrap();
+    assert_eq!(reader.get_meta_entry_from(TagType::Ape, &MetaEntry::Album).unwrap().as_deref(), Some("Album"));
+}
+
+#[test]
+fn test_deferred_id3v2_writer() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("deferred.mp3");
+    std::fs::write(&path, AUDIO.repeat(16)).unwrap();
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&path).unwrap();
+    writer.set_deferred(true);
+    writer.set_meta_entry(&MetaEntry::Title, "Once").unwrap();
+    writer.set_user_text("MOOD", "Calm").unwrap();
+    writer.remove_user_text("MOOD").unwrap();
+    assert!(!has_id3v2_tag(&path).unwrap());
+
+    writer.save().unwrap();
+    let reader = TagReader::new(&path).unwrap();
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Once"));
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Custom("MOOD".to_string())).unwrap(), None);
+}
@@ -0,0 +1,228 @@
+use crate::id3::v1::enhanced::EnhancedTag;
+use crate::id3::v1::genre::{Genre, GENRES};
+use crate::id3::v1::report::FieldIssue;
+use crate::id3::v1::tag::TagReader as Id3v1Reader;
+use crate::id3::v1::tag::TagWriter as Id3v1Writer;
+use crate::id3::{remove_id3v1_tag, sync_tags, SyncDirection};
+use crate::id3::v2::tag::{TagReader as Id3v2Reader, TagWriter as Id3v2Writer};
+use crate::tag::{TagReaderStrategy, TagWriterStrategy};
+use crate::MetaEntry;
+use tempfile::tempdir;
+
+/// Build a 128-byte ID3v1 tag with the given title and genre byte
+fn id3v1_tag(title: &str, genre: u8) -> Vec<u8> {
+    let mut tag = vec![0u8; 128];
+    tag[..3].copy_from_slice(b"TAG");
+    tag[3..3 + title.len()].copy_from_slice(title.as_bytes());
+    tag[127] = genre;
+    tag
+}
+
+#[test]
+fn test_genre_table() {
+    assert_eq!(GENRES.len(), 192);
+    assert_eq!(Genre(0).name(), Some("Blues"));
+    assert_eq!(Genre(79).name(), Some("Hard Rock"));
+    assert_eq!(Genre(80).name(), Some("Folk"));
+    assert_eq!(Genre(147).name(), Some("Synthpop"));
+    assert_eq!(Genre(191).name(), Some("Psybient"));
+    assert_eq!(Genre(192).name(), None);
+    assert_eq!(Genre::NONE.to_string(), "(255)");
+    assert_eq!(Genre::from_name("jazz"), Some(Genre(8)));
+}
+
+#[test]
+fn test_genre_lookup_aliases() {
+    assert_eq!(Genre::lookup("Jazz"), Some(Genre(8)));
+    assert_eq!(Genre::lookup("hip hop"), Some(Genre(7)));
+    assert_eq!(Genre::lookup("RnB"), Some(Genre(14)));
+    assert_eq!(Genre::lookup("rock 'n' roll"), Some(Genre(78)));
+    assert_eq!(Genre::lookup("(17)"), Some(Genre(17)));
+    assert_eq!(Genre::lookup("250"), None);
+    assert_eq!(Genre::lookup("Polka Metal Fusion"), None);
+}
+
+#[test]
+fn test_writer_sets_genre_by_name() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("genre_write.mp3");
+    std::fs::write(&test_file, vec![0xABu8; 200]).unwrap();
+
+    for (name, byte) in [("Jazz", 8), ("drum and bass", 127), ("Not A Genre", 255)] {
+        let mut writer = Id3v1Writer::new();
+        writer.init(&test_file).unwrap();
+        writer.set_meta_entry(&MetaEntry::Genre, name).unwrap();
+        writer.save().unwrap();
+        assert_eq!(*std::fs::read(&test_file).unwrap().last().unwrap(), byte);
+    }
+}
+
+#[test]
+fn test_reader_resolves_genre_byte() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("genre.mp3");
+    let mut data = vec![0xFFu8; 512];
+    data.extend(id3v1_tag("Song", 137));
+    std::fs::write(&test_file, &data).unwrap();
+
+    let mut reader = Id3v1Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Genre).unwrap(), "Heavy Metal");
+
+    data.truncate(512);
+    data.extend(id3v1_tag("Song", 255));
+    std::fs::write(&test_file, &data).unwrap();
+    reader.init(&test_file).unwrap();
+    assert!(reader.get_meta_entry(&test_file, &MetaEntry::Genre).is_err());
+}
+
+#[test]
+fn test_writer_appends_tag_to_untagged_file() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("untagged.mp3");
+
+    for audio in [vec![0xABu8; 1000], vec![0xABu8; 20]] {
+        std::fs::write(&test_file, &audio).unwrap();
+        for title in ["First", "Second"] {
+            let mut writer = Id3v1Writer::new();
+            writer.init(&test_file).unwrap();
+            writer.set_meta_entry(&MetaEntry::Title, title).unwrap();
+            writer.save().unwrap();
+        }
+
+        let data = std::fs::read(&test_file).unwrap();
+        assert_eq!(data.len(), audio.len() + 128);
+        assert_eq!(&data[..audio.len()], audio.as_slice());
+        assert_eq!(&data[audio.len()..audio.len() + 3], b"TAG");
+        assert_eq!(data[data.len() - 1], Genre::NONE.0);
+
+        let mut reader = Id3v1Reader::new();
+        reader.init(&test_file).unwrap();
+        assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Title).unwrap(), "Second");
+    }
+}
+
+#[test]
+fn test_remove_id3v1_tag_truncates_file() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("remove.mp3");
+    let audio = vec![0xABu8; 300];
+    let mut data = audio.clone();
+    data.extend(id3v1_tag("Gone", 8));
+    std::fs::write(&test_file, &data).unwrap();
+
+    assert!(remove_id3v1_tag(&test_file).unwrap());
+    assert_eq!(std::fs::read(&test_file).unwrap(), audio);
+
+    // Without a TAG block nothing is truncated
+    assert!(!remove_id3v1_tag(&test_file).unwrap());
+    assert_eq!(std::fs::read(&test_file).unwrap(), audio);
+}
+
+#[test]
+fn test_enhanced_tag_is_read_and_preserved() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("enhanced.mp3");
+    let enhanced = EnhancedTag {
+        title: " and the rest of a long title".to_string(),
+        speed: 3,
+        genre: "Shoegaze Revival".to_string(),
+        start_time: "000:05".to_string(),
+        ..Default::default()
+    };
+    let audio = vec![0xABu8; 400];
+    let mut data = audio.clone();
+    data.extend_from_slice(&enhanced.to_bytes());
+    data.extend(id3v1_tag("Exactly thirty characters long", 12));
+    std::fs::write(&test_file, &data).unwrap();
+
+    let mut reader = Id3v1Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.enhanced(), Some(&enhanced));
+    assert_eq!(
+        reader.get_meta_entry(&test_file, &MetaEntry::Title).unwrap(),
+        "Exactly thirty characters long and the rest of a long title"
+    );
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Genre).unwrap(), "Shoegaze Revival");
+
+    let mut writer = Id3v1Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_meta_entry(&MetaEntry::Artist, "Someone").unwrap();
+    writer.save().unwrap();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.enhanced(), Some(&enhanced));
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Artist).unwrap(), "Someone");
+
+    assert!(remove_id3v1_tag(&test_file).unwrap());
+    assert_eq!(std::fs::read(&test_file).unwrap(), audio);
+}
+
+#[test]
+fn test_writer_reports_lossy_fields() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("report.mp3");
+    std::fs::write(&test_file, vec![0xABu8; 200]).unwrap();
+
+    let mut writer = Id3v1Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_meta_entry(&MetaEntry::Artist, "A rather long artist name here").unwrap();
+    writer.set_meta_entry(&MetaEntry::Artist, "Short").unwrap();
+    writer.set_meta_entry(&MetaEntry::Title, &"é".repeat(20)).unwrap();
+    writer.set_meta_entry(&MetaEntry::Comment, "fits").unwrap();
+    writer.set_meta_entry(&MetaEntry::Composer, "Someone").unwrap();
+    writer.set_meta_entry(&MetaEntry::Genre, "Polka Metal Fusion").unwrap();
+    writer.save().unwrap();
+
+    let report = writer.take_report();
+    assert!(!report.is_lossless());
+    assert_eq!(report.truncated().collect::<Vec<_>>(), vec![&MetaEntry::Title]);
+    assert!(report.issues.contains(&(
+        MetaEntry::Title,
+        FieldIssue::Truncated { original: "é".repeat(20), written: "é".repeat(15) }
+    )));
+    assert!(report.issues.contains(&(MetaEntry::Composer, FieldIssue::Unsupported("Someone".to_string()))));
+    assert!(report.issues.contains(&(MetaEntry::Genre, FieldIssue::UnknownGenre("Polka Metal Fusion".to_string()))));
+    assert_eq!(report.issues.len(), 3);
+
+    let mut reader = Id3v1Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Title).unwrap(), "é".repeat(15));
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Artist).unwrap(), "Short");
+}
+
+#[test]
+fn test_sync_tags_both_directions() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("sync.mp3");
+    std::fs::write(&test_file, vec![0xABu8; 500]).unwrap();
+
+    // No ID3v2 tag yet
+    assert!(sync_tags(&test_file, SyncDirection::V2ToV1).is_err());
+
+    let mut v2_writer = Id3v2Writer::new();
+    v2_writer.init(&test_file).unwrap();
+    v2_writer.set_meta_entry(&MetaEntry::Title, "A title that is far too long for ID3v1").unwrap();
+    v2_writer.set_meta_entry(&MetaEntry::Artist, "Artist").unwrap();
+    v2_writer.set_meta_entry(&MetaEntry::Genre, "(8)Jazz").unwrap();
+
+    let report = sync_tags(&test_file, SyncDirection::V2ToV1).unwrap();
+    assert_eq!(report.copied, vec![MetaEntry::Title, MetaEntry::Artist, MetaEntry::Genre]);
+    assert_eq!(report.lossy.truncated().collect::<Vec<_>>(), vec![&MetaEntry::Title]);
+
+    let mut v1_reader = Id3v1Reader::new();
+    v1_reader.init(&test_file).unwrap();
+    assert_eq!(v1_reader.get_meta_entry(&test_file, &MetaEntry::Title).unwrap(), "A title that is far too long f");
+    assert_eq!(v1_reader.get_meta_entry(&test_file, &MetaEntry::Genre).unwrap(), "Jazz");
+
+    let mut v1_writer = Id3v1Writer::new();
+    v1_writer.init(&test_file).unwrap();
+    v1_writer.set_meta_entry(&MetaEntry::Album, "From v1").unwrap();
+    v1_writer.save().unwrap();
+
+    let report = sync_tags(&test_file, SyncDirection::V1ToV2).unwrap();
+    assert!(report.copied.contains(&MetaEntry::Album));
+    let mut v2_reader = Id3v2Reader::new();
+    v2_reader.init(&test_file).unwrap();
+    assert_eq!(v2_reader.get_meta_entry(&test_file, &MetaEntry::Album).unwrap(), "From v1");
+    assert_eq!(v2_reader.get_meta_entry(&test_file, &MetaEntry::Genre).unwrap(), "Jazz");
+}
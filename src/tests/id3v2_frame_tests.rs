@@ -0,0 +1,1064 @@
+use crate::id3::v2::chapter::{Chapter, TableOfContents};
+use crate::id3::v2::comment::Comment;
+use crate::id3::v2::encoding::TextEncoding;
+use crate::error::Error;
+use crate::id3::v2::frame::{Frame, SynchronisedLyrics};
+use crate::id3::v2::header::{ExtendedHeader, FLAG_EXTENDED_HEADER};
+use crate::id3::v2::lyrics::Lyrics;
+use crate::id3::v2::picture::{Picture, PictureType};
+use crate::id3::v2::tag::{TagReader as Id3v2Reader, TagWriter as Id3v2Writer};
+use crate::id3::v2::play_counter::{decode_counter, increment_play_count};
+use crate::id3::v2::popularimeter::Rating;
+use crate::id3::v2::private::PrivateFrame;
+use crate::id3::v2::ufid::UniqueFileId;
+use crate::id3::v2::util::{crc32, synchsafe_to_int};
+use crate::id3::v2::version::Version;
+use crate::tag::{TagReaderStrategy, TagWriterStrategy};
+use crate::id3::{convert_version, Id3v2Version};
+use crate::{MetaEntry, TagReader, TagType, TagWriter};
+use tempfile::tempdir;
+
+const JPEG: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F'];
+
+#[test]
+fn test_picture_frame_round_trip() {
+    let picture = Picture::new("image/png", PictureType::CoverBack, "Rückseite ★", vec![0, 1, 2, 0, 0]);
+
+    for version in [Version::V3, Version::V4] {
+        let parsed = Picture::parse(&picture.to_frame_data(version), version).unwrap();
+        assert_eq!(parsed, picture);
+    }
+
+    // ID3v2.2 stores a three character image format
+    let data = picture.to_frame_data(Version::V2);
+    assert_eq!(&data[1..4], b"PNG");
+    assert_eq!(Picture::parse(&data, Version::V2).unwrap().mime_type, "image/png");
+}
+
+#[test]
+fn test_write_and_read_attached_picture() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("cover.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+    let image = temp_dir.path().join("cover.jpg");
+    std::fs::write(&image, JPEG).unwrap();
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    // A text value is not taken as a path to read the image from
+    assert!(matches!(
+        writer.set_meta_entry(&MetaEntry::AttachedPicture, image.to_str().unwrap()),
+        Err(Error::UnsupportedMetaEntry(_))
+    ));
+    writer.set_picture_from_file(&image).unwrap();
+    writer.set_picture(&Picture::new("image/png", PictureType::Artist, "", vec![1, 2, 3])).unwrap();
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    let pictures = reader.pictures();
+    assert_eq!(pictures.len(), 2);
+    let cover = pictures.iter().find(|p| p.picture_type == PictureType::CoverFront).unwrap();
+    assert_eq!(cover.mime_type, "image/jpeg");
+    assert_eq!(cover.data, JPEG);
+    assert!(reader.get_meta_entry(&test_file, &MetaEntry::AttachedPicture).is_ok());
+    assert_eq!(reader.tag().unwrap().frames("APIC").len(), 2);
+
+    writer.set_meta_entry(&MetaEntry::AttachedPicture, "").unwrap();
+    reader.init(&test_file).unwrap();
+    assert!(reader.pictures().is_empty());
+}
+
+#[test]
+fn test_lyrics_frame_parse() {
+    // UTF-16 USLT frame: language "eng", description "v1", text "la"
+    let data = [
+        0x01, b'e', b'n', b'g', 0xFF, 0xFE, b'v', 0, b'1', 0, 0, 0, 0xFF, 0xFE, b'l', 0, b'a', 0,
+    ];
+    let lyrics = Lyrics::parse(&data).unwrap();
+    assert_eq!(lyrics, Lyrics::new("eng", "v1", "la"));
+    assert_eq!(Lyrics::parse(&lyrics.to_frame_data(Version::V3)).unwrap(), lyrics);
+}
+
+#[test]
+fn test_lyrics_meta_entry_round_trip() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("lyrics.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let mut writer = TagWriter::new(&test_file, TagType::Id3v2).unwrap();
+    writer.set_meta_entry(&MetaEntry::Lyrics, "First line\nSecond line").unwrap();
+
+    let reader = TagReader::new(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Lyrics).unwrap().as_deref(), Some("First line\nSecond line"));
+}
+
+#[test]
+fn test_synchronised_lyrics_round_trip() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("sylt.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let mut lyrics = SynchronisedLyrics::new("eng", "karaoke");
+    lyrics.add_line(0, "Hello");
+    lyrics.add_line(1500, "wörld ♪");
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_synchronised_lyrics(&lyrics).unwrap();
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.synchronised_lyrics(), vec![lyrics]);
+}
+
+#[test]
+fn test_multiple_comments_by_description() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("comments.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_meta_entry(&MetaEntry::Comment, "plain comment").unwrap();
+    writer.set_comment(&Comment::new("deu", "Notiz", "Grüße")).unwrap();
+    writer.set_comment(&Comment::new("eng", "Notiz", "note")).unwrap();
+    writer.set_comment(&Comment::new("ENG", "Notiz", "replaced")).unwrap();
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Comment).unwrap(), "plain comment");
+    let mut notes: Vec<_> = reader.comments().into_iter().filter(|c| c.description == "Notiz").collect();
+    notes.sort_by(|a, b| a.lang.cmp(&b.lang));
+    assert_eq!(notes, vec![Comment::new("deu", "Notiz", "Grüße"), Comment::new("eng", "Notiz", "replaced")]);
+
+    writer.remove_comment("eng", "Notiz").unwrap();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.comment("Notiz"), Some(Comment::new("deu", "Notiz", "Grüße")));
+    assert_eq!(reader.comment("").unwrap().lang, "und");
+}
+
+#[test]
+fn test_comment_and_lyrics_languages() {
+    use crate::validation::normalize_language;
+
+    assert_eq!(normalize_language("ENG").unwrap(), "eng");
+    assert_eq!(normalize_language("").unwrap(), "und");
+    assert_eq!(normalize_language("XXX").unwrap(), "und");
+    assert!(normalize_language("english").is_err());
+    assert!(normalize_language("e1g").is_err());
+
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("languages.mp3");
+    // Lyrics stored with the "XXX" placeholder by another tagger
+    write_tag_file(&path, 3, &[("USLT", b"\x00XXX\0old".to_vec())]);
+    let mut writer = Id3v2Writer::new();
+    writer.init(&path).unwrap();
+    writer.set_meta_entry(&MetaEntry::Lyrics, "new").unwrap();
+    assert!(writer.set_comment(&Comment::new("english", "", "text")).is_err());
+    assert!(writer.set_language("german").is_err());
+    writer.set_language("DEU").unwrap();
+    writer.set_meta_entry(&MetaEntry::Comment, "Kommentar").unwrap();
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&path).unwrap();
+    let lyrics = reader.lyrics();
+    assert_eq!(lyrics, [Lyrics::new("und", "", "new")]);
+    assert_eq!(lyrics[0].language(), None);
+    let comment = reader.comment("").unwrap();
+    assert_eq!(comment.lang, "deu");
+    assert_eq!(comment.language().as_deref(), Some("deu"));
+}
+
+#[test]
+fn test_user_text_frames() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("txxx.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_user_text("MOOD", "happy").unwrap();
+    writer.set_user_text("CATALOGNUMBER", "ABC-123").unwrap();
+    writer.set_user_text("mood", "calm").unwrap();
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.user_text("MOOD").as_deref(), Some("calm"));
+    assert_eq!(reader.user_texts().len(), 2);
+
+    writer.remove_user_text("Mood").unwrap();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.user_text("MOOD"), None);
+    assert_eq!(reader.user_text("CATALOGNUMBER").as_deref(), Some("ABC-123"));
+}
+
+#[test]
+fn test_unique_file_id_by_owner() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("ufid.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let mbid = "5b11f4ce-a62d-471e-81fc-a69a8278c7da";
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_unique_file_id(&UniqueFileId::new("http://musicbrainz.org", mbid.as_bytes()).unwrap()).unwrap();
+    writer.set_unique_file_id(&UniqueFileId::new("urn:example", &[0, 1, 2]).unwrap()).unwrap();
+    // Later writes preserve both identifiers
+    writer.set_meta_entry(&MetaEntry::Title, "Still here").unwrap();
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    let ufid = reader.unique_file_id("http://musicbrainz.org").unwrap();
+    assert_eq!(ufid.identifier_str(), Some(mbid));
+    assert_eq!(reader.unique_file_id("urn:example").unwrap().identifier, vec![0, 1, 2]);
+
+    assert!(UniqueFileId::new("owner", &[0; 65]).is_err());
+}
+
+#[test]
+fn test_private_frames_preserved_on_rewrite() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("priv.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let payload = vec![0x00, 0xFF, 0x10, 0x80, 0x00];
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_private_frame(&PrivateFrame::new("WM/MediaClassPrimaryID", payload.clone())).unwrap();
+    writer.set_private_frame(&PrivateFrame::new("com.example.app", vec![1])).unwrap();
+    writer.set_meta_entry(&MetaEntry::Artist, "Rewritten").unwrap();
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    let tag = reader.tag().unwrap();
+    assert_eq!(tag.private_frames_by_owner("WM/MediaClassPrimaryID")[0].data, payload);
+    assert_eq!(reader.private_frames().len(), 2);
+
+    writer.remove_private_frames("com.example.app").unwrap();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.private_frames().len(), 1);
+}
+
+#[test]
+fn test_rating_parse_and_stars() {
+    // 40 bit counter
+    let data = b"user@example.com\0\xC4\x01\x00\x00\x00\x02";
+    let rating = Rating::parse(data).unwrap();
+    assert_eq!(rating.email, "user@example.com");
+    assert_eq!(rating.stars(), 4);
+    assert_eq!(rating.play_count, Some(0x01_0000_0002));
+    assert_eq!(rating.to_frame_data(), data.to_vec());
+
+    for stars in 0..=5 {
+        assert_eq!(Rating::from_stars("", stars).stars(), stars);
+    }
+
+    // Counters shorter than the four bytes the spec asks for
+    assert_eq!(Rating::parse(b"\0\x80\x01\x02").unwrap().play_count, Some(0x0102));
+    assert_eq!(Rating::parse(b"\0\x80").unwrap().play_count, None);
+    assert_eq!(decode_counter(&[]), 0);
+    assert_eq!(decode_counter(&[7]), 7);
+}
+
+#[test]
+fn test_rating_round_trip() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("popm.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let mut rating = Rating::from_stars("Windows Media Player 9 Series", 3);
+    rating.play_count = Some(7);
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_rating(&rating).unwrap();
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.rating("Windows Media Player 9 Series"), Some(rating));
+}
+
+#[test]
+fn test_increment_play_count() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("pcnt.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    assert_eq!(increment_play_count(&test_file).unwrap(), 1);
+    assert_eq!(increment_play_count(&test_file).unwrap(), 2);
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_play_count(u32::MAX as u64).unwrap();
+    assert_eq!(increment_play_count(&test_file).unwrap(), u32::MAX as u64 + 1);
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.play_count(), Some(u32::MAX as u64 + 1));
+    assert_eq!(reader.tag().unwrap().frames("PCNT")[0].data().len(), 5);
+}
+
+#[test]
+fn test_chapters_with_sub_frames() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("podcast.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let mut intro = Chapter::new("chp0", 0, 30_000);
+    intro.set_title("Intro", Version::V3);
+    intro.set_picture(&Picture::front_cover(JPEG.to_vec()), Version::V3);
+    let mut main = Chapter::new("chp1", 30_000, 90_000);
+    main.set_title("Hauptteil – Gespräch", Version::V3);
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.add_chapter(&intro).unwrap();
+    writer.add_chapter(&main).unwrap();
+    writer
+        .set_table_of_contents(&TableOfContents::new("toc", vec!["chp0".to_string(), "chp1".to_string()]))
+        .unwrap();
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    let chapters = reader.chapters();
+    assert_eq!(chapters.len(), 2);
+    let read_intro = chapters.iter().find(|c| c.element_id == "chp0").unwrap();
+    assert_eq!(read_intro.title().as_deref(), Some("Intro"));
+    assert_eq!(read_intro.picture(Version::V3).unwrap().data, JPEG);
+    assert_eq!(read_intro.start_offset, None);
+    let read_main = chapters.iter().find(|c| c.element_id == "chp1").unwrap();
+    assert_eq!(read_main.title().as_deref(), Some("Hauptteil – Gespräch"));
+    assert_eq!(read_main.end_time, 90_000);
+
+    writer.remove_chapter("chp0").unwrap();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.chapters().len(), 1);
+    assert_eq!(reader.tables_of_contents()[0].child_element_ids, vec!["chp1".to_string()]);
+}
+
+#[test]
+fn test_podcast_frames() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("episode.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_podcast(true).unwrap();
+    writer.set_meta_entry(&MetaEntry::PodcastDescription, "Episode 12").unwrap();
+    writer.set_meta_entry(&MetaEntry::PodcastId, "urn:uuid:1234").unwrap();
+    writer.set_meta_entry(&MetaEntry::PodcastFeedUrl, "https://example.com/feed.xml").unwrap();
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    assert!(reader.is_podcast());
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::PodcastDescription).unwrap(), "Episode 12");
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::PodcastId).unwrap(), "urn:uuid:1234");
+    assert_eq!(
+        reader.get_meta_entry(&test_file, &MetaEntry::PodcastFeedUrl).unwrap(),
+        "https://example.com/feed.xml"
+    );
+
+    writer.set_podcast(false).unwrap();
+    reader.init(&test_file).unwrap();
+    assert!(!reader.is_podcast());
+}
+
+#[test]
+fn test_music_cd_identifier_survives_rewrite() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("mcdi.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    // CD TOC header and a lead-out track descriptor; not valid text in any encoding
+    let toc = [0x00, 0x0A, 0x01, 0x01, 0x00, 0x14, 0xAA, 0x00, 0x00, 0x03, 0x8F, 0xC2];
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_music_cd_identifier(&toc).unwrap();
+    writer.set_meta_entry(&MetaEntry::Album, "Rewritten").unwrap();
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.music_cd_identifier(), Some(&toc[..]));
+}
+
+#[test]
+fn test_itunes_frames_round_trip() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("itunes.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let mut writer = TagWriter::new(&test_file, TagType::Id3v2).unwrap();
+    writer.set_meta_entry(&MetaEntry::Compilation, "1").unwrap();
+    writer.set_meta_entry(&MetaEntry::AlbumArtistSortOrder, "Beatles, The").unwrap();
+    writer.set_meta_entry(&MetaEntry::ComposerSortOrder, "Lennon, John").unwrap();
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.tag().unwrap().frames("TCMP").len(), 1);
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Compilation).unwrap(), "1");
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::AlbumArtistSortOrder).unwrap(), "Beatles, The");
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::ComposerSortOrder).unwrap(), "Lennon, John");
+}
+
+/// Write an ID3v2 tag of the given version holding `frames`, followed by a few audio bytes
+fn write_tag_file(path: &std::path::Path, version: u8, frames: &[(&str, Vec<u8>)]) {
+    let mut body = Vec::new();
+    for (id, data) in frames {
+        body.extend_from_slice(id.as_bytes());
+        body.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        body.extend_from_slice(&[0, 0]);
+        body.extend_from_slice(data);
+    }
+    let mut file = vec![b'I', b'D', b'3', version, 0, 0, 0, 0, 0, body.len() as u8];
+    file.extend_from_slice(&body);
+    file.extend_from_slice(&[0xFF, 0xFB, 0x90, 0x44]);
+    std::fs::write(path, file).unwrap();
+}
+
+#[test]
+fn test_multi_value_text_frames() {
+    let temp_dir = tempdir().unwrap();
+    let v4_file = temp_dir.path().join("v24.mp3");
+    write_tag_file(&v4_file, 4, &[("TPE1", b"\x03Artist A\0Artist B".to_vec())]);
+
+    let reader = TagReader::new(&v4_file).unwrap();
+    assert_eq!(
+        reader.get_meta_entry_values(&MetaEntry::Artist).unwrap().unwrap(),
+        vec!["Artist A".to_string(), "Artist B".to_string()]
+    );
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap().as_deref(), Some("Artist A/Artist B"));
+
+    // UTF-16 values each carry a byte order mark
+    let mut artists = vec![0x01, 0xFF, 0xFE];
+    artists.extend(utf16("A", false));
+    artists.extend_from_slice(&[0, 0, 0xFF, 0xFE]);
+    artists.extend(utf16("B", false));
+    write_tag_file(&v4_file, 4, &[("TPE1", artists)]);
+    let reader = TagReader::new(&v4_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap().as_deref(), Some("A/B"));
+
+    let values = vec!["One".to_string(), "Two".to_string()];
+    let mut writer = Id3v2Writer::new();
+    writer.init(&v4_file).unwrap();
+    writer.set_meta_entry_values(&MetaEntry::Composer, &values).unwrap();
+    let reader = TagReader::new(&v4_file).unwrap();
+    assert_eq!(reader.get_meta_entry_values(&MetaEntry::Composer).unwrap().unwrap(), values);
+
+    // ID3v2.3 has no multi-value support; values are joined with the configured separator
+    let v3_file = temp_dir.path().join("v23.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &v3_file).unwrap();
+    let mut writer = Id3v2Writer::new();
+    writer.init(&v3_file).unwrap();
+    writer.set_multi_value_separator("; ");
+    writer.set_meta_entry_values(&MetaEntry::Artist, &values).unwrap();
+    let reader = TagReader::new(&v3_file).unwrap();
+    assert_eq!(reader.get_meta_entry_values(&MetaEntry::Artist).unwrap().unwrap(), vec!["One; Two".to_string()]);
+}
+
+fn utf16(text: &str, big_endian: bool) -> Vec<u8> {
+    text.encode_utf16()
+        .flat_map(|unit| if big_endian { unit.to_be_bytes() } else { unit.to_le_bytes() })
+        .collect()
+}
+
+#[test]
+fn test_utf16_text_frames_decoded() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("utf16.mp3");
+
+    let mut le = vec![0x01, 0xFF, 0xFE];
+    le.extend(utf16("Björk", false));
+    le.extend([0, 0]);
+    let mut be = vec![0x01, 0xFE, 0xFF];
+    be.extend(utf16("Ünïcödé ★", true));
+    let mut be_no_bom = vec![0x02];
+    be_no_bom.extend(utf16("Ελληνικά", true));
+    let mut utf8 = vec![0x03];
+    utf8.extend("日本語".as_bytes());
+    write_tag_file(
+        &test_file,
+        4,
+        &[("TPE1", le), ("TIT2", be), ("TALB", be_no_bom), ("TCOM", utf8)],
+    );
+
+    let reader = TagReader::new(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap().as_deref(), Some("Björk"));
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Ünïcödé ★"));
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Album).unwrap().as_deref(), Some("Ελληνικά"));
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Composer).unwrap().as_deref(), Some("日本語"));
+}
+
+#[test]
+fn test_selectable_text_encoding() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("encoding.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    // Without an explicit choice, non-ASCII text is written as UTF-16 in ID3v2.3
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_meta_entry(&MetaEntry::Artist, "Sigur Rós").unwrap();
+    writer.set_meta_entry(&MetaEntry::Album, "Takk").unwrap();
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.tag().unwrap().frames("TPE1")[0].data()[0], TextEncoding::Utf16.as_byte());
+    assert_eq!(reader.tag().unwrap().frames("TALB")[0].data()[0], TextEncoding::Latin1.as_byte());
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Artist).unwrap(), "Sigur Rós");
+
+    writer.set_text_encoding(Some(TextEncoding::Utf16Be));
+    assert!(writer.set_meta_entry(&MetaEntry::Title, "Hoppípolla").is_err());
+
+    writer.set_text_encoding(Some(TextEncoding::Latin1));
+    writer.set_meta_entry(&MetaEntry::Title, "Hoppípolla").unwrap();
+    assert!(writer.set_meta_entry(&MetaEntry::Composer, "ヨンシー").is_err());
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.tag().unwrap().frames("TIT2")[0].data(), b"\x00Hopp\xEDpolla");
+}
+
+#[test]
+fn test_latin1_text_frames_decoded() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("latin1.mp3");
+    write_tag_file(
+        &test_file,
+        3,
+        &[("TPE1", b"\x00Mot\xF6rhead".to_vec()), ("TIT2", b"\x00Caf\xE9 \xC0 la cr\xE8me\x00".to_vec())],
+    );
+
+    let reader = TagReader::new(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap().as_deref(), Some("Motörhead"));
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Café À la crème"));
+}
+
+#[test]
+fn test_frame_sizes_follow_tag_version() {
+    let long_title = "x".repeat(300);
+    let mut payload = vec![0x00];
+    payload.extend_from_slice(long_title.as_bytes());
+
+    // ID3v2.4 sizes are syncsafe: 301 = 0b10_0101101
+    let v4 = Frame::from_data("TIT2", payload.clone()).to_bytes(Version::V4);
+    assert_eq!(&v4[4..8], &[0, 0, 0x02, 0x2D]);
+    let v3 = Frame::from_data("TIT2", payload.clone()).to_bytes(Version::V3);
+    assert_eq!(&v3[4..8], &301u32.to_be_bytes());
+    let v2 = Frame::from_data("TT2", payload).to_bytes(Version::V2);
+    assert_eq!(&v2[..6], b"TT2\x00\x01\x2D");
+
+    assert_eq!(Frame::parse(&v4, Version::V4).unwrap().content, long_title);
+    assert_eq!(Frame::parse(&v2, Version::V2).unwrap().content, long_title);
+
+    // A v2.4 tag with a frame larger than 127 bytes survives a write and read
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("v24.mp3");
+    write_tag_file(&test_file, 4, &[("TPE1", b"\x03Artist".to_vec())]);
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_meta_entry(&MetaEntry::Title, &long_title).unwrap();
+    writer.set_meta_entry(&MetaEntry::Album, "Album").unwrap();
+
+    let reader = TagReader::new(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().unwrap(), long_title);
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Album).unwrap().as_deref(), Some("Album"));
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap().as_deref(), Some("Artist"));
+}
+
+#[test]
+fn test_extended_header_and_crc() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("extended.mp3");
+    let frames = Frame::new("TIT2", "Title").to_bytes(Version::V3);
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+    let write_v3 = |crc: u32| {
+        let mut tag = vec![0, 0, 0, 10, 0x80, 0, 0, 0, 0, 0];
+        tag.extend_from_slice(&crc.to_be_bytes());
+        tag.extend_from_slice(&frames);
+        let mut file = vec![b'I', b'D', b'3', 3, 0, FLAG_EXTENDED_HEADER, 0, 0, 0, tag.len() as u8];
+        file.extend_from_slice(&tag);
+        file.extend_from_slice(&[0xFF, 0xFB, 0x90, 0x44]);
+        std::fs::write(&test_file, file).unwrap();
+    };
+
+    write_v3(crc32(&frames));
+    let reader = TagReader::new(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Title"));
+
+    write_v3(crc32(&frames) ^ 1);
+    let mut reader = Id3v2Reader::new();
+    assert!(matches!(reader.init(&test_file), Err(Error::CrcMismatch(_, _))));
+
+    // The writer replaces the stale CRC with a fresh one
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_write_crc(true);
+    writer.set_meta_entry(&MetaEntry::Artist, "Artist").unwrap();
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Title).unwrap(), "Title");
+
+    // ID3v2.4 stores the CRC as a 35 bit syncsafe integer
+    let header = ExtendedHeader::with_crc(0xFFFF_FFFF);
+    let bytes = header.to_bytes(Version::V4);
+    assert_eq!(bytes, [0, 0, 0, 12, 1, 0x20, 5, 0x0F, 0x7F, 0x7F, 0x7F, 0x7F]);
+    assert_eq!(ExtendedHeader::parse(&bytes, Version::V4).unwrap().crc, Some(0xFFFF_FFFF));
+}
+
+#[test]
+fn test_url_frames_have_no_encoding_byte() {
+    let bytes = b"WOAR\x00\x00\x00\x13\x00\x00http://example.com/".to_vec();
+    let frame = Frame::parse(&bytes, Version::V3).unwrap();
+    assert_eq!(frame.content, "http://example.com/");
+    assert_eq!(frame.text().as_deref(), Some("http://example.com/"));
+    assert_eq!(Frame::new("WOAR", "http://example.com/").to_bytes(Version::V3), bytes);
+
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("url.mp3");
+    write_tag_file(&test_file, 3, &[("WFED", b"https://example.com/feed".to_vec())]);
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::PodcastFeedUrl).unwrap(), "https://example.com/feed");
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_meta_entry(&MetaEntry::PodcastFeedUrl, "https://example.org/").unwrap();
+    let tag = crate::id3::Id3v2Tag::parse(&std::fs::read(&test_file).unwrap()).unwrap();
+    assert_eq!(tag.frames("WFED")[0].data(), b"https://example.org/");
+}
+
+#[test]
+fn test_frame_flags_parsed() {
+    let mut bytes = Frame::new("TIT2", "Title").to_bytes(Version::V3);
+    bytes[8] = 0xA0;
+    let frame = Frame::parse(&bytes, Version::V3).unwrap();
+    assert!(frame.flags.tag_alter_preservation && frame.flags.read_only);
+    assert!(!frame.flags.file_alter_preservation && !frame.flags.compression);
+    assert_eq!(frame.content, "Title");
+}
+
+#[test]
+#[cfg(not(feature = "compression"))]
+fn test_compressed_frame_kept_without_feature() {
+    // Inflated size followed by a (fake) zlib stream
+    let mut bytes = b"TIT2\x00\x00\x00\x08\x00\x80".to_vec();
+    bytes.extend_from_slice(&[0, 0, 0, 20, 0x78, 0x9C, 1, 2]);
+    let frame = Frame::parse(&bytes, Version::V3).unwrap();
+    assert!(frame.flags.compression);
+    assert_eq!(frame.content, "");
+    assert_eq!(frame.to_bytes(Version::V3), bytes);
+
+    let mut writer = Id3v2Writer::new();
+    assert!(writer.set_compress_frames(true).is_err());
+}
+
+#[test]
+#[cfg(feature = "compression")]
+fn test_compressed_frames_round_trip() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("compressed.mp3");
+    let lyrics = "la ".repeat(200);
+
+    for version in [3, 4] {
+        write_tag_file(&test_file, version, &[("TPE1", b"\x00Artist".to_vec())]);
+        let mut writer = Id3v2Writer::new();
+        writer.init(&test_file).unwrap();
+        writer.set_compress_frames(true).unwrap();
+        writer.set_padding(0);
+        writer.set_meta_entry(&MetaEntry::Title, &lyrics).unwrap();
+
+        let mut reader = Id3v2Reader::new();
+        reader.init(&test_file).unwrap();
+        let tag = reader.tag().unwrap();
+        assert!(tag.frames("TIT2")[0].flags.compression);
+        assert!(!tag.frames("TPE1")[0].flags.compression);
+        assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Title).unwrap(), lyrics);
+        assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Artist).unwrap(), "Artist");
+        assert!(std::fs::metadata(&test_file).unwrap().len() < lyrics.len() as u64);
+    }
+}
+
+#[test]
+#[cfg(feature = "compression")]
+fn test_compressed_frame_inflating_past_its_size_is_kept() {
+    use crate::id3::v2::compression::{deflate, inflate};
+
+    let bomb = deflate(&vec![0u8; 1 << 20]).unwrap();
+    assert!(inflate(&bomb, 1 << 20).is_ok());
+    assert!(inflate(&bomb, 10).is_err());
+
+    // Declares 10 inflated bytes, but inflates to a megabyte
+    let mut bytes = b"TIT2".to_vec();
+    bytes.extend_from_slice(&(4 + bomb.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(&[0x00, 0x80, 0, 0, 0, 10]);
+    bytes.extend_from_slice(&bomb);
+    let frame = Frame::parse(&bytes, Version::V3).unwrap();
+    assert_eq!(frame.content, "");
+    assert_eq!(frame.to_bytes(Version::V3), bytes);
+}
+
+#[test]
+fn test_frame_flags_preserved() {
+    // ID3v2.4: tag alter preservation + read only, grouping + data length indicator
+    let mut v4 = b"TIT2\x00\x00\x00\x0B\x50\x41".to_vec();
+    v4.extend_from_slice(b"\x42\x00\x00\x00\x06\x00Title");
+    let frame = Frame::parse(&v4, Version::V4).unwrap();
+    assert!(frame.flags.tag_alter_preservation && frame.flags.read_only);
+    assert!(frame.flags.grouping_identity && frame.flags.data_length_indicator);
+    assert_eq!(frame.group_id(), Some(0x42));
+    assert_eq!(frame.content, "Title");
+    assert_eq!(frame.to_bytes(Version::V4), v4);
+
+    // ID3v2.3: file alter preservation + grouping
+    let v3 = b"TALB\x00\x00\x00\x07\x40\x20\x07\x00Album".to_vec();
+    let frame = Frame::parse(&v3, Version::V3).unwrap();
+    assert!(frame.flags.file_alter_preservation && frame.flags.grouping_identity);
+    assert_eq!(frame.content, "Album");
+    assert_eq!(frame.to_bytes(Version::V3), v3);
+
+    // Unsynchronised ID3v2.4 payload
+    let unsync = b"PRIV\x00\x00\x00\x06\x00\x02a\x00\xFF\x00\x00\x01".to_vec();
+    let frame = Frame::parse(&unsync, Version::V4).unwrap();
+    assert_eq!(frame.data(), b"a\0\xFF\0\x01");
+
+    // Flags survive a rewrite of the tag
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("flags.mp3");
+    write_tag_file(&test_file, 4, &[]);
+    let mut file = std::fs::read(&test_file).unwrap();
+    file.splice(10..10, v4.iter().copied());
+    file[9] = v4.len() as u8;
+    std::fs::write(&test_file, file).unwrap();
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_meta_entry(&MetaEntry::Album, "Album").unwrap();
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    let title = &reader.tag().unwrap().frames("TIT2")[0];
+    assert!(title.flags.read_only && title.flags.data_length_indicator);
+    assert_eq!(title.group_id(), Some(0x42));
+}
+
+#[test]
+fn test_convert_version() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("convert.mp3");
+    write_tag_file(
+        &test_file,
+        3,
+        &[
+            ("TYER", b"\x002021".to_vec()),
+            ("TDAT", b"\x001503".to_vec()),
+            ("TIME", b"\x001230".to_vec()),
+            ("TORY", b"\x001999".to_vec()),
+            ("TPE1", b"\x00Artist".to_vec()),
+        ],
+    );
+
+    convert_version(&test_file, Id3v2Version::V4).unwrap();
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    let tag = reader.tag().unwrap();
+    assert_eq!(tag.version(), Version::V4);
+    assert_eq!(tag.frames("TDRC")[0].text().unwrap(), "2021-03-15T12:30");
+    assert_eq!(tag.frames("TDOR")[0].text().unwrap(), "1999");
+    assert!(tag.frames("TYER").is_empty() && tag.frames("TORY").is_empty());
+    assert_eq!(tag.frames("TPE1")[0].content, "Artist");
+
+    // UTF-8 does not exist in ID3v2.3, so the text is re-encoded
+    write_tag_file(
+        &test_file,
+        4,
+        &[("TDRC", b"\x032020-12-01".to_vec()), ("TIT2", "\x03Ünï\0Côdé".as_bytes().to_vec())],
+    );
+    convert_version(&test_file, Id3v2Version::V3).unwrap();
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    let tag = reader.tag().unwrap();
+    assert_eq!(tag.version(), Version::V3);
+    assert_eq!(tag.frames("TYER")[0].text().unwrap(), "2020");
+    assert_eq!(tag.frames("TDAT")[0].text().unwrap(), "0112");
+    assert!(tag.frames("TIME").is_empty() && tag.frames("TDRC").is_empty());
+    assert_eq!(tag.frames("TIT2")[0].data()[0], TextEncoding::Utf16.as_byte());
+    assert_eq!(tag.frames("TIT2")[0].content, "Ünï/Côdé");
+
+    assert!(convert_version(&test_file, Id3v2Version::V2).is_err());
+}
+
+#[test]
+fn test_convert_to_v3_drops_v4_only_frames() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("convert_v3.mp3");
+    write_tag_file(
+        &test_file,
+        4,
+        &[
+            ("TPE1", b"\x00One\0Two".to_vec()),
+            ("TIT2", "\x03Tïtle".as_bytes().to_vec()),
+            ("TSOP", b"\x00Sort".to_vec()),
+            ("TDRL", b"\x002020".to_vec()),
+            ("TMOO", b"\x00Calm".to_vec()),
+            ("RVA2", b"track\0\x01\x00\x00\x00".to_vec()),
+        ],
+    );
+    convert_version(&test_file, Id3v2Version::V3).unwrap();
+
+    let tag = crate::id3::Id3v2Tag::parse(&std::fs::read(&test_file).unwrap()).unwrap();
+    assert_eq!(tag.version(), Version::V3);
+    let mut ids: Vec<_> = tag.frame_ids().collect();
+    ids.sort_unstable();
+    assert_eq!(ids, ["TIT2", "TPE1", "TSOP"]);
+    assert_eq!(tag.frames("TSOP")[0].data(), b"\x00Sort");
+    assert_eq!(tag.frames("TPE1")[0].data(), b"\x00One/Two");
+    assert_eq!(tag.frames("TIT2")[0].data()[0], TextEncoding::Utf16.as_byte());
+    assert_eq!(tag.frames("TIT2")[0].content, "Tïtle");
+}
+
+#[test]
+fn test_convert_to_v3_reencodes_utf8_frames() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("convert_utf8.mp3");
+    write_tag_file(
+        &test_file,
+        4,
+        &[
+            ("WXXX", "\x03Lïnk\0http://example.com".as_bytes().to_vec()),
+            ("USER", "\x03engTérms".as_bytes().to_vec()),
+            ("COMR", b"\x03EUR1\x0020301231\x00\x00Seller\x00Offer\x00".to_vec()),
+        ],
+    );
+    convert_version(&test_file, Id3v2Version::V3).unwrap();
+
+    let tag = crate::id3::Id3v2Tag::parse(&std::fs::read(&test_file).unwrap()).unwrap();
+    let mut ids: Vec<_> = tag.frame_ids().collect();
+    ids.sort_unstable();
+    assert_eq!(ids, ["USER", "WXXX"]);
+    let mut wxxx = vec![TextEncoding::Utf16.as_byte()];
+    wxxx.extend_from_slice(&TextEncoding::Utf16.encode_terminated("Lïnk"));
+    wxxx.extend_from_slice(b"http://example.com");
+    assert_eq!(tag.frames("WXXX")[0].data(), &wxxx[..]);
+    let mut user = vec![TextEncoding::Utf16.as_byte()];
+    user.extend_from_slice(b"eng");
+    user.extend_from_slice(&TextEncoding::Utf16.encode("Térms"));
+    assert_eq!(tag.frames("USER")[0].data(), &user[..]);
+}
+
+#[test]
+fn test_in_place_update_keeps_audio() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("in_place.mp3");
+    write_tag_file(&test_file, 3, &[("TIT2", format!("\x00{}", "x".repeat(100)).into_bytes())]);
+    let original = std::fs::read(&test_file).unwrap();
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_meta_entry(&MetaEntry::Title, "Short").unwrap();
+
+    let updated = std::fs::read(&test_file).unwrap();
+    assert_eq!(updated.len(), original.len());
+    assert_eq!(&updated[6..10], &original[6..10]);
+    assert_eq!(&updated[updated.len() - 4..], &[0xFF, 0xFB, 0x90, 0x44]);
+
+    let mut reader = Id3v2Reader::new();
+    reader.init(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Title).unwrap(), "Short");
+}
+
+#[test]
+fn test_configurable_padding() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("padding.mp3");
+    write_tag_file(&test_file, 3, &[("TIT2", b"\x00T".to_vec())]);
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&test_file).unwrap();
+    writer.set_padding(512);
+    writer.set_meta_entry(&MetaEntry::Title, "A longer title").unwrap();
+
+    let frame_size = Frame::new("TIT2", "A longer title").to_bytes(Version::V3).len();
+    let file = std::fs::read(&test_file).unwrap();
+    let tag_size = synchsafe_to_int(&file[6..10]) as usize;
+    assert_eq!(tag_size, frame_size + 512);
+
+    // The padding absorbs the next edit
+    let length = file.len();
+    writer.set_meta_entry(&MetaEntry::Album, "Album").unwrap();
+    assert_eq!(std::fs::read(&test_file).unwrap().len(), length);
+}
+
+#[test]
+fn test_growing_tag_shifts_audio() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("grow.mp3");
+    let audio: Vec<u8> = (0..=255u8).cycle().take(5000).collect();
+
+    // A file with a small tag and one without any tag
+    write_tag_file(&test_file, 3, &[("TIT2", b"\x00T".to_vec())]);
+    let mut with_tag = std::fs::read(&test_file).unwrap();
+    with_tag.truncate(with_tag.len() - 4);
+    with_tag.extend_from_slice(&audio);
+
+    for original in [with_tag, audio.clone()] {
+        std::fs::write(&test_file, &original).unwrap();
+        let mut writer = Id3v2Writer::new();
+        writer.init(&test_file).unwrap();
+        writer.set_padding(0);
+        writer.set_meta_entry(&MetaEntry::Title, &"long title ".repeat(20)).unwrap();
+
+        let updated = std::fs::read(&test_file).unwrap();
+        let tag_size = 10 + synchsafe_to_int(&updated[6..10]) as usize;
+        assert_eq!(&updated[tag_size..], audio.as_slice());
+        assert!(!temp_dir.path().join("grow.mp3.tmp").exists());
+
+        let mut reader = Id3v2Reader::new();
+        reader.init(&test_file).unwrap();
+        assert_eq!(reader.get_meta_entry(&test_file, &MetaEntry::Title).unwrap(), "long title ".repeat(20));
+    }
+}
+
+#[test]
+fn test_concurrent_increments_are_not_lost() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("locked.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let path = test_file.clone();
+            std::thread::spawn(move || {
+                let mut writer = Id3v2Writer::new();
+                writer.init(&path).unwrap();
+                for _ in 0..5 {
+                    writer.increment_play_count().unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(increment_play_count(&test_file).unwrap(), 21);
+}
+
+#[test]
+fn test_tcon_genre_references() {
+    use crate::id3::genre::{format_tcon, parse_tcon, GenreFormat};
+
+    assert_eq!(parse_tcon("(13)"), ["Pop"]);
+    assert_eq!(parse_tcon("(13)Pop"), ["Pop"]);
+    assert_eq!(parse_tcon("(21)Eurodisco"), ["Eurodisco"]);
+    assert_eq!(parse_tcon("(13)(17)"), ["Pop", "Rock"]);
+    assert_eq!(parse_tcon("(RX)(CR)"), ["Remix", "Cover"]);
+    assert_eq!(parse_tcon("((Bracketed)"), ["(Bracketed)"]);
+    assert_eq!(parse_tcon("17\0Jazz\0Rock"), ["Rock", "Jazz"]);
+    assert_eq!(format_tcon("rock", GenreFormat::Combined, Version::V3), "(17)Rock");
+    assert_eq!(format_tcon("Rock", GenreFormat::Numeric, Version::V4), "17");
+    assert_eq!(format_tcon("Chiptune", GenreFormat::Numeric, Version::V3), "Chiptune");
+
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("tcon.mp3");
+    write_tag_file(&path, 3, &[("TCON", b"\x00(13)".to_vec())]);
+    let reader = TagReader::new(&path).unwrap();
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Genre).unwrap().as_deref(), Some("Pop"));
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&path).unwrap();
+    writer.set_genre_format(Some(GenreFormat::Numeric));
+    writer.set_meta_entry(&MetaEntry::Genre, "Hip Hop").unwrap();
+    let data = std::fs::read(&path).unwrap();
+    assert!(data.windows(4).any(|window| window == b"\x00(7)"));
+    let reader = TagReader::new(&path).unwrap();
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Genre).unwrap().as_deref(), Some("Hip-Hop"));
+}
+
+#[test]
+fn test_people_lists() {
+    let pairs = |values: &[(&str, &str)]| -> Vec<(String, String)> {
+        values.iter().map(|(role, name)| (role.to_string(), name.to_string())).collect()
+    };
+    let people = pairs(&[("producer", "George Martin"), ("engineer", "Geoff Emerick")]);
+    let musicians = pairs(&[("bass", "Paul McCartney")]);
+
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("people.mp3");
+    write_tag_file(&path, 3, &[("IPLS", b"\x00producer\0George Martin\0engineer\0Geoff Emerick\0".to_vec())]);
+    let mut reader = Id3v2Reader::new();
+    reader.init(&path).unwrap();
+    assert_eq!(reader.involved_people(), people);
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&path).unwrap();
+    assert!(matches!(writer.set_musician_credits(&musicians), Err(Error::InvalidTagVersion(_))));
+    writer.convert_version(Version::V4).unwrap();
+    writer.set_musician_credits(&musicians).unwrap();
+    reader.init(&path).unwrap();
+    let tag = reader.tag().unwrap();
+    assert!(tag.frames("IPLS").is_empty());
+    assert_eq!(tag.involved_people(), people);
+    assert_eq!(tag.musician_credits(), musicians);
+
+    // ID3v2.3 merges both lists into IPLS
+    writer.convert_version(Version::V3).unwrap();
+    reader.init(&path).unwrap();
+    assert!(reader.musician_credits().is_empty());
+    assert_eq!(reader.involved_people(), [people.clone(), musicians].concat());
+
+    writer.set_involved_people(&people[..1]).unwrap();
+    reader.init(&path).unwrap();
+    assert_eq!(reader.involved_people(), people[..1]);
+    writer.set_involved_people(&[]).unwrap();
+    reader.init(&path).unwrap();
+    assert!(reader.involved_people().is_empty());
+}
+
+#[test]
+fn test_frame_iterator_streams_huge_tags() {
+    use crate::id3::FrameIter;
+
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("frames.mp3");
+    write_tag_file(&path, 3, &[("TIT2", b"\x00Title".to_vec()), ("TPE1", b"\x00Artist".to_vec())]);
+    let frames: Vec<Frame> = FrameIter::open(&path).unwrap().collect::<crate::Result<_>>().unwrap();
+    assert_eq!(frames.iter().map(|frame| frame.content.as_str()).collect::<Vec<_>>(), ["Title", "Artist"]);
+
+    // A tag declaring 256MB, cut off after its frames, yields what is there
+    let mut data = std::fs::read(&path).unwrap();
+    data[6..10].copy_from_slice(&[0x7F; 4]);
+    std::fs::write(&path, &data).unwrap();
+    let mut frames = FrameIter::open(&path).unwrap();
+    assert_eq!(frames.version(), Version::V3);
+    assert_eq!(frames.next().unwrap().unwrap().content, "Title");
+    assert_eq!(frames.next().unwrap().unwrap().content, "Artist");
+    assert!(frames.next().unwrap().is_err());
+    assert!(frames.next().is_none());
+
+    assert!(matches!(FrameIter::new(&b"not a tag"[..]), Err(Error::TagNotFound)));
+}
+
+#[test]
+fn test_parsed_frames_share_the_tag_buffer() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("shared.mp3");
+    write_tag_file(&path, 3, &[("TIT2", b"\x00Title".to_vec()), ("TPE1", b"\x00Artist".to_vec())]);
+    let tag = crate::id3::Id3v2Tag::parse(&std::fs::read(&path).unwrap()).unwrap();
+
+    // Payloads are consecutive ranges of one buffer rather than copies
+    let title = &tag.frames("TIT2")[0];
+    let artist = &tag.frames("TPE1")[0];
+    assert_eq!(title.data(), b"\x00Title");
+    assert_eq!(title.data().as_ptr().wrapping_add(title.data().len() + 10), artist.data().as_ptr());
+    assert_eq!(title.clone().data().as_ptr(), title.data().as_ptr());
+    assert!(format!("{:?}", title).contains(&format!("data: {:?}", b"\x00Title")));
+}
+
+#[test]
+fn test_every_standard_entry_has_one_frame() {
+    use crate::id3::v2::frame_mapping::v3_v4;
+
+    for entry in crate::meta_entry::all_standard_entries() {
+        assert!(v3_v4::get_frame_id(&entry).is_some(), "{}", entry);
+    }
+    assert!(v3_v4::is_supported_frame("PCST"));
+    assert!(crate::id3::v2::frame_mapping::v2_0::is_supported_frame("TT2"));
+}
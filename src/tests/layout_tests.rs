@@ -0,0 +1,73 @@
+use crate::id3::v2::tag::TagWriter as Id3v2Writer;
+use crate::layout::{layout, Structure, StructureKind};
+use crate::tag::TagWriterStrategy;
+use crate::MetaEntry;
+use tempfile::tempdir;
+
+#[test]
+fn test_layout_of_synthetic_file() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("layout.mp3");
+
+    // ID3v2.3 tag with one 5 byte frame and 15 bytes of padding
+    let mut data = vec![b'I', b'D', b'3', 3, 0, 0, 0, 0, 0, 30];
+    data.extend_from_slice(b"TIT2\0\0\0\x05\0\0\0Song");
+    data.extend_from_slice(&[0; 15]);
+    // Zero bytes and a frame before the audio
+    data.extend_from_slice(&[0; 6]);
+    let mut frame = vec![0xFF, 0xFB, 0x90, 0x04];
+    frame.resize(417, 0);
+    data.extend_from_slice(&frame.repeat(3));
+    // Lyrics3v2, TAG+ and ID3v1 behind
+    data.extend_from_slice(b"LYRICSBEGININD00003110000022LYRICS200");
+    let mut enhanced = vec![0u8; 227];
+    enhanced[..4].copy_from_slice(b"TAG+");
+    data.extend_from_slice(&enhanced);
+    let mut id3v1 = vec![0u8; 128];
+    id3v1[..3].copy_from_slice(b"TAG");
+    data.extend_from_slice(&id3v1);
+    std::fs::write(&path, &data).unwrap();
+
+    let map = layout(&path).unwrap();
+    let expected = [
+        (StructureKind::Id3v2, 0, 25),
+        (StructureKind::Padding, 25, 15),
+        (StructureKind::Padding, 40, 6),
+        (StructureKind::Audio, 46, 1251),
+        (StructureKind::Lyrics3, 1297, 37),
+        (StructureKind::EnhancedId3v1, 1334, 227),
+        (StructureKind::Id3v1, 1561, 128),
+    ]
+    .map(|(kind, offset, size)| Structure { kind, offset, size });
+    assert_eq!(map.structures, expected);
+    assert_eq!(map.file_size, data.len() as u64);
+    assert_eq!(map.audio().unwrap().offset, 46);
+    assert_eq!(map.structures.last().unwrap().end(), map.file_size);
+
+    // Junk in front of the audio is not padding, and a file without audio frames is unknown data
+    data[40] = 1;
+    std::fs::write(&path, &data).unwrap();
+    assert_eq!(layout(&path).unwrap().of_kind(StructureKind::Unknown).next().unwrap().size, 6);
+    std::fs::write(&path, b"not audio at all").unwrap();
+    let map = layout(&path).unwrap();
+    assert_eq!(map.structures, [Structure { kind: StructureKind::Unknown, offset: 0, size: 16 }]);
+}
+
+#[test]
+fn test_layout_of_written_tags() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("written.mp3");
+    let mut frame = vec![0xFF, 0xFB, 0x90, 0x04];
+    frame.resize(417, 0);
+    std::fs::write(&path, frame.repeat(4)).unwrap();
+    let mut writer = Id3v2Writer::new();
+    writer.init(&path).unwrap();
+    writer.set_meta_entry(&MetaEntry::Title, "Layout").unwrap();
+
+    let map = layout(&path).unwrap();
+    let kinds: Vec<_> = map.structures.iter().map(|structure| structure.kind).collect();
+    assert_eq!(kinds, [StructureKind::Id3v2, StructureKind::Padding, StructureKind::Audio]);
+    assert_eq!(map.structures[1].end(), map.structures[2].offset);
+    assert_eq!(map.audio().unwrap().size, 4 * 417);
+    assert_eq!(crate::audio_range(&path).unwrap(), map.audio().unwrap().offset..map.file_size);
+}
@@ -0,0 +1,196 @@
+use crate::library::Library;
+use crate::Tag;
+use tempfile::tempdir;
+
+#[test]
+fn test_scan_save_and_load() {
+    let temp_dir = tempdir().unwrap();
+    std::fs::create_dir(temp_dir.path().join("live")).unwrap();
+    for name in ["a.mp3", "live/b.mp3"] {
+        std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", temp_dir.path().join(name)).unwrap();
+    }
+    Tag::open(temp_dir.path().join("a.mp3"))
+        .unwrap()
+        .title("Tab\there")
+        .artist("Line\nbreak \\ slash")
+        .year("1959")
+        .save()
+        .unwrap();
+
+    let library = Library::scan(temp_dir.path()).unwrap();
+    assert_eq!(library.len(), 2);
+    let entry = library.get(temp_dir.path().join("a.mp3")).unwrap();
+    assert_eq!(entry.metadata.title.as_deref(), Some("Tab\there"));
+    assert_eq!(entry.metadata.year, Some(1959));
+
+    let index = temp_dir.path().join("library.idx");
+    library.save(&index).unwrap();
+    assert_eq!(Library::load(&index).unwrap(), library);
+
+    std::fs::write(&index, "not an index\n").unwrap();
+    assert!(Library::load(&index).is_err());
+
+    // Times beyond what the platform can hold are rejected instead of panicking
+    for times in [format!("{}\t0", u64::MAX), "0\t4294967296".to_string(), "0\t1000000000".to_string()] {
+        std::fs::write(&index, format!("mp3tags_r library 2\nroot\t/music\nfile\ta.mp3\t{}\t1\n", times)).unwrap();
+        assert!(Library::load(&index).is_err(), "{}", times);
+    }
+}
+
+#[test]
+fn test_rescan_reads_only_changed_files() {
+    let temp_dir = tempdir().unwrap();
+    for name in ["a.mp3", "b.mp3", "c.mp3"] {
+        std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", temp_dir.path().join(name)).unwrap();
+    }
+    let mut library = Library::new(temp_dir.path());
+    let report = library.rescan().unwrap();
+    assert_eq!((report.added, report.updated, report.removed), (3, 0, 0));
+
+    Tag::open(temp_dir.path().join("a.mp3")).unwrap().album("Kind of Blue").save().unwrap();
+    std::fs::remove_file(temp_dir.path().join("c.mp3")).unwrap();
+    std::fs::write(temp_dir.path().join("d.mp3"), b"no tags").unwrap();
+
+    let report = library.rescan().unwrap();
+    assert_eq!((report.added, report.updated, report.removed), (1, 1, 1));
+    assert!(report.failed.is_empty());
+    assert_eq!(library.len(), 3);
+    assert_eq!(library.get(temp_dir.path().join("d.mp3")).unwrap().metadata, Default::default());
+    assert_eq!(library.get(temp_dir.path().join("a.mp3")).unwrap().metadata.album.as_deref(), Some("Kind of Blue"));
+
+    // A file gone between listing and reading is reported without losing the other entries
+    let mut files: Vec<_> = ["a.mp3", "b.mp3", "d.mp3"].iter().map(|name| temp_dir.path().join(name)).collect();
+    std::fs::remove_file(&files[0]).unwrap();
+    files.insert(1, temp_dir.path().join("vanished.mp3"));
+    let report = library.rescan_files(&files);
+    assert_eq!((report.added, report.updated, report.removed), (0, 0, 1));
+    assert_eq!(report.failed.iter().map(|(path, _)| path.clone()).collect::<Vec<_>>(), [files[0].clone(), files[1].clone()]);
+    assert_eq!(library.len(), 2);
+    assert!(library.get(temp_dir.path().join("b.mp3")).is_some());
+}
+
+#[test]
+fn test_find() {
+    use crate::library::Field;
+
+    let temp_dir = tempdir().unwrap();
+    let albums = [
+        ("a.mp3", "Miles Davis", "1959", "Jazz"),
+        ("b.mp3", "Miles Davis", "1985", "Jazz"),
+        ("c.mp3", "John Coltrane", "1960", "jazz"),
+        ("d.mp3", "", "", ""),
+    ];
+    for (name, artist, year, genre) in albums {
+        let path = temp_dir.path().join(name);
+        if artist.is_empty() {
+            std::fs::write(&path, b"no tags").unwrap();
+            continue;
+        }
+        std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &path).unwrap();
+        Tag::open(&path).unwrap().artist(artist).year(year).genre(genre).save().unwrap();
+    }
+    let library = Library::scan(temp_dir.path()).unwrap();
+
+    let query = library.find().artist_contains("miles").year_between(1950, 1970);
+    assert_eq!(query.paths(), [temp_dir.path().join("a.mp3")]);
+    assert_eq!(query.entries().next().unwrap().metadata.artist.as_deref(), Some("Miles Davis"));
+    assert_eq!(library.find().genre_is("JAZZ").count(), 3);
+    assert_eq!(library.find().missing(Field::Artist).paths(), [temp_dir.path().join("d.mp3")]);
+    assert_eq!(library.find().count(), 4);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_refresh_keeps_entries_of_unreadable_directories() {
+    use crate::library::LibraryEvent;
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path().join("locked");
+    std::fs::create_dir(&dir).unwrap();
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", dir.join("a.mp3")).unwrap();
+    let mut library = Library::scan(temp_dir.path()).unwrap();
+
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o000)).unwrap();
+    let unreadable = std::fs::read_dir(&dir).is_err();
+    let events = library.refresh(&dir);
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+    // Permissions do not apply to root
+    if unreadable {
+        assert!(matches!(events.as_slice(), [LibraryEvent::Failed(path, _)] if *path == dir));
+    }
+    assert_eq!(library.len(), 1);
+}
+
+#[cfg(feature = "watch")]
+#[test]
+fn test_watcher_follows_changes() {
+    use crate::library::LibraryEvent;
+    use crate::watch::LibraryWatcher;
+    use std::path::PathBuf;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let temp_dir = tempdir().unwrap();
+    let (sender, events) = mpsc::channel();
+    let watcher = LibraryWatcher::new(Library::scan(temp_dir.path()).unwrap(), move |event| {
+        let _ = sender.send(match event {
+            LibraryEvent::Added(entry) => ("added", entry.path.clone()),
+            LibraryEvent::Updated(entry) => ("updated", entry.path.clone()),
+            LibraryEvent::Removed(path) => ("removed", path.clone()),
+            LibraryEvent::Failed(path, _) => ("failed", path.clone()),
+        });
+    })
+    .unwrap();
+    let wait_for = |kind: &str, path: &PathBuf| loop {
+        let event = events.recv_timeout(Duration::from_secs(10)).expect("no event from the watcher");
+        if event == (kind, path.clone()) {
+            break;
+        }
+    };
+
+    let path = temp_dir.path().join("a.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &path).unwrap();
+    wait_for("added", &path);
+    assert!(watcher.library().get(&path).is_some());
+
+    std::fs::remove_file(&path).unwrap();
+    wait_for("removed", &path);
+    assert!(watcher.into_library().is_empty());
+}
+
+#[test]
+fn test_stats() {
+    use crate::library::stats;
+    use crate::TagType;
+
+    let temp_dir = tempdir().unwrap();
+    let tagged = temp_dir.path().join("a.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &tagged).unwrap();
+    // Too long to fit in place, so the tag is rewritten with padding
+    Tag::open(&tagged).unwrap().title(&"Long title ".repeat(40)).genre("Jazz").save().unwrap();
+    let untagged = temp_dir.path().join("b.mp3");
+    std::fs::write(&untagged, b"no tags").unwrap();
+    let missing = temp_dir.path().join("missing.mp3");
+
+    let stats = stats([&tagged, &untagged, &missing]);
+    assert_eq!((stats.files, stats.untagged), (3, 1));
+    assert_eq!(stats.failed.len(), 1);
+    assert_eq!(stats.failed[0].0, missing);
+    assert_eq!(stats.tag_types.get(&TagType::Id3v2), Some(&1));
+    assert_eq!(stats.genres.get("Jazz"), Some(&1));
+    assert_eq!(stats.missing.genre, 1);
+    assert!(stats.missing.title >= 1);
+    assert!(stats.padding > 0);
+
+    // The index answers from its entries, without reading the files again
+    let library = Library::scan(temp_dir.path()).unwrap();
+    std::fs::remove_file(&tagged).unwrap();
+    let indexed = library.stats();
+    assert_eq!((indexed.files, indexed.untagged), (2, 1));
+    assert!(indexed.failed.is_empty());
+    assert_eq!(indexed.tag_types, stats.tag_types);
+    assert_eq!(indexed.tag_versions, stats.tag_versions);
+    assert_eq!(indexed.genres, stats.genres);
+    assert_eq!(indexed.padding, stats.padding);
+}
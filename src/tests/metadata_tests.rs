@@ -0,0 +1,87 @@
+use crate::artwork::{Picture, PictureType};
+use crate::id3::v1::report::FieldIssue;
+use crate::tag::TagType;
+use crate::{Error, MetaEntry, Metadata};
+use std::collections::HashMap;
+use tempfile::tempdir;
+
+const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+fn sample() -> Metadata {
+    let mut metadata = Metadata {
+        title: Some("Typed".to_string()),
+        artist: Some("Artist".to_string()),
+        album_artist: Some("Various".to_string()),
+        year: Some(2004),
+        track_number: Some(3),
+        track_total: Some(12),
+        disc_number: Some(1),
+        genre: Some("Jazz".to_string()),
+        pictures: vec![Picture::new("image/png", PictureType::CoverFront, "cover.png", PNG.to_vec())],
+        ..Default::default()
+    };
+    metadata.custom.insert("MOOD".to_string(), "Calm".to_string());
+    metadata
+}
+
+#[test]
+fn test_metadata_from_entries() {
+    let entries = HashMap::from([
+        (MetaEntry::Title, "Title".to_string()),
+        (MetaEntry::Year, "2004-05-17".to_string()),
+        (MetaEntry::Track, "3/12".to_string()),
+        (MetaEntry::DiscNumber, "x/2".to_string()),
+        (MetaEntry::Comment, String::new()),
+        (MetaEntry::Custom("MOOD".to_string()), "Calm".to_string()),
+    ]);
+    let metadata = Metadata::from_entries(&entries);
+    assert_eq!(metadata.title.as_deref(), Some("Title"));
+    assert_eq!(metadata.year, Some(2004));
+    assert_eq!((metadata.track_number, metadata.track_total), (Some(3), Some(12)));
+    assert_eq!((metadata.disc_number, metadata.disc_total), (None, None));
+    assert_eq!(metadata.comment, None);
+    assert_eq!(metadata.custom.get("MOOD").map(String::as_str), Some("Calm"));
+
+    let entries = metadata.to_entries();
+    assert!(entries.contains(&(MetaEntry::Track, "3/12".to_string())));
+    assert!(!entries.iter().any(|(entry, _)| *entry == MetaEntry::DiscNumber));
+
+    // A total without a number is not written
+    let metadata = Metadata { disc_total: Some(2), ..Default::default() };
+    assert!(metadata.to_entries().is_empty());
+}
+
+#[test]
+fn test_metadata_round_trip_all_tag_types() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("metadata.mp3");
+    std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x44, 0x00, 0x01, 0x02, 0x03].repeat(64)).unwrap();
+    assert!(matches!(Metadata::read(&path), Err(Error::TagNotFound)));
+
+    let metadata = sample();
+    for tag_type in [TagType::Id3v2, TagType::Ape] {
+        assert!(metadata.write_to(&path, tag_type).unwrap().is_lossless());
+        assert_eq!(Metadata::read_from(&path, tag_type).unwrap(), metadata);
+    }
+    assert_eq!(Metadata::read(&path).unwrap(), metadata);
+
+    let report = metadata.write_to(&path, TagType::Id3v1).unwrap();
+    assert!(report
+        .issues
+        .iter()
+        .any(|(entry, issue)| *entry == MetaEntry::Track && matches!(issue, FieldIssue::Unsupported(_))));
+    let v1 = Metadata::read_from(&path, TagType::Id3v1).unwrap();
+    assert_eq!(v1.title, metadata.title);
+    assert_eq!(v1.year, Some(2004));
+    assert_eq!(v1.genre.as_deref(), Some("Jazz"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_metadata_serde() {
+    let metadata = sample();
+    let json = serde_json::to_string(&metadata).unwrap();
+    assert_eq!(serde_json::from_str::<Metadata>(&json).unwrap(), metadata);
+    let partial: Metadata = serde_json::from_str(r#"{"title": "Only"}"#).unwrap();
+    assert_eq!(partial.title.as_deref(), Some("Only"));
+}
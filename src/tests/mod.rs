@@ -2,6 +2,24 @@ mod simple_tests;
 mod tag_tests;
 mod blackbox_security_tests;
 mod property_based_tests;
+mod telemetry_tests;
+mod util_tests;
+mod id3v2_frame_tests;
+mod artwork_tests;
+mod replaygain_tests;
+mod musicbrainz_tests;
+mod vacuum_tests;
+mod backup_tests;
+mod id3v1_tag_tests;
+mod ape_tag_tests;
+mod convert_tests;
+mod metadata_tests;
+mod strip_tests;
+mod editor_tests;
+mod audio_tests;
+mod layout_tests;
+mod batch_tests;
+mod library_tests;
 // Disabled complex tests that don't align with simplified YAGNI API
 // mod id3v2_tests;
 // mod ape_tests;
@@ -0,0 +1,32 @@
+use crate::musicbrainz::{self, is_valid_mbid, MusicBrainzId};
+use crate::TagType;
+use tempfile::tempdir;
+
+const RECORDING: &str = "5b11f4ce-a62d-471e-81fc-a69a8278c7da";
+const RELEASE: &str = "f5093c06-23e3-404f-aeaa-40f72885ee3a";
+const ARTIST: &str = "b10bbbfc-cf9e-42e0-be17-e2c3e1d2600d";
+
+#[test]
+fn test_mbid_validation() {
+    assert!(is_valid_mbid(RECORDING));
+    assert!(!is_valid_mbid("5b11f4ce-a62d-471e-81fc"));
+    assert!(!is_valid_mbid("5b11f4ce_a62d_471e_81fc_a69a8278c7da"));
+}
+
+#[test]
+fn test_musicbrainz_ids_round_trip() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("mb.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    musicbrainz::write(&test_file, MusicBrainzId::Track, RECORDING, TagType::Id3v2).unwrap();
+    musicbrainz::write(&test_file, MusicBrainzId::Release, RELEASE, TagType::Id3v2).unwrap();
+    musicbrainz::write(&test_file, MusicBrainzId::Artist, ARTIST, TagType::Ape).unwrap();
+
+    assert_eq!(musicbrainz::read(&test_file, MusicBrainzId::Track).unwrap().as_deref(), Some(RECORDING));
+    assert_eq!(musicbrainz::read(&test_file, MusicBrainzId::Release).unwrap().as_deref(), Some(RELEASE));
+    assert_eq!(musicbrainz::read(&test_file, MusicBrainzId::Artist).unwrap().as_deref(), Some(ARTIST));
+    assert_eq!(musicbrainz::read(&test_file, MusicBrainzId::Work).unwrap(), None);
+
+    assert!(musicbrainz::write(&test_file, MusicBrainzId::Work, "not-an-mbid", TagType::Id3v2).is_err());
+}
@@ -4,6 +4,7 @@ use std::fs::copy;
 use tempfile::tempdir;
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod property_based_tests {
     use super::*;
 
@@ -28,13 +29,13 @@ mod property_based_tests {
                 // If writes succeed, reads should work and return same data
                 if write_title.is_ok() && write_artist.is_ok() && write_album.is_ok() {
                     if let Ok(reader) = TagReader::new(&test_file) {
-                        if let Ok(read_title) = reader.get_meta_entry(&MetaEntry::Title) {
+                        if let Ok(Some(read_title)) = reader.get_meta_entry(&MetaEntry::Title) {
                             prop_assert_eq!(read_title, title);
                         }
-                        if let Ok(read_artist) = reader.get_meta_entry(&MetaEntry::Artist) {
+                        if let Ok(Some(read_artist)) = reader.get_meta_entry(&MetaEntry::Artist) {
                             prop_assert_eq!(read_artist, artist);
                         }
-                        if let Ok(read_album) = reader.get_meta_entry(&MetaEntry::Album) {
+                        if let Ok(Some(read_album)) = reader.get_meta_entry(&MetaEntry::Album) {
                             prop_assert_eq!(read_album, album);
                         }
                     }
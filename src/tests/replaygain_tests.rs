@@ -0,0 +1,40 @@
+use crate::replaygain::{self, parse_value, ReplayGain};
+use crate::TagType;
+use tempfile::tempdir;
+
+#[test]
+fn test_parse_value() {
+    assert_eq!(parse_value("-6.54 dB"), Some(-6.54));
+    assert_eq!(parse_value("+1.20dB"), Some(1.2));
+    assert_eq!(parse_value(" 0.988547 "), Some(0.988547));
+    assert_eq!(parse_value("loud"), None);
+}
+
+#[test]
+fn test_replaygain_round_trip_id3v2_and_ape() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("rg.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let track = ReplayGain {
+        track_gain: Some(-6.54),
+        track_peak: Some(0.988547),
+        ..Default::default()
+    };
+    replaygain::write(&test_file, &track, TagType::Id3v2).unwrap();
+
+    let album = ReplayGain {
+        album_gain: Some(-5.1),
+        album_peak: Some(1.0),
+        ..Default::default()
+    };
+    replaygain::write(&test_file, &album, TagType::Ape).unwrap();
+
+    let values = replaygain::read(&test_file).unwrap();
+    assert_eq!(values.track_gain, Some(-6.54));
+    assert_eq!(values.track_peak, Some(0.988547));
+    assert_eq!(values.album_gain, Some(-5.1));
+    assert_eq!(values.album_peak, Some(1.0));
+
+    assert!(replaygain::write(&test_file, &track, TagType::Id3v1).is_err());
+}
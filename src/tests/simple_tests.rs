@@ -16,7 +16,7 @@ fn test_write_and_read_single_tag() {
 
     // Read it back
     let reader = TagReader::new(&test_file).unwrap();
-    let title = reader.get_meta_entry(&MetaEntry::Title).unwrap();
+    let title = reader.get_meta_entry(&MetaEntry::Title).unwrap().unwrap();
     
     assert_eq!(title, "Test Title");
 }
@@ -37,9 +37,9 @@ fn test_write_multiple_tags() {
     // Read them back
     let reader = TagReader::new(&test_file).unwrap();
     
-    assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap(), "Multi Title");
-    assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap(), "Multi Artist");
-    assert_eq!(reader.get_meta_entry(&MetaEntry::Album).unwrap(), "Multi Album");
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Multi Title"));
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap().as_deref(), Some("Multi Artist"));
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Album).unwrap().as_deref(), Some("Multi Album"));
 }
 
 #[test]
@@ -59,8 +59,8 @@ fn test_tag_preservation() {
 
     // Verify both tags exist
     let reader = TagReader::new(&test_file).unwrap();
-    assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap(), "Original Title");
-    assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap(), "New Artist");
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Original Title"));
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap().as_deref(), Some("New Artist"));
 }
 
 #[test]
@@ -78,7 +78,7 @@ fn test_unicode_content() {
 
     // Read it back
     let reader = TagReader::new(&test_file).unwrap();
-    let title = reader.get_meta_entry(&MetaEntry::Title).unwrap();
+    let title = reader.get_meta_entry(&MetaEntry::Title).unwrap().unwrap();
     
     assert_eq!(title, unicode_title);
 }
@@ -96,7 +96,7 @@ fn test_empty_tag_values() {
 
     // Read it back
     let reader = TagReader::new(&test_file).unwrap();
-    let title = reader.get_meta_entry(&MetaEntry::Title).unwrap();
+    let title = reader.get_meta_entry(&MetaEntry::Title).unwrap().unwrap();
     
     assert_eq!(title, "");
 }
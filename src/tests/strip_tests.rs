@@ -0,0 +1,44 @@
+use crate::ape::ApeWriter;
+use crate::id3::v2::tag::TagWriter as Id3v2Writer;
+use crate::strip::strip_tags;
+use crate::tag::{detect_tags, TagReader, TagType, TagWriterStrategy};
+use crate::MetaEntry;
+use std::collections::HashMap;
+use tempfile::tempdir;
+
+const AUDIO: [u8; 8] = [0xFF, 0xFB, 0x90, 0x44, 0x00, 0x01, 0x02, 0x03];
+
+fn id3v1_tag(title: &str) -> Vec<u8> {
+    let mut tag = b"TAG".to_vec();
+    tag.extend_from_slice(title.as_bytes());
+    tag.resize(128, 0);
+    tag[127] = 255;
+    tag
+}
+
+#[test]
+fn test_strip_selected_tags() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("strip.mp3");
+    let mut data = AUDIO.repeat(16);
+    data.extend(id3v1_tag("Old"));
+    std::fs::write(&path, &data).unwrap();
+
+    let mut writer = Id3v2Writer::new();
+    writer.init(&path).unwrap();
+    writer.set_meta_entry(&MetaEntry::Title, "New").unwrap();
+    let entries = HashMap::from([(MetaEntry::Title, "Ape".to_string())]);
+    ApeWriter::new().set_meta_entries(&path, &entries).unwrap();
+
+    let removed = strip_tags(&path, &[TagType::Id3v2, TagType::Ape]).unwrap();
+    assert_eq!(removed, [TagType::Id3v2, TagType::Ape]);
+    let presence = detect_tags(&path).unwrap();
+    assert_eq!(presence.tags.len(), 1);
+    assert!(presence.has(TagType::Id3v1));
+    assert_eq!(TagReader::new(&path).unwrap().get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Old"));
+
+    // Nothing left to strip of these types
+    assert!(strip_tags(&path, &[TagType::Ape]).unwrap().is_empty());
+    assert_eq!(strip_tags(&path, &[TagType::Id3v1]).unwrap(), [TagType::Id3v1]);
+    assert_eq!(std::fs::read(&path).unwrap(), AUDIO.repeat(16));
+}
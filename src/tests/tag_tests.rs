@@ -1,5 +1,6 @@
 use std::path::Path;
-use crate::tag::{TagReader, TagWriter, TagType};
+use crate::tag::{detect_tags, ChangeKind, MergePolicy, TagReader, TagType, TagVersion, TagReaderStrategy, TagWriter, TagWriterStrategy};
+use crate::MetaEntry;
 
 #[cfg(test)]
 mod tests {
@@ -20,4 +21,766 @@ mod tests {
         let result = TagWriter::new(dummy_path, TagType::Id3v2);
         assert!(result.is_ok() || result.is_err()); // Either outcome is acceptable
     }
+
+    /// File with an ID3v2.3 title "New", an ID3v1 title "Old" and an APEv2 title "Ape"
+    fn file_with_three_tags(dir: &Path) -> std::path::PathBuf {
+        let path = dir.join("merge.mp3");
+        let mut data = [0xFF, 0xFB, 0x90, 0x44, 0x00, 0x01, 0x02, 0x03].repeat(64);
+        let mut v1 = vec![0u8; 128];
+        v1[..3].copy_from_slice(b"TAG");
+        v1[3..6].copy_from_slice(b"Old");
+        v1[33..39].copy_from_slice(b"Artist");
+        v1[127] = 255;
+        data.extend(v1);
+        std::fs::write(&path, data).unwrap();
+
+        let mut writer = crate::id3::v2::tag::TagWriter::new();
+        writer.init(&path).unwrap();
+        writer.set_meta_entry(&MetaEntry::Title, "New").unwrap();
+        writer.set_meta_entry(&MetaEntry::Artist, "Artist").unwrap();
+        let entries = std::collections::HashMap::from([(MetaEntry::Title, "Ape".to_string())]);
+        crate::ape::ApeWriter::new().set_meta_entries(&path, &entries).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_merge_policies() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+        let mut reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.merge_policy(), MergePolicy::PreferId3v2);
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("New"));
+
+        // APEv2 is newer than ID3v2.3
+        reader.set_merge_policy(MergePolicy::PreferNewest);
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Ape"));
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap().as_deref(), Some("Artist"));
+
+        reader.set_merge_policy(MergePolicy::CollectAll);
+        assert_eq!(reader.get_meta_entry_values(&MetaEntry::Title).unwrap().unwrap(), ["New", "Old", "Ape"]);
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("New; Old; Ape"));
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap().as_deref(), Some("Artist"));
+    }
+
+    #[test]
+    fn test_per_tag_values_and_conflicts() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(
+            reader.get_meta_entry_by_tag(&MetaEntry::Title),
+            [
+                (TagType::Id3v2, "New".to_string()),
+                (TagType::Id3v1, "Old".to_string()),
+                (TagType::Ape, "Ape".to_string()),
+            ]
+        );
+        assert!(reader.has_conflict(&MetaEntry::Title));
+        assert!(!reader.has_conflict(&MetaEntry::Artist));
+        assert_eq!(reader.conflicting_entries(), [MetaEntry::Title]);
+    }
+
+    #[test]
+    fn test_targeted_reads() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_meta_entry_from(TagType::Id3v1, &MetaEntry::Title).unwrap().as_deref(), Some("Old"));
+        assert_eq!(reader.get_meta_entry_from(TagType::Ape, &MetaEntry::Title).unwrap().as_deref(), Some("Ape"));
+        assert_eq!(reader.get_meta_entry_from(TagType::Ape, &MetaEntry::Artist).unwrap(), None);
+        // A missing entry is not an error
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Composer).unwrap(), None);
+        assert_eq!(crate::tag::get_composer(&path).unwrap(), None);
+
+        let v1 = reader.get_all_from(TagType::Id3v1).unwrap();
+        assert_eq!(v1.get(&MetaEntry::Artist).map(String::as_str), Some("Artist"));
+        assert!(!v1.contains_key(&MetaEntry::Composer));
+
+        crate::ape::ApeWriter::new().remove_tag(&path).unwrap();
+        let reader = TagReader::new(&path).unwrap();
+        assert!(matches!(reader.get_all_from(TagType::Ape), Err(crate::Error::TagNotFound)));
+    }
+
+    #[test]
+    fn test_detect_tags() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+        let file_size = std::fs::metadata(&path).unwrap().len();
+        let presence = detect_tags(&path).unwrap();
+        let types: Vec<TagType> = presence.tags.iter().map(|tag| tag.tag_type).collect();
+        assert_eq!(types, [TagType::Id3v2, TagType::Ape, TagType::Id3v1]);
+
+        let id3v2 = presence.get(TagType::Id3v2).unwrap();
+        assert_eq!(id3v2.version.to_string(), "ID3v2.3");
+        assert_eq!(id3v2.offset, 0);
+        let ape = presence.get(TagType::Ape).unwrap();
+        assert_eq!(ape.version, TagVersion::Ape(2000));
+        let id3v1 = presence.get(TagType::Id3v1).unwrap();
+        assert_eq!(id3v1.version, TagVersion::Id3v1 { revision: 0 });
+        assert_eq!((id3v1.offset, id3v1.size), (file_size - 128, 128));
+        assert_eq!(ape.offset + ape.size, id3v1.offset);
+
+        let untagged = temp_dir.path().join("untagged.mp3");
+        std::fs::write(&untagged, [0xFF, 0xFB, 0x90, 0x44]).unwrap();
+        assert!(detect_tags(&untagged).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_dry_run_collects_changes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+        let original = std::fs::read(&path).unwrap();
+
+        let mut writer = TagWriter::new(&path, TagType::Id3v2).unwrap();
+        writer.set_dry_run(true);
+        writer.set_meta_entry(&MetaEntry::Title, "Newer").unwrap();
+        writer.set_meta_entry(&MetaEntry::Composer, "Composer").unwrap();
+        writer.remove_meta_entry(&MetaEntry::Artist).unwrap();
+        // Setting a value back to the original cancels the change
+        writer.set_meta_entry(&MetaEntry::Composer, "").unwrap();
+        writer.save().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), original);
+
+        let change_set = writer.change_set().unwrap();
+        let kinds: Vec<(MetaEntry, ChangeKind)> =
+            change_set.changes.iter().map(|change| (change.entry.clone(), change.kind)).collect();
+        assert_eq!(kinds, [(MetaEntry::Title, ChangeKind::Replaced), (MetaEntry::Artist, ChangeKind::Removed)]);
+        assert_eq!(change_set.changes[0].old_value.as_deref(), Some("New"));
+        // The ID3v2 tag has enough padding to be updated in place
+        let id3v2 = detect_tags(&path).unwrap().get(TagType::Id3v2).unwrap().size;
+        assert_eq!(change_set.bytes_to_rewrite, id3v2);
+    }
+
+    #[test]
+    fn test_save_writes_deferred_tags() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+        let mut writer = TagWriter::new(&path, TagType::Id3v1).unwrap();
+        writer.set_meta_entry(&MetaEntry::Title, "Saved").unwrap();
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_meta_entry_from(TagType::Id3v1, &MetaEntry::Title).unwrap().as_deref(), Some("Old"));
+
+        writer.save().unwrap();
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_meta_entry_from(TagType::Id3v1, &MetaEntry::Title).unwrap().as_deref(), Some("Saved"));
+        assert_eq!(reader.get_meta_entry_from(TagType::Ape, &MetaEntry::Title).unwrap().as_deref(), Some("Ape"));
+    }
+
+    #[test]
+    fn test_remove_meta_entry_deletes_frames_and_items() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+        TagWriter::new(&path, TagType::Id3v2).unwrap().remove_meta_entry(&MetaEntry::Title).unwrap();
+        let mut writer = TagWriter::new(&path, TagType::Ape).unwrap();
+        writer.remove_meta_entry(&MetaEntry::Title).unwrap();
+        writer.save().unwrap();
+
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_meta_entry_from(TagType::Id3v2, &MetaEntry::Title).unwrap(), None);
+        assert_eq!(reader.get_meta_entry_from(TagType::Id3v2, &MetaEntry::Artist).unwrap().as_deref(), Some("Artist"));
+        // The APE tag held only the title and is removed with it
+        assert!(!detect_tags(&path).unwrap().has(TagType::Ape));
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Old"));
+
+        // ID3v1 fields are blanked and reported
+        let mut v1 = crate::id3::v1::tag::TagWriter::new();
+        v1.init(&path).unwrap();
+        v1.remove_meta_entry(&MetaEntry::Title).unwrap();
+        v1.save().unwrap();
+        let report = v1.take_report();
+        assert!(matches!(report.issues.as_slice(), [(MetaEntry::Title, crate::id3::v1::report::FieldIssue::Blanked)]));
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_meta_entry_from(TagType::Id3v1, &MetaEntry::Title).unwrap().as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_remove_all_meta_entries_strips_tag() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+        let size = std::fs::metadata(&path).unwrap().len();
+        let id3v2 = detect_tags(&path).unwrap().get(TagType::Id3v2).unwrap().size;
+
+        let mut writer = TagWriter::new(&path, TagType::Id3v2).unwrap();
+        writer.remove_all_meta_entries().unwrap();
+        let presence = detect_tags(&path).unwrap();
+        assert!(!presence.has(TagType::Id3v2));
+        assert!(presence.has(TagType::Ape) && presence.has(TagType::Id3v1));
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), size - id3v2);
+
+        // Kept tags stay in the file without their items
+        let mut writer = TagWriter::new(&path, TagType::Ape).unwrap();
+        writer.set_keep_empty_tags(true);
+        writer.remove_all_meta_entries().unwrap();
+        writer.save().unwrap();
+        let reader = TagReader::new(&path).unwrap();
+        assert!(detect_tags(&path).unwrap().has(TagType::Ape));
+        assert_eq!(reader.get_meta_entry_from(TagType::Ape, &MetaEntry::Title).unwrap(), None);
+    }
+
+    #[test]
+    fn test_remove_all_meta_entries_strips_synced_id3v1() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+
+        let mut writer = TagWriter::builder(&path).tag_type(TagType::Ape).sync_id3v1(true).build().unwrap();
+        writer.remove_all_meta_entries().unwrap();
+        writer.save().unwrap();
+        let presence = detect_tags(&path).unwrap();
+        assert!(!presence.has(TagType::Ape) && !presence.has(TagType::Id3v1));
+        assert!(presence.has(TagType::Id3v2));
+    }
+
+    #[test]
+    fn test_writer_builder() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("built.mp3");
+        std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x44].repeat(64)).unwrap();
+
+        let mut writer = TagWriter::builder(&path)
+            .tag_type(TagType::Id3v2)
+            .version(crate::id3::v2::version::Version::V4)
+            .encoding(crate::id3::v2::encoding::TextEncoding::Utf8)
+            .padding(64)
+            .sync_id3v1(true)
+            .build()
+            .unwrap();
+        writer.set_meta_entry(&MetaEntry::Title, "Built").unwrap();
+        writer.save().unwrap();
+
+        let presence = detect_tags(&path).unwrap();
+        let id3v2 = presence.get(TagType::Id3v2).unwrap();
+        assert_eq!(id3v2.version.to_string(), "ID3v2.4");
+        assert!(id3v2.size >= 64);
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_meta_entry_from(TagType::Id3v1, &MetaEntry::Title).unwrap().as_deref(), Some("Built"));
+
+        let result = TagWriter::builder(&path).version(crate::id3::v2::version::Version::V2).build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_reader_strategy_order() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+        let reader = TagReader::with_strategies(&path, &[TagType::Ape, TagType::Id3v2, TagType::Ape]).unwrap();
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Ape"));
+        // Tags left out are never consulted
+        assert_eq!(
+            reader.get_meta_entry_by_tag(&MetaEntry::Title),
+            [(TagType::Ape, "Ape".to_string()), (TagType::Id3v2, "New".to_string())]
+        );
+        assert!(reader.get_meta_entry_from(TagType::Id3v1, &MetaEntry::Title).is_err());
+        assert!(TagReader::with_strategies(&path, &[]).is_err());
+    }
+
+    /// Tag format kept in a shared map, standing in for a proprietary format
+    #[derive(Default, Clone)]
+    struct MemoryTag {
+        entries: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<MetaEntry, String>>>,
+        saved: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    impl TagReaderStrategy for MemoryTag {
+        fn init(&mut self, _path: &Path) -> crate::Result<()> {
+            Ok(())
+        }
+
+        fn get_meta_entry(&self, _path: &Path, entry: &MetaEntry) -> crate::Result<String> {
+            self.entries.lock().unwrap().get(entry).cloned().ok_or(crate::Error::EntryNotFound)
+        }
+
+        fn tag_type(&self) -> TagType {
+            TagType::Custom("memory")
+        }
+    }
+
+    impl TagWriterStrategy for MemoryTag {
+        fn init(&mut self, _path: &Path) -> crate::Result<()> {
+            Ok(())
+        }
+
+        fn set_meta_entry(&mut self, entry: &MetaEntry, value: &str) -> crate::Result<()> {
+            self.entries.lock().unwrap().insert(entry.clone(), value.to_string());
+            Ok(())
+        }
+
+        fn save(&mut self) -> crate::Result<()> {
+            self.saved.store(true, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn tag_type(&self) -> TagType {
+            TagType::Custom("memory")
+        }
+    }
+
+    #[test]
+    fn test_custom_strategies() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("custom.mp3");
+        std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x44].repeat(64)).unwrap();
+        let memory = MemoryTag::default();
+
+        let mut writer = TagWriter::new(&path, TagType::Custom("memory")).unwrap();
+        writer.add_strategy(Box::new(memory.clone()));
+        writer.set_meta_entry(&MetaEntry::Title, "Proprietary").unwrap();
+        writer.save().unwrap();
+        assert!(memory.saved.load(std::sync::atomic::Ordering::SeqCst));
+        assert!(!detect_tags(&path).unwrap().has(TagType::Id3v2));
+
+        let mut reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap(), None);
+        reader.add_strategy(Box::new(memory));
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Proprietary"));
+        assert_eq!(
+            reader.get_meta_entry_from(TagType::Custom("memory"), &MetaEntry::Title).unwrap().as_deref(),
+            Some("Proprietary")
+        );
+        assert!(TagReader::with_strategies(&path, &[TagType::Custom("memory")]).is_err());
+    }
+
+    /// Tag format whose reads always fail
+    struct BrokenTag;
+
+    impl TagReaderStrategy for BrokenTag {
+        fn init(&mut self, _path: &Path) -> crate::Result<()> {
+            Ok(())
+        }
+
+        fn get_meta_entry(&self, _path: &Path, _entry: &MetaEntry) -> crate::Result<String> {
+            Err(crate::Error::Other("Device not ready".to_string()))
+        }
+
+        fn tag_type(&self) -> TagType {
+            TagType::Custom("broken")
+        }
+    }
+
+    #[test]
+    fn test_reader_propagates_strategy_failures() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("broken.mp3");
+        std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x44].repeat(64)).unwrap();
+
+        let mut reader = TagReader::new(&path).unwrap();
+        reader.add_strategy(Box::new(MemoryTag::default()));
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap(), None);
+        reader.add_strategy(Box::new(BrokenTag));
+        assert!(reader.get_meta_entry(&MetaEntry::Title).is_err());
+        assert!(reader.get_meta_entry_values(&MetaEntry::Title).is_err());
+    }
+
+    #[test]
+    fn test_typed_numeric_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("numbers.mp3");
+        std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x44].repeat(64)).unwrap();
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_track().unwrap(), None);
+
+        let mut writer = TagWriter::new(&path, TagType::Id3v2).unwrap();
+        writer.set_track(3).unwrap();
+        writer.set_year(2004).unwrap();
+        writer.set_bpm(128).unwrap();
+        assert!(writer.set_track(0).is_err());
+        assert!(writer.set_year(10000).is_err());
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_track().unwrap(), Some(3));
+        assert_eq!(reader.get_year().unwrap(), Some(2004));
+        assert_eq!(reader.get_bpm().unwrap(), Some(128));
+
+        writer.set_meta_entry(&MetaEntry::Track, "7/12").unwrap();
+        writer.set_meta_entry(&MetaEntry::BeatsPerMinute, "120.6").unwrap();
+        writer.set_meta_entry(&MetaEntry::Year, "Spring").unwrap();
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_track().unwrap(), Some(7));
+        assert_eq!(reader.get_bpm().unwrap(), Some(121));
+        assert!(reader.get_year().is_err());
+
+        // APE stores the tempo as BPM
+        let mut writer = TagWriter::new(&path, TagType::Ape).unwrap();
+        writer.set_bpm(90).unwrap();
+        writer.save().unwrap();
+        let reader = TagReader::with_strategies(&path, &[TagType::Ape]).unwrap();
+        assert_eq!(reader.get_bpm().unwrap(), Some(90));
+    }
+
+    #[test]
+    fn test_bpm_and_initial_key() {
+        use crate::validation::{ApeValidator, BaseValidator, Id3v2Validator, StandardValidator};
+
+        for key in ["A", "C#m", "Bb", "o", "8A", "12B", "1m", "10d"] {
+            assert!(StandardValidator.validate_format(&MetaEntry::InitialKey, key).is_ok(), "{}", key);
+        }
+        for key in ["H", "Cmaj", "13A", "0B", "08A", "am"] {
+            assert!(StandardValidator.validate_format(&MetaEntry::InitialKey, key).is_err(), "{}", key);
+        }
+        assert!(StandardValidator.validate_frame("TBPM", "120.5").is_ok());
+        assert!(StandardValidator.validate_frame("TBPM", "fast").is_err());
+        assert!(StandardValidator.validate_item("Bpm", "-3").is_err());
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("dj.mp3");
+        std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x44].repeat(64)).unwrap();
+        for tag_type in [TagType::Id3v2, TagType::Ape] {
+            let mut writer = TagWriter::new(&path, tag_type).unwrap();
+            writer.set_bpm(126).unwrap();
+            writer.set_initial_key("F#m").unwrap();
+            assert!(writer.set_initial_key("Z").is_err());
+            assert!(writer.set_meta_entry(&MetaEntry::BeatsPerMinute, "1 2 8").is_err());
+            writer.save().unwrap();
+
+            let reader = TagReader::with_strategies(&path, &[tag_type]).unwrap();
+            assert_eq!(reader.get_bpm().unwrap(), Some(126));
+            assert_eq!(reader.get_initial_key().unwrap().as_deref(), Some("F#m"));
+        }
+    }
+
+    #[test]
+    fn test_isrc() {
+        use crate::validation::normalize_isrc;
+
+        assert_eq!(normalize_isrc("us-rc1-76-07839").unwrap(), "USRC17607839");
+        assert_eq!(normalize_isrc(" GBAYE0601498 ").unwrap(), "GBAYE0601498");
+        for isrc in ["USRC1760783", "1SRC17607839", "US-RC1-76-0783X", "US_RC17607839"] {
+            assert!(normalize_isrc(isrc).is_err(), "{}", isrc);
+        }
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("isrc.mp3");
+        std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x44].repeat(64)).unwrap();
+        for tag_type in [TagType::Id3v2, TagType::Ape] {
+            let mut writer = TagWriter::new(&path, tag_type).unwrap();
+            writer.set_isrc("us-rc1-76-07839").unwrap();
+            assert!(writer.set_isrc("not an isrc").is_err());
+            assert!(writer.set_meta_entry(&MetaEntry::Isrc, "US-RC1").is_err());
+            writer.save().unwrap();
+
+            let reader = TagReader::with_strategies(&path, &[tag_type]).unwrap();
+            assert_eq!(reader.get_meta_entry(&MetaEntry::Isrc).unwrap().as_deref(), Some("USRC17607839"));
+            assert_eq!(reader.get_isrc().unwrap().as_deref(), Some("USRC17607839"));
+        }
+    }
+
+    #[test]
+    fn test_compilation_flag() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("compilation.mp3");
+        std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x44].repeat(64)).unwrap();
+        for tag_type in [TagType::Id3v2, TagType::Ape] {
+            let reader = TagReader::with_strategies(&path, &[tag_type]).unwrap();
+            assert!(!reader.is_compilation().unwrap());
+
+            let mut writer = TagWriter::new(&path, tag_type).unwrap();
+            writer.set_compilation(true).unwrap();
+            writer.save().unwrap();
+            let reader = TagReader::with_strategies(&path, &[tag_type]).unwrap();
+            assert_eq!(reader.get_meta_entry(&MetaEntry::Compilation).unwrap().as_deref(), Some("1"));
+            assert!(reader.is_compilation().unwrap());
+
+            let mut writer = TagWriter::new(&path, tag_type).unwrap();
+            writer.set_compilation(false).unwrap();
+            writer.save().unwrap();
+            let reader = TagReader::with_strategies(&path, &[tag_type]).unwrap();
+            assert_eq!(reader.get_meta_entry(&MetaEntry::Compilation).unwrap(), None);
+            assert!(!reader.is_compilation().unwrap());
+        }
+
+        let mut writer = TagWriter::new(&path, TagType::Id3v2).unwrap();
+        writer.set_meta_entry(&MetaEntry::Compilation, "yes").unwrap();
+        assert!(TagReader::new(&path).unwrap().is_compilation().is_err());
+    }
+
+    #[test]
+    fn test_track_and_disc_positions() {
+        use crate::Position;
+
+        assert_eq!(" 3 / 12".parse::<Position>().unwrap(), Position::new(3, Some(12)));
+        assert_eq!("03".parse::<Position>().unwrap(), Position::new(3, None));
+        assert!("3/x".parse::<Position>().is_err());
+        assert_eq!(Position::new(3, Some(12)).to_string(), "3/12");
+        assert!(Position::new(5, Some(3)).validate().is_err());
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("positions.mp3");
+        std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x44].repeat(64)).unwrap();
+        for tag_type in [TagType::Id3v2, TagType::Ape] {
+            let mut writer = TagWriter::new(&path, tag_type).unwrap();
+            writer.set_track_position(Position::new(3, Some(12))).unwrap();
+            writer.set_disc_position(Position::new(1, Some(2))).unwrap();
+            writer.save().unwrap();
+            // Only the number changes
+            let mut writer = TagWriter::new(&path, tag_type).unwrap();
+            writer.set_track(4).unwrap();
+            writer.set_disc(2).unwrap();
+            writer.save().unwrap();
+
+            let reader = TagReader::with_strategies(&path, &[tag_type]).unwrap();
+            assert_eq!(reader.get_track_position().unwrap(), Some(Position::new(4, Some(12))));
+            assert_eq!(reader.get_disc_position().unwrap(), Some(Position::new(2, Some(2))));
+            assert_eq!(reader.get_track().unwrap(), Some(4));
+        }
+    }
+
+    #[test]
+    fn test_recording_date_across_versions() {
+        use crate::id3::v2::tag::TagReader as Id3v2Reader;
+        use crate::id3::v2::version::Version;
+        use crate::RecordingDate;
+
+        let read_id3v2 = |path: &Path| {
+            let mut reader = Id3v2Reader::new();
+            reader.init(path).unwrap();
+            reader.tag().unwrap().clone()
+        };
+
+        let date = RecordingDate::ymd(2004, 5, 17).unwrap().at(20, 15).unwrap();
+        assert_eq!("2004-05-17T20:15".parse::<RecordingDate>().unwrap(), date);
+        assert_eq!(date.to_string(), "2004-05-17T20:15");
+        assert_eq!("1999".parse::<RecordingDate>().unwrap(), RecordingDate::new(1999));
+        assert!("2004-13".parse::<RecordingDate>().is_err());
+        assert!("2004T10".parse::<RecordingDate>().is_err());
+        assert!(RecordingDate::ymd(2004, 2, 0).is_err());
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        for (name, version, frames) in [
+            ("v3.mp3", Version::V3, ["TYER", "TDAT", "TIME"].as_slice()),
+            ("v4.mp3", Version::V4, ["TDRC"].as_slice()),
+        ] {
+            let path = temp_dir.path().join(name);
+            std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x44].repeat(64)).unwrap();
+            let mut writer = TagWriter::builder(&path).version(version).build().unwrap();
+            writer.set_date(&date).unwrap();
+            let reader = TagReader::new(&path).unwrap();
+            assert_eq!(reader.get_date().unwrap(), Some(date));
+            assert_eq!(reader.get_year().unwrap().is_some(), version == Version::V3);
+
+            let tag = read_id3v2(&path);
+            for id in ["TYER", "TDAT", "TIME", "TDRC"] {
+                assert_eq!(!tag.frames(id).is_empty(), frames.contains(&id), "{} in {}", id, name);
+            }
+
+            // A year-only date drops the day and time frames
+            writer.set_date(&RecordingDate::new(2010)).unwrap();
+            let reader = TagReader::new(&path).unwrap();
+            assert_eq!(reader.get_date().unwrap(), Some(RecordingDate::new(2010)));
+            assert!(read_id3v2(&path).frames("TDAT").is_empty());
+        }
+
+        // Other tags keep the timestamp in their year entry
+        let path = temp_dir.path().join("ape.mp3");
+        std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x44].repeat(64)).unwrap();
+        let mut writer = TagWriter::new(&path, TagType::Ape).unwrap();
+        writer.set_date(&date).unwrap();
+        writer.save().unwrap();
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Year).unwrap().as_deref(), Some("2004-05-17T20:15"));
+        assert_eq!(reader.get_date().unwrap(), Some(date));
+    }
+
+    #[test]
+    fn test_extended_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("extended.mp3");
+        std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x44].repeat(64)).unwrap();
+        let values = [
+            (MetaEntry::AlbumArtist, "Various"),
+            (MetaEntry::DiscNumber, "1/2"),
+            (MetaEntry::Publisher, "Label"),
+            (MetaEntry::Copyright, "2024 Label"),
+            (MetaEntry::EncodedBy, "Encoder"),
+            (MetaEntry::Conductor, "Conductor"),
+            (MetaEntry::Remixer, "Remixer"),
+            (MetaEntry::Rating, "4"),
+            (MetaEntry::TitleSortOrder, "Day in the Life, A"),
+            (MetaEntry::ArtistSortOrder, "Beatles, The"),
+            (MetaEntry::AlbumSortOrder, "Sgt. Pepper"),
+            (MetaEntry::AlbumArtistSortOrder, "Beatles, The"),
+        ];
+        for tag_type in [TagType::Id3v2, TagType::Ape] {
+            let mut writer = TagWriter::new(&path, tag_type).unwrap();
+            for (entry, value) in &values {
+                writer.set_meta_entry(entry, value).unwrap();
+            }
+            writer.save().unwrap();
+            let reader = TagReader::with_strategies(&path, &[tag_type]).unwrap();
+            for (entry, value) in &values {
+                assert_eq!(reader.get_meta_entry(entry).unwrap().as_deref(), Some(*value), "{:?} {}", tag_type, entry);
+            }
+        }
+
+        let reader = TagReader::with_strategies(&path, &[TagType::Id3v2]).unwrap();
+        assert_eq!(reader.get_meta_entry(&MetaEntry::BandOrchestra).unwrap().as_deref(), Some("Various"));
+        assert_eq!(MetaEntry::DiscNumber, MetaEntry::PartOfSet);
+        assert_eq!(MetaEntry::DiscNumber.to_string(), "PartOfSet");
+        let mut id3v2_reader = crate::id3::v2::tag::TagReader::new();
+        id3v2_reader.init(&path).unwrap();
+        let tag = id3v2_reader.tag().unwrap();
+        for id in ["TSOT", "TSOP", "TSOA", "TSO2"] {
+            assert_eq!(tag.frames(id).len(), 1, "{}", id);
+        }
+        let mut writer = TagWriter::new(&path, TagType::Id3v2).unwrap();
+        assert!(writer.set_meta_entry(&MetaEntry::Rating, "6").is_err());
+        writer.remove_meta_entry(&MetaEntry::Rating).unwrap();
+        let reader = TagReader::with_strategies(&path, &[TagType::Id3v2]).unwrap();
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Rating).unwrap(), None);
+    }
+
+    #[test]
+    fn test_custom_entries_use_txxx_frames() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("custom_txxx.mp3");
+        std::fs::write(&path, [0xFF, 0xFB, 0x90, 0x44].repeat(64)).unwrap();
+        let mood = MetaEntry::Custom("MOOD".to_string());
+
+        let mut writer = TagWriter::new(&path, TagType::Id3v2).unwrap();
+        writer.set_meta_entry(&mood, "Calm").unwrap();
+        assert!(!detect_tags(&path).unwrap().has(TagType::Ape));
+        let mut id3v2 = crate::id3::v2::tag::TagReader::new();
+        id3v2.init(&path).unwrap();
+        assert_eq!(id3v2.user_text("MOOD").as_deref(), Some("Calm"));
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_meta_entry_from(TagType::Id3v2, &mood).unwrap().as_deref(), Some("Calm"));
+
+        writer.remove_meta_entry(&mood).unwrap();
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_meta_entry(&mood).unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_tags_from_memory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let data = std::fs::read(file_with_three_tags(temp_dir.path())).unwrap();
+
+        let tag = crate::id3::Id3v2Tag::parse(&data).unwrap();
+        assert_eq!(tag.frames("TIT2")[0].text().unwrap(), "New");
+        let ape = crate::ape::ApeTag::parse(&data).unwrap();
+        assert_eq!(ape.get_item_text("Title").unwrap(), "Ape");
+        let v1: &[u8; 128] = data[data.len() - 128..].try_into().unwrap();
+        assert_eq!(&crate::id3::Id3v1Tag::parse(v1).unwrap().title[..3], b"Old");
+
+        // Data without the tags, or with a truncated ID3v2 tag
+        assert!(matches!(crate::id3::Id3v2Tag::parse(&data[10..]), Err(crate::Error::TagNotFound)));
+        assert!(crate::id3::Id3v2Tag::parse(&data[..20]).is_err());
+        assert!(matches!(crate::ape::ApeTag::parse(&data[..256]), Err(crate::Error::TagNotFound)));
+        assert!(matches!(crate::id3::Id3v1Tag::parse(&[0; 128]), Err(crate::Error::TagNotFound)));
+    }
+
+    #[test]
+    fn test_render_tags_to_bytes() {
+        use crate::id3::v2::frame::Frame;
+        use crate::id3::Id3v2Version;
+
+        let mut tag = crate::id3::Id3v2Tag::new(Id3v2Version::V4);
+        tag.add_frame(Frame::new_text("TIT2", "Streamed", Id3v2Version::V4));
+        let bytes = tag.to_bytes();
+        // Header, frame header, encoding byte and text, and the default padding
+        assert_eq!(bytes.len(), 10 + 10 + 9 + crate::id3::constants::ID3V2_PADDING_SIZE);
+        assert_eq!(crate::id3::Id3v2Tag::parse(&bytes).unwrap().frames("TIT2")[0].text().unwrap(), "Streamed");
+
+        let mut ape = crate::ape::ApeTag::new(2000);
+        ape.set_text_item("Artist", "Streamer");
+        let ape_bytes = ape.to_bytes().unwrap();
+        assert!(ape_bytes.starts_with(b"APETAGEX") && ape_bytes[ape_bytes.len() - 32..].starts_with(b"APETAGEX"));
+
+        // A file assembled from the rendered tags reads like a written one
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("assembled.mp3");
+        let mut data = bytes;
+        data.extend([0xFF, 0xFB, 0x90, 0x44].repeat(64));
+        data.extend(ape_bytes);
+        std::fs::write(&path, data).unwrap();
+        let reader = TagReader::new(&path).unwrap();
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Streamed"));
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap().as_deref(), Some("Streamer"));
+    }
+
+    #[test]
+    fn test_reading_through_file_access() {
+        use crate::file_access::{FileAccessStrategy, StandardFileAccess};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+        let accesses: Vec<Box<dyn FileAccessStrategy>> = vec![
+            Box::new(StandardFileAccess),
+            #[cfg(feature = "mmap")]
+            Box::new(crate::file_access::MappedFileAccess),
+        ];
+        for access in accesses {
+            let mut reader = TagReader::with_file_access(&path, access.as_ref()).unwrap();
+            reader.set_merge_policy(MergePolicy::CollectAll);
+            assert_eq!(reader.get_meta_entry_values(&MetaEntry::Title).unwrap().unwrap(), ["New", "Old", "Ape"]);
+            assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap().as_deref(), Some("Artist"));
+        }
+    }
+
+    #[test]
+    fn test_entries_are_cached_when_reading() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+        let mut reader = TagReader::new(&path).unwrap();
+        reader.set_merge_policy(MergePolicy::CollectAll);
+
+        // Every tag was read while opening the file once
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reader.get_meta_entry_values(&MetaEntry::Title).unwrap().unwrap(), ["New", "Old", "Ape"]);
+        assert_eq!(reader.get_meta_entry(&MetaEntry::Artist).unwrap().as_deref(), Some("Artist"));
+    }
+
+    #[test]
+    fn test_readers_and_tags_are_shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<TagReader>();
+        assert_send_sync::<crate::id3::Id3v2Tag>();
+        assert_send_sync::<crate::id3::Id3v1Tag>();
+        assert_send_sync::<crate::ape::ApeTag>();
+        assert_send_sync::<crate::Metadata>();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+        let reader = std::sync::Arc::new(TagReader::new(&path).unwrap());
+        let tag = std::sync::Arc::new(crate::id3::Id3v2Tag::parse(&std::fs::read(&path).unwrap()).unwrap());
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let (reader, tag) = (reader.clone(), tag.clone());
+                std::thread::spawn(move || (reader.get_meta_entry(&MetaEntry::Title).unwrap(), tag.frames("TIT2").len()))
+            })
+            .collect();
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), (Some("New".to_string()), 1));
+        }
+    }
+
+    #[test]
+    fn test_reader_cache() {
+        use crate::cache::ReaderCache;
+        use std::sync::Arc;
+        use std::time::{Duration, SystemTime};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = file_with_three_tags(temp_dir.path());
+        let other = temp_dir.path().join("other.mp3");
+        std::fs::copy(&path, &other).unwrap();
+        let cache = ReaderCache::with_capacity(1);
+
+        let reader = cache.reader(&path).unwrap();
+        assert!(Arc::ptr_eq(&reader, &cache.reader(&path).unwrap()));
+
+        // A changed modification time is noticed
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+        let reread = cache.reader(&path).unwrap();
+        assert!(!Arc::ptr_eq(&reader, &reread));
+        assert_eq!(reread.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("New"));
+
+        assert!(cache.invalidate(&path));
+        assert!(!Arc::ptr_eq(&reread, &cache.reader(&path).unwrap()));
+
+        // The least recently used file makes room
+        cache.reader(&other).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert!(!cache.invalidate(&path));
+        cache.clear();
+        assert!(cache.is_empty());
+    }
 }
@@ -0,0 +1,49 @@
+use crate::telemetry::AnomalyKind;
+use crate::{TagReader, MetaEntry};
+use std::fs::write;
+use std::sync::{Arc, Mutex};
+use tempfile::tempdir;
+
+#[test]
+fn test_reader_hook_receives_bad_frame_size() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("corrupt.mp3");
+
+    // ID3v2.3 tag of 20 bytes holding a TIT2 frame that claims 200 bytes of payload
+    let mut data = b"ID3\x03\x00\x00\x00\x00\x00\x14".to_vec();
+    data.extend_from_slice(b"TIT2\x00\x00\x00\xC8\x00\x00");
+    data.extend_from_slice(&[0u8; 10]);
+    data.extend_from_slice(&[0xFF, 0xFB, 0x90, 0x44]);
+    write(&test_file, data).unwrap();
+
+    let anomalies = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&anomalies);
+    let reader = TagReader::with_anomaly_hook(&test_file, move |anomaly| {
+        sink.lock().unwrap().push(anomaly.clone());
+    }).unwrap();
+    let _ = reader.get_meta_entry(&MetaEntry::Title);
+
+    let anomalies = anomalies.lock().unwrap();
+    let anomaly = anomalies
+        .iter()
+        .find(|a| a.kind == AnomalyKind::InvalidFrameSize)
+        .expect("bad frame size should be reported");
+    assert_eq!(anomaly.path.as_deref(), Some(test_file.as_path()));
+    assert_eq!(anomaly.offset, Some(0));
+}
+
+#[test]
+fn test_clean_file_reports_nothing() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("clean.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+
+    let count = Arc::new(Mutex::new(0));
+    let sink = Arc::clone(&count);
+    let reader = TagReader::with_anomaly_hook(&test_file, move |_| {
+        *sink.lock().unwrap() += 1;
+    }).unwrap();
+    let _ = reader.get_meta_entry(&MetaEntry::Title);
+
+    assert_eq!(*count.lock().unwrap(), 0);
+}
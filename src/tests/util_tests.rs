@@ -0,0 +1,107 @@
+use crate::util::{extract_string, extract_string_with_encoding, PreserveOptions, StringEncoding};
+
+#[test]
+fn test_extract_string_preserves_utf8() {
+    let mut buffer = "Björk – Jóga".as_bytes().to_vec();
+    buffer.extend_from_slice(&[0, 0, 0]);
+
+    let text = extract_string(&buffer, 0, buffer.len()).unwrap();
+    assert_eq!(text, "Björk – Jóga");
+}
+
+#[test]
+fn test_extract_string_falls_back_to_latin1() {
+    // "Café" in ISO-8859-1 followed by NUL padding
+    let buffer = [b'C', b'a', b'f', 0xE9, 0, 0];
+
+    assert_eq!(extract_string(&buffer, 0, buffer.len()).unwrap(), "Café");
+    assert_eq!(
+        extract_string_with_encoding(&buffer, 0, buffer.len(), StringEncoding::Latin1).unwrap(),
+        "Café"
+    );
+}
+
+#[test]
+fn test_extract_string_strips_control_characters_and_checks_bounds() {
+    let buffer = b"\x01Title\x00\x1F";
+    assert_eq!(extract_string(buffer, 0, buffer.len()).unwrap(), "Title");
+    assert!(extract_string(buffer, 4, usize::MAX).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_rewrite_preserves_mode_and_optionally_mtime() {
+    use crate::ape::ApeWriter;
+    use crate::MetaEntry;
+    use std::collections::HashMap;
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::{Duration, SystemTime};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let test_file = temp_dir.path().join("preserve.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+    std::fs::set_permissions(&test_file, std::fs::Permissions::from_mode(0o640)).unwrap();
+    let old_mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    let file = std::fs::OpenOptions::new().write(true).open(&test_file).unwrap();
+    file.set_modified(old_mtime).unwrap();
+    drop(file);
+
+    let entries = HashMap::from([(MetaEntry::Title, "Kept".to_string())]);
+    let mut writer = ApeWriter::new();
+    writer.set_meta_entries(&test_file, &entries).unwrap();
+    let metadata = std::fs::metadata(&test_file).unwrap();
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o640);
+    assert_ne!(metadata.modified().unwrap(), old_mtime);
+
+    let file = std::fs::OpenOptions::new().write(true).open(&test_file).unwrap();
+    file.set_modified(old_mtime).unwrap();
+    drop(file);
+    writer.set_preserve_options(PreserveOptions { mtime: true, ..Default::default() });
+    writer.set_meta_entries(&test_file, &entries).unwrap();
+    let metadata = std::fs::metadata(&test_file).unwrap();
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o640);
+    assert_eq!(metadata.modified().unwrap(), old_mtime);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_preserve_options_on_every_write_path() {
+    use crate::strip::strip_tags_preserving;
+    use crate::util::{get_temp_path, replace_file};
+    use crate::{MetaEntry, TagType, TagWriter};
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::{Duration, SystemTime};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let test_file = temp_dir.path().join("preserve.mp3");
+    std::fs::copy("audio_files/mp3_44100Hz_128kbps_stereo.mp3", &test_file).unwrap();
+    let old_mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    let set_old_mtime = || {
+        let file = std::fs::OpenOptions::new().write(true).open(&test_file).unwrap();
+        file.set_modified(old_mtime).unwrap();
+    };
+    let preserve = PreserveOptions { mtime: true, ..Default::default() };
+
+    // A read-only original keeps its times and mode
+    set_old_mtime();
+    std::fs::set_permissions(&test_file, std::fs::Permissions::from_mode(0o444)).unwrap();
+    let temp_path = get_temp_path(&test_file);
+    std::fs::copy(&test_file, &temp_path).unwrap();
+    std::fs::set_permissions(&temp_path, std::fs::Permissions::from_mode(0o644)).unwrap();
+    replace_file(&temp_path, &test_file, preserve).unwrap();
+    let metadata = std::fs::metadata(&test_file).unwrap();
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o444);
+    assert_eq!(metadata.modified().unwrap(), old_mtime);
+    std::fs::set_permissions(&test_file, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+    // In-place ID3v1 writes through the facade
+    set_old_mtime();
+    let mut writer = TagWriter::builder(&test_file).tag_type(TagType::Id3v1).preserve(preserve).build().unwrap();
+    writer.set_meta_entry(&MetaEntry::Title, "Kept").unwrap();
+    writer.save().unwrap();
+    assert_eq!(std::fs::metadata(&test_file).unwrap().modified().unwrap(), old_mtime);
+
+    set_old_mtime();
+    assert_eq!(strip_tags_preserving(&test_file, &[TagType::Id3v1], preserve).unwrap(), [TagType::Id3v1]);
+    assert_eq!(std::fs::metadata(&test_file).unwrap().modified().unwrap(), old_mtime);
+}
@@ -0,0 +1,88 @@
+use crate::vacuum::{vacuum, VacuumReport};
+use crate::{MetaEntry, TagReader};
+use tempfile::tempdir;
+
+fn id3v2_tag(title: &str, padding: usize) -> Vec<u8> {
+    let mut body = b"TIT2".to_vec();
+    body.extend_from_slice(&(title.len() as u32 + 1).to_be_bytes());
+    body.extend_from_slice(&[0, 0, 0]);
+    body.extend_from_slice(title.as_bytes());
+    body.resize(body.len() + padding, 0);
+
+    let size = body.len() as u32;
+    let mut tag = vec![b'I', b'D', b'3', 3, 0, 0];
+    tag.extend_from_slice(&[(size >> 21) as u8 & 0x7F, (size >> 14) as u8 & 0x7F, (size >> 7) as u8 & 0x7F, size as u8 & 0x7F]);
+    tag.extend_from_slice(&body);
+    tag
+}
+
+fn id3v1_tag(title: &str) -> Vec<u8> {
+    let mut tag = b"TAG".to_vec();
+    tag.extend_from_slice(title.as_bytes());
+    tag.resize(128, 0);
+    tag
+}
+
+#[test]
+fn test_vacuum_reclaims_padding_and_duplicates() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("vacuum.mp3");
+    let audio = [0xFF, 0xFB, 0x90, 0x44, 0x00, 0x01, 0x02, 0x03];
+
+    let mut file = id3v2_tag("Current", 4096);
+    file.extend(id3v2_tag("Stale", 0));
+    file.extend([0u8; 100]);
+    file.extend_from_slice(&audio);
+    file.extend(id3v1_tag("Old"));
+    file.extend(id3v1_tag("New"));
+    std::fs::write(&test_file, &file).unwrap();
+
+    let report = vacuum(&test_file).unwrap();
+    assert_eq!(report.original_size, file.len() as u64);
+    assert_eq!(report.duplicate_tags_removed, 2);
+    assert!(report.bytes_reclaimed() > 4096 + 128);
+
+    let compacted = std::fs::read(&test_file).unwrap();
+    assert_eq!(compacted.len() as u64, report.new_size);
+    assert_eq!(&compacted[compacted.len() - 128..], id3v1_tag("New").as_slice());
+    let audio_end = compacted.len() - 128;
+    assert_eq!(&compacted[audio_end - audio.len()..audio_end], &audio);
+    // Zero bytes behind the tag are not its padding and stay
+    assert_eq!(&compacted[audio_end - audio.len() - 100..audio_end - audio.len()], &[0u8; 100]);
+
+    let reader = TagReader::new(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Current"));
+
+    // Nothing left to reclaim
+    assert_eq!(vacuum(&test_file).unwrap().bytes_reclaimed(), 0);
+}
+
+#[test]
+fn test_vacuum_report_of_a_grown_file_reclaims_nothing() {
+    let report = VacuumReport { original_size: 100, new_size: 120, duplicate_tags_removed: 0 };
+    assert_eq!(report.bytes_reclaimed(), 0);
+}
+
+#[test]
+fn test_vacuum_keeps_unknown_frames() {
+    let temp_dir = tempdir().unwrap();
+    let test_file = temp_dir.path().join("unknown.mp3");
+
+    let mut file = id3v2_tag("Title", 0);
+    let mut unknown = b"XYZW".to_vec();
+    unknown.extend_from_slice(&[0, 0, 0, 3, 0, 0, 1, 2, 3]);
+    file.splice(10..10, unknown);
+    let size = file.len() as u32 - 10 + 256;
+    file[6..10].copy_from_slice(&[0, 0, (size >> 7) as u8 & 0x7F, size as u8 & 0x7F]);
+    file.splice(file.len()..file.len(), [0u8; 256]);
+    file.extend_from_slice(&[0xFF, 0xFB, 0x90, 0x44]);
+    std::fs::write(&test_file, &file).unwrap();
+
+    let report = vacuum(&test_file).unwrap();
+    assert_eq!(report.bytes_reclaimed(), 256);
+    let compacted = std::fs::read(&test_file).unwrap();
+    let unknown_frame = [b'X', b'Y', b'Z', b'W', 0, 0, 0, 3, 0, 0, 1, 2, 3];
+    assert!(compacted.windows(unknown_frame.len()).any(|w| w == unknown_frame));
+    let reader = TagReader::new(&test_file).unwrap();
+    assert_eq!(reader.get_meta_entry(&MetaEntry::Title).unwrap().as_deref(), Some("Title"));
+}
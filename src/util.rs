@@ -3,6 +3,7 @@ use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
 use crate::Error;
+use crate::progress;
 use crate::Result;
 
 /// The modified file extension for temporary files
@@ -28,6 +29,64 @@ pub fn rename_file<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<()>
     fs::rename(&from, &to).map_err(|e| Error::FileRenameError(e.to_string()))
 }
 
+/// Attributes of the original file carried over when it is replaced by a rewritten copy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreserveOptions {
+    /// Copy the permission bits (enabled by default)
+    pub permissions: bool,
+    /// Copy owner and group where the process is allowed to (enabled by default)
+    pub ownership: bool,
+    /// Restore the modification and access times of the original file
+    pub mtime: bool,
+}
+
+impl Default for PreserveOptions {
+    fn default() -> Self {
+        Self {
+            permissions: true,
+            ownership: true,
+            mtime: false,
+        }
+    }
+}
+
+/// Replaces `path` with the rewritten file at `temp_path`, carrying over the selected attributes
+pub fn replace_file(temp_path: &Path, path: &Path, options: PreserveOptions) -> Result<()> {
+    let metadata = fs::metadata(path)?;
+    // Times first, as the temp file has to be opened for writing, and
+    // permissions last, as they may make it read-only
+    if options.mtime {
+        restore_times(&fs::OpenOptions::new().write(true).open(temp_path)?, &metadata)?;
+    }
+    if options.ownership {
+        copy_ownership(temp_path, &metadata);
+    }
+    if options.permissions {
+        fs::set_permissions(temp_path, metadata.permissions())?;
+    }
+    rename_file(temp_path, path)
+}
+
+/// Sets the access and modification times of `file` to those recorded in `metadata`
+pub fn restore_times(file: &File, metadata: &fs::Metadata) -> Result<()> {
+    let times = fs::FileTimes::new()
+        .set_accessed(metadata.accessed()?)
+        .set_modified(metadata.modified()?);
+    file.set_times(times)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_ownership(path: &Path, metadata: &fs::Metadata) {
+    use std::os::unix::fs::MetadataExt;
+
+    // Only privileged processes may give files away; keep our ownership otherwise
+    let _ = std::os::unix::fs::chown(path, Some(metadata.uid()), Some(metadata.gid()));
+}
+
+#[cfg(not(unix))]
+fn copy_ownership(_path: &Path, _metadata: &fs::Metadata) {}
+
 /// Creates a temporary path for a file
 pub fn get_temp_path<P: AsRef<Path>>(path: P) -> PathBuf {
     let path = path.as_ref();
@@ -40,9 +99,12 @@ pub fn get_temp_path<P: AsRef<Path>>(path: P) -> PathBuf {
 }
 
 /// Copies a range of bytes from one file to another
-pub fn copy_file_range(source: &mut File, target: &mut File) -> Result<()> {
-    const BUFFER_SIZE: usize = 8192;
-    let mut buffer = [0u8; BUFFER_SIZE];
+///
+/// The copied bytes are reported to the `Progress` of the current thread,
+/// which stops the copy with `Error::Cancelled` once cancelled.
+pub fn copy_file_range<R: Read, W: Write>(source: &mut R, target: &mut W) -> Result<()> {
+    const BUFFER_SIZE: usize = 64 * 1024;
+    let mut buffer = vec![0u8; BUFFER_SIZE];
     
     loop {
         let bytes_read = source.read(&mut buffer)?;
@@ -50,6 +112,7 @@ pub fn copy_file_range(source: &mut File, target: &mut File) -> Result<()> {
             break;
         }
         target.write_all(&buffer[..bytes_read])?;
+        progress::copied(bytes_read)?;
     }
     
     Ok(())
@@ -66,27 +129,55 @@ pub fn absolute_path<P: AsRef<Path>>(path: P) -> Result<PathBuf> {
     Ok(absolute)
 }
 
+/// Text encoding used when extracting strings from raw tag buffers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StringEncoding {
+    /// UTF-8, falling back to ISO-8859-1 when the bytes are not valid UTF-8
+    #[default]
+    Auto,
+    /// UTF-8, replacing invalid sequences
+    Utf8,
+    /// ISO-8859-1 (Latin-1)
+    Latin1,
+}
+
+/// Decodes ISO-8859-1 bytes, which map one-to-one onto the first 256 code points
+pub fn decode_latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| b as char).collect()
+}
+
 /// Extracts a string from a buffer at a given position and length
+///
+/// The bytes are decoded as UTF-8 with an ISO-8859-1 fallback; see
+/// `extract_string_with_encoding` to force an encoding.
 pub fn extract_string(buffer: &[u8], start: usize, length: usize) -> Result<String> {
-    if start + length > buffer.len() {
-        return Err(Error::Other(format!(
+    extract_string_with_encoding(buffer, start, length, StringEncoding::Auto)
+}
+
+/// Extracts a string from a buffer using the given encoding
+///
+/// NUL padding and other control characters are removed; everything else,
+/// including non-ASCII text, is preserved.
+pub fn extract_string_with_encoding(buffer: &[u8], start: usize, length: usize, encoding: StringEncoding) -> Result<String> {
+    let end = start.checked_add(length).filter(|&end| end <= buffer.len()).ok_or_else(|| {
+        Error::Other(format!(
             "Buffer size {} < requested length: {}",
             buffer.len(),
-            start + length
-        )));
-    }
+            start.saturating_add(length)
+        ))
+    })?;
+
+    let bytes = &buffer[start..end];
+    let text = match encoding {
+        StringEncoding::Auto => match std::str::from_utf8(bytes) {
+            Ok(text) => text.to_string(),
+            Err(_) => decode_latin1(bytes),
+        },
+        StringEncoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+        StringEncoding::Latin1 => decode_latin1(bytes),
+    };
 
-    let bytes = &buffer[start..start + length];
-    
-    // Filter out non-printable characters
-    let filtered: Vec<u8> = bytes
-        .iter()
-        .filter(|&&b| (32..=126).contains(&b))
-        .cloned()
-        .collect();
-    
-    String::from_utf8(filtered)
-        .map_err(|_| Error::NonPrintableContent)
+    Ok(text.chars().filter(|c| !c.is_control()).collect())
 }
 
 /// Gets the tag size from a buffer using specified parameters
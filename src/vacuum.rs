@@ -0,0 +1,134 @@
+//! Compaction of tagged files: drops ID3v2 padding and duplicate tags
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::error::Result;
+use crate::id3::constants::{ID3V1_IDENTIFIER, ID3V1_TAG_SIZE};
+use crate::id3::v2::header::Header;
+use crate::file_access::FileLock;
+use crate::id3::v2::tag::TagWriter;
+use crate::tag::TagWriterStrategy;
+use crate::util::{copy_file_range, get_temp_path, replace_file, PreserveOptions};
+
+/// Outcome of a `vacuum` run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VacuumReport {
+    /// File size before compaction
+    pub original_size: u64,
+    /// File size after compaction
+    pub new_size: u64,
+    /// Number of redundant ID3v2 and ID3v1 tags that were dropped
+    pub duplicate_tags_removed: usize,
+}
+
+impl VacuumReport {
+    /// Number of bytes freed
+    pub fn bytes_reclaimed(&self) -> u64 {
+        self.original_size.saturating_sub(self.new_size)
+    }
+}
+
+/// Rewrite a file without ID3v2 padding and duplicate tags
+///
+/// The first ID3v2 tag is kept without padding, and with all its frames;
+/// further ID3v2 tags directly following it are dropped, as are all but the
+/// last of repeated ID3v1 tags. Bytes outside the tags are kept.
+/// The file is left untouched when there is nothing to reclaim, or when the
+/// rewrite would not make it smaller.
+pub fn vacuum<P: AsRef<Path>>(path: P) -> Result<VacuumReport> {
+    vacuum_preserving(path, PreserveOptions::default())
+}
+
+/// Compact a file like `vacuum`, carrying the selected attributes over to the rewritten file
+pub fn vacuum_preserving<P: AsRef<Path>>(path: P, preserve: PreserveOptions) -> Result<VacuumReport> {
+    let path = path.as_ref();
+    // Held from reading the tag until the rewritten file replaces the original
+    let _lock = FileLock::acquire(path)?;
+    let mut file = File::open(path)?;
+    let original_size = file.metadata()?.len();
+    let mut duplicate_tags_removed = 0;
+
+    let mut writer = TagWriter::new();
+    writer.init(path)?;
+    let tag = writer.compacted_tag()?;
+
+    // Skip the first tag and any ID3v2 tags stacked behind it
+    let mut audio_start = 0;
+    while let Some(size) = id3v2_tag_size_at(&mut file, audio_start)? {
+        if audio_start > 0 {
+            duplicate_tags_removed += 1;
+        }
+        audio_start += size;
+    }
+
+    // Keep only the last of repeated ID3v1 tags
+    let mut audio_end = original_size;
+    let keeps_id3v1 = has_id3v1_at(&mut file, original_size, audio_end)?;
+    if keeps_id3v1 {
+        audio_end -= ID3V1_TAG_SIZE as u64;
+        while audio_end >= audio_start + ID3V1_TAG_SIZE as u64 && has_id3v1_at(&mut file, original_size, audio_end)? {
+            audio_end -= ID3V1_TAG_SIZE as u64;
+            duplicate_tags_removed += 1;
+        }
+    }
+    let audio_end = audio_end.max(audio_start);
+    let trailer_len = if keeps_id3v1 { ID3V1_TAG_SIZE as u64 } else { 0 };
+
+    let tag = tag.unwrap_or_default();
+    let new_size = tag.len() as u64 + (audio_end - audio_start) + trailer_len;
+    let report = VacuumReport {
+        original_size,
+        new_size,
+        duplicate_tags_removed,
+    };
+    // Never rewrite a file only to make it larger
+    if new_size >= original_size {
+        return Ok(VacuumReport { original_size, new_size: original_size, duplicate_tags_removed: 0 });
+    }
+
+    let temp_path = get_temp_path(path);
+    let result = (|| -> Result<()> {
+        let mut temp = OpenOptions::new().write(true).create(true).truncate(true).open(&temp_path)?;
+        temp.write_all(&tag)?;
+        file.seek(SeekFrom::Start(audio_start))?;
+        copy_file_range(&mut (&mut file).take(audio_end - audio_start), &mut temp)?;
+        if keeps_id3v1 {
+            file.seek(SeekFrom::End(-(ID3V1_TAG_SIZE as i64)))?;
+            copy_file_range(&mut file, &mut temp)?;
+        }
+        temp.sync_all()?;
+        drop(file);
+        replace_file(&temp_path, path, preserve)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result?;
+    Ok(report)
+}
+
+/// Total size of the ID3v2 tag starting at `offset`, if there is one
+fn id3v2_tag_size_at(file: &mut File, offset: u64) -> Result<Option<u64>> {
+    let mut buffer = [0u8; 10];
+    file.seek(SeekFrom::Start(offset))?;
+    if file.read(&mut buffer)? < buffer.len() {
+        return Ok(None);
+    }
+    Ok(Header::parse(&buffer).ok().map(|header| {
+        let footer = if header.version == 4 && header.flags & 0x10 != 0 { 10 } else { 0 };
+        10 + header.size as u64 + footer
+    }))
+}
+
+/// Whether an ID3v1 tag ends at `end`
+fn has_id3v1_at(file: &mut File, file_size: u64, end: u64) -> Result<bool> {
+    if end < ID3V1_TAG_SIZE as u64 || end > file_size {
+        return Ok(false);
+    }
+    let mut identifier = [0u8; 3];
+    file.seek(SeekFrom::Start(end - ID3V1_TAG_SIZE as u64))?;
+    file.read_exact(&mut identifier)?;
+    Ok(identifier == ID3V1_IDENTIFIER)
+}
@@ -9,6 +9,14 @@ pub enum ValidationError {
     InvalidCharacters(String),
     #[error("Invalid year format")]
     InvalidYear,
+    #[error("Invalid tempo, expected beats per minute: {0}")]
+    InvalidBpm(String),
+    #[error("Invalid musical key: {0}")]
+    InvalidKey(String),
+    #[error("Invalid ISRC, expected CC-XXX-YY-NNNNN: {0}")]
+    InvalidIsrc(String),
+    #[error("Invalid ISO 639-2 language code: {0}")]
+    InvalidLanguage(String),
 }
 
 pub trait BaseValidator {
@@ -34,6 +42,81 @@ pub trait BaseValidator {
             _ => Ok(())
         }
     }
+
+    /// Check the notation of numeric and musical key entries; empty values are allowed
+    fn validate_format(&self, entry: &MetaEntry, value: &str) -> Result<(), ValidationError> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(());
+        }
+        match entry {
+            MetaEntry::BeatsPerMinute if !is_bpm(value) => Err(ValidationError::InvalidBpm(value.to_string())),
+            MetaEntry::InitialKey if !is_musical_key(value) => Err(ValidationError::InvalidKey(value.to_string())),
+            MetaEntry::Isrc => normalize_isrc(value).map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Canonical form of an International Standard Recording Code
+///
+/// Accepts any case, with or without dashes ("us-rc1-76-07839"), and returns
+/// the 12 character code stored in tags ("USRC17607839"): a country code,
+/// a registrant code, a two-digit year and a five-digit designation.
+pub fn normalize_isrc(value: &str) -> Result<String, ValidationError> {
+    let code: String = value.trim().chars().filter(|c| *c != '-').collect::<String>().to_ascii_uppercase();
+    let bytes = code.as_bytes();
+    let valid = bytes.len() == 12
+        && bytes[..2].iter().all(u8::is_ascii_uppercase)
+        && bytes[2..5].iter().all(u8::is_ascii_alphanumeric)
+        && bytes[5..].iter().all(u8::is_ascii_digit);
+    if !valid {
+        return Err(ValidationError::InvalidIsrc(value.to_string()));
+    }
+    Ok(code)
+}
+
+/// Lowercase ISO 639-2 code for the language of a comment or lyrics frame
+///
+/// An empty code and the "XXX" placeholder written by some taggers become
+/// "und", the code for an undetermined language.
+pub fn normalize_language(code: &str) -> Result<String, ValidationError> {
+    let code = code.trim().to_ascii_lowercase();
+    if code.is_empty() || code == "xxx" {
+        return Ok("und".to_string());
+    }
+    if code.len() != 3 || !code.bytes().all(|b| b.is_ascii_lowercase()) {
+        return Err(ValidationError::InvalidLanguage(code));
+    }
+    Ok(code)
+}
+
+/// A non-negative number of beats, with an optional fraction ("128", "120.5")
+fn is_bpm(value: &str) -> bool {
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, "0"));
+    [whole, fraction]
+        .iter()
+        .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+        && value.parse::<f64>().is_ok_and(|bpm| bpm <= u16::MAX as f64)
+}
+
+/// A key in ID3v2 TKEY notation ("A", "C#m", "Bb", "o" for off key), or a
+/// Camelot ("8A") or Open Key ("1m") wheel position as written by DJ software
+fn is_musical_key(value: &str) -> bool {
+    if value == "o" {
+        return true;
+    }
+    let mut chars = value.chars();
+    if matches!(chars.next(), Some('A'..='G')) {
+        let rest = chars.as_str();
+        let rest = rest.strip_prefix(['b', '#']).unwrap_or(rest);
+        return rest.is_empty() || rest == "m";
+    }
+    let split = value.len() - value.chars().last().map_or(0, char::len_utf8);
+    let (number, suffix) = value.split_at(split);
+    matches!(suffix, "A" | "B" | "d" | "m")
+        && number.parse::<u8>().is_ok_and(|number| (1..=12).contains(&number))
+        && !number.starts_with('0')
 }
 
 pub trait Id3v2Validator: BaseValidator {
@@ -46,10 +129,14 @@ pub trait Id3v2Validator: BaseValidator {
             "TYER" => MetaEntry::Year,
             "COMM" => MetaEntry::Comment,
             "TCOM" => MetaEntry::Composer,
+            "TBPM" => MetaEntry::BeatsPerMinute,
+            "TKEY" => MetaEntry::InitialKey,
+            "TSRC" => MetaEntry::Isrc,
             _ => return Ok(()), // Unknown frame IDs are allowed
         };
         self.validate_length(&entry, value)?;
-        self.validate_chars(&entry, value)
+        self.validate_chars(&entry, value)?;
+        self.validate_format(&entry, value)
     }
 }
 
@@ -63,10 +150,14 @@ pub trait ApeValidator: BaseValidator {
             "YEAR" => MetaEntry::Year,
             "COMMENT" => MetaEntry::Comment,
             "COMPOSER" => MetaEntry::Composer,
+            "BPM" => MetaEntry::BeatsPerMinute,
+            "INITIALKEY" => MetaEntry::InitialKey,
+            "ISRC" => MetaEntry::Isrc,
             _ => return Ok(()), // Unknown keys are allowed
         };
         self.validate_length(&entry, value)?;
-        self.validate_chars(&entry, value)
+        self.validate_chars(&entry, value)?;
+        self.validate_format(&entry, value)
     }
 }
 
@@ -0,0 +1,86 @@
+//! Library index kept up to date by watching the filesystem
+//!
+//! Available with the `watch` feature.
+//!
+//! ```no_run
+//! use mp3tags_r::library::{Library, LibraryEvent};
+//! use mp3tags_r::watch::LibraryWatcher;
+//!
+//! let watcher = LibraryWatcher::new(Library::scan("/music")?, |event| {
+//!     if let LibraryEvent::Added(entry) = event {
+//!         println!("New: {:?}", entry.metadata.title);
+//!     }
+//! })?;
+//! // ... later
+//! let library = watcher.into_library();
+//! # Ok::<(), mp3tags_r::Error>(())
+//! ```
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use log::warn;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::error::{Error, Result};
+use crate::library::{Library, LibraryEvent};
+
+/// Watches the root of a `Library` and refreshes the files that change below it
+///
+/// The events are handled on a thread of the watcher; `on_event` is called
+/// there for every file added, updated, removed or failing to read.
+/// Changes made while no watcher runs are picked up by `Library::rescan`.
+#[derive(Debug)]
+pub struct LibraryWatcher {
+    library: Arc<Mutex<Library>>,
+    watcher: RecommendedWatcher,
+}
+
+impl LibraryWatcher {
+    /// Start watching the root of `library`, recursively
+    pub fn new<F>(library: Library, on_event: F) -> Result<Self>
+    where
+        F: Fn(&LibraryEvent) + Send + 'static,
+    {
+        let root = library.root().to_path_buf();
+        let library = Arc::new(Mutex::new(library));
+        let shared = Arc::clone(&library);
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+            Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                for path in event.paths {
+                    let events = lock(&shared).refresh(&path);
+                    events.iter().for_each(&on_event);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Library watch error: {}", e),
+        })
+        .map_err(watch_error)?;
+        watcher.watch(&root, RecursiveMode::Recursive).map_err(watch_error)?;
+        Ok(Self { library, watcher })
+    }
+
+    /// The index; holding it blocks the handling of further changes
+    pub fn library(&self) -> MutexGuard<'_, Library> {
+        lock(&self.library)
+    }
+
+    /// Stop watching and return the index
+    pub fn into_library(self) -> Library {
+        let Self { library, watcher } = self;
+        // Stops the event thread; the index is copied if it still holds a reference
+        drop(watcher);
+        match Arc::try_unwrap(library) {
+            Ok(library) => library.into_inner().unwrap_or_else(PoisonError::into_inner),
+            Err(library) => lock(&library).clone(),
+        }
+    }
+}
+
+/// A panicking event callback leaves the index usable
+fn lock(library: &Mutex<Library>) -> MutexGuard<'_, Library> {
+    library.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn watch_error(error: notify::Error) -> Error {
+    Error::Other(format!("Failed to watch library: {}", error))
+}